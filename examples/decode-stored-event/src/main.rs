@@ -0,0 +1,59 @@
+//! Decodes a `Stored` event of the transaction storage pallet and checks that its chunk root is
+//! the chunk root of some data, using only `transaction-storage-primitives` and `sp-core`.
+//!
+//! Usage: `decode-stored-event <event> <data file>`, where `<event>` is the hex encoded event as
+//! found in `System::Events`: the index of the pallet in the runtime, the index of the event and
+//! its fields.
+
+use sp_core::{bytes::from_hex, hexdisplay::HexDisplay};
+use std::process::ExitCode;
+use transaction_storage_primitives::{codec::Decode, events::Stored, Upload};
+
+fn main() -> ExitCode {
+	let args: Vec<_> = std::env::args().skip(1).collect();
+	let [event, path] = &args[..] else {
+		eprintln!("Usage: decode-stored-event <event> <data file>");
+		return ExitCode::FAILURE
+	};
+	let event = match from_hex(event) {
+		Ok(event) => event,
+		Err(e) => {
+			eprintln!("Invalid event: {e}");
+			return ExitCode::FAILURE
+		},
+	};
+	let stored = match &event[..] {
+		[_pallet_index, Stored::INDEX, fields @ ..] => Stored::decode(&mut &fields[..]),
+		_ => {
+			eprintln!("Not a `Stored` event");
+			return ExitCode::FAILURE
+		},
+	};
+	let stored = match stored {
+		Ok(stored) => stored,
+		Err(e) => {
+			eprintln!("Invalid `Stored` event: {e}");
+			return ExitCode::FAILURE
+		},
+	};
+	let data = match std::fs::read(path) {
+		Ok(data) => data,
+		Err(e) => {
+			eprintln!("Cannot read {path}: {e}");
+			return ExitCode::FAILURE
+		},
+	};
+
+	let upload = Upload::new(&data);
+	println!("Transaction index: {}", stored.index);
+	println!("Chunk root: {:?}", stored.chunk_root);
+	println!("Content hash: 0x{}", HexDisplay::from(&upload.content_hash));
+	println!("Chunks: {}", upload.chunk_count);
+	if upload.matches(&stored) {
+		println!("The chunk root matches the data");
+		ExitCode::SUCCESS
+	} else {
+		println!("The chunk root does not match the data");
+		ExitCode::FAILURE
+	}
+}
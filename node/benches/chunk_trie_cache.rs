@@ -0,0 +1,40 @@
+//! Compares building storage proofs with a cold and a warm chunk trie cache.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use polkadot_bulletin_chain::chunk_trie_cache::ChunkTrieCache;
+
+const TRANSACTION_SIZE: usize = 8 * 1024 * 1024;
+const TRANSACTIONS: usize = 4;
+const CACHE_SIZE: usize = TRANSACTIONS * TRANSACTION_SIZE;
+
+fn transactions() -> Vec<Vec<u8>> {
+	(0..TRANSACTIONS)
+		.map(|t| (0..TRANSACTION_SIZE).map(|i| (i * 31 + t) as u8).collect())
+		.collect()
+}
+
+fn build_proof(c: &mut Criterion) {
+	let transactions = transactions();
+	let random_hash = [7u8; 32];
+	let mut group = c.benchmark_group("build_proof");
+	group.sample_size(10);
+
+	group.bench_function("cold", |b| {
+		b.iter_batched(
+			|| ChunkTrieCache::new(CACHE_SIZE, None).unwrap(),
+			|cache| cache.build_proof(&random_hash, &transactions).unwrap(),
+			BatchSize::PerIteration,
+		)
+	});
+
+	let cache = ChunkTrieCache::new(CACHE_SIZE, None).unwrap();
+	cache.build_proof(&random_hash, &transactions).unwrap();
+	group.bench_function("warm", |b| {
+		b.iter(|| cache.build_proof(&random_hash, &transactions).unwrap())
+	});
+
+	group.finish();
+}
+
+criterion_group!(benches, build_proof);
+criterion_main!(benches);
@@ -0,0 +1,319 @@
+//! Cache of the chunk tries of indexed transactions, used to build storage proofs.
+//!
+//! The storage proof of a block covers a single chunk, selected by the parent hash among the
+//! chunks of the transactions indexed `DEFAULT_STORAGE_PERIOD` blocks earlier. Only the trie of
+//! the transaction holding that chunk is needed, and it is known as soon as the parent is
+//! imported, so it is built then rather than when the block is authored. Tries are kept in a
+//! cache bounded by bytes and keyed by content hash: the trie of some content is the same on every
+//! fork, so nothing needs to be invalidated on reorgs.
+
+use codec::Codec;
+use futures::StreamExt;
+use lru::LruCache;
+use pallet_transaction_storage::{
+	challenged_chunk, runtime_api::TransactionStorageApi, ChunkIndex, CHALLENGE_INHERENT_IDENTIFIER,
+};
+use sc_client_api::BlockchainEvents;
+use sp_api::ProvideRuntimeApi;
+use sp_core::{blake2_256, H256};
+use sp_inherents::{Error, InherentData, InherentIdentifier};
+use sp_runtime::traits::{BlakeTwo256, Block as BlockT, NumberFor, One, Saturating, Zero};
+use sp_transaction_storage_proof::{
+	encode_index, random_chunk, IndexedBody, InherentDataProvider, TransactionStorageProof,
+	CHUNK_SIZE, DEFAULT_STORAGE_PERIOD,
+};
+use sp_trie::{generate_trie_proof, LayoutV1, MemoryDB, TrieDBMutBuilder, TrieMut};
+use std::sync::{Arc, Mutex};
+use substrate_prometheus_endpoint::{register, Counter, PrometheusError, Registry, U64};
+
+type Layout = LayoutV1<BlakeTwo256>;
+
+const LOG_TARGET: &str = "chunk-trie-cache";
+
+/// Chunk trie of an indexed transaction.
+struct ChunkTrie {
+	root: H256,
+	db: MemoryDB<BlakeTwo256>,
+	// Approximated by the size of the data, which dominates the size of the trie
+	size: usize,
+}
+
+impl ChunkTrie {
+	fn build(data: &[u8]) -> Result<Self, Error> {
+		let mut db = MemoryDB::default();
+		let mut root = Default::default();
+		{
+			let mut trie = TrieDBMutBuilder::<Layout>::new(&mut db, &mut root).build();
+			for (index, chunk) in data.chunks(CHUNK_SIZE).enumerate() {
+				trie.insert(&encode_index(index as u32), chunk)
+					.map_err(|e| Error::Application(Box::new(e)))?;
+			}
+		}
+		Ok(Self { root, db, size: data.len() })
+	}
+
+	fn prove(&self, chunk_index: u32) -> Result<Vec<Vec<u8>>, Error> {
+		generate_trie_proof::<Layout, _, _, _>(&self.db, self.root, &[encode_index(chunk_index)])
+			.map_err(|e| Error::Application(Box::new(e)))
+	}
+}
+
+struct Tries {
+	lru: LruCache<H256, Arc<ChunkTrie>>,
+	bytes: usize,
+}
+
+struct Metrics {
+	hits: Counter<U64>,
+	misses: Counter<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			hits: register(
+				Counter::new(
+					"substrate_chunk_trie_cache_hits",
+					"Storage proofs built from a cached chunk trie",
+				)?,
+				registry,
+			)?,
+			misses: register(
+				Counter::new(
+					"substrate_chunk_trie_cache_misses",
+					"Storage proofs that had to build their chunk trie",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// Chunk tries by content hash, bounded by the size of their data.
+pub struct ChunkTrieCache {
+	tries: Mutex<Tries>,
+	max_bytes: usize,
+	metrics: Option<Metrics>,
+}
+
+impl ChunkTrieCache {
+	pub fn new(max_bytes: usize, registry: Option<&Registry>) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			tries: Mutex::new(Tries { lru: LruCache::unbounded(), bytes: 0 }),
+			max_bytes,
+			metrics: registry.map(Metrics::register).transpose()?,
+		})
+	}
+
+	fn cached(&self, content_hash: &H256) -> Option<Arc<ChunkTrie>> {
+		self.tries.lock().expect("not poisoned").lru.get(content_hash).cloned()
+	}
+
+	fn build(&self, content_hash: H256, data: &[u8]) -> Result<Arc<ChunkTrie>, Error> {
+		let trie = Arc::new(ChunkTrie::build(data)?);
+		let mut tries = self.tries.lock().expect("not poisoned");
+		if trie.size <= self.max_bytes && tries.lru.put(content_hash, trie.clone()).is_none() {
+			tries.bytes += trie.size;
+			while tries.bytes > self.max_bytes {
+				let Some((_, evicted)) = tries.lru.pop_lru() else { break };
+				tries.bytes -= evicted.size;
+			}
+		}
+		Ok(trie)
+	}
+
+	/// Builds the storage proof of a block whose parent hash is `random_hash`, for the given
+	/// transactions of the target block. Returns `None` if there's nothing to prove.
+	pub fn build_proof(
+		&self,
+		random_hash: &[u8],
+		transactions: &[Vec<u8>],
+	) -> Result<Option<TransactionStorageProof>, Error> {
+		let Some((index, chunk_index)) = select_chunk(random_hash, transactions) else {
+			return Ok(None)
+		};
+		let (_, proof) = self.prove_chunk(&transactions[index], chunk_index)?;
+		Ok(Some(proof))
+	}
+
+	/// Proves the chunk at `chunk_index` of `data`, with its trie taken from the cache. Returns the
+	/// root of the chunk trie along with the proof.
+	pub fn prove_chunk(
+		&self,
+		data: &[u8],
+		chunk_index: ChunkIndex,
+	) -> Result<(H256, TransactionStorageProof), Error> {
+		let content_hash = blake2_256(data).into();
+		let trie = match self.cached(&content_hash) {
+			Some(trie) => {
+				if let Some(metrics) = &self.metrics {
+					metrics.hits.inc();
+				}
+				trie
+			},
+			None => {
+				if let Some(metrics) = &self.metrics {
+					metrics.misses.inc();
+				}
+				self.build(content_hash, data)?
+			},
+		};
+		let chunk = data
+			.chunks(CHUNK_SIZE)
+			.nth(chunk_index.0 as usize)
+			.ok_or_else(|| Error::Application("Chunk index out of range".into()))?
+			.to_vec();
+		Ok((trie.root, TransactionStorageProof { chunk, proof: trie.prove(chunk_index.0)? }))
+	}
+
+	/// Same as `sp_transaction_storage_proof::registration::new_data_provider`, with the chunk
+	/// trie taken from the cache.
+	pub fn new_data_provider<B, C>(
+		&self,
+		client: &C,
+		parent: &B::Hash,
+	) -> Result<InherentDataProvider, Error>
+	where
+		B: BlockT,
+		C: IndexedBody<B>,
+	{
+		let proof = match target_transactions(client, parent)? {
+			Some(transactions) => self.build_proof(parent.as_ref(), &transactions)?,
+			None => None,
+		};
+		Ok(InherentDataProvider::new(proof))
+	}
+
+	/// Provides the storage proofs of the content challenged for the block built on `parent`.
+	/// Challenged content that is no longer indexed is reported as unprovable rather than failing
+	/// the block.
+	pub fn new_challenge_data_provider<B, C, AccountId>(
+		&self,
+		client: &C,
+		parent: &B::Hash,
+	) -> Result<ChallengeDataProvider, Error>
+	where
+		B: BlockT,
+		C: IndexedBody<B> + ProvideRuntimeApi<B>,
+		C::Api: TransactionStorageApi<B, AccountId, NumberFor<B>>,
+		AccountId: Codec,
+	{
+		let challenges = client
+			.runtime_api()
+			.challenges(*parent)
+			.map_err(|e| Error::Application(Box::new(e)))?;
+		let mut proofs = Vec::with_capacity(challenges.len());
+		for challenge in challenges {
+			let transactions = client.block_indexed_body(challenge.block)?;
+			let Some(data) = transactions
+				.as_ref()
+				.and_then(|transactions| transactions.get(challenge.index.0 as usize))
+			else {
+				log::warn!(
+					target: LOG_TARGET,
+					"Challenged content {:?} of block {} is not found",
+					challenge.content_hash,
+					challenge.block,
+				);
+				proofs.push(None);
+				continue
+			};
+			let chunk_index =
+				challenged_chunk(parent.as_ref(), &challenge.content_hash, challenge.chunk_count);
+			let (_, proof) = self.prove_chunk(data, chunk_index)?;
+			proofs.push(Some(proof));
+		}
+		Ok(ChallengeDataProvider { proofs })
+	}
+
+	/// Builds the chunk trie that the storage proof of the blocks built on each imported block
+	/// will need.
+	pub async fn prefetch<B, C>(self: Arc<Self>, client: Arc<C>)
+	where
+		B: BlockT,
+		C: BlockchainEvents<B> + IndexedBody<B>,
+	{
+		let mut imports = client.import_notification_stream();
+		while let Some(notification) = imports.next().await {
+			if let Err(e) = self.prefetch_for(&*client, &notification.hash) {
+				log::debug!(target: LOG_TARGET, "Failed to prefetch a chunk trie: {e}");
+			}
+		}
+	}
+
+	fn prefetch_for<B, C>(&self, client: &C, parent: &B::Hash) -> Result<(), Error>
+	where
+		B: BlockT,
+		C: IndexedBody<B>,
+	{
+		let Some(transactions) = target_transactions(client, parent)? else { return Ok(()) };
+		if let Some((index, _)) = select_chunk(parent.as_ref(), &transactions) {
+			let data = &transactions[index];
+			let content_hash = blake2_256(data).into();
+			if self.cached(&content_hash).is_none() {
+				self.build(content_hash, data)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Provides the storage proofs of challenged content, under `CHALLENGE_INHERENT_IDENTIFIER`.
+pub struct ChallengeDataProvider {
+	proofs: Vec<Option<TransactionStorageProof>>,
+}
+
+#[async_trait::async_trait]
+impl sp_inherents::InherentDataProvider for ChallengeDataProvider {
+	async fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), Error> {
+		if self.proofs.is_empty() {
+			return Ok(())
+		}
+		inherent_data.put_data(CHALLENGE_INHERENT_IDENTIFIER, &self.proofs)
+	}
+
+	async fn try_handle_error(
+		&self,
+		_identifier: &InherentIdentifier,
+		_error: &[u8],
+	) -> Option<Result<(), Error>> {
+		// Invalid proofs are reported under the storage proof identifier
+		None
+	}
+}
+
+/// The transactions indexed in the block whose storage is proved in the child of `parent`.
+fn target_transactions<B, C>(client: &C, parent: &B::Hash) -> Result<Option<Vec<Vec<u8>>>, Error>
+where
+	B: BlockT,
+	C: IndexedBody<B>,
+{
+	let parent_number = client.number(*parent)?.unwrap_or(Zero::zero());
+	let number = parent_number
+		.saturating_add(One::one())
+		.saturating_sub(DEFAULT_STORAGE_PERIOD.into());
+	if number.is_zero() {
+		// Too early to collect proofs
+		return Ok(None)
+	}
+	client.block_indexed_body(number)
+}
+
+/// Locates the chunk selected by `random_hash` among the chunks of `transactions`. Returns the
+/// index of its transaction and its index within the transaction.
+fn select_chunk(random_hash: &[u8], transactions: &[Vec<u8>]) -> Option<(usize, ChunkIndex)> {
+	let num_chunks = |data: &Vec<u8>| ((data.len() + CHUNK_SIZE - 1) / CHUNK_SIZE) as u32;
+	let total_chunks: u32 = transactions.iter().map(num_chunks).sum();
+	if total_chunks == 0 {
+		return None
+	}
+	let mut selected = random_chunk(random_hash, total_chunks);
+	for (index, data) in transactions.iter().enumerate() {
+		let chunks = num_chunks(data);
+		if selected < chunks {
+			return Some((index, ChunkIndex(selected)))
+		}
+		selected -= chunks;
+	}
+	None
+}
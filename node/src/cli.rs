@@ -1,3 +1,4 @@
+use crate::retention::DEFAULT_RETENTION_MARGIN;
 use sc_cli::RunCmd;
 
 #[derive(Debug, clap::Parser)]
@@ -7,6 +8,16 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	/// Keep as many blocks, with the transactions they index, as the storage period of the
+	/// genesis requires, plus `--retention-margin` blocks. Overrides `--blocks-pruning`.
+	#[arg(long)]
+	pub auto_blocks_pruning: bool,
+
+	/// Number of blocks to keep beyond those the storage period requires, used by
+	/// `--auto-blocks-pruning` and to check `--blocks-pruning` against the storage period.
+	#[arg(long, default_value_t = DEFAULT_RETENTION_MARGIN)]
+	pub retention_margin: u32,
 }
 
 #[derive(Debug, clap::Subcommand)]
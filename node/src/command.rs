@@ -2,7 +2,7 @@ use crate::{
 	benchmarking::{inherent_benchmark_data, RemarkBuilder},
 	chain_spec,
 	cli::{Cli, Subcommand},
-	service,
+	retention, service,
 };
 use frame_benchmarking_cli::{BenchmarkCmd, ExtrinsicFactory, SUBSTRATE_REFERENCE_HARDWARE};
 use polkadot_bulletin_chain_runtime::Block;
@@ -197,8 +197,15 @@ pub fn run() -> sc_cli::Result<()> {
 		},
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
-			runner.run_node_until_exit(|config| async move {
-				service::new_full(config).map_err(sc_cli::Error::Service)
+			let retention_margin = cli.retention_margin;
+			let auto_blocks_pruning = cli.auto_blocks_pruning;
+			runner.run_node_until_exit(|mut config| async move {
+				if auto_blocks_pruning {
+					config.blocks_pruning =
+						retention::derived_pruning(&*config.chain_spec, retention_margin)
+							.map_err(sc_cli::Error::Input)?;
+				}
+				service::new_full(config, retention_margin).map_err(sc_cli::Error::Service)
 			})
 		},
 	}
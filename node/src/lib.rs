@@ -1,3 +1,5 @@
 pub mod chain_spec;
+pub mod chunk_trie_cache;
+pub mod retention;
 pub mod rpc;
 pub mod service;
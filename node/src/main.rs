@@ -3,11 +3,13 @@
 #![warn(missing_docs)]
 
 mod chain_spec;
+mod chunk_trie_cache;
 #[macro_use]
 mod service;
 mod benchmarking;
 mod cli;
 mod command;
+mod retention;
 mod rpc;
 
 fn main() -> sc_cli::Result<()> {
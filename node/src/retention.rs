@@ -0,0 +1,209 @@
+//! Coordination of how long the node keeps indexed transactions with the storage period of the
+//! runtime.
+//!
+//! Indexed transactions are pruned with the bodies of their blocks, as set by `--blocks-pruning`.
+//! Data stored in block `n` is proven, and can be renewed until, block `n + StoragePeriod`, so the
+//! bodies of the last `StoragePeriod + 1` blocks must be kept, plus a margin for the blocks the
+//! node lags behind. Keeping more only keeps expired data.
+//!
+//! With `--auto-blocks-pruning`, the number of blocks kept is derived from the `StoragePeriod` of
+//! the genesis, as the database is opened before any runtime can be called. The storage period is
+//! then read from the runtime of the best block at startup and after every runtime upgrade, and a
+//! warning is logged whenever the blocks kept are too few to serve the storage proofs.
+
+use codec::{Codec, Decode};
+use futures::StreamExt;
+use pallet_transaction_storage::{primitives::storage_keys, runtime_api::TransactionStorageApi};
+use sc_client_api::BlockchainEvents;
+use sc_service::{BlocksPruning, ChainSpec};
+use sp_api::{ApiError, Core, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	traits::{Block as BlockT, NumberFor, UniqueSaturatedInto},
+	BuildStorage,
+};
+use std::sync::Arc;
+
+const LOG_TARGET: &str = "transaction-retention";
+
+/// Default number of blocks kept beyond those the storage period requires, about 10 minutes of
+/// 6 second blocks.
+pub const DEFAULT_RETENTION_MARGIN: u32 = 100;
+
+/// Number of most recent blocks whose indexed transactions must be kept to serve the storage
+/// proofs and renewals of a runtime with the given storage period, plus `margin` blocks.
+pub fn required_retention(storage_period: u32, margin: u32) -> u32 {
+	storage_period.saturating_add(1).saturating_add(margin)
+}
+
+/// How the blocks kept by the node compare with those the storage period requires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Retention {
+	/// Enough blocks are kept to serve storage proofs, and expired data is pruned.
+	Sufficient,
+	/// Too few blocks are kept, so data is pruned before its storage proof is due.
+	TooShort {
+		/// Number of blocks kept.
+		kept: u32,
+		/// Number of blocks required.
+		required: u32,
+	},
+	/// Blocks are never pruned, so expired data is kept forever.
+	Unbounded,
+}
+
+/// Checks whether blocks pruned as set by `pruning` keep the `required` number of blocks.
+pub fn check_retention(pruning: BlocksPruning, required: u32) -> Retention {
+	match pruning {
+		BlocksPruning::Some(kept) if kept < required => Retention::TooShort { kept, required },
+		BlocksPruning::Some(_) => Retention::Sufficient,
+		BlocksPruning::KeepAll | BlocksPruning::KeepFinalized => Retention::Unbounded,
+	}
+}
+
+/// Storage period set in the genesis of the given chain spec, if any.
+pub fn genesis_storage_period(chain_spec: &dyn ChainSpec) -> Result<Option<u32>, String> {
+	let storage = chain_spec.as_storage_builder().build_storage()?;
+	storage
+		.top
+		.get(&storage_keys::storage_period_key())
+		.map(|value| u32::decode(&mut &value[..]).map_err(|e| e.to_string()))
+		.transpose()
+}
+
+/// Blocks pruning keeping the blocks required by the genesis storage period of the given chain
+/// spec, plus `margin` blocks.
+pub fn derived_pruning(chain_spec: &dyn ChainSpec, margin: u32) -> Result<BlocksPruning, String> {
+	let storage_period = genesis_storage_period(chain_spec)?
+		.ok_or("The genesis does not set the `StoragePeriod` of the transaction storage")?;
+	let kept = required_retention(storage_period, margin);
+	log::info!(
+		target: LOG_TARGET,
+		"Keeping {kept} blocks for a storage period of {storage_period} blocks",
+	);
+	Ok(BlocksPruning::Some(kept))
+}
+
+/// Tracks the storage period of the runtime, to check the blocks kept against it when it changes.
+pub struct RetentionMonitor {
+	pruning: BlocksPruning,
+	margin: u32,
+	storage_period: Option<u32>,
+}
+
+impl RetentionMonitor {
+	/// Creates a monitor for blocks pruned as set by `pruning`, requiring `margin` blocks beyond
+	/// those of the storage period.
+	pub fn new(pruning: BlocksPruning, margin: u32) -> Self {
+		Self { pruning, margin, storage_period: None }
+	}
+
+	/// Records the storage period of the runtime. Returns how the blocks kept compare with those
+	/// it requires if it is the first one recorded or it changed.
+	pub fn update(&mut self, storage_period: u32) -> Option<Retention> {
+		if self.storage_period == Some(storage_period) {
+			return None
+		}
+		self.storage_period = Some(storage_period);
+		Some(check_retention(self.pruning, required_retention(storage_period, self.margin)))
+	}
+}
+
+fn report(retention: Retention, storage_period: u32) {
+	match retention {
+		Retention::Sufficient => log::info!(
+			target: LOG_TARGET,
+			"Indexed transactions are kept long enough for a storage period of {storage_period} \
+			 blocks",
+		),
+		Retention::TooShort { kept, required } => log::warn!(
+			target: LOG_TARGET,
+			"⚠️  Only {kept} blocks are kept, but a storage period of {storage_period} blocks \
+			 requires {required}: indexed transactions will be pruned before their storage \
+			 proofs are due, so this node cannot author blocks or serve the data. Restart with \
+			 `--blocks-pruning {required}` or `--auto-blocks-pruning`.",
+		),
+		Retention::Unbounded => log::info!(
+			target: LOG_TARGET,
+			"Blocks are not pruned: indexed transactions are kept after their storage period of \
+			 {storage_period} blocks is over",
+		),
+	}
+}
+
+/// Checks the blocks kept against the storage period of the best block at startup, and again
+/// whenever a new best block runs another runtime version.
+pub async fn watch_storage_period<B, C, AccountId>(client: Arc<C>, mut monitor: RetentionMonitor)
+where
+	B: BlockT,
+	C: BlockchainEvents<B> + HeaderBackend<B> + ProvideRuntimeApi<B>,
+	C::Api: TransactionStorageApi<B, AccountId, NumberFor<B>> + Core<B>,
+	AccountId: Codec,
+{
+	let mut spec_version = None;
+	let mut check = |hash: B::Hash| {
+		let api = client.runtime_api();
+		let version = api.version(hash)?.spec_version;
+		if spec_version == Some(version) {
+			return Ok(())
+		}
+		spec_version = Some(version);
+		let storage_period = api.storage_period(hash)?.unique_saturated_into();
+		if let Some(retention) = monitor.update(storage_period) {
+			report(retention, storage_period);
+		}
+		Ok::<_, ApiError>(())
+	};
+	if let Err(e) = check(client.info().best_hash) {
+		log::warn!(target: LOG_TARGET, "Failed to read the storage period: {e}");
+	}
+	let mut imports = client.import_notification_stream();
+	while let Some(notification) = imports.next().await {
+		if !notification.is_new_best {
+			continue
+		}
+		if let Err(e) = check(notification.hash) {
+			log::warn!(target: LOG_TARGET, "Failed to read the storage period: {e}");
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn warns_when_too_few_blocks_are_kept() {
+		let required = required_retention(100, 10);
+		assert_eq!(required, 111);
+		assert_eq!(
+			check_retention(BlocksPruning::Some(110), required),
+			Retention::TooShort { kept: 110, required: 111 }
+		);
+		assert_eq!(check_retention(BlocksPruning::Some(111), required), Retention::Sufficient);
+		assert_eq!(check_retention(BlocksPruning::KeepFinalized, required), Retention::Unbounded);
+		assert_eq!(check_retention(BlocksPruning::KeepAll, required), Retention::Unbounded);
+	}
+
+	#[test]
+	fn checks_again_when_the_storage_period_changes() {
+		let mut monitor = RetentionMonitor::new(BlocksPruning::Some(120), 10);
+		assert_eq!(monitor.update(100), Some(Retention::Sufficient));
+		// Runtime upgrades keeping the storage period are not reported again
+		assert_eq!(monitor.update(100), None);
+		assert_eq!(monitor.update(200), Some(Retention::TooShort { kept: 120, required: 211 }));
+		assert_eq!(monitor.update(200), None);
+		assert_eq!(monitor.update(100), Some(Retention::Sufficient));
+	}
+
+	#[test]
+	fn reads_the_genesis_storage_period() {
+		let chain_spec = crate::chain_spec::development_config().unwrap();
+		let storage_period = genesis_storage_period(&chain_spec).unwrap();
+		assert_eq!(storage_period, Some(sp_transaction_storage_proof::DEFAULT_STORAGE_PERIOD));
+		assert_eq!(
+			derived_pruning(&chain_spec, 10).unwrap(),
+			BlocksPruning::Some(required_retention(storage_period.unwrap(), 10))
+		);
+	}
+}
@@ -5,14 +5,22 @@
 
 #![warn(missing_docs)]
 
+pub mod transaction_storage;
+
 use std::sync::Arc;
 
+use crate::chunk_trie_cache::ChunkTrieCache;
+use codec::{Decode, Encode};
 use jsonrpsee::RpcModule;
-use polkadot_bulletin_chain_runtime::{opaque::Block, AccountId, Nonce};
+use polkadot_bulletin_chain_runtime::{
+	opaque::Block, AccountId, BlockNumber, Nonce, RuntimeCall, UncheckedExtrinsic,
+};
+use sc_client_api::BlockBackend;
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_runtime::{traits::Block as BlockT, MultiAddress};
 
 pub use sc_rpc_api::DenyUnsafe;
 
@@ -24,6 +32,8 @@ pub struct FullDeps<C, P> {
 	pub pool: Arc<P>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// Chunk tries of indexed data, shared with block authoring.
+	pub chunk_trie_cache: Arc<ChunkTrieCache>,
 }
 
 /// Instantiate all full RPC extensions.
@@ -33,17 +43,33 @@ pub fn create_full<C, P>(
 where
 	C: ProvideRuntimeApi<Block>,
 	C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError> + 'static,
+	C: BlockBackend<Block>,
 	C: Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
 	C::Api: BlockBuilder<Block>,
-	P: TransactionPool + 'static,
+	C::Api: pallet_transaction_storage::runtime_api::TransactionStorageApi<
+		Block,
+		AccountId,
+		BlockNumber,
+	>,
+	P: TransactionPool<Block = Block> + 'static,
 {
 	use substrate_frame_rpc_system::{System, SystemApiServer};
+	use transaction_storage::{TransactionStorage, TransactionStorageApiServer};
 
 	let mut module = RpcModule::new(());
-	let FullDeps { client, pool, deny_unsafe } = deps;
+	let FullDeps { client, pool, deny_unsafe, chunk_trie_cache } = deps;
 
-	module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
+	module.merge(System::new(client.clone(), pool.clone(), deny_unsafe).into_rpc())?;
+	module.merge(
+		TransactionStorage::<_, _, Block, AccountId>::new(
+			client,
+			pool,
+			chunk_trie_cache,
+			Box::new(decode_store),
+		)
+		.into_rpc(),
+	)?;
 
 	// Extend this RPC with a custom API by using the following syntax.
 	// `YourRpcStruct` should have a reference to a client, which is needed
@@ -52,3 +78,82 @@ where
 
 	Ok(module)
 }
+
+/// Signer of a signed transaction of the runtime storing data, and the size of the data.
+fn decode_store(extrinsic: &<Block as BlockT>::Extrinsic) -> Option<(AccountId, u32)> {
+	use pallet_transaction_storage::Call;
+	let extrinsic = UncheckedExtrinsic::decode(&mut &extrinsic.encode()[..]).ok()?;
+	let (MultiAddress::Id(signer), ..) = extrinsic.signature? else { return None };
+	let size = match extrinsic.function {
+		RuntimeCall::TransactionStorage(Call::store { data, .. }) => data.len(),
+		RuntimeCall::TransactionStorage(Call::store_compressed { compressed_data, .. }) =>
+			compressed_data.len(),
+		_ => return None,
+	};
+	Some((signer, size as u32))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use polkadot_bulletin_chain_runtime::{Runtime, SignedExtra};
+	use sp_core::sr25519;
+	use sp_keyring::AccountKeyring;
+	use sp_runtime::{generic::Era, MultiSignature, OpaqueExtrinsic};
+
+	fn extrinsic(signer: Option<AccountKeyring>, call: RuntimeCall) -> OpaqueExtrinsic {
+		let extrinsic = match signer {
+			Some(signer) => {
+				let extra: SignedExtra = (
+					frame_system::CheckNonZeroSender::<Runtime>::new(),
+					frame_system::CheckSpecVersion::<Runtime>::new(),
+					frame_system::CheckTxVersion::<Runtime>::new(),
+					frame_system::CheckGenesis::<Runtime>::new(),
+					frame_system::CheckEra::<Runtime>::from(Era::Immortal),
+					frame_system::CheckNonce::<Runtime>::from(0),
+					frame_system::CheckWeight::<Runtime>::new(),
+					pallet_transaction_storage::TrackStoreFailures::<Runtime>::new(),
+				);
+				// Transactions are not checked when scanning the pool
+				let signature = MultiSignature::Sr25519(sr25519::Signature::from_raw([0; 64]));
+				UncheckedExtrinsic::new_signed(
+					call,
+					signer.to_account_id().into(),
+					signature,
+					extra,
+				)
+			},
+			None => UncheckedExtrinsic::new_unsigned(call),
+		};
+		OpaqueExtrinsic::from_bytes(&extrinsic.encode()).unwrap()
+	}
+
+	#[test]
+	fn decodes_signed_stores() {
+		let store = |data: Vec<u8>| {
+			RuntimeCall::TransactionStorage(pallet_transaction_storage::Call::store {
+				data,
+				namespace: None,
+			})
+		};
+		let alice = AccountKeyring::Alice;
+		assert_eq!(
+			decode_store(&extrinsic(Some(alice), store(vec![1; 1000]))),
+			Some((alice.to_account_id(), 1000))
+		);
+		let compressed =
+			RuntimeCall::TransactionStorage(pallet_transaction_storage::Call::store_compressed {
+				compressed_data: vec![1; 100],
+				uncompressed_hash: [0; 32],
+				uncompressed_len: 1000,
+			});
+		assert_eq!(
+			decode_store(&extrinsic(Some(alice), compressed)),
+			Some((alice.to_account_id(), 100))
+		);
+		// Preimage stores have no signer whose authorizations they would use
+		assert_eq!(decode_store(&extrinsic(None, store(vec![1; 1000]))), None);
+		let remark = RuntimeCall::System(frame_system::Call::remark { remark: vec![1; 1000] });
+		assert_eq!(decode_store(&extrinsic(Some(alice), remark)), None);
+	}
+}
@@ -0,0 +1,537 @@
+//! RPC methods serving chunks of indexed data, with proofs that can be checked against the chunk
+//! root stored on chain using `pallet_transaction_storage::verify_chunk`, without trusting the
+//! serving node, listing the content stored under a namespace or all the content stored,
+//! reporting when stored content expires, projecting the unused authorizations of an account
+//! once its transactions waiting in the pool are included, listing the authorizations an
+//! account used in its last authorization periods, and reporting when stored content must be
+//! renewed by.
+
+use crate::chunk_trie_cache::ChunkTrieCache;
+use codec::Codec;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use pallet_transaction_storage::{
+	runtime_api::TransactionStorageApi as RuntimeApi, AuthorizationExtent, ContentRecord,
+	Namespace, PeriodUsage, RenewalDeadline, MAX_STORED_CONTENT_PAGE,
+};
+use sc_client_api::BlockBackend;
+use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, H256};
+use sp_runtime::traits::{Block as BlockT, NumberFor, Saturating};
+use std::sync::Arc;
+
+/// The requested content is not indexed by this node.
+const CONTENT_NOT_FOUND: i32 = 1;
+/// The chunk could not be proved, e.g. because the chunk index is out of range.
+const PROOF_FAILED: i32 = 2;
+/// The namespace is not 8 bytes long.
+const INVALID_NAMESPACE: i32 = 3;
+/// The runtime API call failed.
+const RUNTIME_ERROR: i32 = 4;
+/// More content hashes were given than `MAX_RENEWAL_DEADLINES`.
+const TOO_MANY_HASHES: i32 = 5;
+
+/// Maximum number of content hashes returned by `transactionStorage_namespaceContent`.
+pub const MAX_NAMESPACE_PAGE: u32 = 1000;
+
+/// Maximum number of content hashes `transactionStorage_renewalDeadlines` takes.
+pub const MAX_RENEWAL_DEADLINES: usize = 1000;
+
+/// Maximum number of ready transactions of the pool scanned by
+/// `transactionStorage_projectedUnusedExtent`.
+pub const MAX_POOL_SCAN: usize = 4096;
+
+/// Returns the signer of a transaction storing data and the size of the data, or `None` for any
+/// other transaction. Decoding transactions is specific to the runtime.
+pub type StoreDecoder<Extrinsic, AccountId> =
+	Box<dyn Fn(&Extrinsic) -> Option<(AccountId, u32)> + Send + Sync>;
+
+/// A chunk of indexed data with its proof.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkProof {
+	/// The chunk.
+	pub chunk: Bytes,
+	/// Nodes of the chunk trie proving the chunk.
+	pub proof: Vec<Bytes>,
+	/// Root of the chunk trie of the data, as emitted in the `Stored` event.
+	pub chunk_root: H256,
+}
+
+/// Content currently stored.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredContent<BlockNumber> {
+	/// Plain hash of the content.
+	pub content_hash: H256,
+	/// Size of the content in bytes.
+	pub size: u32,
+	/// Block the content was last stored or renewed in.
+	pub block: BlockNumber,
+	/// Index of the content's transaction in `block`.
+	pub index: u32,
+	/// Last block of the storage period of the content.
+	pub expires_at: BlockNumber,
+}
+
+impl<BlockNumber> From<ContentRecord<BlockNumber>> for StoredContent<BlockNumber> {
+	fn from(record: ContentRecord<BlockNumber>) -> Self {
+		Self {
+			content_hash: record.content_hash,
+			size: record.size,
+			block: record.block,
+			index: record.index.into(),
+			expires_at: record.expires_at,
+		}
+	}
+}
+
+/// Expiry of stored content, as of the best block and as of the finalized block. During long
+/// finality lag, renewals included only in unfinalized blocks may be reverted, so renewals should
+/// be scheduled against the finalized figures.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentStatus<BlockNumber> {
+	/// Last block of the storage period of the content, as of the best block.
+	pub expires_at: BlockNumber,
+	/// Blocks from the best block to `expires_at`.
+	pub blocks_remaining: BlockNumber,
+	/// Last block of the storage period of the content as of the finalized block, or `None` if
+	/// the content is not stored as of the finalized block.
+	pub expires_at_finalized: Option<BlockNumber>,
+	/// Blocks from the finalized block to `expires_at_finalized`.
+	pub blocks_remaining_finalized: Option<BlockNumber>,
+	/// Number of times the storage proof of a block checked a chunk of the content during its
+	/// lifetime, as of the best block, or `None` if it is not counted, e.g. for content stored
+	/// before proof checks were counted and not stored or renewed since.
+	pub times_challenged: Option<u32>,
+}
+
+impl<BlockNumber: Copy + Saturating> ContentStatus<BlockNumber> {
+	/// Status of content expiring at `expires_at` as of the best block `best`, and at
+	/// `expires_at_finalized` as of the finalized block `finalized`.
+	fn new(
+		best: BlockNumber,
+		expires_at: BlockNumber,
+		finalized: BlockNumber,
+		expires_at_finalized: Option<BlockNumber>,
+	) -> Self {
+		Self {
+			expires_at,
+			blocks_remaining: expires_at.saturating_sub(best),
+			expires_at_finalized,
+			blocks_remaining_finalized: expires_at_finalized
+				.map(|expires_at| expires_at.saturating_sub(finalized)),
+			times_challenged: None,
+		}
+	}
+}
+
+/// Unused authorizations of an account, as of the best block and once the account's transactions
+/// storing data that are ready in the pool are included.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectedExtent {
+	/// Unused transactions as of the best block.
+	pub transactions: u32,
+	/// Unused bytes as of the best block.
+	pub bytes: u64,
+	/// Number of the account's transactions storing data that are ready in the pool.
+	pub pending_transactions: u32,
+	/// Bytes stored by the account's transactions that are ready in the pool.
+	pub pending_bytes: u64,
+	/// Unused transactions once the pending ones are included.
+	pub projected_transactions: u32,
+	/// Unused bytes once the pending ones are included.
+	pub projected_bytes: u64,
+	/// Whether the pool had more than `MAX_POOL_SCAN` ready transactions, in which case the
+	/// pending figures only cover the first ones.
+	pub truncated: bool,
+}
+
+impl ProjectedExtent {
+	/// Projects the `unused` extent of an account given the sizes of its `pending` stores.
+	fn new(unused: AuthorizationExtent, pending: impl IntoIterator<Item = u32>) -> Self {
+		let pending = pending.into_iter().fold(AuthorizationExtent::default(), |total, size| {
+			total.saturating_add(AuthorizationExtent::transaction(size.into()))
+		});
+		let projected = unused.saturating_sub(pending);
+		Self {
+			transactions: unused.transactions,
+			bytes: unused.bytes,
+			pending_transactions: pending.transactions,
+			pending_bytes: pending.bytes,
+			projected_transactions: projected.transactions,
+			projected_bytes: projected.bytes,
+			truncated: false,
+		}
+	}
+}
+
+/// Authorizations used by an account in an authorization period.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UsedAuthorizations<BlockNumber> {
+	/// Index of the authorization period: its first block divided by the authorization period.
+	pub period: BlockNumber,
+	/// Transactions used by stores, renewals and proof requests.
+	pub transactions: u32,
+	/// Bytes stored or renewed.
+	pub bytes: u64,
+	/// Transactions used by renewals.
+	pub renewals: u32,
+}
+
+impl<BlockNumber> From<PeriodUsage<BlockNumber>> for UsedAuthorizations<BlockNumber> {
+	fn from(usage: PeriodUsage<BlockNumber>) -> Self {
+		Self {
+			period: usage.period,
+			transactions: usage.transactions,
+			bytes: usage.bytes,
+			renewals: usage.renewals,
+		}
+	}
+}
+
+/// When stored content must be renewed by.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RenewalSchedule<BlockNumber> {
+	/// Block the content was last stored or renewed in, to renew it with.
+	pub block: BlockNumber,
+	/// Index of the content's transaction in `block`, to renew it with.
+	pub index: u32,
+	/// Last block in which renewing the content succeeds.
+	pub last_block: BlockNumber,
+	/// Block from which renewing the content is recommended.
+	pub recommended_block: BlockNumber,
+}
+
+impl<BlockNumber> From<RenewalDeadline<BlockNumber>> for RenewalSchedule<BlockNumber> {
+	fn from(deadline: RenewalDeadline<BlockNumber>) -> Self {
+		Self {
+			block: deadline.block,
+			index: deadline.index.into(),
+			last_block: deadline.last_block,
+			recommended_block: deadline.recommended_block,
+		}
+	}
+}
+
+/// Transaction storage RPC methods.
+#[rpc(server)]
+pub trait TransactionStorageApi<Hash, BlockNumber, AccountId> {
+	/// Returns the chunk at `chunk_index` of the indexed data with the given content hash, with
+	/// its proof.
+	#[method(name = "transactionStorage_getChunk")]
+	fn get_chunk(&self, content_hash: Hash, chunk_index: u32) -> RpcResult<ChunkProof>;
+
+	/// Returns up to `limit` hashes of the content stored under the 8-byte `namespace` as of
+	/// block `at`, ordered by hash and starting after `start_after` if given. `limit` is capped
+	/// at `MAX_NAMESPACE_PAGE`.
+	#[method(name = "transactionStorage_namespaceContent")]
+	fn namespace_content(
+		&self,
+		namespace: Bytes,
+		start_after: Option<H256>,
+		limit: u32,
+		at: Option<Hash>,
+	) -> RpcResult<Vec<H256>>;
+
+	/// Returns all the content stored as of block `at`, fetched from the runtime one page at a
+	/// time.
+	#[method(name = "transactionStorage_storedContent")]
+	fn stored_content(&self, at: Option<Hash>) -> RpcResult<Vec<StoredContent<BlockNumber>>>;
+
+	/// Returns when the content with the given hash expires, as of the best block and as of the
+	/// finalized block, and how many times its storage was proven, or `None` if it is not stored
+	/// as of the best block.
+	#[method(name = "transactionStorage_status")]
+	fn status(&self, content_hash: H256) -> RpcResult<Option<ContentStatus<BlockNumber>>>;
+
+	/// Returns the unused authorizations of `who` as of the best block, and what remains of them
+	/// once its transactions storing data that are ready in the pool are included. Stores with a
+	/// preimage authorization may not use the account's authorizations, so the projection is a
+	/// lower bound. Renewals in the pool are not counted.
+	#[method(name = "transactionStorage_projectedUnusedExtent")]
+	fn projected_unused_extent(&self, who: AccountId) -> RpcResult<ProjectedExtent>;
+
+	/// Returns the authorizations used by `who` in each of the last authorization periods it
+	/// used any in as of block `at`, oldest first.
+	#[method(name = "transactionStorage_accountUsageHistory")]
+	fn account_usage_history(
+		&self,
+		who: AccountId,
+		at: Option<Hash>,
+	) -> RpcResult<Vec<UsedAuthorizations<BlockNumber>>>;
+
+	/// Returns, for each of the given content hashes, the block and index to renew the content
+	/// with, the last block in which renewing it succeeds and the block from which renewing it is
+	/// recommended, or `None` if it is not stored. As renewals in unfinalized blocks may be
+	/// reverted, `at` defaults to the finalized block. At most `MAX_RENEWAL_DEADLINES` hashes can
+	/// be given.
+	#[method(name = "transactionStorage_renewalDeadlines")]
+	fn renewal_deadlines(
+		&self,
+		hashes: Vec<H256>,
+		at: Option<Hash>,
+	) -> RpcResult<Vec<Option<RenewalSchedule<BlockNumber>>>>;
+}
+
+/// Implements the `TransactionStorageApi` RPC trait, proving chunks with the tries of the
+/// `ChunkTrieCache` and finding pending stores in the transaction pool with a `StoreDecoder`.
+pub struct TransactionStorage<C, P, Block: BlockT, AccountId> {
+	client: Arc<C>,
+	pool: Arc<P>,
+	cache: Arc<ChunkTrieCache>,
+	decode_store: StoreDecoder<Block::Extrinsic, AccountId>,
+}
+
+impl<C, P, Block: BlockT, AccountId> TransactionStorage<C, P, Block, AccountId> {
+	/// Creates a new instance of the `TransactionStorage` RPC handler.
+	pub fn new(
+		client: Arc<C>,
+		pool: Arc<P>,
+		cache: Arc<ChunkTrieCache>,
+		decode_store: StoreDecoder<Block::Extrinsic, AccountId>,
+	) -> Self {
+		Self { client, pool, cache, decode_store }
+	}
+}
+
+impl<C, P, Block, AccountId> TransactionStorageApiServer<Block::Hash, NumberFor<Block>, AccountId>
+	for TransactionStorage<C, P, Block, AccountId>
+where
+	Block: BlockT,
+	C: BlockBackend<Block> + HeaderBackend<Block> + ProvideRuntimeApi<Block>,
+	C: Send + Sync + 'static,
+	C::Api: RuntimeApi<Block, AccountId, NumberFor<Block>>,
+	P: TransactionPool<Block = Block> + 'static,
+	AccountId: Codec + Clone + PartialEq + Send + Sync + DeserializeOwned + 'static,
+{
+	fn get_chunk(&self, content_hash: Block::Hash, chunk_index: u32) -> RpcResult<ChunkProof> {
+		let data = self
+			.client
+			.indexed_transaction(content_hash)
+			.map_err(|e| error(CONTENT_NOT_FOUND, e))?
+			.ok_or_else(|| error(CONTENT_NOT_FOUND, "Content is not indexed"))?;
+		let (chunk_root, proof) = self
+			.cache
+			.prove_chunk(&data, chunk_index.into())
+			.map_err(|e| error(PROOF_FAILED, e))?;
+		Ok(ChunkProof {
+			chunk: proof.chunk.into(),
+			proof: proof.proof.into_iter().map(Into::into).collect(),
+			chunk_root,
+		})
+	}
+
+	fn namespace_content(
+		&self,
+		namespace: Bytes,
+		start_after: Option<H256>,
+		limit: u32,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<H256>> {
+		let namespace = Namespace::try_from(&namespace[..])
+			.map_err(|_| error(INVALID_NAMESPACE, "Namespace must be 8 bytes long"))?;
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let hashes = self
+			.client
+			.runtime_api()
+			.namespace_content(
+				at,
+				namespace,
+				start_after.map(Into::into),
+				limit.min(MAX_NAMESPACE_PAGE),
+			)
+			.map_err(|e| error(RUNTIME_ERROR, e))?;
+		Ok(hashes.into_iter().map(Into::into).collect())
+	}
+
+	fn stored_content(
+		&self,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<StoredContent<NumberFor<Block>>>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		Ok(self.stored_records(at)?.into_iter().map(Into::into).collect())
+	}
+
+	fn status(&self, content_hash: H256) -> RpcResult<Option<ContentStatus<NumberFor<Block>>>> {
+		let info = self.client.info();
+		let Some(expires_at) = self.expires_at(info.best_hash, content_hash)? else {
+			return Ok(None)
+		};
+		let expires_at_finalized = self.expires_at(info.finalized_hash, content_hash)?;
+		let mut status = ContentStatus::new(
+			info.best_number,
+			expires_at,
+			info.finalized_number,
+			expires_at_finalized,
+		);
+		status.times_challenged = self
+			.client
+			.runtime_api()
+			.times_challenged(info.best_hash, content_hash.into())
+			.map_err(|e| error(RUNTIME_ERROR, e))?;
+		Ok(Some(status))
+	}
+
+	fn projected_unused_extent(&self, who: AccountId) -> RpcResult<ProjectedExtent> {
+		// Ready transactions were validated against the best block
+		let best = self.client.info().best_hash;
+		let unused = self
+			.client
+			.runtime_api()
+			.unused_account_authorization_extent(best, who.clone())
+			.map_err(|e| error(RUNTIME_ERROR, e))?;
+		let mut ready = self.pool.ready();
+		let pending: Vec<u32> = ready
+			.by_ref()
+			.take(MAX_POOL_SCAN)
+			.filter_map(|transaction| (self.decode_store)(transaction.data()))
+			.filter(|(signer, _)| *signer == who)
+			.map(|(_, size)| size)
+			.collect();
+		let mut projected = ProjectedExtent::new(unused, pending);
+		projected.truncated = ready.next().is_some();
+		Ok(projected)
+	}
+
+	fn account_usage_history(
+		&self,
+		who: AccountId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<UsedAuthorizations<NumberFor<Block>>>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let history = self
+			.client
+			.runtime_api()
+			.account_usage_history(at, who)
+			.map_err(|e| error(RUNTIME_ERROR, e))?;
+		Ok(history.into_iter().map(Into::into).collect())
+	}
+
+	fn renewal_deadlines(
+		&self,
+		hashes: Vec<H256>,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<Option<RenewalSchedule<NumberFor<Block>>>>> {
+		if hashes.len() > MAX_RENEWAL_DEADLINES {
+			return Err(error(
+				TOO_MANY_HASHES,
+				format!("At most {MAX_RENEWAL_DEADLINES} content hashes can be given"),
+			))
+		}
+		let at = at.unwrap_or_else(|| self.client.info().finalized_hash);
+		let deadlines = self
+			.client
+			.runtime_api()
+			.renewal_deadlines(at, hashes.into_iter().map(Into::into).collect())
+			.map_err(|e| error(RUNTIME_ERROR, e))?;
+		Ok(deadlines.into_iter().map(|deadline| deadline.map(Into::into)).collect())
+	}
+}
+
+impl<C, P, Block, AccountId> TransactionStorage<C, P, Block, AccountId>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: RuntimeApi<Block, AccountId, NumberFor<Block>>,
+	AccountId: Codec,
+{
+	/// Fetches the records of all the content stored as of block `at`, one page at a time.
+	fn stored_records(&self, at: Block::Hash) -> RpcResult<Vec<ContentRecord<NumberFor<Block>>>> {
+		// All pages are fetched at the same block, so that they are consistent
+		let api = self.client.runtime_api();
+		let mut content = Vec::new();
+		let mut start_key = None;
+		loop {
+			let (records, next_key) = api
+				.stored_content(at, start_key, MAX_STORED_CONTENT_PAGE)
+				.map_err(|e| error(RUNTIME_ERROR, e))?;
+			content.extend(records);
+			match next_key {
+				Some(key) => start_key = Some(key),
+				None => return Ok(content),
+			}
+		}
+	}
+
+	/// Last block of the storage period of the content with the given hash as of block `at`, or
+	/// `None` if it is not stored. Renewed content is listed once per live renewal, so the latest
+	/// expiry is taken.
+	fn expires_at(
+		&self,
+		at: Block::Hash,
+		content_hash: H256,
+	) -> RpcResult<Option<NumberFor<Block>>> {
+		Ok(latest_expiry(self.stored_records(at)?, content_hash))
+	}
+}
+
+/// Latest expiry of the content with the given hash among `records`.
+fn latest_expiry<BlockNumber: Ord>(
+	records: Vec<ContentRecord<BlockNumber>>,
+	content_hash: H256,
+) -> Option<BlockNumber> {
+	records
+		.into_iter()
+		.filter(|record| record.content_hash == content_hash)
+		.map(|record| record.expires_at)
+		.max()
+}
+
+fn error(code: i32, message: impl ToString) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(code, message.to_string(), None::<()>)).into()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pallet_transaction_storage::TxIndex;
+
+	fn record(content_hash: H256, block: u32) -> ContentRecord<u32> {
+		ContentRecord { content_hash, size: 1, block, index: TxIndex(0), expires_at: block + 10 }
+	}
+
+	#[test]
+	fn latest_expiry_takes_the_last_renewal() {
+		let (hash, other) = (H256::repeat_byte(1), H256::repeat_byte(2));
+		let records = vec![record(hash, 1), record(other, 8), record(hash, 5)];
+		assert_eq!(latest_expiry(records.clone(), hash), Some(15));
+		assert_eq!(latest_expiry(records, H256::repeat_byte(3)), None);
+	}
+
+	#[test]
+	fn projected_extent_subtracts_pending_stores() {
+		let unused = AuthorizationExtent { transactions: 3, bytes: 5000 };
+		let projected = ProjectedExtent::new(unused, [1000, 2500]);
+		assert_eq!((projected.pending_transactions, projected.pending_bytes), (2, 3500));
+		assert_eq!((projected.projected_transactions, projected.projected_bytes), (1, 1500));
+		assert_eq!((projected.transactions, projected.bytes), (3, 5000));
+		// More is pending than is authorized
+		let projected = ProjectedExtent::new(unused, [4000, 4000, 4000, 4000]);
+		assert_eq!((projected.projected_transactions, projected.projected_bytes), (0, 0));
+		assert_eq!(ProjectedExtent::new(unused, []).projected_bytes, 5000);
+	}
+
+	#[test]
+	fn status_counts_down_from_both_heads() {
+		// Renewed in unfinalized block 5: the finalized state still expires the content at 11
+		let status = ContentStatus::new(9, 15, 4, Some(11));
+		assert_eq!(status.blocks_remaining, 6);
+		assert_eq!(status.blocks_remaining_finalized, Some(7));
+		// Already expired as of the finalized block
+		assert_eq!(ContentStatus::new(20, 25, 13, Some(11)).blocks_remaining_finalized, Some(0));
+		// Stored in an unfinalized block only
+		let status = ContentStatus::new(9, 15, 4, None);
+		assert_eq!((status.expires_at_finalized, status.blocks_remaining_finalized), (None, None));
+	}
+}
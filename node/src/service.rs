@@ -1,5 +1,9 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
+use crate::{
+	chunk_trie_cache::ChunkTrieCache,
+	retention::{self, RetentionMonitor},
+};
 use futures::FutureExt;
 use polkadot_bulletin_chain_runtime as runtime;
 use runtime::{opaque::Block, RuntimeApi};
@@ -38,6 +42,9 @@ pub(crate) type FullClient =
 type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
 
+/// Maximal size of the indexed data whose chunk tries are cached to build storage proofs.
+const CHUNK_TRIE_CACHE_SIZE: usize = 256 * 1024 * 1024;
+
 #[allow(clippy::type_complexity)]
 pub fn new_partial(
 	config: &Configuration,
@@ -140,8 +147,9 @@ pub fn new_partial(
 	})
 }
 
-/// Builds a new service for a full client.
-pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
+/// Builds a new service for a full client. The blocks it keeps are checked against the storage
+/// period of the runtime, requiring `retention_margin` more blocks.
+pub fn new_full(config: Configuration, retention_margin: u32) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -209,13 +217,32 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 
+	task_manager.spawn_handle().spawn(
+		"transaction-retention",
+		None,
+		retention::watch_storage_period::<_, _, runtime::AccountId>(
+			client.clone(),
+			RetentionMonitor::new(config.blocks_pruning, retention_margin),
+		),
+	);
+
+	let chunk_trie_cache = Arc::new(
+		ChunkTrieCache::new(CHUNK_TRIE_CACHE_SIZE, prometheus_registry.as_ref())
+			.map_err(|e| ServiceError::Other(e.to_string()))?,
+	);
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
+		let chunk_trie_cache = chunk_trie_cache.clone();
 
 		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				chunk_trie_cache: chunk_trie_cache.clone(),
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
@@ -244,6 +271,12 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 			telemetry.as_ref().map(|x| x.handle()),
 		);
 
+		task_manager.spawn_handle().spawn_blocking(
+			"chunk-trie-prefetch",
+			None,
+			chunk_trie_cache.clone().prefetch(client.clone()),
+		);
+
 		let client_clone = client.clone();
 		let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
 		let aura = sc_consensus_aura::start_aura::<AuraPair, _, _, _, _, _, _, _, _, _, _>(
@@ -255,6 +288,7 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 				proposer_factory,
 				create_inherent_data_providers: move |parent, ()| {
 					let client_clone = client_clone.clone();
+					let chunk_trie_cache = chunk_trie_cache.clone();
 					async move {
 						let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
 
@@ -265,12 +299,14 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 							);
 
 						let storage_proof =
-							sp_transaction_storage_proof::registration::new_data_provider(
+							chunk_trie_cache.new_data_provider(&*client_clone, &parent)?;
+						let challenge_proofs = chunk_trie_cache
+							.new_challenge_data_provider::<_, _, runtime::AccountId>(
 								&*client_clone,
 								&parent,
 							)?;
 
-						Ok((slot, timestamp, storage_proof))
+						Ok((slot, timestamp, storage_proof, challenge_proofs))
 					}
 				},
 				force_authoring,
@@ -0,0 +1,43 @@
+//! Round-trips the chunk proofs served by `transactionStorage_getChunk` through the client side
+//! verification.
+
+use pallet_transaction_storage::{verify_chunk, ChunkIndex};
+use polkadot_bulletin_chain::chunk_trie_cache::ChunkTrieCache;
+use sp_transaction_storage_proof::CHUNK_SIZE;
+
+fn data() -> Vec<u8> {
+	(0..CHUNK_SIZE * 5 + 100).map(|i| (i * 31) as u8).collect()
+}
+
+#[test]
+fn served_chunks_verify_against_chunk_root() {
+	let data = data();
+	let chunks: Vec<_> = data.chunks(CHUNK_SIZE).map(|c| c.to_vec()).collect();
+	// The chunk root stored on chain
+	let chunk_root =
+		sp_io::trie::blake2_256_ordered_root(chunks.clone(), sp_runtime::StateVersion::V1);
+	let cache = ChunkTrieCache::new(data.len(), None).unwrap();
+	for (index, expected) in chunks.iter().enumerate() {
+		let index = ChunkIndex(index as u32);
+		let (root, proof) = cache.prove_chunk(&data, index).unwrap();
+		assert_eq!(root, chunk_root);
+		assert_eq!(&proof.chunk, expected);
+		assert!(verify_chunk(chunk_root, index, &proof.chunk, &proof.proof));
+	}
+	assert!(cache.prove_chunk(&data, ChunkIndex(chunks.len() as u32)).is_err());
+}
+
+#[test]
+fn tampered_chunks_do_not_verify() {
+	let data = data();
+	let cache = ChunkTrieCache::new(data.len(), None).unwrap();
+	let (chunk_root, proof) = cache.prove_chunk(&data, ChunkIndex(2)).unwrap();
+	assert!(verify_chunk(chunk_root, ChunkIndex(2), &proof.chunk, &proof.proof));
+
+	let mut chunk = proof.chunk.clone();
+	chunk[0] ^= 1;
+	assert!(!verify_chunk(chunk_root, ChunkIndex(2), &chunk, &proof.proof));
+	assert!(!verify_chunk(chunk_root, ChunkIndex(3), &proof.chunk, &proof.proof));
+	assert!(!verify_chunk(Default::default(), ChunkIndex(2), &proof.chunk, &proof.proof));
+	assert!(!verify_chunk(chunk_root, ChunkIndex(2), &proof.chunk, &proof.proof[1..]));
+}
@@ -0,0 +1,89 @@
+//! Walks through the lifecycle of stored data on the mock runtime of the pallet's tests:
+//! authorize an account, store data, wait, renew it and prove its availability. The `store` and
+//! `renew` calls are encoded and the `Stored` event decoded with the client helpers of
+//! `transaction-storage-primitives`, as a client of a live chain would.
+//!
+//! Run with `cargo run -p pallet-transaction-storage --features mock --example lifecycle`.
+
+use codec::{Decode, Encode};
+use frame_support::traits::PalletInfoAccess;
+use pallet_transaction_storage::{
+	mock::*,
+	primitives::{calls, events, Upload},
+	test_utils::StoredData,
+	Call, ProofStatus,
+};
+use sp_runtime::traits::Dispatchable;
+
+/// Decodes `call` as encoded by a client and dispatches it from `origin`.
+fn submit(origin: RuntimeOrigin, call: Vec<u8>) {
+	let call = RuntimeCall::decode(&mut &call[..]).expect("client calls decode as runtime calls");
+	call.dispatch(origin).expect("call succeeds");
+	next_extrinsic();
+}
+
+/// Finds the last event of the pallet with the given index, decoded as a client would.
+fn last_event<E: Decode>(event_index: u8) -> E {
+	System::events()
+		.into_iter()
+		.rev()
+		.find_map(|record| match record.event {
+			RuntimeEvent::TransactionStorage(event) => match &event.encode()[..] {
+				[index, fields @ ..] if *index == event_index => E::decode(&mut &fields[..]).ok(),
+				_ => None,
+			},
+			_ => None,
+		})
+		.expect("the call emitted the event")
+}
+
+fn main() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let pallet_index = TransactionStorage::index() as u8;
+		let who = 1;
+		let data = b"Hello, Bulletin!".repeat(100);
+		let upload = Upload::new(&data);
+		println!("Uploading {} bytes in {} chunks", upload.size, upload.chunk_count);
+
+		// Authorize two transactions of the data: the store and the renewal
+		let authorize = Call::<Test>::authorize_account {
+			who,
+			transactions: 2,
+			bytes: 2 * upload.size as u64,
+			allowed_hashes: None,
+		};
+		RuntimeCall::from(authorize).dispatch(RuntimeOrigin::root()).unwrap();
+		println!("Authorized account {who}");
+
+		let store = calls::Store { data: data.clone(), namespace: None };
+		submit(RuntimeOrigin::signed(who), (pallet_index, calls::Store::INDEX, store).encode());
+		let stored: events::Stored = last_event(events::Stored::INDEX);
+		assert!(upload.matches(&stored));
+		println!("Stored as transaction {} of block 1", stored.index);
+		let mut proofs = StoredData::default();
+		proofs.insert(data);
+
+		// Renew before the storage period of the data is over
+		run_to_block(5, || proofs.proof::<Test>());
+		let renew = calls::Renew {
+			block: 1,
+			index: stored.index,
+			expected_hash: Some(upload.content_hash),
+		};
+		submit(
+			RuntimeOrigin::signed(who),
+			(pallet_index, calls::Renew::<u64>::INDEX, renew).encode(),
+		);
+		let renewed: events::Renewed = last_event(events::Renewed::INDEX);
+		assert_eq!(renewed.chunk_root, upload.chunk_root);
+		println!("Renewed as transaction {} of block 5", renewed.index);
+
+		// Block authors prove that the data is available when its storage period is over
+		run_to_block(16, || proofs.proof::<Test>());
+		for block in [1, 5] {
+			assert_eq!(TransactionStorage::proof_status(block), ProofStatus::Proven);
+			println!("Availability of the data stored in block {block} proven");
+		}
+	});
+}
@@ -20,91 +20,23 @@
 #![cfg(feature = "runtime-benchmarks")]
 
 use super::*;
-use frame_benchmarking::v1::{benchmarks, whitelisted_caller};
-use frame_support::traits::{Currency, Get, OnFinalize, OnInitialize};
+use frame_benchmarking::v2::*;
+use frame_support::{
+	traits::{Get, OnFinalize, OnInitialize, QueryPreimage, StorePreimage},
+	BoundedVec,
+};
 use frame_system::{pallet_prelude::BlockNumberFor, EventRecord, Pallet as System, RawOrigin};
-use sp_runtime::traits::{Bounded, One, Zero};
+use sp_runtime::traits::One;
 use sp_std::*;
 use sp_transaction_storage_proof::TransactionStorageProof;
 
-use crate::Pallet as TransactionStorage;
-
-// Proof generated from max size storage:
-// ```
-// let mut transactions = Vec::new();
-// let tx_size = DEFAULT_MAX_TRANSACTION_SIZE;
-// for _ in 0..DEFAULT_MAX_BLOCK_TRANSACTIONS {
-//   transactions.push(vec![0; tx_size]);
-// }
-// let hash = vec![0; 32];
-// build_proof(hash.as_slice(), transactions).unwrap().encode()
-// ```
-// while hardforcing target chunk key in `build_proof` to [22, 21, 1, 0].
-const PROOF: &str = "\
-	0104000000000000000000000000000000000000000000000000000000000000000000000000\
-	0000000000000000000000000000000000000000000000000000000000000000000000000000\
-	0000000000000000000000000000000000000000000000000000000000000000000000000000\
-	0000000000000000000000000000000000000000000000000000000000000000000000000000\
-	0000000000000000000000000000000000000000000000000000000000000000000000000000\
-	0000000000000000000000000000000000000000000000000000000000000000000000000000\
-	00000000000000000000000000000000000000000000000000000000000014cd0780ffff8030\
-	2eb0a6d2f63b834d15f1e729d1c1004657e3048cf206d697eeb153f61a30ba0080302eb0a6d2\
-	f63b834d15f1e729d1c1004657e3048cf206d697eeb153f61a30ba80302eb0a6d2f63b834d15\
-	f1e729d1c1004657e3048cf206d697eeb153f61a30ba80302eb0a6d2f63b834d15f1e729d1c1\
-	004657e3048cf206d697eeb153f61a30ba80302eb0a6d2f63b834d15f1e729d1c1004657e304\
-	8cf206d697eeb153f61a30ba80302eb0a6d2f63b834d15f1e729d1c1004657e3048cf206d697\
-	eeb153f61a30ba80302eb0a6d2f63b834d15f1e729d1c1004657e3048cf206d697eeb153f61a\
-	30ba80302eb0a6d2f63b834d15f1e729d1c1004657e3048cf206d697eeb153f61a30ba80302e\
-	b0a6d2f63b834d15f1e729d1c1004657e3048cf206d697eeb153f61a30ba80302eb0a6d2f63b\
-	834d15f1e729d1c1004657e3048cf206d697eeb153f61a30ba80302eb0a6d2f63b834d15f1e7\
-	29d1c1004657e3048cf206d697eeb153f61a30ba80302eb0a6d2f63b834d15f1e729d1c10046\
-	57e3048cf206d697eeb153f61a30ba80302eb0a6d2f63b834d15f1e729d1c1004657e3048cf2\
-	06d697eeb153f61a30ba80302eb0a6d2f63b834d15f1e729d1c1004657e3048cf206d697eeb1\
-	53f61a30ba80302eb0a6d2f63b834d15f1e729d1c1004657e3048cf206d697eeb153f61a30ba\
-	bd058077778010fd81bc1359802f0b871aeb95e4410a8ec92b93af10ea767a2027cf4734e8de\
-	808da338e6b722f7bf2051901bd5bccee5e71d5cf6b1faff338ad7120b0256c28380221ce17f\
-	19117affa96e077905fe48a99723a065969c638593b7d9ab57b538438010fd81bc1359802f0b\
-	871aeb95e4410a8ec92b93af10ea767a2027cf4734e8de808da338e6b722f7bf2051901bd5bc\
-	cee5e71d5cf6b1faff338ad7120b0256c283008010fd81bc1359802f0b871aeb95e4410a8ec9\
-	2b93af10ea767a2027cf4734e8de808da338e6b722f7bf2051901bd5bccee5e71d5cf6b1faff\
-	338ad7120b0256c28380221ce17f19117affa96e077905fe48a99723a065969c638593b7d9ab\
-	57b538438010fd81bc1359802f0b871aeb95e4410a8ec92b93af10ea767a2027cf4734e8de80\
-	8da338e6b722f7bf2051901bd5bccee5e71d5cf6b1faff338ad7120b0256c28380221ce17f19\
-	117affa96e077905fe48a99723a065969c638593b7d9ab57b53843cd0780ffff804509f59593\
-	fd47b1a97189127ba65a5649cfb0346637f9836e155eaf891a939c00804509f59593fd47b1a9\
-	7189127ba65a5649cfb0346637f9836e155eaf891a939c804509f59593fd47b1a97189127ba6\
-	5a5649cfb0346637f9836e155eaf891a939c804509f59593fd47b1a97189127ba65a5649cfb0\
-	346637f9836e155eaf891a939c804509f59593fd47b1a97189127ba65a5649cfb0346637f983\
-	6e155eaf891a939c804509f59593fd47b1a97189127ba65a5649cfb0346637f9836e155eaf89\
-	1a939c804509f59593fd47b1a97189127ba65a5649cfb0346637f9836e155eaf891a939c8045\
-	09f59593fd47b1a97189127ba65a5649cfb0346637f9836e155eaf891a939c804509f59593fd\
-	47b1a97189127ba65a5649cfb0346637f9836e155eaf891a939c804509f59593fd47b1a97189\
-	127ba65a5649cfb0346637f9836e155eaf891a939c804509f59593fd47b1a97189127ba65a56\
-	49cfb0346637f9836e155eaf891a939c804509f59593fd47b1a97189127ba65a5649cfb03466\
-	37f9836e155eaf891a939c804509f59593fd47b1a97189127ba65a5649cfb0346637f9836e15\
-	5eaf891a939c804509f59593fd47b1a97189127ba65a5649cfb0346637f9836e155eaf891a93\
-	9c804509f59593fd47b1a97189127ba65a5649cfb0346637f9836e155eaf891a939ccd0780ff\
-	ff8078916e776c64ccea05e958559f015c082d9d06feafa3610fc44a5b2ef543cb818078916e\
-	776c64ccea05e958559f015c082d9d06feafa3610fc44a5b2ef543cb818078916e776c64ccea\
-	05e958559f015c082d9d06feafa3610fc44a5b2ef543cb818078916e776c64ccea05e958559f\
-	015c082d9d06feafa3610fc44a5b2ef543cb818078916e776c64ccea05e958559f015c082d9d\
-	06feafa3610fc44a5b2ef543cb81008078916e776c64ccea05e958559f015c082d9d06feafa3\
-	610fc44a5b2ef543cb818078916e776c64ccea05e958559f015c082d9d06feafa3610fc44a5b\
-	2ef543cb818078916e776c64ccea05e958559f015c082d9d06feafa3610fc44a5b2ef543cb81\
-	8078916e776c64ccea05e958559f015c082d9d06feafa3610fc44a5b2ef543cb818078916e77\
-	6c64ccea05e958559f015c082d9d06feafa3610fc44a5b2ef543cb818078916e776c64ccea05\
-	e958559f015c082d9d06feafa3610fc44a5b2ef543cb818078916e776c64ccea05e958559f01\
-	5c082d9d06feafa3610fc44a5b2ef543cb818078916e776c64ccea05e958559f015c082d9d06\
-	feafa3610fc44a5b2ef543cb818078916e776c64ccea05e958559f015c082d9d06feafa3610f\
-	c44a5b2ef543cb818078916e776c64ccea05e958559f015c082d9d06feafa3610fc44a5b2ef5\
-	43cb811044010000\
-";
-fn proof() -> Vec<u8> {
-	array_bytes::hex2bytes_unchecked(PROOF)
-}
+use crate::{test_utils::chunk_proof, Pallet as TransactionStorage};
 
-type BalanceOf<T> =
-	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+// Proof of the only chunk of a one byte transaction `[0]`. The chunk trie consists of a single
+// leaf node: header (leaf, 2 nibbles), partial key `encode_index(0)`, compact length and value.
+fn single_byte_proof() -> TransactionStorageProof {
+	TransactionStorageProof { chunk: vec![0], proof: vec![vec![0x42, 0x00, 0x04, 0x00]] }
+}
 
 fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
 	let events = System::<T>::events();
@@ -113,6 +45,90 @@ fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
 	assert_eq!(event, &system_event);
 }
 
+// Block scoped state that every store and the block hooks access. It is in the overlay after the
+// first access in a block, so it should not be charged as a DB access.
+fn whitelist_block_state<T: Config>() {
+	let pallet = <TransactionStorage<T> as PalletInfoAccess>::name().as_bytes();
+	for key in [
+		storage_prefix(pallet, b"CounterForBlockTransactions").to_vec(),
+		BlockChunks::<T>::hashed_key().to_vec(),
+		AuthorizerStores::<T>::hashed_key().to_vec(),
+	] {
+		frame_benchmarking::benchmarking::add_to_whitelist(key.into());
+	}
+}
+
+fn authorizer_origin<T: Config>() -> Result<T::RuntimeOrigin, BenchmarkError> {
+	T::Authorizer::try_successful_origin().map_err(|_| BenchmarkError::Weightless)
+}
+
+fn schedule_removals<T: Config>(n: u32) -> Result<(), BenchmarkError> {
+	let origin = authorizer_origin::<T>()?;
+	for i in 0..n {
+		TransactionStorage::<T>::schedule_removal(
+			origin.clone(),
+			sp_io::hashing::blake2_256(&i.encode()),
+			One::one(),
+			RemovalReason::Takedown,
+		)?;
+	}
+	Ok(())
+}
+
+/// Sets up the worst case for `n` more authorizations: all but the last fit in the current
+/// expiry block, which the last one fills, and they bring the outstanding authorizations to
+/// `MaxOutstandingAuthorizations` and those of the block to `MaxAuthorizationsPerBlock`.
+fn fill_authorizations<T: Config>(n: u32) -> Result<(), BenchmarkError> {
+	let origin = authorizer_origin::<T>()?;
+	let expiries = T::MaxBlockAuthorizationExpiries::get();
+	for i in 0..expiries.saturating_sub(n) {
+		let who: T::AccountId = account("filler", i, 0);
+		TransactionStorage::<T>::authorize_account(origin.clone(), who, 1, 1, None)?;
+	}
+	OutstandingAuthorizations::<T>::put(T::MaxOutstandingAuthorizations::get().saturating_sub(n));
+	BlockAuthorizations::<T>::put(T::MaxAuthorizationsPerBlock::get().saturating_sub(n));
+	Ok(())
+}
+
+/// Sets up the most expensive authorization of `size` bytes of `content_hash` for `who`: neither
+/// the authorization of the preimage nor the restricted, standing and account authorizations of
+/// `who` cover it, so that it falls back to the authorization of its group, and its lifetime usage
+/// is capped. `content_hash` is allowed to `who`, with no extent.
+fn fall_back_to_group<T: Config>(
+	who: &T::AccountId,
+	content_hash: Preimage,
+	size: u32,
+) -> Result<(), BenchmarkError> {
+	let origin = authorizer_origin::<T>()?;
+	let allowed =
+		BoundedVec::try_from(vec![content_hash]).map_err(|_| BenchmarkError::Weightless)?;
+	TransactionStorage::<T>::authorize_account(origin.clone(), who.clone(), 0, 0, Some(allowed))?;
+	TransactionStorage::<T>::set_standing_quota(origin.clone(), who.clone(), 0, 0)?;
+	TransactionStorage::<T>::set_lifetime_cap(origin.clone(), who.clone(), Some(u64::MAX))?;
+	let group = NextGroupId::<T>::get();
+	let members =
+		BoundedVec::try_from(vec![who.clone()]).map_err(|_| BenchmarkError::Weightless)?;
+	TransactionStorage::<T>::create_group(origin.clone(), members)?;
+	TransactionStorage::<T>::authorize_group(origin, group, 1, size.into())?;
+	Ok(())
+}
+
+/// Sets up the most expensive bookkeeping of a renewal of `content_hash`, stored in `block`: the
+/// content is compressed and listed in a namespace, and was owned by an account that no longer
+/// owns it, so that the renewer becomes its owner.
+fn renewal_bookkeeping<T: Config>(content_hash: Preimage, block: BlockNumberFor<T>) {
+	let info = CompressionInfo { uncompressed_hash: [1; 32], uncompressed_len: 1, block };
+	CompressedContent::<T>::insert(content_hash, info);
+	ContentNamespaces::<T>::insert(content_hash, ([0; 8], block));
+	let previous: T::AccountId = account("previous", 0, 0);
+	OwnedByBlock::<T>::insert(block, BoundedVec::truncate_from(vec![(previous, content_hash)]));
+}
+
+fn group_members<T: Config>(n: u32) -> BoundedVec<T::AccountId, T::MaxGroupMembers> {
+	let members: Vec<_> = (0..n).map(|i| account("member", i, 0)).collect();
+	members.try_into().unwrap()
+}
+
 pub fn run_to_block<T: Config>(n: frame_system::pallet_prelude::BlockNumberFor<T>) {
 	while frame_system::Pallet::<T>::block_number() < n {
 		crate::Pallet::<T>::on_finalize(frame_system::Pallet::<T>::block_number());
@@ -125,46 +141,722 @@ pub fn run_to_block<T: Config>(n: frame_system::pallet_prelude::BlockNumberFor<T
 	}
 }
 
-benchmarks! {
-	store {
-		let l in 1 .. T::MaxTransactionSize::get();
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn store(l: Linear<1, { T::MaxTransactionSize::get() }>) -> Result<(), BenchmarkError> {
 		let caller: T::AccountId = whitelisted_caller();
-		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
-	}: _(RawOrigin::Signed(caller.clone()), vec![0u8; l as usize])
-	verify {
-		assert!(!BlockTransactions::<T>::get().is_empty());
-		assert_last_event::<T>(Event::Stored { index: 0 }.into());
+		let data = vec![0u8; l as usize];
+		fall_back_to_group::<T>(&caller, sp_io::hashing::blake2_256(&data), l)?;
+		whitelist_block_state::<T>();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), data, None);
+
+		let chunk_root = BlockTransactions::<T>::get(0).unwrap().chunk_root;
+		assert_last_event::<T>(Event::Stored { index: TxIndex(0), chunk_root }.into());
+		Ok(())
 	}
 
-	renew {
+	#[benchmark]
+	fn store_via_authorizer(
+		l: Linear<1, { T::MaxTransactionSize::get() }>,
+	) -> Result<(), BenchmarkError> {
+		if T::AuthorizerStoresPerBlock::get() == 0 {
+			return Err(BenchmarkError::Weightless)
+		}
+		let origin = authorizer_origin::<T>()?;
+		whitelist_block_state::<T>();
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, vec![0u8; l as usize]);
+
+		assert_last_event::<T>(Event::StoredByAuthorizer { index: TxIndex(0) }.into());
+		Ok(())
+	}
+
+	// Cumulative cost of filling a block with `n` stores, including moving them to `Transactions`
+	// on finalization. The cost per store should not grow with the number of transactions already
+	// in the block.
+	#[benchmark]
+	fn store_block(n: Linear<1, { T::MaxBlockTransactions::get() }>) -> Result<(), BenchmarkError> {
 		let caller: T::AccountId = whitelisted_caller();
-		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
-		TransactionStorage::<T>::store(
-			RawOrigin::Signed(caller.clone()).into(),
-			vec![0u8; T::MaxTransactionSize::get() as usize],
+		TransactionStorage::<T>::authorize_account(
+			authorizer_origin::<T>()?,
+			caller.clone(),
+			n,
+			(n * 4).into(),
+			None,
 		)?;
-		run_to_block::<T>(1u32.into());
-	}: _(RawOrigin::Signed(caller.clone()), BlockNumberFor::<T>::zero(), 0)
-	verify {
-		assert_last_event::<T>(Event::Renewed { index: 0 }.into());
+		// With the allowance of stores by the authorizer used up, stores can fill the block
+		AuthorizerStores::<T>::put(T::AuthorizerStoresPerBlock::get());
+		whitelist_block_state::<T>();
+		let block = frame_system::Pallet::<T>::block_number();
+
+		#[block]
+		{
+			for i in 0..n {
+				// Each store is in its own extrinsic
+				IndexedExtrinsic::<T>::kill();
+				TransactionStorage::<T>::store(
+					RawOrigin::Signed(caller.clone()).into(),
+					i.encode(),
+					None
+				)?;
+			}
+			TransactionStorage::<T>::on_finalize(block);
+		}
+
+		assert_eq!(TransactionStorage::<T>::transaction_roots(block).unwrap().len(), n as usize);
+		Ok(())
 	}
 
-	check_proof_max {
-		run_to_block::<T>(1u32.into());
+	#[benchmark]
+	fn renew(s: Linear<1, { T::MaxBlockTransactions::get() }>) -> Result<(), BenchmarkError> {
 		let caller: T::AccountId = whitelisted_caller();
-		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
-		for _ in 0 .. T::MaxBlockTransactions::get() {
-			TransactionStorage::<T>::store(
-				RawOrigin::Signed(caller.clone()).into(),
-				vec![0u8; T::MaxTransactionSize::get() as usize],
+		let size = T::MaxTransactionSize::get();
+		fall_back_to_group::<T>(&caller, Default::default(), size)?;
+		// Source block with `s` transactions, in the format of `Transactions`: the worst case, as
+		// `TransactionInfos` is read first and then the whole block is decoded
+		let source: Vec<_> = (1..=s)
+			.map(|i| LegacyTransactionInfo {
+				chunk_root: Default::default(),
+				content_hash: Default::default(),
+				size,
+				block_chunks: i * num_chunks(size),
+			})
+			.collect();
+		let block: BlockNumberFor<T> = One::one();
+		Transactions::<T>::insert(block, BoundedVec::try_from(source).unwrap());
+		renewal_bookkeeping::<T>(Default::default(), block);
+		whitelist_block_state::<T>();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), block, TxIndex(s - 1), Some(Default::default()));
+
+		assert_last_event::<T>(
+			Event::Renewed { index: TxIndex(0), chunk_root: Default::default() }.into(),
+		);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn check_proof(n: Linear<1, { T::MaxBlockTransactions::get() }>) {
+		// The target block holds `n` one byte transactions with the same content, so the proof
+		// is the same whichever chunk is selected. They are in the format of `Transactions`,
+		// which is only read after `TransactionInfos`.
+		let data = vec![0u8];
+		let chunk_root =
+			sp_io::trie::blake2_256_ordered_root(vec![data.clone()], sp_runtime::StateVersion::V1);
+		let transactions: Vec<_> = (1..=n)
+			.map(|block_chunks| LegacyTransactionInfo {
+				chunk_root,
+				content_hash: sp_io::hashing::blake2_256(&data).into(),
+				size: 1,
+				block_chunks,
+			})
+			.collect();
+		let target: BlockNumberFor<T> = One::one();
+		Transactions::<T>::insert(target, BoundedVec::try_from(transactions).unwrap());
+		ChunkEnds::<T>::insert(target, BoundedVec::try_from((1..=n).collect::<Vec<_>>()).unwrap());
+		ChunkCount::<T>::insert(target, n);
+		let content_hash = sp_io::hashing::blake2_256(&data);
+		ChallengeCounts::<T>::insert(content_hash, (0, target));
+		frame_system::Pallet::<T>::set_block_number(target + StoragePeriod::<T>::get());
+		whitelist_block_state::<T>();
+
+		#[extrinsic_call]
+		_(RawOrigin::None, single_byte_proof());
+
+		assert_last_event::<T>(Event::ProofChecked.into());
+		assert_eq!(ChallengeCounts::<T>::get(content_hash), Some((1, target)));
+	}
+
+	#[benchmark]
+	fn check_challenge_proofs(n: Linear<1, { T::MaxBlockChallenges::get() }>) {
+		// Challenges of one byte transactions with the same content
+		let data = vec![0u8];
+		let chunk_root =
+			sp_io::trie::blake2_256_ordered_root(vec![data.clone()], sp_runtime::StateVersion::V1);
+		let challenges: Vec<_> = (0..n)
+			.map(|i| Challenge {
+				who: account("challenger", i, 0),
+				block: One::one(),
+				index: TxIndex(0),
+				content_hash: sp_io::hashing::blake2_256(&data).into(),
+				chunk_root,
+				chunk_count: 1,
+			})
+			.collect();
+		let now = frame_system::Pallet::<T>::block_number();
+		Challenges::<T>::insert(now, BoundedVec::try_from(challenges).unwrap());
+		let proofs = BoundedVec::try_from(vec![Some(single_byte_proof()); n as usize]).unwrap();
+
+		#[extrinsic_call]
+		check_proofs(RawOrigin::None, None, proofs);
+
+		assert!(!Challenges::<T>::contains_key(now));
+	}
+
+	// The last transaction of a full target block is as large as possible and the last of its
+	// chunks is challenged, so the proof is as deep as it gets
+	#[benchmark]
+	fn check_proof_worst_case() {
+		let n = T::MaxBlockTransactions::get();
+		let size = T::MaxTransactionSize::get();
+		let data = vec![0u8; size as usize];
+		let (chunk_root, proof) = chunk_proof(&data, ChunkIndex(num_chunks(size) - 1));
+		let mut transactions: Vec<_> = (1..n)
+			.map(|block_chunks| LegacyTransactionInfo {
+				chunk_root: sp_io::trie::blake2_256_ordered_root(
+					vec![vec![0u8]],
+					sp_runtime::StateVersion::V1,
+				),
+				content_hash: sp_io::hashing::blake2_256(&[0u8]).into(),
+				size: 1,
+				block_chunks,
+			})
+			.collect();
+		let total_chunks = n - 1 + num_chunks(size);
+		transactions.push(LegacyTransactionInfo {
+			chunk_root,
+			content_hash: sp_io::hashing::blake2_256(&data).into(),
+			size,
+			block_chunks: total_chunks,
+		});
+		let chunk_ends: Vec<_> = transactions.iter().map(|info| info.block_chunks).collect();
+		let target: BlockNumberFor<T> = One::one();
+		Transactions::<T>::insert(target, BoundedVec::try_from(transactions).unwrap());
+		ChunkEnds::<T>::insert(target, BoundedVec::try_from(chunk_ends).unwrap());
+		ChunkCount::<T>::insert(target, total_chunks);
+		ChallengeCounts::<T>::insert(sp_io::hashing::blake2_256(&data), (0, target));
+		frame_system::Pallet::<T>::set_block_number(target + StoragePeriod::<T>::get());
+		// The chunk is selected by the leading bytes of the parent hash
+		let mut parent_hash = T::Hash::default();
+		parent_hash.as_mut()[..8].copy_from_slice(&u64::from(total_chunks - 1).to_be_bytes());
+		frame_system::Pallet::<T>::set_parent_hash(parent_hash);
+		assert_eq!(random_chunk(parent_hash.as_ref(), total_chunks), total_chunks - 1);
+		whitelist_block_state::<T>();
+
+		#[extrinsic_call]
+		check_proof(RawOrigin::None, proof);
+
+		assert_last_event::<T>(Event::ProofChecked.into());
+	}
+
+	#[benchmark]
+	fn expire_authorizations(
+		n: Linear<0, { T::MaxBlockAuthorizationExpiries::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let origin = authorizer_origin::<T>()?;
+		// Restricted authorizations also clear the allowed hashes of the account on expiry
+		for i in 0..n {
+			let who: T::AccountId = account("who", i, 0);
+			let allowed_hashes = vec![[0u8; 32]].try_into().unwrap();
+			TransactionStorage::<T>::authorize_account(
+				origin.clone(),
+				who,
+				1,
+				1,
+				Some(allowed_hashes),
 			)?;
 		}
-		run_to_block::<T>(StoragePeriod::<T>::get() + BlockNumberFor::<T>::one());
-		let encoded_proof = proof();
-		let proof = TransactionStorageProof::decode(&mut &*encoded_proof).unwrap();
-	}: check_proof(RawOrigin::None, proof)
-	verify {
-		assert_last_event::<T>(Event::ProofChecked.into());
+		let expiry = frame_system::Pallet::<T>::block_number() + T::AuthorizationPeriod::get();
+		assert_eq!(AuthorizationsByExpiry::<T>::get(expiry).len(), n as usize);
+		let events = System::<T>::events().len();
+
+		#[block]
+		{
+			TransactionStorage::<T>::expire_authorizations(expiry);
+		}
+
+		assert!(AuthorizationsByExpiry::<T>::get(expiry).is_empty());
+		assert_eq!(AllowedHashes::<T>::iter().count(), 0);
+		// The weight includes the `AuthorizationExpired` event of each authorization
+		assert_eq!(System::<T>::events().len(), events + n as usize);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn authorize_account() -> Result<(), BenchmarkError> {
+		let origin = authorizer_origin::<T>()?;
+		let who: T::AccountId = account("who", 0, 0);
+		let allowed_hashes: BoundedVec<_, _> = (0..T::MaxAllowedHashes::get())
+			.map(|i| sp_io::hashing::blake2_256(&i.encode()))
+			.collect::<Vec<_>>()
+			.try_into()
+			.unwrap();
+		fill_authorizations::<T>(1)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, who.clone(), 1, 1, Some(allowed_hashes));
+
+		assert_eq!(AllowedHashes::<T>::get(&who).len(), T::MaxAllowedHashes::get() as usize);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn authorize_preimage() -> Result<(), BenchmarkError> {
+		let origin = authorizer_origin::<T>()?;
+		let preimage = [0u8; 32];
+		fill_authorizations::<T>(1)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, preimage, 1, true);
+
+		assert!(TransactionStorage::<T>::preimage_authorization(preimage).is_some());
+		Ok(())
+	}
+
+	#[benchmark]
+	fn authorize_preimages(
+		n: Linear<0, { T::MaxBlockAuthorizationExpiries::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let origin = authorizer_origin::<T>()?;
+		let preimages: Vec<_> =
+			(0..n).map(|i| (sp_io::hashing::blake2_256(&i.encode()), 1)).collect();
+		let preimages = BoundedVec::try_from(preimages).unwrap();
+		fill_authorizations::<T>(n)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, preimages, true);
+
+		let last = sp_io::hashing::blake2_256(&n.saturating_sub(1).encode());
+		assert_eq!(TransactionStorage::<T>::preimage_authorization(last).is_some(), n > 0);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn revoke_account_authorization(
+		n: Linear<0, { T::MaxAccountAuthorizationExpiries::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let origin = authorizer_origin::<T>()?;
+		let who: T::AccountId = account("who", 0, 0);
+		let scope = AuthorizationScope::Account(who.clone());
+		// The account has an authorization in each of `n` full expiry blocks, and used half of
+		// them
+		let extent = AuthorizationExtent { transactions: 2, bytes: 2 };
+		let mut block = frame_system::Pallet::<T>::block_number();
+		let mut expiries = Vec::new();
+		let mut pending = Vec::new();
+		let mut id = 0;
+		for j in 0..n {
+			block += One::one();
+			let authorizations: Vec<_> = (0..T::MaxBlockAuthorizationExpiries::get())
+				.map(|i| {
+					let scope = match i {
+						0 => scope.clone(),
+						i => AuthorizationScope::Account(account("filler", i, 0)),
+					};
+					id += 1;
+					Authorization { id, scope, extent }
+				})
+				.collect();
+			// The earliest expiring authorizations were used first
+			let used = n.saturating_sub(2 * j).min(2);
+			let unused = AuthorizationExtent { transactions: 2 - used, bytes: (2 - used).into() };
+			if unused != Default::default() {
+				let id = authorizations[0].id;
+				pending.push(PendingAuthorization { id, expiry: Some(block), unused });
+			}
+			AuthorizationsByExpiry::<T>::insert(block, BoundedVec::truncate_from(authorizations));
+			expiries.push(block);
+		}
+		AccountAuthorizationExpiries::<T>::insert(&who, BoundedVec::truncate_from(expiries));
+		PendingAuthorizations::<T>::insert(&scope, BoundedVec::truncate_from(pending));
+		let used = AuthorizationExtent { transactions: n, bytes: n.into() };
+		let usage = AuthorizationUsage { used, unused: used, exact_size: None };
+		AuthorizationUsageByScope::<T>::insert(&scope, usage);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, who.clone());
+
+		assert_eq!(AccountAuthorizationExpiries::<T>::get(&who).len(), n.div_ceil(2) as usize);
+		assert_last_event::<T>(Event::AuthorizationRevoked { who, extent: used }.into());
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_standing_quota() -> Result<(), BenchmarkError> {
+		let origin = authorizer_origin::<T>()?;
+		let who: T::AccountId = account("who", 0, 0);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, who.clone(), 1, 1);
+
+		assert!(StandingQuotas::<T>::contains_key(&who));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn clear_standing_quota() -> Result<(), BenchmarkError> {
+		let origin = authorizer_origin::<T>()?;
+		let who: T::AccountId = account("who", 0, 0);
+		TransactionStorage::<T>::set_standing_quota(origin.clone(), who.clone(), 1, 1)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, who.clone());
+
+		assert!(!StandingQuotas::<T>::contains_key(&who));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_lifetime_cap() -> Result<(), BenchmarkError> {
+		let origin = authorizer_origin::<T>()?;
+		let who: T::AccountId = account("who", 0, 0);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, who.clone(), Some(1));
+
+		assert_eq!(LifetimeCaps::<T>::get(&who), Some(1));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn add_authorizer() {
+		let who: T::AccountId = account("who", 0, 0);
+
+		#[extrinsic_call]
+		_(RawOrigin::Root, who.clone());
+
+		assert!(Authorizers::<T>::contains_key(&who));
+	}
+
+	#[benchmark]
+	fn remove_authorizer() -> Result<(), BenchmarkError> {
+		let who: T::AccountId = account("who", 0, 0);
+		TransactionStorage::<T>::add_authorizer(RawOrigin::Root.into(), who.clone())?;
+
+		#[extrinsic_call]
+		_(RawOrigin::Root, who.clone());
+
+		assert!(!Authorizers::<T>::contains_key(&who));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn create_group(m: Linear<0, { T::MaxGroupMembers::get() }>) -> Result<(), BenchmarkError> {
+		let origin = authorizer_origin::<T>()?;
+		let members = group_members::<T>(m);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, members);
+
+		assert_eq!(Groups::<T>::get(0).map(|members| members.len()), Some(m as usize));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn authorize_group() -> Result<(), BenchmarkError> {
+		let origin = authorizer_origin::<T>()?;
+		let members = group_members::<T>(T::MaxGroupMembers::get());
+		TransactionStorage::<T>::create_group(origin.clone(), members)?;
+		fill_authorizations::<T>(1)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, 0, 1, 1);
+
+		assert_eq!(
+			TransactionStorage::<T>::unused_group_authorization_extent(0),
+			AuthorizationExtent { transactions: 1, bytes: 1 }
+		);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn add_group_member() -> Result<(), BenchmarkError> {
+		let origin = authorizer_origin::<T>()?;
+		let n = T::MaxGroupMembers::get();
+		let members = group_members::<T>(n - 1);
+		TransactionStorage::<T>::create_group(origin.clone(), members)?;
+		let who: T::AccountId = account("member", n - 1, 0);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, 0, who.clone());
+
+		assert_eq!(GroupOf::<T>::get(&who), Some(0));
+		Ok(())
+	}
+
+	// Worst case: the member is the last one of a full group
+	#[benchmark]
+	fn remove_group_member() -> Result<(), BenchmarkError> {
+		let origin = authorizer_origin::<T>()?;
+		let n = T::MaxGroupMembers::get();
+		let members = group_members::<T>(n);
+		TransactionStorage::<T>::create_group(origin.clone(), members)?;
+		let who: T::AccountId = account("member", n - 1, 0);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, 0, who.clone());
+
+		assert!(!GroupOf::<T>::contains_key(&who));
+		Ok(())
+	}
+
+	// Worst case: the content is pending removal in a full schedule
+	#[benchmark]
+	fn force_remove() -> Result<(), BenchmarkError> {
+		let origin = authorizer_origin::<T>()?;
+		let n = T::MaxBlockRemovals::get();
+		schedule_removals::<T>(n)?;
+		let content_hash = sp_io::hashing::blake2_256(&(n - 1).encode());
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, content_hash, RemovalReason::Takedown);
+
+		assert!(RemovedContent::<T>::contains_key(content_hash));
+		assert!(!PendingRemovals::<T>::contains_key(content_hash));
+		assert!(Tombstones::<T>::contains_key(content_hash));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn schedule_removal() -> Result<(), BenchmarkError> {
+		let origin = authorizer_origin::<T>()?;
+		let n = T::MaxBlockRemovals::get();
+		schedule_removals::<T>(n - 1)?;
+		let content_hash = sp_io::hashing::blake2_256(&(n - 1).encode());
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, content_hash, One::one(), RemovalReason::Takedown);
+
+		assert!(PendingRemovals::<T>::contains_key(content_hash));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn cancel_removal() -> Result<(), BenchmarkError> {
+		let origin = authorizer_origin::<T>()?;
+		let n = T::MaxBlockRemovals::get();
+		schedule_removals::<T>(n)?;
+		let content_hash = sp_io::hashing::blake2_256(&(n - 1).encode());
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, content_hash);
+
+		assert!(!PendingRemovals::<T>::contains_key(content_hash));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn clear_bar() -> Result<(), BenchmarkError> {
+		let origin = authorizer_origin::<T>()?;
+		let who: T::AccountId = account("who", 0, 0);
+		Barred::<T>::insert(&who, BlockNumberFor::<T>::from(1u32));
+		Strikes::<T>::insert(&who, 1);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, who.clone());
+
+		assert!(!Barred::<T>::contains_key(&who));
+		Ok(())
+	}
+
+	// Worst case: all other challenges of the block are already requested
+	#[benchmark]
+	fn request_proof() -> Result<(), BenchmarkError> {
+		let caller: T::AccountId = whitelisted_caller();
+		let data = vec![0u8];
+		fall_back_to_group::<T>(&caller, sp_io::hashing::blake2_256(&data), 0)?;
+		let info = TransactionInfo {
+			chunk_root: sp_io::trie::blake2_256_ordered_root(
+				vec![data.clone()],
+				sp_runtime::StateVersion::V1,
+			),
+			content_hash: sp_io::hashing::blake2_256(&data).into(),
+			size: 1,
+			block_chunks: 1,
+			chunk_count: 1,
+		};
+		// The content is the last of a full block, behind other content
+		let block: BlockNumberFor<T> = One::one();
+		let last = T::MaxBlockTransactions::get() - 1;
+		for index in 0..last {
+			let other = TransactionInfo {
+				content_hash: sp_io::hashing::blake2_256(&index.encode()).into(),
+				..info.clone()
+			};
+			TransactionInfos::<T>::insert(block, index, other);
+		}
+		TransactionInfos::<T>::insert(block, last, info.clone());
+		ChallengeCounts::<T>::insert(info.content_hash.0, (0, block));
+		let others: Vec<_> = (1..T::MaxBlockChallenges::get())
+			.map(|i| Challenge {
+				who: account("challenger", i, 0),
+				block,
+				index: TxIndex(last),
+				content_hash: info.content_hash,
+				chunk_root: info.chunk_root,
+				chunk_count: info.chunk_count,
+			})
+			.collect();
+		let at = frame_system::Pallet::<T>::block_number() + One::one();
+		Challenges::<T>::insert(at, BoundedVec::try_from(others).unwrap());
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), info.content_hash.into());
+
+		assert_eq!(Challenges::<T>::get(at).len() as u32, T::MaxBlockChallenges::get());
+		Ok(())
+	}
+
+	#[benchmark]
+	fn verify_content(
+		l: Linear<1, { T::MaxTransactionSize::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let caller: T::AccountId = whitelisted_caller();
+		let data = vec![0u8; l as usize];
+		let content_hash = sp_io::hashing::blake2_256(&data);
+		fall_back_to_group::<T>(&caller, content_hash, l)?;
+		let chunks = data.chunks(CHUNK_SIZE).map(|c| c.to_vec()).collect();
+		let info = TransactionInfo {
+			chunk_root: sp_io::trie::blake2_256_ordered_root(chunks, sp_runtime::StateVersion::V1),
+			content_hash: content_hash.into(),
+			size: l,
+			block_chunks: num_chunks(l),
+			chunk_count: num_chunks(l),
+		};
+		let block: BlockNumberFor<T> = One::one();
+		TransactionInfos::<T>::insert(block, 0, info);
+		renewal_bookkeeping::<T>(content_hash, block);
+		whitelist_block_state::<T>();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), block, TxIndex(0), data);
+
+		assert_last_event::<T>(Event::ContentVerified { block, index: TxIndex(0) }.into());
+		assert_eq!(BlockTransactions::<T>::count(), 1);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn on_initialize_pruning() {
+		let obsolete: BlockNumberFor<T> = One::one();
+		let n = obsolete + StoragePeriod::<T>::get() + One::one();
+		let info = LegacyTransactionInfo {
+			chunk_root: Default::default(),
+			content_hash: Default::default(),
+			size: 1,
+			block_chunks: 1,
+		};
+		Transactions::<T>::insert(obsolete, BoundedVec::try_from(vec![info.clone()]).unwrap());
+		TransactionInfos::<T>::insert(obsolete, 0, TransactionInfo::from(info));
+		ChunkEnds::<T>::insert(obsolete, BoundedVec::try_from(vec![1]).unwrap());
+		ChunkCount::<T>::insert(obsolete, 1);
+
+		#[block]
+		{
+			TransactionStorage::<T>::on_initialize(n);
+		}
+
+		assert!(Transactions::<T>::get(obsolete).is_none());
+		assert!(TransactionInfos::<T>::get(obsolete, 0).is_none());
+		assert!(ChunkEnds::<T>::get(obsolete).is_none());
+	}
+
+	#[benchmark]
+	fn execute_scheduled_removals(
+		n: Linear<0, { T::MaxBlockRemovals::get() }>,
+	) -> Result<(), BenchmarkError> {
+		schedule_removals::<T>(n)?;
+		let at = frame_system::Pallet::<T>::block_number() + One::one();
+
+		#[block]
+		{
+			TransactionStorage::<T>::execute_scheduled_removals(at);
+		}
+
+		assert_eq!(RemovedContent::<T>::iter().count(), n as usize);
+		assert_eq!(Tombstones::<T>::count(), n);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn prune_tombstone() -> Result<(), BenchmarkError> {
+		let content_hash = [0u8; 32];
+		TransactionStorage::<T>::force_remove(
+			authorizer_origin::<T>()?,
+			content_hash,
+			RemovalReason::Takedown,
+		)?;
+
+		#[extrinsic_call]
+		_(RawOrigin::Root, content_hash);
+
+		assert!(!Tombstones::<T>::contains_key(content_hash));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn on_finalize(n: Linear<0, { T::MaxBlockTransactions::get() }>) {
+		for i in 0..n {
+			let info = TransactionInfo {
+				chunk_root: Default::default(),
+				content_hash: Default::default(),
+				size: CHUNK_SIZE as u32,
+				block_chunks: i + 1,
+				chunk_count: 1,
+			};
+			BlockTransactions::<T>::insert(i, info);
+		}
+		BlockChunks::<T>::put(n);
+		whitelist_block_state::<T>();
+		let block = frame_system::Pallet::<T>::block_number();
+
+		#[block]
+		{
+			TransactionStorage::<T>::on_finalize(block);
+		}
+
+		assert_eq!(ChunkCount::<T>::get(block), n);
+	}
+
+	#[benchmark]
+	fn migrate_block_transactions(n: Linear<0, { T::MaxBlockTransactions::get() }>) {
+		let transactions: Vec<_> = (0..n)
+			.map(|i| LegacyTransactionInfo {
+				chunk_root: Default::default(),
+				content_hash: Default::default(),
+				size: 1,
+				block_chunks: i + 1,
+			})
+			.collect();
+		let block: BlockNumberFor<T> = One::one();
+		Transactions::<T>::insert(block, BoundedVec::try_from(transactions).unwrap());
+
+		#[block]
+		{
+			migrations::v1::migrate_block::<T>(block);
+		}
+
+		assert!(Transactions::<T>::get(block).is_none());
+		assert_eq!(TransactionInfos::<T>::iter_prefix(block).count(), n as usize);
+	}
+
+	// Noting inline data as `store_internal` does, in a block listing as many preimages as it can
+	#[benchmark]
+	fn note_preimage(l: Linear<1, MAX_INLINE_DATA_SIZE>) -> Result<(), BenchmarkError> {
+		let block = frame_system::Pallet::<T>::block_number();
+		let listed: Vec<Preimage> = (1..T::MaxBlockTransactions::get())
+			.map(|i| sp_io::hashing::blake2_256(&i.encode()))
+			.collect();
+		InlineByBlock::<T>::insert(block, BoundedVec::truncate_from(listed));
+		let data = vec![0u8; l as usize];
+		let content_hash = sp_io::hashing::blake2_256(&data);
+
+		#[block]
+		{
+			let hash = <TransactionStorage<T> as StorePreimage>::note((&data[..]).into())?;
+			<TransactionStorage<T> as QueryPreimage>::unrequest(&hash);
+		}
+
+		assert_eq!(InlineData::<T>::decode_len(content_hash), Some(l as usize));
+		assert!(!InlineDataRequests::<T>::contains_key(content_hash));
+		Ok(())
 	}
 
 	impl_benchmark_test_suite!(TransactionStorage, crate::mock::new_test_ext(), crate::mock::Test);
@@ -0,0 +1,162 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers for clients of the transaction storage pallet, e.g. light clients following headers.
+
+use crate::{
+	chunk_root, content_commitment, primitives::calls, ContentHash, StoredContent,
+	CONTENT_COMMITMENT_ENGINE_ID,
+};
+use codec::{Compact, Decode, Encode};
+use sp_runtime::{
+	traits::{BlakeTwo256, Hash, Header},
+	StateVersion,
+};
+
+/// Checks that `contents` is the content stored in the block with the given header, in index
+/// order, against the content commitment digest item of the header. A header without the digest
+/// item only matches an empty list, so this always fails for non-empty lists if the runtime
+/// doesn't deposit the digest item.
+pub fn verify_block_content_commitment<H: Header>(header: &H, contents: &[StoredContent]) -> bool {
+	let commitment = header.digest().convert_first(|item| {
+		item.consensus_try_to::<<BlakeTwo256 as Hash>::Output>(&CONTENT_COMMITMENT_ENGINE_ID)
+	});
+	match commitment {
+		Some(commitment) => commitment == content_commitment(contents),
+		None => contents.is_empty(),
+	}
+}
+
+/// Why a claim that an extrinsic stored some content was rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InclusionError {
+	/// The block has no extrinsic at the given index.
+	NoSuchExtrinsic,
+	/// The extrinsic does not call `store`.
+	NotAStore,
+	/// The extrinsic stored data with another content hash.
+	ContentMismatch,
+	/// The inclusion proof does not prove the extrinsic against the extrinsics root.
+	InvalidProof,
+}
+
+/// Proof that an extrinsic is included in a block, against the extrinsics root of its header.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct ExtrinsicInclusionProof {
+	/// Index of the extrinsic in its block.
+	pub index: u32,
+	/// The extrinsic, encoded as in the block body.
+	pub extrinsic: Vec<u8>,
+	/// Nodes of the extrinsics trie proving the extrinsic.
+	pub proof: Vec<Vec<u8>>,
+}
+
+/// Returns the `store` call of `extrinsic`, encoded as in the block body, if it calls `store` of
+/// the pallet at `pallet_index` in the runtime.
+///
+/// The call is the last field of an extrinsic, so it is the first tail of the extrinsic which
+/// decodes as a `store` call: the tail of some stored data that itself contains an encoded call
+/// comes later.
+pub fn store_call(extrinsic: &[u8], pallet_index: u8) -> Option<calls::Store> {
+	let prefix = [pallet_index, calls::Store::INDEX];
+	(0..extrinsic.len())
+		.filter(|&offset| extrinsic[offset..].starts_with(&prefix))
+		.find_map(|offset| {
+			let mut tail = &extrinsic[offset + prefix.len()..];
+			calls::Store::decode(&mut tail).ok().filter(|_| tail.is_empty())
+		})
+}
+
+/// Checks that the extrinsic at `index` of a block with the given `extrinsics` stored data with
+/// `content_hash`, by hashing and chunking the data again. Returns the chunk root of the data, as
+/// its `Stored` event reported.
+pub fn verify_stored_content(
+	extrinsics: &[Vec<u8>],
+	index: u32,
+	pallet_index: u8,
+	content_hash: ContentHash,
+) -> Result<<BlakeTwo256 as Hash>::Output, InclusionError> {
+	let extrinsic = extrinsics.get(index as usize).ok_or(InclusionError::NoSuchExtrinsic)?;
+	stored_chunk_root(extrinsic, pallet_index, content_hash)
+}
+
+/// Builds the proof of the extrinsic at `index` of a block with the given `extrinsics` against
+/// the extrinsics root of the block's header. Needs the `sp-trie` feature.
+#[cfg(any(test, feature = "sp-trie"))]
+pub fn extrinsic_inclusion_proof(
+	extrinsics: &[Vec<u8>],
+	index: u32,
+) -> Result<ExtrinsicInclusionProof, InclusionError> {
+	use sp_trie::{generate_trie_proof, LayoutV0, MemoryDB, TrieDBMutBuilder, TrieMut};
+	// The extrinsics trie is built with state version 0, see `frame_system::extrinsics_data_root`
+	type Layout = LayoutV0<BlakeTwo256>;
+
+	let extrinsic = extrinsics.get(index as usize).ok_or(InclusionError::NoSuchExtrinsic)?;
+	let mut db = MemoryDB::<BlakeTwo256>::default();
+	let mut root = Default::default();
+	{
+		let mut trie = TrieDBMutBuilder::<Layout>::new(&mut db, &mut root).build();
+		for (i, extrinsic) in extrinsics.iter().enumerate() {
+			trie.insert(&Compact(i as u32).encode(), extrinsic)
+				.expect("Inserting into an in-memory trie cannot fail; qed");
+		}
+	}
+	let proof = generate_trie_proof::<Layout, _, _, _>(&db, root, &[Compact(index).encode()])
+		.expect("All the nodes of the trie are in memory; qed");
+	Ok(ExtrinsicInclusionProof { index, extrinsic: extrinsic.clone(), proof })
+}
+
+/// Checks the proof of an extrinsic against the extrinsics root of the given header.
+pub fn verify_extrinsic_inclusion<H: Header<Hashing = BlakeTwo256>>(
+	header: &H,
+	proof: &ExtrinsicInclusionProof,
+) -> bool {
+	sp_io::trie::blake2_256_verify_proof(
+		*header.extrinsics_root(),
+		&proof.proof,
+		&Compact(proof.index).encode(),
+		&proof.extrinsic,
+		StateVersion::V0,
+	)
+}
+
+/// Checks that the extrinsic of `proof` is included in the block with the given header and
+/// stored data with `content_hash`, trusting only the header. Returns the chunk root of the data,
+/// like `verify_stored_content`.
+pub fn verify_stored_content_inclusion<H: Header<Hashing = BlakeTwo256>>(
+	header: &H,
+	proof: &ExtrinsicInclusionProof,
+	pallet_index: u8,
+	content_hash: ContentHash,
+) -> Result<<BlakeTwo256 as Hash>::Output, InclusionError> {
+	if !verify_extrinsic_inclusion(header, proof) {
+		return Err(InclusionError::InvalidProof)
+	}
+	stored_chunk_root(&proof.extrinsic, pallet_index, content_hash)
+}
+
+fn stored_chunk_root(
+	extrinsic: &[u8],
+	pallet_index: u8,
+	content_hash: ContentHash,
+) -> Result<<BlakeTwo256 as Hash>::Output, InclusionError> {
+	let call = store_call(extrinsic, pallet_index).ok_or(InclusionError::NotAStore)?;
+	if sp_io::hashing::blake2_256(&call.data) != content_hash {
+		return Err(InclusionError::ContentMismatch)
+	}
+	Ok(chunk_root(&call.data))
+}
@@ -0,0 +1,140 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Signed extension tracking the uploads of each account that fail authorization checks.
+//!
+//! Authorized uploads are feeless, so nothing stops an account from filling blocks with uploads
+//! that fail once dispatched. Failed dispatches are rolled back, so their strikes are recorded
+//! after dispatch by this extension instead. Barred accounts are rejected from the pool.
+
+use super::*;
+use frame_support::traits::IsSubType;
+use sp_runtime::{
+	traits::{DispatchInfoOf, PostDispatchInfoOf, SignedExtension},
+	transaction_validity::{
+		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+	DispatchResult,
+};
+
+/// Custom `InvalidTransaction` code of uploads from barred accounts.
+pub const BARRED_ACCOUNT: u8 = 1;
+
+/// Records a strike against the signer of each upload (`store`, `store_compressed`, `renew` or
+/// `verify_content`) that fails authorization checks, barring the account from uploading after
+/// `Config::StrikeThreshold` strikes. Uploads from barred accounts are invalid.
+#[derive(Encode, Decode, CloneNoBound, EqNoBound, PartialEqNoBound, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct TrackStoreFailures<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> TrackStoreFailures<T> {
+	/// Creates new `SignedExtension` to track failed uploads.
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config + Send + Sync> Default for TrackStoreFailures<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Config + Send + Sync> sp_std::fmt::Debug for TrackStoreFailures<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "TrackStoreFailures")
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Config + Send + Sync> SignedExtension for TrackStoreFailures<T>
+where
+	<T as frame_system::Config>::RuntimeCall: IsSubType<Call<T>>,
+{
+	const IDENTIFIER: &'static str = "TrackStoreFailures";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::RuntimeCall;
+	type AdditionalSigned = ();
+	/// The signer, for uploads.
+	type Pre = Option<T::AccountId>;
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		if is_upload::<T>(call) && Pallet::<T>::is_barred(who) {
+			return Err(InvalidTransaction::Custom(BARRED_ACCOUNT).into())
+		}
+		Ok(ValidTransaction::default())
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		self.validate(who, call, info, len)?;
+		Ok(is_upload::<T>(call).then(|| who.clone()))
+	}
+
+	fn post_dispatch(
+		pre: Option<Self::Pre>,
+		_info: &DispatchInfoOf<Self::Call>,
+		_post_info: &PostDispatchInfoOf<Self::Call>,
+		_len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		if let (Some(Some(who)), Err(error)) = (pre, result) {
+			let failed_authorization = [Error::<T>::NotAuthorized, Error::<T>::SizeMismatch]
+				.into_iter()
+				.any(|e| *error == DispatchError::from(e));
+			if failed_authorization {
+				Pallet::<T>::strike(&who);
+			}
+		}
+		Ok(())
+	}
+}
+
+fn is_upload<T: Config>(call: &<T as frame_system::Config>::RuntimeCall) -> bool
+where
+	<T as frame_system::Config>::RuntimeCall: IsSubType<Call<T>>,
+{
+	matches!(
+		call.is_sub_type(),
+		Some(
+			Call::store { .. } |
+				Call::store_compressed { .. } |
+				Call::renew { .. } |
+				Call::verify_content { .. }
+		)
+	)
+}
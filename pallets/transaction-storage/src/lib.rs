@@ -21,32 +21,80 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod benchmarking;
+#[cfg(feature = "std")]
+pub mod client;
+mod extension;
+pub mod migrations;
+mod preimages;
+pub mod quota;
+pub mod runtime_api;
+#[cfg(any(test, feature = "sp-trie"))]
+pub mod test_utils;
 pub mod weights;
 
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
 #[cfg(test)]
-mod mock;
+mod simulation;
+#[cfg(test)]
+mod test_vectors;
 #[cfg(test)]
 mod tests;
 
-use codec::{Decode, Encode, MaxEncodedLen};
-use frame_support::dispatch::{Dispatchable, GetDispatchInfo, RawOrigin};
-use sp_runtime::traits::{BlakeTwo256, CheckedAdd, Hash, One, Saturating, Zero};
-use sp_std::{prelude::*, result};
+use codec::{Compact, CompactLen, Decode, Encode, FullCodec, MaxEncodedLen};
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, RawOrigin},
+	storage::{storage_prefix, unhashed},
+	traits::{
+		fungible,
+		tokens::{Fortitude, Precision},
+		EnsureOrigin, Get, PalletInfoAccess,
+	},
+	weights::{Weight, WeightMeter},
+	CloneNoBound, EqNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+};
+use scale_info::TypeInfo;
+use sp_inherents::InherentIdentifier;
+use sp_runtime::{
+	traits::{
+		BlakeTwo256, CheckedAdd, CheckedDiv, Hash, Member, One, SaturatedConversion, Saturating,
+		Zero,
+	},
+	ArithmeticError, ConsensusEngineId, DigestItem, DispatchError, Perbill,
+};
+use sp_std::{marker::PhantomData, prelude::*, result};
 use sp_transaction_storage_proof::{
-	encode_index, random_chunk, InherentError, TransactionStorageProof, CHUNK_SIZE,
-	INHERENT_IDENTIFIER,
+	encode_index, random_chunk, InherentError, TransactionStorageProof, INHERENT_IDENTIFIER,
 };
 
 // Re-export pallet items so that they can be accessed from the crate namespace.
+pub use extension::{TrackStoreFailures, BARRED_ACCOUNT};
 pub use pallet::*;
+pub use quota::{InspectQuota, MutateQuota, StorageQuota};
+pub use transaction_storage_primitives::{
+	self as primitives, num_chunks, AuthorizationExtent, Challenge, ChunkIndex, ContentHash,
+	ContentRecord, Namespace, PeriodUsage, ProofStatus, RemovalReason, RenewalDeadline, ScopeRef,
+	StoreRejection, TxIndex, CHUNK_SIZE, DEFAULT_MAX_BLOCK_TRANSACTIONS,
+	DEFAULT_MAX_TRANSACTION_SIZE,
+};
 pub use weights::WeightInfo;
 
-/// Maximum bytes that can be stored in one transaction.
-// Setting higher limit also requires raising the allocator limit.
-pub const DEFAULT_MAX_TRANSACTION_SIZE: u32 = 8 * 1024 * 1024;
-pub const DEFAULT_MAX_BLOCK_TRANSACTIONS: u32 = 512;
+const LOG_TARGET: &str = "runtime::transaction-storage";
 
-/// Number of transactions and bytes covered by an authorization or authorizations.
+/// Maximum encoded size of a storage proof, enough for the proofs of `DEFAULT_MAX_TRANSACTION_SIZE`
+/// transactions.
+pub const DEFAULT_MAX_PROOF_SIZE: u32 = 16 * 1024;
+/// Maximum size of a preimage noted through `StorePreimage`, which is kept in the state.
+pub const MAX_INLINE_DATA_SIZE: u32 = 64 * 1024;
+/// Identifier of the inherent data holding the storage proofs of challenged content, a
+/// `Vec<TransactionStorageProof>` in the order of `Pallet::challenges`.
+pub const CHALLENGE_INHERENT_IDENTIFIER: InherentIdentifier = *b"tschllng";
+/// Maximum number of transactions scanned by a call of `Pallet::stored_content`.
+pub const MAX_STORED_CONTENT_PAGE: u32 = 1000;
+/// Engine ID of the consensus digest item holding the `content_commitment` of a block.
+pub const CONTENT_COMMITMENT_ENGINE_ID: ConsensusEngineId = *b"tscc";
+
+/// For tracking usage of authorizations for a particular account or preimage.
 #[derive(
 	Default,
 	PartialEq,
@@ -57,44 +105,133 @@ pub const DEFAULT_MAX_BLOCK_TRANSACTIONS: u32 = 512;
 	scale_info::TypeInfo,
 	MaxEncodedLen,
 )]
-pub struct AuthorizationExtent {
-	/// Number of transactions.
-	pub transactions: u32,
-	/// Number of bytes.
-	pub bytes: u64,
+pub struct AuthorizationUsage {
+	/// Extent of (unexpired) authorizations used. When an authorization expires, the extent it
+	/// had used is taken from this pool.
+	pub used: AuthorizationExtent,
+	/// Extent of authorizations not yet used, the sum of the unused extents of the scope's
+	/// `PendingAuthorizations`.
+	pub unused: AuthorizationExtent,
+	/// If set, data stored using this authorization must be exactly this many bytes. Only used
+	/// for preimage authorizations.
+	pub exact_size: Option<u64>,
 }
 
-/// For tracking usage of authorizations for a particular account or preimage.
-#[derive(Default, PartialEq, Eq, Encode, Decode, scale_info::TypeInfo, MaxEncodedLen)]
-struct AuthorizationUsage {
-	/// Extent of (unexpired) authorizations used. When an authorization expires, it consumes from
-	/// this pool first.
-	used: AuthorizationExtent,
-	/// Extent of authorizations not yet used.
-	unused: AuthorizationExtent,
+/// Preimage of a stored blob of data.
+pub type Preimage = ContentHash;
+
+/// Identifier of a group of accounts sharing authorizations.
+pub type GroupId = u32;
+
+/// How repeated authorizations of the same preimage are honoured, see `Config::PreimagePolicy`.
+#[derive(Clone, Copy, PartialEq, Eq, sp_runtime::RuntimeDebug)]
+pub enum PreimageStorePolicy {
+	/// Each authorization of a preimage allows one store of it.
+	Accumulate,
+	/// A preimage can be stored once with preimage authorizations, however many times it was
+	/// authorized. Once stored, it is satisfied until its authorizations have all expired.
+	Strict,
 }
 
-/// Preimage of a stored blob of data.
-type Preimage = [u8; 32];
+/// Which authorizations a signed store uses first when both a preimage authorization and the
+/// signer's own authorizations could cover it, see `Config::AuthorizationOrder`.
+#[derive(Clone, Copy, Default, PartialEq, Eq, sp_runtime::RuntimeDebug)]
+pub enum ConsumptionOrder {
+	/// The preimage authorization is used first, preserving the signer's own authorizations.
+	#[default]
+	PreimageFirst,
+	/// The signer's own authorizations are used first, preserving the preimage authorization for
+	/// others.
+	AccountFirst,
+}
 
 /// The scope of an authorization.
-#[derive(Clone, sp_runtime::RuntimeDebug, Encode, Decode, scale_info::TypeInfo, MaxEncodedLen)]
-enum AuthorizationScope<AccountId> {
+#[derive(
+	Clone,
+	PartialEq,
+	Eq,
+	sp_runtime::RuntimeDebug,
+	Encode,
+	Decode,
+	scale_info::TypeInfo,
+	MaxEncodedLen,
+)]
+pub enum AuthorizationScope<AccountId> {
 	/// Authorization for the given account to store arbitrary data.
 	Account(AccountId),
 	/// Authorization for anyone to store data with a specific hash.
 	Preimage(Preimage),
+	/// Authorization for the given account to store data with one of the hashes in its
+	/// `AllowedHashes` list.
+	RestrictedAccount(AccountId),
+	/// Authorization for the members of the given group to store arbitrary data, used once
+	/// their own authorizations are exhausted.
+	Group(GroupId),
 }
 
+/// A grant of an authorization: its scope, the extent credited and the block it was granted in.
+pub type AuthorizationGrant<AccountId, BlockNumber> =
+	(AuthorizationScope<AccountId>, AuthorizationExtent, BlockNumber);
+
+/// Identifier of an authorization, unique among all the authorizations granted.
+pub type AuthorizationId = u64;
+
 /// An authorization to store data.
-#[derive(sp_runtime::RuntimeDebug, Encode, Decode, scale_info::TypeInfo, MaxEncodedLen)]
+#[derive(
+	PartialEq, Eq, sp_runtime::RuntimeDebug, Encode, Decode, scale_info::TypeInfo, MaxEncodedLen,
+)]
 struct Authorization<AccountId> {
+	/// Identifier of the authorization, see `PendingAuthorizations`.
+	id: AuthorizationId,
 	/// Scope of the authorization (account/preimage).
 	scope: AuthorizationScope<AccountId>,
-	/// Extent of the authorization (number of transactions/bytes).
+	/// Extent credited by the authorization (number of transactions/bytes).
 	extent: AuthorizationExtent,
 }
 
+/// What is left of an authorization of a scope, see `PendingAuthorizations`.
+#[derive(
+	Clone,
+	PartialEq,
+	Eq,
+	sp_runtime::RuntimeDebug,
+	Encode,
+	Decode,
+	scale_info::TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct PendingAuthorization<BlockNumber> {
+	/// Identifier of the authorization.
+	pub id: AuthorizationId,
+	/// Block the authorization expires in, or `None` if it never expires.
+	pub expiry: Option<BlockNumber>,
+	/// Extent of the authorization not yet used.
+	pub unused: AuthorizationExtent,
+}
+
+/// A recurring authorization for an account, refilled at the start of every authorization period.
+#[derive(
+	PartialEq, Eq, sp_runtime::RuntimeDebug, Encode, Decode, scale_info::TypeInfo, MaxEncodedLen,
+)]
+pub struct StandingQuota<BlockNumber> {
+	/// Extent made available in every authorization period.
+	pub per_period: AuthorizationExtent,
+	/// Extent remaining in authorization period `period`.
+	pub remaining: AuthorizationExtent,
+	/// Index of the authorization period that `remaining` applies to.
+	pub period: BlockNumber,
+}
+
+impl<BlockNumber: PartialOrd> StandingQuota<BlockNumber> {
+	/// Reset `remaining` to `per_period` if `period` is later than the last refilled period.
+	fn refill(&mut self, period: BlockNumber) {
+		if self.period < period {
+			self.remaining = self.per_period;
+			self.period = period;
+		}
+	}
+}
+
 /// State data for a stored transaction.
 #[derive(
 	Encode,
@@ -116,18 +253,394 @@ pub struct TransactionInfo {
 	/// Total number of chunks added in the block with this transaction. This
 	/// is used find transaction info by block chunk index using binary search.
 	block_chunks: u32,
+	/// Number of chunks of indexed data.
+	chunk_count: u32,
+}
+
+/// `TransactionInfo` as stored in `Transactions`, before `chunk_count` was added.
+#[derive(
+	Encode,
+	Decode,
+	Clone,
+	sp_runtime::RuntimeDebug,
+	PartialEq,
+	Eq,
+	scale_info::TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct LegacyTransactionInfo {
+	chunk_root: <BlakeTwo256 as Hash>::Output,
+	content_hash: <BlakeTwo256 as Hash>::Output,
+	size: u32,
+	block_chunks: u32,
+}
+
+impl From<LegacyTransactionInfo> for TransactionInfo {
+	fn from(info: LegacyTransactionInfo) -> Self {
+		Self {
+			chunk_root: info.chunk_root,
+			content_hash: info.content_hash,
+			size: info.size,
+			block_chunks: info.block_chunks,
+			chunk_count: num_chunks(info.size),
+		}
+	}
+}
+
+/// Uncompressed form of content stored with `store_compressed`.
+#[derive(
+	Encode,
+	Decode,
+	Clone,
+	sp_runtime::RuntimeDebug,
+	PartialEq,
+	Eq,
+	scale_info::TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct CompressionInfo<BlockNumber> {
+	/// Plain hash of the uncompressed data, as declared by the uploader.
+	pub uncompressed_hash: Preimage,
+	/// Size of the uncompressed data in bytes, as declared by the uploader.
+	pub uncompressed_len: u32,
+	/// Last block the compressed data was stored or renewed in.
+	pub block: BlockNumber,
+}
+
+/// Content hash, size and chunk root of a transaction stored in a block.
+pub type StoredContent = (<BlakeTwo256 as Hash>::Output, u32, <BlakeTwo256 as Hash>::Output);
+
+/// Commitment to the content stored in a block, deposited as a digest item so that light clients
+/// can check it from the header: the Blake2-256 hash of the SCALE encoded `StoredContent` of the
+/// block's transactions, in index order.
+pub fn content_commitment(contents: &[StoredContent]) -> <BlakeTwo256 as Hash>::Output {
+	BlakeTwo256::hash_of(&contents)
+}
+
+/// Index of the chunk of challenged content that must be proved in the block with the given
+/// parent hash.
+pub fn challenged_chunk(
+	parent_hash: &[u8],
+	content_hash: &<BlakeTwo256 as Hash>::Output,
+	chunk_count: u32,
+) -> ChunkIndex {
+	let seed = sp_io::hashing::blake2_256(&(parent_hash, content_hash).encode());
+	ChunkIndex(random_chunk(&seed, chunk_count))
+}
+
+/// Root of the chunk trie of `data`, as recorded when it is stored.
+pub(crate) fn chunk_root(data: &[u8]) -> <BlakeTwo256 as Hash>::Output {
+	let chunks = data.chunks(CHUNK_SIZE).map(|c| c.to_vec()).collect();
+	sp_io::trie::blake2_256_ordered_root(chunks, sp_runtime::StateVersion::V1)
+}
+
+/// Checks the proof of the chunk at `chunk_index` of some data against the root of its chunk
+/// trie, as storage proofs are checked. Lets clients verify chunks served by any node.
+pub fn verify_chunk(
+	chunk_root: <BlakeTwo256 as Hash>::Output,
+	chunk_index: ChunkIndex,
+	chunk: &[u8],
+	proof: &[Vec<u8>],
+) -> bool {
+	sp_io::trie::blake2_256_verify_proof(
+		chunk_root,
+		proof,
+		&encode_index(chunk_index.0),
+		chunk,
+		sp_runtime::StateVersion::V1,
+	)
+}
+
+/// Maximum size of a node of a chunk trie: header, partial key, bitmap and 16 child hashes.
+const MAX_PROOF_NODE_SIZE: u32 = 4 + 4 + 2 + 16 * 33;
+
+/// Upper bound of the encoded size of the storage proof of a chunk of a transaction of `bytes`
+/// bytes: the chunk, a branch node for each nibble of the longest chunk index and a leaf.
+fn max_proof_size(bytes: u32) -> u32 {
+	let nodes = 2 * encode_index(num_chunks(bytes).saturating_sub(1)).len() as u32 + 1;
+	// Length prefixes of the chunk, of the nodes and of each node
+	CHUNK_SIZE as u32 + 8 + nodes * (MAX_PROOF_NODE_SIZE + 4)
+}
+
+/// Structural defect of a storage proof, found before verifying it against a chunk root.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MalformedProof {
+	/// The chunk is empty or does not have the length of the challenged chunk.
+	Chunk,
+	/// The proof has no nodes.
+	Empty,
+	/// A node of the proof is larger than any chunk trie node.
+	NodeTooLarge,
+}
+
+/// Checks the structure of a storage proof of a chunk of `chunk_len` bytes, or of up to
+/// `CHUNK_SIZE` bytes if the length of the chunk is not known.
+fn check_proof_structure(
+	proof: &TransactionStorageProof,
+	chunk_len: Option<usize>,
+) -> Result<(), MalformedProof> {
+	let len = proof.chunk.len();
+	let chunk_ok = match chunk_len {
+		Some(expected) => len == expected,
+		None => (1..=CHUNK_SIZE).contains(&len),
+	};
+	frame_support::ensure!(chunk_ok, MalformedProof::Chunk);
+	frame_support::ensure!(!proof.proof.is_empty(), MalformedProof::Empty);
+	frame_support::ensure!(
+		proof.proof.iter().all(|node| node.len() <= MAX_PROOF_NODE_SIZE as usize),
+		MalformedProof::NodeTooLarge
+	);
+	Ok(())
+}
+
+/// Length of the chunk at `chunk_index` of data of `size` bytes.
+fn chunk_len(size: u32, chunk_index: ChunkIndex) -> usize {
+	(size as usize)
+		.saturating_sub(chunk_index.0 as usize * CHUNK_SIZE)
+		.min(CHUNK_SIZE)
+}
+
+/// Finds the transaction holding the given chunk of a block, from the `block_chunks` of the
+/// block's transactions. Returns the index of the transaction and the index of the chunk within
+/// the transaction.
+fn find_chunk(chunk_ends: &[u32], block_chunk: u32) -> Option<(TxIndex, ChunkIndex)> {
+	// The end of a transaction's chunk range is the first chunk of the next transaction
+	let index = match chunk_ends.binary_search(&block_chunk) {
+		Ok(index) => index + 1,
+		Err(index) => index,
+	};
+	chunk_ends.get(index)?;
+	let start = index.checked_sub(1).map_or(0, |prev| chunk_ends[prev]);
+	Some((TxIndex(index as u32), ChunkIndex(block_chunk - start)))
+}
+
+/// Selects the chunk challenged by `random_hash` among the chunks of a block, from the
+/// `block_chunks` of the block's transactions, the way `check_proof` does. Every chunk of the
+/// block is equally likely to be selected. Returns the index of the transaction and the index of
+/// the chunk within the transaction, or `None` if the block has no chunks.
+pub fn select_chunk(random_hash: &[u8], chunk_ends: &[u32]) -> Option<(TxIndex, ChunkIndex)> {
+	let total_chunks = *chunk_ends.last()?;
+	if total_chunks == 0 {
+		return None
+	}
+	find_chunk(chunk_ends, random_chunk(random_hash, total_chunks))
+}
+
+/// Handler of data stored with `store`.
+pub trait OnStored<AccountId> {
+	/// Called once `size` bytes of content `content_hash` have been stored as transaction
+	/// `index` of the current block. `owner` is the signer, if the data was not stored with a
+	/// preimage authorization.
+	fn on_stored(content_hash: Preimage, size: u32, owner: Option<&AccountId>, index: TxIndex);
+
+	/// Weight of `on_stored`, included in the weight of `store`.
+	fn on_stored_weight() -> Weight;
+}
+
+impl<AccountId> OnStored<AccountId> for () {
+	fn on_stored(_: Preimage, _: u32, _: Option<&AccountId>, _: TxIndex) {}
+
+	fn on_stored_weight() -> Weight {
+		Weight::zero()
+	}
+}
+
+/// Handler of data renewed with `renew`.
+pub trait OnRenewed<AccountId> {
+	/// Called once `size` bytes of content `content_hash` have been renewed as transaction
+	/// `index` of the current block. `owner` is the owner of the renewed content, who may not be
+	/// the signer paying for the renewal.
+	fn on_renewed(content_hash: Preimage, size: u32, owner: Option<&AccountId>, index: TxIndex);
+
+	/// Weight of `on_renewed`, included in the weight of `renew`.
+	fn on_renewed_weight() -> Weight;
+}
+
+impl<AccountId> OnRenewed<AccountId> for () {
+	fn on_renewed(_: Preimage, _: u32, _: Option<&AccountId>, _: TxIndex) {}
+
+	fn on_renewed_weight() -> Weight {
+		Weight::zero()
+	}
+}
+
+/// Decides who can renew content owned by an account. The payer of a renewal is its signer, or
+/// `None` with a preimage authorization.
+pub trait RenewalPolicy<AccountId> {
+	/// Whether `payer` can renew content owned by `owner`, or without an owner if `owner` is
+	/// `None`.
+	fn can_renew(payer: Option<&AccountId>, owner: Option<&AccountId>) -> bool;
+}
+
+/// Anyone can renew any content, e.g. archives keeping the content of others alive with their
+/// own authorizations.
+impl<AccountId> RenewalPolicy<AccountId> for () {
+	fn can_renew(_: Option<&AccountId>, _: Option<&AccountId>) -> bool {
+		true
+	}
+}
+
+/// Only the owner of content can renew it. Content without an owner can be renewed by anyone.
+pub struct OwnerOnlyRenewals;
+
+impl<AccountId: PartialEq> RenewalPolicy<AccountId> for OwnerOnlyRenewals {
+	fn can_renew(payer: Option<&AccountId>, owner: Option<&AccountId>) -> bool {
+		owner.map_or(true, |owner| payer == Some(owner))
+	}
+}
+
+/// Sends receipts for data stored with a preimage authorization, e.g. to the chain that requested
+/// the upload over a bridge.
+pub trait SendReceipt<BlockNumber> {
+	/// Called once `size` bytes of content `content_hash` have been stored in block `block`.
+	fn send_receipt(content_hash: Preimage, size: u32, block: BlockNumber);
+
+	/// Weight of `send_receipt`, included in the weight of `store`.
+	fn send_receipt_weight() -> Weight;
 }
 
-fn num_chunks(bytes: u32) -> u32 {
-	((bytes as u64 + CHUNK_SIZE as u64 - 1) / CHUNK_SIZE as u64) as u32
+impl<BlockNumber> SendReceipt<BlockNumber> for () {
+	fn send_receipt(_: Preimage, _: u32, _: BlockNumber) {}
+
+	fn send_receipt_weight() -> Weight {
+		Weight::zero()
+	}
+}
+
+/// Deposit held from the signer of a preimage authorization until the preimage is stored, in
+/// the style of `Consideration`. Returned once the preimage is stored and burned if the
+/// authorization expires unused.
+pub trait PreimageDeposit<AccountId>: Member + FullCodec + TypeInfo + MaxEncodedLen {
+	/// Takes a deposit from `who` for authorizing a preimage of `bytes` bytes.
+	fn new(who: &AccountId, bytes: u64) -> Result<Self, DispatchError>;
+
+	/// Returns the deposit to `who`.
+	fn drop(self, who: &AccountId) -> Result<(), DispatchError>;
+
+	/// Burns the deposit of `who`.
+	fn burn(self, who: &AccountId);
+}
+
+/// Takes no deposit.
+impl<AccountId> PreimageDeposit<AccountId> for () {
+	fn new(_: &AccountId, _: u64) -> Result<Self, DispatchError> {
+		Ok(())
+	}
+
+	fn drop(self, _: &AccountId) -> Result<(), DispatchError> {
+		Ok(())
+	}
+
+	fn burn(self, _: &AccountId) {}
+}
+
+/// `PreimageDeposit` that holds `Base` plus `PerByte` for each authorized byte from the signer's
+/// balance in `F`, with the hold reason `R`.
+#[derive(
+	CloneNoBound,
+	EqNoBound,
+	PartialEqNoBound,
+	RuntimeDebugNoBound,
+	Encode,
+	Decode,
+	scale_info::TypeInfo,
+	MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(A, F, R, Base, PerByte))]
+#[codec(mel_bound())]
+pub struct HoldDeposit<A, F: fungible::MutateHold<A>, R, Base, PerByte>(
+	F::Balance,
+	PhantomData<fn() -> (A, R, Base, PerByte)>,
+);
+
+impl<A, F, R, Base, PerByte> PreimageDeposit<A> for HoldDeposit<A, F, R, Base, PerByte>
+where
+	A: 'static,
+	F: 'static + fungible::MutateHold<A>,
+	R: 'static + Get<F::Reason>,
+	Base: 'static + Get<F::Balance>,
+	PerByte: 'static + Get<F::Balance>,
+{
+	fn new(who: &A, bytes: u64) -> Result<Self, DispatchError> {
+		let amount = PerByte::get()
+			.saturating_mul(F::Balance::saturated_from(bytes))
+			.saturating_add(Base::get());
+		F::hold(&R::get(), who, amount)?;
+		Ok(Self(amount, PhantomData))
+	}
+
+	fn drop(self, who: &A) -> Result<(), DispatchError> {
+		F::release(&R::get(), who, self.0, Precision::BestEffort).map(|_| ())
+	}
+
+	fn burn(self, who: &A) {
+		let _ = F::burn_held(&R::get(), who, self.0, Precision::BestEffort, Fortitude::Force);
+	}
+}
+
+/// Ensures that the origin is signed by an account in the pallet's `Authorizers` set. Can be used
+/// as `Config::Authorizer` in runtimes without a governance pallet. Succeeds with the account.
+pub struct EnsureAuthorizedAccount<T>(PhantomData<T>);
+
+impl<T: Config> EnsureOrigin<T::RuntimeOrigin> for EnsureAuthorizedAccount<T> {
+	type Success = T::AccountId;
+
+	fn try_origin(o: T::RuntimeOrigin) -> Result<Self::Success, T::RuntimeOrigin> {
+		o.into().and_then(|o| match o {
+			RawOrigin::Signed(who) if Authorizers::<T>::contains_key(&who) => Ok(who),
+			r => Err(T::RuntimeOrigin::from(r)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin() -> Result<T::RuntimeOrigin, ()> {
+		let who: T::AccountId = frame_benchmarking::account("authorizer", 0, 0);
+		Authorizers::<T>::insert(&who, ());
+		Ok(RawOrigin::Signed(who).into())
+	}
+}
+
+impl<T: Config> From<StoreRejection> for Error<T> {
+	fn from(rejection: StoreRejection) -> Self {
+		match rejection {
+			StoreRejection::NotAuthorized |
+			StoreRejection::InsufficientBytes |
+			StoreRejection::InsufficientTransactions => Error::NotAuthorized,
+			StoreRejection::TooLarge => Error::TransactionTooLarge,
+			StoreRejection::BlockFull => Error::TooManyTransactions,
+			StoreRejection::Banned => Error::AccountBarred,
+			StoreRejection::Empty => Error::EmptyTransaction,
+			StoreRejection::SizeMismatch => Error::SizeMismatch,
+			StoreRejection::LifetimeCapExceeded => Error::LifetimeCapExceeded,
+			StoreRejection::AlreadyStored => Error::PreimageAlreadyStored,
+			StoreRejection::Removed => Error::ContentRemoved,
+			StoreRejection::AlreadySatisfied => Error::PreimageAlreadySatisfied,
+			StoreRejection::Reserved => Error::PreimageReserved,
+			StoreRejection::Duplicate => Error::DuplicateContent,
+			StoreRejection::TooSmall => Error::TransactionTooSmall,
+		}
+	}
+}
+
+impl<T: Config> From<MalformedProof> for Error<T> {
+	fn from(malformed: MalformedProof) -> Self {
+		match malformed {
+			MalformedProof::Chunk => Error::MalformedProofChunk,
+			MalformedProof::Empty => Error::EmptyProof,
+			MalformedProof::NodeTooLarge => Error::ProofNodeTooLarge,
+		}
+	}
 }
 
-#[frame_support::pallet(dev_mode)]
+#[frame_support::pallet]
 pub mod pallet {
 	use super::*;
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
 
+	/// The current storage version.
+	pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
+
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		/// The overarching event type.
@@ -148,8 +661,101 @@ pub mod pallet {
 		type MaxBlockAuthorizationExpiries: Get<u32>;
 		/// Authorizations expire after this many blocks.
 		type AuthorizationPeriod: Get<BlockNumberFor<Self>>;
+		/// Maximum number of content hashes an account authorization can be restricted to.
+		type MaxAllowedHashes: Get<u32>;
 		/// The origin that can authorize data storage.
 		type Authorizer: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Maximum number of accounts in the `Authorizers` set.
+		type MaxAuthorizers: Get<u32>;
+		/// Maximum number of scheduled content removals executed per block.
+		type MaxBlockRemovals: Get<u32>;
+		/// Maximum fraction of the block weight used to expire authorizations and execute
+		/// scheduled removals. Work that does not fit is carried over to the following blocks.
+		/// While `migrations::v1` is in progress, what is left of it is used to migrate
+		/// `Transactions`.
+		type ExpiryWeightRatio: Get<Perbill>;
+		/// Maximum encoded size of a storage proof in bytes. Must be large enough for the proof
+		/// of any chunk of a `MaxTransactionSize` transaction.
+		type MaxProofSize: Get<u32>;
+		/// Handler of stored and renewed data.
+		type StorageEventHandler: OnStored<Self::AccountId> + OnRenewed<Self::AccountId>;
+		/// Sender of receipts for data stored with a preimage authorization.
+		type StoreReceipt: SendReceipt<BlockNumberFor<Self>>;
+		/// Deposit taken from signed origins authorizing preimages, returned once the preimage
+		/// is stored. `()` takes no deposit.
+		type PreimageDeposit: PreimageDeposit<Self::AccountId>;
+		/// Number of uploads failing authorization checks, as seen by `TrackStoreFailures`,
+		/// after which an account is barred from uploading. Zero disables barring.
+		type StrikeThreshold: Get<u32>;
+		/// Number of blocks an account stays barred for.
+		type BarPeriod: Get<BlockNumberFor<Self>>;
+		/// Maximum number of availability challenges requested per block.
+		type MaxBlockChallenges: Get<u32>;
+		/// Maximum number of tombstones kept for removed content.
+		type MaxTombstones: Get<u32>;
+		/// Maximum number of live content entries in a namespace.
+		type MaxNamespaceEntries: Get<u32>;
+		/// Maximum number of members of a group.
+		type MaxGroupMembers: Get<u32>;
+		/// Whether to deposit a digest item committing to the content stored in each block, see
+		/// `content_commitment`. Runtimes that don't want headers to grow can disable it.
+		type DepositContentDigest: Get<bool>;
+		/// Maximum number of live content entries owned by an account.
+		type MaxOwnedContent: Get<u32>;
+		/// Whether a preimage authorized several times can be stored once per authorization, or
+		/// only once. Stores of the content authorized by account do not count.
+		type PreimagePolicy: Get<PreimageStorePolicy>;
+		/// Whether signed stores use a preimage authorization of the content before the signer's
+		/// own authorizations, or after them. Either is only used if it covers the store.
+		type AuthorizationOrder: Get<ConsumptionOrder>;
+		/// Maximum number of authorizations pending expiry across all blocks. Bounds the number
+		/// of blocks whose expiries are full, including those pushed later by full blocks.
+		type MaxOutstandingAuthorizations: Get<u32>;
+		/// Maximum number of authorizations issued in a single block. Keeping this at most
+		/// `MaxBlockAuthorizationExpiries` lets the authorizations of a block expire together,
+		/// rather than spilling over into later blocks.
+		type MaxAuthorizationsPerBlock: Get<u32>;
+		/// Number of blocks after a reserved preimage authorization during which only the
+		/// reserved account can store the preimage.
+		type ReservationPeriod: Get<BlockNumberFor<Self>>;
+		/// Number of authorization periods, including the current one, whose usage is kept in
+		/// the usage history of each account.
+		type MaxUsageHistory: Get<u32>;
+		/// Number of the most recent authorization grants kept in `RecentAuthorizations`, for
+		/// verifiers that can only read state proofs. Zero keeps none.
+		type MaxRecentAuthorizations: Get<u32>;
+		/// Whether content hashes are live at most once. If `true`, storing content that is
+		/// stored and not expired fails with `DuplicateContent`, and `renew` is the only way to
+		/// extend it. Live content is looked up in the index of proof check counts, which misses
+		/// content stored before those were counted and not renewed since, so this should only
+		/// be enabled once such content has expired.
+		type StrictDedup: Get<bool>;
+		/// Number of stores by the `Authorizer` origin allowed per block through
+		/// `store_via_authorizer`. Other stores and renewals leave this many transactions of
+		/// the block free while the allowance is unused.
+		type AuthorizerStoresPerBlock: Get<u32>;
+		/// Maximum number of blocks the unrestricted account authorizations of an account can be
+		/// pending expiry in, which bounds the work of revoking them. Authorizations granted in
+		/// the same block expire in the same block.
+		type MaxAccountAuthorizationExpiries: Get<u32>;
+		/// Who can renew content owned by an account other than the payer. `()` lets anyone
+		/// renew any content, `OwnerOnlyRenewals` only its owner.
+		type RenewalPolicy: RenewalPolicy<Self::AccountId>;
+		/// Minimum data set in a single transaction in bytes. Smaller stores fail with
+		/// `TransactionTooSmall`.
+		type MinTransactionSize: Get<u32>;
+		/// Minimum number of bytes of account authorizations charged for each store or renewal,
+		/// so that stores of a few bytes, which take as much of a block's transactions as any,
+		/// still use up a meaningful part of them. Preimage authorizations are charged the size
+		/// of their preimage.
+		type MinBytesPerStore: Get<u32>;
+		/// Number of blocks before the last block content can be renewed in from which the
+		/// `renewal_deadlines` runtime API recommends renewing it, leaving time for the renewal
+		/// to be included and finalized.
+		type RenewalLeadTime: Get<BlockNumberFor<Self>>;
+		/// Maximum number of authorizations of a single scope with unused extent left, which
+		/// stores use in the order they expire in.
+		type MaxScopeAuthorizations: Get<u32>;
 	}
 
 	#[pallet::error]
@@ -174,147 +780,420 @@ pub mod pallet {
 		TooManyTransactions,
 		/// Attempted to call `store` outside of block execution.
 		BadContext,
+		/// Data was already indexed by the current extrinsic. Only one `store` or `renew` can
+		/// be executed per extrinsic, e.g. they cannot be batched.
+		AlreadyIndexedInExtrinsic,
+		/// Data can only be stored or renewed by an extrinsic. Calls dispatched outside of
+		/// extrinsics, e.g. by the scheduler, cannot index data.
+		NotInExtrinsic,
+		/// Storing the data would exceed the account's lifetime cap.
+		LifetimeCapExceeded,
+		/// The size of the data does not match the exact size it was authorized for.
+		SizeMismatch,
+		/// Too many content hashes in the account's allowed hashes list.
+		TooManyAllowedHashes,
+		/// The account is already in the authorizers set.
+		AlreadyAuthorizer,
+		/// The account is not in the authorizers set.
+		NotAuthorizer,
+		/// The authorizers set is full.
+		TooManyAuthorizers,
+		/// The content has been removed.
+		ContentRemoved,
+		/// The content is scheduled for removal.
+		RemovalPending,
+		/// The content is not scheduled for removal.
+		RemovalNotScheduled,
+		/// Too many removals are already scheduled for the target block.
+		TooManyRemovals,
+		/// The storage period of the renewed data is over.
+		RenewalExpired,
+		/// Too many chunks in the block.
+		TooManyChunks,
+		/// The storage proof is larger than `MaxProofSize`.
+		ProofTooLarge,
+		/// The account is barred from uploading.
+		AccountBarred,
+		/// Challenged content is not found.
+		ChallengedNotFound,
+		/// Too many challenges were requested in the block.
+		TooManyChallenges,
+		/// The account already requested a challenge in the block.
+		ChallengeAlreadyRequested,
+		/// The data does not match the stored entry.
+		VerificationFailed,
+		/// No tombstone is kept for the content.
+		NoTombstone,
+		/// The namespace has `MaxNamespaceEntries` live entries.
+		NamespaceFull,
+		/// The content is listed under another namespace.
+		NamespaceMismatch,
+		/// The group does not exist.
+		UnknownGroup,
+		/// The account is already a member of a group.
+		AlreadyInGroup,
+		/// The account is not a member of the group.
+		NotGroupMember,
+		/// The group has `MaxGroupMembers` members.
+		TooManyGroupMembers,
+		/// The preimage authorization was already used to store the content, e.g. by a
+		/// competing upload.
+		PreimageAlreadyStored,
+		/// The account owns `MaxOwnedContent` live content entries.
+		TooMuchOwnedContent,
+		/// The chunk of the storage proof is empty or does not have the length of the challenged
+		/// chunk.
+		MalformedProofChunk,
+		/// The storage proof has no trie nodes.
+		EmptyProof,
+		/// A trie node of the storage proof is larger than any chunk trie node.
+		ProofNodeTooLarge,
+		/// The preimage was already stored with preimage authorizations, which are honoured once
+		/// under the `Strict` preimage policy.
+		PreimageAlreadySatisfied,
+		/// The content to renew does not have the expected hash.
+		UnexpectedContent,
+		/// `MaxOutstandingAuthorizations` authorizations are pending expiry.
+		TooManyAuthorizations,
+		/// `MaxAuthorizationsPerBlock` authorizations were already issued in this block.
+		TooManyBlockAuthorizations,
+		/// The preimage is reserved for another account until its reservation ends.
+		PreimageReserved,
+		/// An exact preimage size exceeds `MaxTransactionSize`, so no transaction could match it.
+		ExactSizeTooLarge,
+		/// The content is already stored and not expired, and duplicates are rejected.
+		DuplicateContent,
+		/// `AuthorizerStoresPerBlock` stores by the authorizer were already included in this
+		/// block.
+		TooManyAuthorizerStores,
+		/// The storage quota of the account does not cover the extent, see `StorageQuota`.
+		InsufficientQuota,
+		/// The account authorizations of the account are pending expiry in
+		/// `MaxAccountAuthorizationExpiries` blocks.
+		TooManyAccountAuthorizations,
+		/// The `RenewalPolicy` does not let the payer renew the content of its owner.
+		RenewalNotAllowed,
+		/// Transaction is smaller than `MinTransactionSize`.
+		TransactionTooSmall,
+		/// `MaxScopeAuthorizations` authorizations of the scope have unused extent left.
+		TooManyScopeAuthorizations,
+		/// Data small enough to be kept inline was stored with an owner, which inline data
+		/// cannot have.
+		InlineDataHasNoOwner,
 	}
 
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
-			let mut weight = Weight::zero();
-			let db_weight = T::DbWeight::get();
-
 			// Drop obsolete roots. The proof for `obsolete` will be checked later
 			// in this block, so we drop `obsolete` - 1.
-			weight += db_weight.reads(1);
+			let mut weight = T::WeightInfo::on_initialize_pruning();
 			let period = <StoragePeriod<T>>::get();
 			let obsolete = n.saturating_sub(period.saturating_add(One::one()));
 			if obsolete > Zero::zero() {
-				weight += db_weight.writes(2);
 				<Transactions<T>>::remove(obsolete);
+				let removed = <TransactionInfos<T>>::clear_prefix(
+					obsolete,
+					T::MaxBlockTransactions::get(),
+					None,
+				);
+				weight +=
+					T::DbWeight::get().reads_writes(removed.loops.into(), removed.unique.into());
+				<ChunkEnds<T>>::remove(obsolete);
 				<ChunkCount<T>>::remove(obsolete);
+				// Proof statuses are kept until the block the proof was due in is obsolete
+				<ProofStatuses<T>>::remove(obsolete.saturating_sub(period));
+				weight += T::DbWeight::get().writes(1);
+				weight += Self::prune_compressed(obsolete);
+				weight += Self::prune_challenge_counts(obsolete);
+				weight += Self::prune_namespaced(obsolete);
+				weight += Self::prune_owned(obsolete);
+				weight += Self::prune_inline(obsolete, n);
 			}
 
-			weight += Self::expire_authorizations(n);
+			let expiry_weight = Self::process_expiries(n);
+			weight += expiry_weight;
+			let limit = T::ExpiryWeightRatio::get() * T::BlockWeights::get().max_block;
+			weight += migrations::v1::step::<T>(limit.saturating_sub(expiry_weight));
 
-			// For `on_finalize`
-			weight += db_weight.reads_writes(2, 2);
+			// For `on_finalize`, which doesn't know the number of transactions yet
+			weight += Self::on_finalize_weight(T::MaxBlockTransactions::get());
 
 			weight
 		}
 
 		fn on_finalize(n: BlockNumberFor<T>) {
+			// Proof is not required for early or empty blocks.
+			let target_number = n.saturating_sub(<StoragePeriod<T>>::get());
+			if !target_number.is_zero() && <ChunkCount<T>>::get(target_number) != 0 {
+				let status = <ProofStatuses<T>>::get(target_number).unwrap_or_else(|| {
+					// No proof can be checked without the transactions of the block, which must
+					// not stop block production until `repair_block_metadata` is called
+					let status = match Self::chunk_ends(target_number) {
+						Some(_) => ProofStatus::Missed,
+						None => {
+							Self::deposit_event(Event::ProofUnprovable { block: target_number });
+							ProofStatus::Unprovable
+						},
+					};
+					<ProofStatuses<T>>::insert(target_number, status);
+					status
+				});
+				assert!(
+					matches!(status, ProofStatus::Proven | ProofStatus::Unprovable),
+					"Storage proof must be checked once in the block"
+				);
+			}
 			assert!(
-				<ProofChecked<T>>::take() || {
-					// Proof is not required for early or empty blocks.
-					let number = <frame_system::Pallet<T>>::block_number();
-					let period = <StoragePeriod<T>>::get();
-					let target_number = number.saturating_sub(period);
-					target_number.is_zero() || <ChunkCount<T>>::get(target_number) == 0
-				},
-				"Storage proof must be checked once in the block"
+				!<Challenges<T>>::contains_key(n),
+				"Challenged content must be proved in the block"
 			);
+			<IndexedExtrinsic<T>>::kill();
+			// The per-block limits are only kept for the block, so they never reach the state
+			BlockAuthorizations::<T>::kill();
+			AuthorizerStores::<T>::kill();
 			// Insert new transactions
-			let transactions = <BlockTransactions<T>>::take();
-			let total_chunks = transactions.last().map_or(0, |t| t.block_chunks);
+			let total_chunks = <BlockChunks<T>>::take();
+			let count = <BlockTransactions<T>>::count();
+			let mut chunk_ends = Vec::with_capacity(count as usize);
+			let mut contents = Vec::new();
+			for index in 0..count {
+				if let Some(info) = <BlockTransactions<T>>::take(index) {
+					chunk_ends.push(info.block_chunks);
+					if T::DepositContentDigest::get() {
+						contents.push((info.content_hash, info.size, info.chunk_root));
+					}
+					<TransactionInfos<T>>::insert(n, index, info);
+				}
+			}
 			if total_chunks != 0 {
 				<ChunkCount<T>>::insert(n, total_chunks);
-				<Transactions<T>>::insert(n, transactions);
+				<ChunkEnds<T>>::insert(n, BoundedVec::truncate_from(chunk_ends));
 			}
+			if !contents.is_empty() {
+				<frame_system::Pallet<T>>::deposit_log(DigestItem::Consensus(
+					CONTENT_COMMITMENT_ENGINE_ID,
+					content_commitment(&contents).encode(),
+				));
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_n: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+			Self::do_try_state()
+		}
+
+		fn integrity_test() {
+			// Clients chunk data as the proofs do
+			assert_eq!(
+				CHUNK_SIZE,
+				sp_transaction_storage_proof::CHUNK_SIZE,
+				"`primitives::CHUNK_SIZE` differs from the chunk size of storage proofs"
+			);
+			// Stored data is sliced and compared by `usize` lengths
+			assert!(
+				usize::try_from(T::MaxTransactionSize::get()).is_ok(),
+				"`MaxTransactionSize` does not fit in a `usize`"
+			);
+			// Nor can any store be too small, nor charged more bytes than the largest one
+			assert!(
+				T::MinTransactionSize::get() <= T::MaxTransactionSize::get(),
+				"`MinTransactionSize` is larger than `MaxTransactionSize`"
+			);
+			assert!(
+				T::MinBytesPerStore::get() <= T::MaxTransactionSize::get(),
+				"`MinBytesPerStore` is larger than `MaxTransactionSize`"
+			);
+			// Chunks are counted with `u32`s
+			assert!(
+				u64::from(T::MaxBlockTransactions::get()) *
+					u64::from(num_chunks(T::MaxTransactionSize::get())) <=
+					u64::from(u32::MAX),
+				"The chunks of `MaxBlockTransactions` transactions cannot be counted"
+			);
+			// Nor can honest proofs be rejected
+			assert!(
+				max_proof_size(T::MaxTransactionSize::get()) <= T::MaxProofSize::get(),
+				"`MaxProofSize` is too small for the proofs of `MaxTransactionSize` transactions"
+			);
+			// The expiry work of a block must fit, or later expiries would never be processed
+			let block_weights = T::BlockWeights::get();
+			let max_block = block_weights.max_block;
+			assert!(
+				Self::expiry_weight(
+					T::MaxBlockAuthorizationExpiries::get(),
+					T::MaxBlockRemovals::get()
+				)
+				.all_lte(T::ExpiryWeightRatio::get() * max_block),
+				"The expiry work of a block does not fit in `ExpiryWeightRatio` of a block"
+			);
+			// Authorizer stores must leave room for the others
+			assert!(
+				T::AuthorizerStoresPerBlock::get() < T::MaxBlockTransactions::get(),
+				"`AuthorizerStoresPerBlock` leaves no room for other transactions"
+			);
+			// Storage limits that cannot be reached in a block are misleading
+			let normal_weights = block_weights.get(DispatchClass::Normal);
+			let max_extrinsic = normal_weights.max_extrinsic.unwrap_or(max_block);
+			let max_total = normal_weights.max_total.unwrap_or(max_block);
+			let normal_length = *T::BlockLength::get().max.get(DispatchClass::Normal);
+			assert!(
+				T::MaxTransactionSize::get() <= normal_length,
+				"`MaxTransactionSize` does not fit in the block length of a normal extrinsic"
+			);
+			assert!(
+				u64::from(T::MaxBlockTransactions::get()) * u64::from(T::MinTransactionSize::get()) <=
+					u64::from(normal_length),
+				"`MaxBlockTransactions` transactions do not fit in the block length"
+			);
+			// A block stores at most `MaxBlockTransactions * MaxTransactionSize` bytes, and no more
+			// than its normal extrinsics can carry
+			let block_bytes = (u64::from(T::MaxBlockTransactions::get()) *
+				u64::from(T::MaxTransactionSize::get()))
+			.min(normal_length.into()) as u32;
+			// The proof inherent is mandatory, so a block that cannot include it cannot be built.
+			// Under block production with a PoV budget, this length counts towards the PoV.
+			assert!(
+				T::MaxProofSize::get() <= *T::BlockLength::get().max.get(DispatchClass::Mandatory),
+				"`MaxProofSize` does not fit in the block length of a mandatory extrinsic"
+			);
+			assert!(
+				Self::store_weight(T::MaxTransactionSize::get()).all_lte(max_extrinsic),
+				"Storing `MaxTransactionSize` bytes does not fit in the weight of an extrinsic"
+			);
+			assert!(
+				Self::store_weight(0)
+					.saturating_mul(T::MaxBlockTransactions::get().saturating_sub(1).into())
+					.saturating_add(Self::store_weight(block_bytes))
+					.all_lte(max_total),
+				"`MaxBlockTransactions` stores of a full block do not fit in the normal weight"
+			);
+			// Nor can the mandatory work of a block: pruning, expiries up to `ExpiryWeightRatio`,
+			// finalizing a full block of transactions and checking the proof of another
+			let worst_case = T::WeightInfo::on_initialize_pruning()
+				.saturating_add(T::DbWeight::get().writes(T::MaxBlockTransactions::get().into()))
+				.saturating_add(T::ExpiryWeightRatio::get() * max_block)
+				.saturating_add(Self::on_finalize_weight(T::MaxBlockTransactions::get()))
+				.saturating_add(Self::check_proof_weight(T::MaxBlockTransactions::get()))
+				.saturating_add(block_weights.get(DispatchClass::Mandatory).base_extrinsic)
+				.saturating_add(block_weights.base_block);
+			assert!(
+				worst_case.all_lte(max_block),
+				"The mandatory work of a full block and expiry backlog does not fit in a block"
+			);
 		}
 	}
 
+	// Wallets and bridges encode calls by their index, so call indices are stable: a new call
+	// takes the next free index, a removed call leaves its index unused, and an incompatible
+	// change of the arguments of a call is made under a new index. The encoding of existing calls
+	// is checked against fixtures in `tests::calls_decode_from_fixtures`.
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Index and store data off chain. Minimum data size is 1 bytes, maximum is
 		/// `MaxTransactionSize`. Data will be removed after `STORAGE_PERIOD` blocks, unless `renew`
-		/// is called.
+		/// is called. At most one `store` or `renew` can be executed per extrinsic, so these calls
+		/// cannot be batched.
+		///
+		/// If `namespace` is given, the content is listed under it until its storage period, as
+		/// extended by renewals, is over. Content is listed under a single namespace at a time.
+		///
+		/// Signed stores of content with a preimage authorization can use it, so preimage
+		/// authorizations don't require unsigned transactions. It is used before or after the
+		/// signer's own authorizations, as set by `AuthorizationOrder`.
+		///
+		/// If `StrictDedup` is enabled, storing content that is stored and not expired fails with
+		/// `DuplicateContent`, even within the same block. Use `renew` to extend it instead.
+		///
+		/// Authorized calls do not pay fees, failed ones do. With transaction payment, the fee is
+		/// withdrawn up front and refunded once the authorization has been used.
 		/// ## Complexity
 		/// - O(n*log(n)) of data size, as all data is pushed to an in-memory trie.
 		#[pallet::call_index(0)]
-		#[pallet::weight(T::WeightInfo::store(data.len() as u32))]
-		pub fn store(origin: OriginFor<T>, data: Vec<u8>) -> DispatchResult {
-			ensure!(!data.is_empty(), Error::<T>::EmptyTransaction);
-			ensure!(
-				data.len() <= T::MaxTransactionSize::get() as usize,
-				Error::<T>::TransactionTooLarge
-			);
-			let content_hash = sp_io::hashing::blake2_256(&data);
-
-			Self::use_authorization(origin, content_hash, data.len() as u32)?;
-
-			// Chunk data and compute storage root
-			let chunk_count = num_chunks(data.len() as u32);
-			let chunks = data.chunks(CHUNK_SIZE).map(|c| c.to_vec()).collect();
-			let root = sp_io::trie::blake2_256_ordered_root(chunks, sp_runtime::StateVersion::V1);
-
-			let extrinsic_index =
-				<frame_system::Pallet<T>>::extrinsic_index().ok_or(Error::<T>::BadContext)?;
-			sp_io::transaction_index::index(extrinsic_index, data.len() as u32, content_hash);
-
-			let mut index = 0;
-			<BlockTransactions<T>>::mutate(|transactions| {
-				if transactions.len() + 1 > T::MaxBlockTransactions::get() as usize {
-					return Err(Error::<T>::TooManyTransactions)
-				}
-				let total_chunks = transactions.last().map_or(0, |t| t.block_chunks) + chunk_count;
-				index = transactions.len() as u32;
-				transactions
-					.try_push(TransactionInfo {
-						chunk_root: root,
-						size: data.len() as u32,
-						content_hash: content_hash.into(),
-						block_chunks: total_chunks,
-					})
-					.map_err(|_| Error::<T>::TooManyTransactions)?;
-				Ok(())
-			})?;
-			Self::deposit_event(Event::Stored { index });
-			Ok(())
+		#[pallet::weight(
+			Self::store_weight(data.len().saturated_into())
+				.saturating_add(Self::namespace_weight(namespace.is_some()))
+		)]
+		pub fn store(
+			origin: OriginFor<T>,
+			data: Vec<u8>,
+			namespace: Option<Namespace>,
+		) -> DispatchResultWithPostInfo {
+			let (content_hash, index, mut weight) = Self::do_store(origin, &data)?;
+			if let Some(namespace) = namespace {
+				let block = frame_system::Pallet::<T>::block_number();
+				Self::note_namespace(content_hash, namespace, block)?;
+				Self::deposit_content_event(
+					content_hash,
+					Event::StoredInNamespace { index, namespace },
+				);
+				weight.saturating_accrue(Self::namespace_weight(true));
+			}
+			Ok((Some(weight), Pays::No).into())
 		}
 
 		/// Renew previously stored data. Parameters are the block number that contains
 		/// previous `store` or `renew` call and transaction index within that block.
 		/// Transaction index is emitted in the `Stored` or `Renewed` event.
-		/// Requires same authorization as `store`.
+		/// Requires same authorization as `store`, and is free of fees in the same way.
+		///
+		/// Data stored in block `n` can be renewed up to and including block
+		/// `n + StoragePeriod`, the block in which its storage proof is checked. Later renewals
+		/// fail with `RenewalExpired`. Renewing does not release the original entry, which is
+		/// still challenged in block `n + StoragePeriod`.
+		///
+		/// If `expected_hash` is given, the call fails with `UnexpectedContent` unless it is the
+		/// hash of the content at `block` and `index`, so that a wrong index does not renew, and
+		/// spend authorization on, some other content.
+		///
+		/// The renewal is paid for by the authorization of the caller, who need not own the
+		/// content: owned content keeps its owner, and content without an owner becomes owned by
+		/// the caller. Renewals of the content of others fail with `RenewalNotAllowed` unless the
+		/// `RenewalPolicy` allows them.
 		/// ## Complexity
-		/// - O(1).
+		/// - Linear w.r.t the number of transactions in the source block.
 		#[pallet::call_index(1)]
-		#[pallet::weight(T::WeightInfo::renew())]
+		#[pallet::weight(Self::renew_weight(T::MaxBlockTransactions::get()))]
 		pub fn renew(
 			origin: OriginFor<T>,
 			block: BlockNumberFor<T>,
-			index: u32,
+			index: TxIndex,
+			expected_hash: Option<Preimage>,
 		) -> DispatchResultWithPostInfo {
-			let transactions = <Transactions<T>>::get(block).ok_or(Error::<T>::RenewedNotFound)?;
-			let info = transactions.get(index as usize).ok_or(Error::<T>::RenewedNotFound)?;
+			let extrinsic_index = Self::ensure_extrinsic_context()?;
+			let (info, src_len) = Self::stored_transaction(block, index).ok_or_else(|| {
+				// Entries are pruned once their storage period is over
+				let now = <frame_system::Pallet<T>>::block_number();
+				if block < now.saturating_sub(<StoragePeriod<T>>::get()) {
+					Error::<T>::RenewalExpired
+				} else {
+					Error::<T>::RenewedNotFound
+				}
+			})?;
+			let content_hash = info.content_hash.into();
+			ensure!(
+				expected_hash.map_or(true, |hash| hash == content_hash),
+				Error::<T>::UnexpectedContent
+			);
+			ensure!(!RemovedContent::<T>::contains_key(content_hash), Error::<T>::ContentRemoved);
+			ensure!(!PendingRemovals::<T>::contains_key(content_hash), Error::<T>::RemovalPending);
 
-			Self::use_authorization(origin, info.content_hash.into(), info.size)?;
+			let payer = Self::use_authorization(origin, content_hash, info.size, true)?;
+			let owner = Self::content_owner(block, content_hash);
+			ensure!(
+				T::RenewalPolicy::can_renew(payer.as_ref(), owner.as_ref()),
+				Error::<T>::RenewalNotAllowed
+			);
 
-			let extrinsic_index =
-				<frame_system::Pallet<T>>::extrinsic_index().ok_or(Error::<T>::BadContext)?;
+			Self::note_indexed_extrinsic(extrinsic_index);
 			sp_io::transaction_index::renew(extrinsic_index, info.content_hash.into());
 
-			let mut index = 0;
-			<BlockTransactions<T>>::mutate(|transactions| {
-				if transactions.len() + 1 > T::MaxBlockTransactions::get() as usize {
-					return Err(Error::<T>::TooManyTransactions)
-				}
-				let chunks = num_chunks(info.size);
-				let total_chunks = transactions.last().map_or(0, |t| t.block_chunks) + chunks;
-				index = transactions.len() as u32;
-				transactions
-					.try_push(TransactionInfo {
-						chunk_root: info.chunk_root,
-						size: info.size,
-						content_hash: info.content_hash,
-						block_chunks: total_chunks,
-					})
-					.map_err(|_| Error::<T>::TooManyTransactions)
-			})?;
-			Self::deposit_event(Event::Renewed { index });
-			Ok(().into())
+			Self::note_renewal(&info, owner, payer)?;
+			Ok((Some(Self::renew_weight(src_len)), Pays::No).into())
 		}
 
 		/// Check storage proof for block number `block_number() - StoragePeriod`.
@@ -324,77 +1203,667 @@ pub mod pallet {
 		///   probing.
 		/// There's a DB read for each transaction.
 		#[pallet::call_index(2)]
-		#[pallet::weight((T::WeightInfo::check_proof_max(), DispatchClass::Mandatory))]
+		#[pallet::weight((
+			Self::check_proof_weight(T::MaxBlockTransactions::get()),
+			DispatchClass::Mandatory
+		))]
 		pub fn check_proof(
 			origin: OriginFor<T>,
 			proof: TransactionStorageProof,
 		) -> DispatchResultWithPostInfo {
 			ensure_none(origin)?;
-			ensure!(!ProofChecked::<T>::get(), Error::<T>::DoubleCheck);
-			let number = <frame_system::Pallet<T>>::block_number();
-			let period = <StoragePeriod<T>>::get();
-			let target_number = number.saturating_sub(period);
-			ensure!(!target_number.is_zero(), Error::<T>::UnexpectedProof);
-			let total_chunks = <ChunkCount<T>>::get(target_number);
-			ensure!(total_chunks != 0, Error::<T>::UnexpectedProof);
-			let parent_hash = <frame_system::Pallet<T>>::parent_hash();
-			let selected_chunk_index = random_chunk(parent_hash.as_ref(), total_chunks);
-			let (info, chunk_index) = match <Transactions<T>>::get(target_number) {
-				Some(infos) => {
-					let index = match infos
-						.binary_search_by_key(&selected_chunk_index, |info| info.block_chunks)
-					{
-						Ok(index) => index,
-						Err(index) => index,
-					};
-					let info = infos.get(index).ok_or(Error::<T>::MissingStateData)?.clone();
-					let chunks = num_chunks(info.size);
-					let prev_chunks = info.block_chunks - chunks;
-					(info, selected_chunk_index - prev_chunks)
-				},
-				None => return Err(Error::<T>::MissingStateData.into()),
-			};
-			ensure!(
-				sp_io::trie::blake2_256_verify_proof(
-					info.chunk_root,
-					&proof.proof,
-					&encode_index(chunk_index),
-					&proof.chunk,
-					sp_runtime::StateVersion::V1,
-				),
-				Error::<T>::InvalidProof
-			);
-			ProofChecked::<T>::put(true);
-			Self::deposit_event(Event::ProofChecked);
-			Ok(().into())
+			let transaction_count = Self::do_check_proof(proof)?;
+			Ok(Some(Self::check_proof_weight(transaction_count)).into())
 		}
 
 		/// Authorize the given account to store the given amount of arbitrary data. The
 		/// authorization will expire after a configured number of blocks.
+		///
+		/// If `allowed_hashes` is provided, the authorization can only be used to store data with
+		/// one of the given content hashes. Restricted authorizations of an account are pooled,
+		/// as are their allowed hashes; stores of other data fall back to the account's
+		/// unrestricted authorizations.
 		#[pallet::call_index(3)]
-		#[pallet::weight(1)] // TODO
+		#[pallet::weight(T::WeightInfo::authorize_account())]
 		pub fn authorize_account(
 			origin: OriginFor<T>,
 			who: T::AccountId,
 			transactions: u32,
 			bytes: u64,
+			allowed_hashes: Option<BoundedVec<Preimage, T::MaxAllowedHashes>>,
 		) -> DispatchResult {
 			T::Authorizer::ensure_origin(origin)?;
-			Self::authorize(AuthorizationScope::Account(who), transactions, bytes);
-			Ok(())
-		}
+			let scope = match allowed_hashes {
+				Some(allowed_hashes) => {
+					AllowedHashes::<T>::try_mutate(&who, |list| {
+						for hash in allowed_hashes {
+							if !list.contains(&hash) {
+								list.try_push(hash)
+									.map_err(|_| Error::<T>::TooManyAllowedHashes)?;
+							}
+						}
+						Ok::<_, Error<T>>(())
+					})?;
+					AuthorizationScope::RestrictedAccount(who)
+				},
+				None => AuthorizationScope::Account(who),
+			};
+			Self::authorize(scope, transactions, bytes, None)
+		}
 
-		/// Authorize anyone to store a blob up to the given size with the given preimage. The
-		/// authorization will expire after a configured number of blocks.
+		/// Authorize anyone to store a blob up to the given size with the given preimage. If
+		/// `exact_size` is `true`, the blob must be exactly `bytes` long, which fails with
+		/// `ExactSizeTooLarge` above `MaxTransactionSize`. The most recent authorization of a
+		/// preimage determines whether its size must match exactly. The authorization will expire
+		/// after a configured number of blocks.
+		///
+		/// A signed origin pays a `PreimageDeposit`, returned once the preimage is stored and
+		/// burned if the authorization expires unused.
 		#[pallet::call_index(4)]
-		#[pallet::weight(1)] // TODO
+		#[pallet::weight(
+			T::WeightInfo::authorize_preimage().saturating_add(Pallet::<T>::deposit_weight())
+		)]
 		pub fn authorize_preimage(
 			origin: OriginFor<T>,
 			preimage: Preimage,
 			bytes: u64,
+			exact_size: bool,
+		) -> DispatchResult {
+			let depositor = ensure_signed(origin.clone()).ok();
+			T::Authorizer::ensure_origin(origin)?;
+			Self::authorize_preimage_with_deposit(
+				depositor.as_ref(),
+				preimage,
+				bytes,
+				exact_size,
+				None,
+			)
+		}
+
+		/// Like `authorize_preimage`, for each of the given preimages and sizes.
+		#[pallet::call_index(13)]
+		#[pallet::weight(
+			T::WeightInfo::authorize_preimages(preimages.len() as u32).saturating_add(
+				Pallet::<T>::deposit_weight().saturating_mul(preimages.len() as u64)
+			)
+		)]
+		pub fn authorize_preimages(
+			origin: OriginFor<T>,
+			preimages: BoundedVec<(Preimage, u64), T::MaxBlockAuthorizationExpiries>,
+			exact_size: bool,
+		) -> DispatchResult {
+			let depositor = ensure_signed(origin.clone()).ok();
+			T::Authorizer::ensure_origin(origin)?;
+			for (preimage, bytes) in preimages {
+				Self::authorize_preimage_with_deposit(
+					depositor.as_ref(),
+					preimage,
+					bytes,
+					exact_size,
+					None,
+				)?;
+			}
+			Ok(())
+		}
+
+		/// Like `authorize_preimage`, reserving the preimage for `reserved_for`: during the first
+		/// `ReservationPeriod` blocks of the authorization, only `reserved_for` can store the
+		/// preimage, with a signed transaction, so that third parties who learn the data cannot
+		/// front-run its upload. Afterwards anyone can store it. The reservation is kept until the
+		/// authorizations of the preimage expire.
+		#[pallet::call_index(25)]
+		#[pallet::weight(
+			T::WeightInfo::authorize_preimage()
+				.saturating_add(Pallet::<T>::deposit_weight())
+				.saturating_add(T::DbWeight::get().writes(1))
+		)]
+		pub fn authorize_reserved_preimage(
+			origin: OriginFor<T>,
+			preimage: Preimage,
+			bytes: u64,
+			exact_size: bool,
+			reserved_for: T::AccountId,
+		) -> DispatchResult {
+			let depositor = ensure_signed(origin.clone()).ok();
+			T::Authorizer::ensure_origin(origin)?;
+			Self::authorize_preimage_with_deposit(
+				depositor.as_ref(),
+				preimage,
+				bytes,
+				exact_size,
+				Some(reserved_for),
+			)
+		}
+
+		/// Revoke the unused extent of the (unrestricted) account authorizations of the given
+		/// account, including the quota held from them. The extent they already used is kept
+		/// until they expire, so data stored with them is not affected.
+		#[pallet::call_index(27)]
+		#[pallet::weight(T::WeightInfo::revoke_account_authorization(
+			T::MaxAccountAuthorizationExpiries::get()
+		))]
+		pub fn revoke_account_authorization(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			T::Authorizer::ensure_origin(origin)?;
+			let scope = AuthorizationScope::Account(who.clone());
+			let mut extent = AuthorizationExtent::default();
+			AuthorizationUsageByScope::<T>::mutate_exists(&scope, |usage_slot| {
+				if let Some(usage) = usage_slot {
+					extent = sp_std::mem::take(&mut usage.unused);
+					if usage.used == Default::default() {
+						*usage_slot = None;
+					}
+				}
+			});
+			let blocks = Self::shrink_account_authorizations(&who);
+			HeldQuota::<T>::remove(&who);
+			Self::deposit_event(Event::AuthorizationRevoked { who, extent });
+			Ok(Some(T::WeightInfo::revoke_account_authorization(blocks)).into())
+		}
+
+		/// Give the given account a standing quota of arbitrary data storage. At the start of
+		/// every authorization period, the account's remaining standing quota is reset to the
+		/// given amount. Standing quotas are additive with authorizations granted by
+		/// `authorize_account`, and are used before unrestricted ones. Replaces any existing
+		/// standing quota for the account.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::set_standing_quota())]
+		pub fn set_standing_quota(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+			transactions: u32,
+			bytes: u64,
+		) -> DispatchResult {
+			T::Authorizer::ensure_origin(origin)?;
+			let period = Self::current_authorization_period().unwrap_or_else(Zero::zero);
+			StandingQuotas::<T>::insert(
+				&who,
+				StandingQuota {
+					per_period: AuthorizationExtent { transactions, bytes },
+					remaining: AuthorizationExtent { transactions, bytes },
+					period,
+				},
+			);
+			Self::deposit_event(Event::StandingQuotaSet { who, transactions, bytes });
+			Ok(())
+		}
+
+		/// Remove the standing quota of the given account. Authorizations granted by
+		/// `authorize_account` are not affected.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::clear_standing_quota())]
+		pub fn clear_standing_quota(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::Authorizer::ensure_origin(origin)?;
+			StandingQuotas::<T>::remove(&who);
+			Self::deposit_event(Event::StandingQuotaCleared { who });
+			Ok(())
+		}
+
+		/// Set or remove the lifetime cap of the given account. This bounds the total number of
+		/// bytes the account can ever store or renew, regardless of its authorizations. Bytes
+		/// stored before the cap was set count towards it.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::set_lifetime_cap())]
+		pub fn set_lifetime_cap(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+			cap: Option<u64>,
+		) -> DispatchResult {
+			T::Authorizer::ensure_origin(origin)?;
+			LifetimeCaps::<T>::set(&who, cap);
+			Self::deposit_event(Event::LifetimeCapSet { who, cap });
+			Ok(())
+		}
+
+		/// Add the given account to the authorizers set, allowing it to pass the
+		/// `EnsureAuthorizedAccount` origin check.
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::add_authorizer())]
+		pub fn add_authorizer(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(!Authorizers::<T>::contains_key(&who), Error::<T>::AlreadyAuthorizer);
+			ensure!(
+				Authorizers::<T>::count() < T::MaxAuthorizers::get(),
+				Error::<T>::TooManyAuthorizers
+			);
+			Authorizers::<T>::insert(&who, ());
+			Self::deposit_event(Event::AuthorizerAdded { who });
+			Ok(())
+		}
+
+		/// Remove the given account from the authorizers set.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::remove_authorizer())]
+		pub fn remove_authorizer(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(Authorizers::<T>::contains_key(&who), Error::<T>::NotAuthorizer);
+			Authorizers::<T>::remove(&who);
+			Self::deposit_event(Event::AuthorizerRemoved { who });
+			Ok(())
+		}
+
+		/// Remove the content with the given hash immediately, for the given reason. Removed
+		/// content can no longer be renewed or stored again. Entries already in `Transactions`
+		/// are left in place until they expire, as the storage proofs for their blocks still
+		/// cover them.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::force_remove())]
+		pub fn force_remove(
+			origin: OriginFor<T>,
+			content_hash: Preimage,
+			reason: RemovalReason,
+		) -> DispatchResult {
+			T::Authorizer::ensure_origin(origin)?;
+			ensure!(!RemovedContent::<T>::contains_key(content_hash), Error::<T>::ContentRemoved);
+			if let Some((at, _)) = PendingRemovals::<T>::take(content_hash) {
+				RemovalSchedule::<T>::mutate(at, |hashes| hashes.retain(|h| *h != content_hash));
+			}
+			Self::remove_content(content_hash, reason);
+			Ok(())
+		}
+
+		/// Schedule removal of the content with the given hash after `after` blocks. Renewals of
+		/// the content are rejected immediately. Unless cancelled with `cancel_removal`, the
+		/// removal is executed at the start of the target block, with the same effect as
+		/// `force_remove`.
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::schedule_removal())]
+		pub fn schedule_removal(
+			origin: OriginFor<T>,
+			content_hash: Preimage,
+			after: BlockNumberFor<T>,
+			reason: RemovalReason,
+		) -> DispatchResult {
+			T::Authorizer::ensure_origin(origin)?;
+			ensure!(!RemovedContent::<T>::contains_key(content_hash), Error::<T>::ContentRemoved);
+			ensure!(!PendingRemovals::<T>::contains_key(content_hash), Error::<T>::RemovalPending);
+			// Removals for the current block have already been executed
+			let at =
+				frame_system::Pallet::<T>::block_number().saturating_add(after.max(One::one()));
+			RemovalSchedule::<T>::try_mutate(at, |hashes| hashes.try_push(content_hash))
+				.map_err(|_| Error::<T>::TooManyRemovals)?;
+			PendingRemovals::<T>::insert(content_hash, (at, reason));
+			Self::deposit_content_event(content_hash, Event::RemovalScheduled { content_hash, at });
+			Ok(())
+		}
+
+		/// Cancel a removal scheduled with `schedule_removal`.
+		#[pallet::call_index(12)]
+		#[pallet::weight(T::WeightInfo::cancel_removal())]
+		pub fn cancel_removal(origin: OriginFor<T>, content_hash: Preimage) -> DispatchResult {
+			T::Authorizer::ensure_origin(origin)?;
+			let (at, _) =
+				PendingRemovals::<T>::take(content_hash).ok_or(Error::<T>::RemovalNotScheduled)?;
+			RemovalSchedule::<T>::mutate(at, |hashes| hashes.retain(|h| *h != content_hash));
+			Self::deposit_content_event(content_hash, Event::RemovalCancelled { content_hash });
+			Ok(())
+		}
+
+		/// Lift the bar of the given account, if any, and clear its strikes.
+		#[pallet::call_index(14)]
+		#[pallet::weight(T::WeightInfo::clear_bar())]
+		pub fn clear_bar(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::Authorizer::ensure_origin(origin)?;
+			Barred::<T>::remove(&who);
+			Strikes::<T>::remove(&who);
+			Self::deposit_event(Event::BarCleared { who });
+			Ok(())
+		}
+
+		/// Challenge the availability of the content with the given hash, as last stored or
+		/// renewed. The next block must prove a chunk of the content, selected pseudo-randomly,
+		/// with `check_proofs`, or it is invalid, as with a missing storage proof. Uses one
+		/// transaction of the caller's own authorizations, with no bytes, never the preimage
+		/// authorizations of the content.
+		///
+		/// Fails with `ChallengedNotFound` if the content is not stored, or its storage period is
+		/// over by the next block.
+		#[pallet::call_index(15)]
+		#[pallet::weight(T::WeightInfo::request_proof())]
+		pub fn request_proof(origin: OriginFor<T>, content_hash: Preimage) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!RemovedContent::<T>::contains_key(content_hash), Error::<T>::ContentRemoved);
+			ensure!(!PendingRemovals::<T>::contains_key(content_hash), Error::<T>::RemovalPending);
+			let (block, index, info) =
+				Self::last_stored(content_hash).ok_or(Error::<T>::ChallengedNotFound)?;
+			let at = frame_system::Pallet::<T>::block_number().saturating_add(One::one());
+			// The content only has to be kept until the storage proof of its block
+			ensure!(
+				at <= block.saturating_add(StoragePeriod::<T>::get()),
+				Error::<T>::ChallengedNotFound
+			);
+			Challenges::<T>::try_mutate(at, |challenges| {
+				ensure!(
+					!challenges.iter().any(|challenge| challenge.who == who),
+					Error::<T>::ChallengeAlreadyRequested
+				);
+				let challenge = Challenge {
+					who: who.clone(),
+					block,
+					index,
+					content_hash: info.content_hash,
+					chunk_root: info.chunk_root,
+					chunk_count: info.chunk_count,
+				};
+				challenges.try_push(challenge).map_err(|_| Error::<T>::TooManyChallenges)?;
+				Ok::<_, DispatchError>(())
+			})?;
+			Self::check_not_barred(&who).map_err(Error::<T>::from)?;
+			Self::note_usage(&who, 0, false);
+			Self::use_account_scopes(&who, content_hash, 0)?;
+			Self::deposit_content_event(
+				content_hash,
+				Event::ProofRequested { who, content_hash, at },
+			);
+			Ok(())
+		}
+
+		/// Check the storage proofs of the content challenged for this block, in the order of
+		/// `challenges`, along with the storage proof `check_proof` would check, if any. Used
+		/// instead of `check_proof` in blocks with challenges. A `None` challenge proof means the
+		/// block author no longer has the challenged content, which is reported with
+		/// `ChallengeUnprovable`.
+		#[pallet::call_index(16)]
+		#[pallet::weight((
+			Self::check_proof_weight(T::MaxBlockTransactions::get()).saturating_add(
+				T::WeightInfo::check_challenge_proofs(challenge_proofs.len() as u32)
+			),
+			DispatchClass::Mandatory
+		))]
+		pub fn check_proofs(
+			origin: OriginFor<T>,
+			proof: Option<TransactionStorageProof>,
+			challenge_proofs: BoundedVec<Option<TransactionStorageProof>, T::MaxBlockChallenges>,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+			let mut weight = T::WeightInfo::check_challenge_proofs(challenge_proofs.len() as u32);
+			if let Some(proof) = proof {
+				let transaction_count = Self::do_check_proof(proof)?;
+				weight.saturating_accrue(Self::check_proof_weight(transaction_count));
+			}
+			let now = <frame_system::Pallet<T>>::block_number();
+			let challenges = Challenges::<T>::take(now);
+			ensure!(!challenges.is_empty(), Error::<T>::UnexpectedProof);
+			ensure!(challenges.len() == challenge_proofs.len(), Error::<T>::InvalidProof);
+			let parent_hash = <frame_system::Pallet<T>>::parent_hash();
+			for (challenge, proof) in challenges.into_iter().zip(challenge_proofs) {
+				let content_hash = challenge.content_hash.into();
+				let Some(proof) = proof else {
+					Self::deposit_content_event(
+						content_hash,
+						Event::ChallengeUnprovable { content_hash },
+					);
+					continue
+				};
+				ensure!(
+					proof.encoded_size() <= T::MaxProofSize::get() as usize,
+					Error::<T>::ProofTooLarge
+				);
+				let chunk_index = challenged_chunk(
+					parent_hash.as_ref(),
+					&challenge.content_hash,
+					challenge.chunk_count,
+				);
+				// Only the last chunk may be shorter, and its length is not known
+				let chunk_len = (chunk_index.0 + 1 < challenge.chunk_count).then_some(CHUNK_SIZE);
+				check_proof_structure(&proof, chunk_len).map_err(Error::<T>::from)?;
+				ensure!(
+					verify_chunk(challenge.chunk_root, chunk_index, &proof.chunk, &proof.proof),
+					Error::<T>::InvalidProof
+				);
+				Self::deposit_content_event(content_hash, Event::ChallengeProved { content_hash });
+			}
+			Ok(Some(weight).into())
+		}
+
+		/// Verify that `data` is the content stored by the `store` or `renew` call `index` of
+		/// block `block`, and index it again, repairing its availability. The data is recorded
+		/// as a transaction of the current block, as by `renew`, so that it is challenged with
+		/// the other transactions of the block, and `Renewed` is emitted for it. Requires same
+		/// authorization as `store`, and is free of fees in the same way. Subject to the
+		/// `RenewalPolicy` like `renew`.
+		///
+		/// Fails with `VerificationFailed` if there is no such entry or the data does not match it.
+		/// ## Complexity
+		/// - O(n*log(n)) of data size, as all data is pushed to an in-memory trie.
+		#[pallet::call_index(17)]
+		#[pallet::weight(Self::verify_content_weight(data.len().saturated_into()))]
+		pub fn verify_content(
+			origin: OriginFor<T>,
+			block: BlockNumberFor<T>,
+			index: TxIndex,
+			data: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let extrinsic_index = Self::ensure_extrinsic_context()?;
+			let (info, _) =
+				Self::stored_transaction(block, index).ok_or(Error::<T>::VerificationFailed)?;
+			ensure!(info.size as usize == data.len(), Error::<T>::VerificationFailed);
+			let content_hash = sp_io::hashing::blake2_256(&data);
+			ensure!(info.content_hash == content_hash.into(), Error::<T>::VerificationFailed);
+			ensure!(info.chunk_root == chunk_root(&data), Error::<T>::VerificationFailed);
+			ensure!(!RemovedContent::<T>::contains_key(content_hash), Error::<T>::ContentRemoved);
+			ensure!(!PendingRemovals::<T>::contains_key(content_hash), Error::<T>::RemovalPending);
+
+			// The size of the data matches the stored `u32` size, checked above
+			let payer = Self::use_authorization(origin, content_hash, info.size, false)?;
+			let owner = Self::content_owner(block, content_hash);
+			ensure!(
+				T::RenewalPolicy::can_renew(payer.as_ref(), owner.as_ref()),
+				Error::<T>::RenewalNotAllowed
+			);
+
+			// The node indexes the data of this extrinsic as a transaction of the block, so it
+			// must be recorded as one for storage proofs to select the same chunks as the node
+			Self::note_indexed_extrinsic(extrinsic_index);
+			sp_io::transaction_index::index(extrinsic_index, info.size, content_hash);
+			Self::note_renewal(&info, owner, payer)?;
+
+			Self::deposit_content_event(content_hash, Event::ContentVerified { block, index });
+			Ok(Pays::No.into())
+		}
+
+		/// Store compressed data, like `store`, recording the hash and size of the uncompressed
+		/// data so that consumers can look the content up by either hash and verify it once
+		/// decompressed. Authorization is used by the compressed size. Chunk proofs cover the
+		/// compressed data.
+		///
+		/// The runtime does not decompress the data, by design: there is no vetted `no_std`
+		/// decoder, and the memory and weight of decompression are bounded by the declared size
+		/// only, which a crafted payload may exceed. Checking a sample of the data would not
+		/// verify a hash of all of it either. The uncompressed hash and size are therefore the
+		/// uploader's claim, recorded as is. By convention the data is a zstd frame; consumers
+		/// must check the decompressed data against `uncompressed_hash` before trusting it.
+		/// ## Complexity
+		/// - O(n*log(n)) of data size, as all data is pushed to an in-memory trie.
+		#[pallet::call_index(18)]
+		#[pallet::weight(
+			Self::store_weight(compressed_data.len().saturated_into())
+				.saturating_add(T::DbWeight::get().reads_writes(2, 3))
+		)]
+		pub fn store_compressed(
+			origin: OriginFor<T>,
+			compressed_data: Vec<u8>,
+			uncompressed_hash: Preimage,
+			uncompressed_len: u32,
+		) -> DispatchResultWithPostInfo {
+			let (content_hash, index, mut weight) = Self::do_store(origin, &compressed_data)?;
+			let block = frame_system::Pallet::<T>::block_number();
+			Self::note_compressed(
+				content_hash,
+				CompressionInfo { uncompressed_hash, uncompressed_len, block },
+			)?;
+			Self::deposit_content_event(
+				content_hash,
+				Event::StoredCompressed { index, uncompressed_hash, uncompressed_len },
+			);
+			weight.saturating_accrue(T::DbWeight::get().reads_writes(2, 3));
+			Ok((Some(weight), Pays::No).into())
+		}
+
+		/// Store data on behalf of the `Authorizer` origin, e.g. the canonical text of a
+		/// referendum, without using any authorization. The call is operational, so that it is
+		/// included ahead of other stores when blocks are full of them, and at most
+		/// `AuthorizerStoresPerBlock` of these stores are included per block, in transactions of
+		/// the block that other stores and renewals leave free. Otherwise like `store`, without
+		/// an owner. As with `store`, the data must be part of the extrinsic, so this cannot be
+		/// dispatched while a block is initialized, e.g. when the scheduler enacts a referendum,
+		/// but can be submitted by an authorizer account or through sudo.
+		/// ## Complexity
+		/// - O(n*log(n)) of data size, as all data is pushed to an in-memory trie.
+		#[pallet::call_index(26)]
+		#[pallet::weight((
+			Self::store_via_authorizer_weight(data.len().saturated_into()),
+			DispatchClass::Operational,
+			Pays::No
+		))]
+		pub fn store_via_authorizer(origin: OriginFor<T>, data: Vec<u8>) -> DispatchResult {
+			let extrinsic_index = Self::ensure_extrinsic_context()?;
+			T::Authorizer::ensure_origin(origin)?;
+			let size = u32::try_from(data.len()).map_err(|_| Error::<T>::TransactionTooLarge)?;
+			Self::check_size(size).map_err(Error::<T>::from)?;
+			let content_hash = sp_io::hashing::blake2_256(&data);
+			Self::check_not_removed(content_hash).map_err(Error::<T>::from)?;
+			Self::check_not_duplicate(content_hash).map_err(Error::<T>::from)?;
+			// Counted first, so that the transaction left free for it is available
+			AuthorizerStores::<T>::try_mutate(|stores| {
+				ensure!(
+					*stores < T::AuthorizerStoresPerBlock::get(),
+					Error::<T>::TooManyAuthorizerStores
+				);
+				*stores += 1;
+				Ok::<_, Error<T>>(())
+			})?;
+			let index = Self::index_data(extrinsic_index, &data, size, content_hash, None)?;
+			Self::deposit_content_event(content_hash, Event::StoredByAuthorizer { index });
+			Ok(())
+		}
+
+		/// Forget the tombstone of removed content, making room for new ones. The content still
+		/// cannot be renewed or stored again.
+		#[pallet::call_index(19)]
+		#[pallet::weight(T::WeightInfo::prune_tombstone())]
+		pub fn prune_tombstone(origin: OriginFor<T>, content_hash: Preimage) -> DispatchResult {
+			ensure_root(origin)?;
+			Tombstones::<T>::take(content_hash).ok_or(Error::<T>::NoTombstone)?;
+			Self::deposit_content_event(content_hash, Event::TombstonePruned { content_hash });
+			Ok(())
+		}
+
+		/// Create a group of accounts sharing the authorizations granted with `authorize_group`.
+		/// An account can be a member of a single group. The id of the new group is emitted in
+		/// the `GroupCreated` event.
+		#[pallet::call_index(20)]
+		#[pallet::weight(T::WeightInfo::create_group(members.len() as u32))]
+		pub fn create_group(
+			origin: OriginFor<T>,
+			members: BoundedVec<T::AccountId, T::MaxGroupMembers>,
+		) -> DispatchResult {
+			T::Authorizer::ensure_origin(origin)?;
+			let group = NextGroupId::<T>::get();
+			for who in &members {
+				ensure!(!GroupOf::<T>::contains_key(who), Error::<T>::AlreadyInGroup);
+				GroupOf::<T>::insert(who, group);
+			}
+			NextGroupId::<T>::put(group.checked_add(1).ok_or(ArithmeticError::Overflow)?);
+			Groups::<T>::insert(group, &members);
+			Self::deposit_event(Event::GroupCreated { group, members: members.into_inner() });
+			Ok(())
+		}
+
+		/// Authorize the members of the given group to store the given amount of arbitrary data,
+		/// once their own authorizations are exhausted. The authorization will expire after a
+		/// configured number of blocks.
+		#[pallet::call_index(21)]
+		#[pallet::weight(T::WeightInfo::authorize_group())]
+		pub fn authorize_group(
+			origin: OriginFor<T>,
+			group: GroupId,
+			transactions: u32,
+			bytes: u64,
 		) -> DispatchResult {
 			T::Authorizer::ensure_origin(origin)?;
-			Self::authorize(AuthorizationScope::Preimage(preimage), 1, bytes);
+			ensure!(Groups::<T>::contains_key(group), Error::<T>::UnknownGroup);
+			Self::authorize(AuthorizationScope::Group(group), transactions, bytes, None)
+		}
+
+		/// Add an account to the given group.
+		#[pallet::call_index(22)]
+		#[pallet::weight(T::WeightInfo::add_group_member())]
+		pub fn add_group_member(
+			origin: OriginFor<T>,
+			group: GroupId,
+			who: T::AccountId,
+		) -> DispatchResult {
+			T::Authorizer::ensure_origin(origin)?;
+			ensure!(!GroupOf::<T>::contains_key(&who), Error::<T>::AlreadyInGroup);
+			Groups::<T>::try_mutate(group, |members| {
+				let members = members.as_mut().ok_or(Error::<T>::UnknownGroup)?;
+				members.try_push(who.clone()).map_err(|_| Error::<T>::TooManyGroupMembers)
+			})?;
+			GroupOf::<T>::insert(&who, group);
+			Self::deposit_event(Event::GroupMemberAdded { group, who });
+			Ok(())
+		}
+
+		/// Remove an account from the given group. The group keeps its authorizations.
+		#[pallet::call_index(23)]
+		#[pallet::weight(T::WeightInfo::remove_group_member())]
+		pub fn remove_group_member(
+			origin: OriginFor<T>,
+			group: GroupId,
+			who: T::AccountId,
+		) -> DispatchResult {
+			T::Authorizer::ensure_origin(origin)?;
+			ensure!(GroupOf::<T>::get(&who) == Some(group), Error::<T>::NotGroupMember);
+			Groups::<T>::mutate(group, |members| {
+				if let Some(members) = members {
+					members.retain(|member| *member != who);
+				}
+			});
+			GroupOf::<T>::remove(&who);
+			Self::deposit_event(Event::GroupMemberRemoved { group, who });
+			Ok(())
+		}
+
+		/// Reconcile the chunk count of `block` with its transactions, should they ever diverge.
+		/// The chunk count is recomputed from the transactions if there are any, otherwise the
+		/// chunk count and any partial transaction data of the block are cleared, so that no
+		/// proof is required for it.
+		#[pallet::call_index(24)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(
+			T::MaxBlockTransactions::get().saturating_add(3).into(),
+			T::MaxBlockTransactions::get().saturating_add(4).into(),
+		))]
+		pub fn repair_block_metadata(
+			origin: OriginFor<T>,
+			block: BlockNumberFor<T>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			let chunk_count = match Self::transaction_roots(block) {
+				Some(transactions) => {
+					let chunk_ends: Vec<_> = transactions.iter().map(|t| t.block_chunks).collect();
+					let chunk_count = chunk_ends.last().copied().unwrap_or_default();
+					<ChunkCount<T>>::insert(block, chunk_count);
+					if <ChunkEnds<T>>::contains_key(block) {
+						<ChunkEnds<T>>::insert(block, BoundedVec::truncate_from(chunk_ends));
+					}
+					chunk_count
+				},
+				None => {
+					<ChunkCount<T>>::remove(block);
+					<ChunkEnds<T>>::remove(block);
+					<Transactions<T>>::remove(block);
+					let _ = <TransactionInfos<T>>::clear_prefix(
+						block,
+						T::MaxBlockTransactions::get(),
+						None,
+					);
+					0
+				},
+			};
+			Self::deposit_event(Event::BlockMetadataRepaired { block, chunk_count });
 			Ok(())
 		}
 	}
@@ -402,15 +1871,94 @@ pub mod pallet {
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		/// Stored data under specified index.
-		Stored { index: u32 },
-		/// Renewed data under specified index.
-		Renewed { index: u32 },
+		/// Stored data under specified index. `chunk_root` is the root of the trie built over the
+		/// data chunks, against which storage proofs are checked.
+		Stored { index: TxIndex, chunk_root: <BlakeTwo256 as Hash>::Output },
+		/// Renewed data under specified index. `chunk_root` is the chunk root of the renewed data.
+		Renewed { index: TxIndex, chunk_root: <BlakeTwo256 as Hash>::Output },
 		/// Storage proof was successfully checked.
 		ProofChecked,
+		/// A standing quota was set for an account.
+		StandingQuotaSet { who: T::AccountId, transactions: u32, bytes: u64 },
+		/// The standing quota of an account was removed.
+		StandingQuotaCleared { who: T::AccountId },
+		/// The lifetime cap of an account was set or removed.
+		LifetimeCapSet { who: T::AccountId, cap: Option<u64> },
+		/// An account was added to the authorizers set.
+		AuthorizerAdded { who: T::AccountId },
+		/// An account was removed from the authorizers set.
+		AuthorizerRemoved { who: T::AccountId },
+		/// Removal of content was scheduled for block `at`.
+		RemovalScheduled { content_hash: Preimage, at: BlockNumberFor<T> },
+		/// A scheduled removal of content was cancelled.
+		RemovalCancelled { content_hash: Preimage },
+		/// Content was removed.
+		ContentRemoved { content_hash: Preimage, reason: RemovalReason },
+		/// An upload of an account failed authorization checks.
+		Struck { who: T::AccountId, strikes: u32 },
+		/// An account was barred from uploading until block `until`.
+		AccountBarred { who: T::AccountId, until: BlockNumberFor<T> },
+		/// The bar and strikes of an account were cleared.
+		BarCleared { who: T::AccountId },
+		/// An account challenged the availability of content, to be proved in block `at`.
+		ProofRequested { who: T::AccountId, content_hash: Preimage, at: BlockNumberFor<T> },
+		/// The availability of challenged content was proved.
+		ChallengeProved { content_hash: Preimage },
+		/// Stored content was verified against its data, which was indexed again.
+		ContentVerified { block: BlockNumberFor<T>, index: TxIndex },
+		/// The unused extent of the authorizations of a scope was increased by `extent`.
+		Authorized { scope: AuthorizationScope<T::AccountId>, extent: AuthorizationExtent },
+		/// `extent` of the authorizations of a scope was used.
+		AuthorizationUsed { scope: AuthorizationScope<T::AccountId>, extent: AuthorizationExtent },
+		/// An authorization of a scope crediting `authorized`, less what was revoked of it,
+		/// expired. `extent` of it expired unused. Deposited for each expired authorization, even
+		/// if fully used.
+		AuthorizationExpired {
+			scope: AuthorizationScope<T::AccountId>,
+			extent: AuthorizationExtent,
+			authorized: AuthorizationExtent,
+		},
+		/// Stored compressed data under specified index, following its `Stored` event.
+		StoredCompressed { index: TxIndex, uncompressed_hash: Preimage, uncompressed_len: u32 },
+		/// The tombstone of removed content was pruned.
+		TombstonePruned { content_hash: Preimage },
+		/// Stored data under specified index in `namespace`, following its `Stored` event.
+		StoredInNamespace { index: TxIndex, namespace: Namespace },
+		/// A group of accounts sharing authorizations was created.
+		GroupCreated { group: GroupId, members: Vec<T::AccountId> },
+		/// An account was added to a group.
+		GroupMemberAdded { group: GroupId, who: T::AccountId },
+		/// An account was removed from a group.
+		GroupMemberRemoved { group: GroupId, who: T::AccountId },
+		/// The preimage was stored under the `Strict` preimage policy. Further stores of it with
+		/// preimage authorizations are rejected until its authorizations have all expired.
+		PreimageSatisfied { content_hash: Preimage },
+		/// The authorizations of a satisfied preimage have all expired.
+		PreimageSatisfactionExpired { content_hash: Preimage },
+		/// The storage proof of `block` could not be checked, as its transactions are missing.
+		ProofUnprovable { block: BlockNumberFor<T> },
+		/// The chunk count of `block` was reconciled with its transactions. A zero `chunk_count`
+		/// means the block's metadata was cleared.
+		BlockMetadataRepaired { block: BlockNumberFor<T>, chunk_count: u32 },
+		/// Until block `until`, only `who` can store the authorized preimage.
+		PreimageReserved { content_hash: Preimage, who: T::AccountId, until: BlockNumberFor<T> },
+		/// Data under specified index was stored by the `Authorizer` origin, bypassing
+		/// authorizations, following its `Stored` event.
+		StoredByAuthorizer { index: TxIndex },
+		/// `extent` of the storage quota of an account was held.
+		QuotaHeld { who: T::AccountId, extent: AuthorizationExtent },
+		/// `extent` of the held storage quota of an account was released.
+		QuotaReleased { who: T::AccountId, extent: AuthorizationExtent },
+		/// `extent` of the unused account authorizations of an account was revoked.
+		AuthorizationRevoked { who: T::AccountId, extent: AuthorizationExtent },
+		/// Content owned by `owner` was renewed under specified index, paid for by `payer`, or
+		/// with a preimage authorization if `None`. Follows the `Renewed` event.
+		RenewedOnBehalf { index: TxIndex, payer: Option<T::AccountId>, owner: T::AccountId },
+		/// The challenged content could not be proved, as the block author no longer has it.
+		ChallengeUnprovable { content_hash: Preimage },
 	}
 
-	/// Authorization usage by scope.
+	/// Authorization usage by scope, adding up the authorizations of the scope.
 	#[pallet::storage]
 	pub(super) type AuthorizationUsageByScope<T: Config> = StorageMap<
 		_,
@@ -420,6 +1968,28 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// The authorizations of each scope with unused extent left, in the order they expire in.
+	/// Stores use the authorization expiring first, so an expiring authorization only takes
+	/// its own unused extent with it. `AuthorizationUsageByScope` adds them up.
+	#[pallet::storage]
+	pub(super) type PendingAuthorizations<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AuthorizationScope<T::AccountId>,
+		BoundedVec<PendingAuthorization<BlockNumberFor<T>>, T::MaxScopeAuthorizations>,
+		ValueQuery,
+	>;
+
+	/// Identifier of the next authorization.
+	#[pallet::storage]
+	pub(super) type NextAuthorizationId<T: Config> = StorageValue<_, AuthorizationId, ValueQuery>;
+
+	/// Storage quota held by each account, out of the unused extent of its account
+	/// authorizations, which stores and renewals cannot use. See `StorageQuota`.
+	#[pallet::storage]
+	pub(super) type HeldQuota<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, AuthorizationExtent, ValueQuery>;
+
 	/// Authorizations, keyed by expiry. Authorizations with no expiry are not added. Note that
 	/// authorizations are _not_ removed when they are used, only when they expire.
 	#[pallet::storage]
@@ -437,108 +2007,576 @@ pub mod pallet {
 	pub(super) type MinAuthorizationExpiryMinus1<T: Config> =
 		StorageValue<_, BlockNumberFor<T>, ValueQuery>;
 
-	/// Collection of transaction metadata by block number.
+	/// Blocks the unrestricted account authorizations of each account are pending expiry in,
+	/// in order, for revoking them without going through all of `AuthorizationsByExpiry`.
 	#[pallet::storage]
-	#[pallet::getter(fn transaction_roots)]
-	pub(super) type Transactions<T: Config> = StorageMap<
+	pub(super) type AccountAuthorizationExpiries<T: Config> = StorageMap<
 		_,
 		Blake2_128Concat,
-		BlockNumberFor<T>,
-		BoundedVec<TransactionInfo, T::MaxBlockTransactions>,
-		OptionQuery,
+		T::AccountId,
+		BoundedVec<BlockNumberFor<T>, T::MaxAccountAuthorizationExpiries>,
+		ValueQuery,
 	>;
 
-	/// Count indexed chunks for each block.
+	/// Number of authorizations in `AuthorizationsByExpiry`.
 	#[pallet::storage]
-	pub(super) type ChunkCount<T: Config> =
-		StorageMap<_, Blake2_128Concat, BlockNumberFor<T>, u32, ValueQuery>;
+	pub(super) type OutstandingAuthorizations<T: Config> = StorageValue<_, u32, ValueQuery>;
 
-	/// Storage period for data in blocks. Should match `sp_storage_proof::DEFAULT_STORAGE_PERIOD`
-	/// for block authoring.
+	/// First of the blocks authorizations expire in, which are linked in order through
+	/// `ExpiryBlockAfter`, so that blocks no authorizations expire in do not read
+	/// `AuthorizationsByExpiry`. Blocks whose authorizations were all revoked may stay linked.
 	#[pallet::storage]
-	pub(super) type StoragePeriod<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+	pub(super) type NextExpiryBlock<T: Config> = StorageValue<_, BlockNumberFor<T>, OptionQuery>;
 
-	// Intermediates
+	/// Last of the blocks authorizations expire in. New authorizations expire no earlier.
 	#[pallet::storage]
-	pub(super) type BlockTransactions<T: Config> =
-		StorageValue<_, BoundedVec<TransactionInfo, T::MaxBlockTransactions>, ValueQuery>;
+	pub(super) type LastExpiryBlock<T: Config> = StorageValue<_, BlockNumberFor<T>, OptionQuery>;
 
-	/// Was the proof checked in this block?
+	/// For each of the blocks linked from `NextExpiryBlock` but the last, the next block
+	/// authorizations expire in.
 	#[pallet::storage]
-	pub(super) type ProofChecked<T: Config> = StorageValue<_, bool, ValueQuery>;
+	pub(super) type ExpiryBlockAfter<T: Config> =
+		StorageMap<_, Blake2_128Concat, BlockNumberFor<T>, BlockNumberFor<T>, OptionQuery>;
 
-	#[pallet::genesis_config]
-	pub struct GenesisConfig<T: Config> {
-		pub storage_period: BlockNumberFor<T>,
-	}
+	/// Number of authorizations issued in the current block. Cleared in `on_finalize`.
+	#[pallet::storage]
+	pub(super) type BlockAuthorizations<T: Config> = StorageValue<_, u32, ValueQuery>;
 
-	impl<T: Config> Default for GenesisConfig<T> {
-		fn default() -> Self {
-			Self { storage_period: sp_transaction_storage_proof::DEFAULT_STORAGE_PERIOD.into() }
-		}
-	}
+	/// Number of stores by the authorizer included in the current block. Cleared in
+	/// `on_finalize`.
+	#[pallet::storage]
+	pub(super) type AuthorizerStores<T: Config> = StorageValue<_, u32, ValueQuery>;
 
-	#[pallet::genesis_build]
-	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
-		fn build(&self) {
-			<StoragePeriod<T>>::put(self.storage_period);
-		}
-	}
+	/// Standing quotas by account. These are refilled lazily, the first time they are used in a
+	/// new authorization period.
+	#[pallet::storage]
+	pub(super) type StandingQuotas<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		StandingQuota<BlockNumberFor<T>>,
+		OptionQuery,
+	>;
 
-	#[pallet::inherent]
-	impl<T: Config> ProvideInherent for Pallet<T> {
-		type Call = Call<T>;
-		type Error = InherentError;
-		const INHERENT_IDENTIFIER: InherentIdentifier = INHERENT_IDENTIFIER;
+	/// Lifetime caps by account, in bytes.
+	#[pallet::storage]
+	pub(super) type LifetimeCaps<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u64, OptionQuery>;
 
-		fn create_inherent(data: &InherentData) -> Option<Self::Call> {
-			let proof = data
-				.get_data::<TransactionStorageProof>(&Self::INHERENT_IDENTIFIER)
-				.unwrap_or(None);
-			proof.map(|proof| Call::check_proof { proof })
-		}
+	/// Total bytes ever stored or renewed by each account. Never decremented.
+	#[pallet::storage]
+	pub(super) type LifetimeUsed<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u64, ValueQuery>;
 
-		fn check_inherent(
-			_call: &Self::Call,
-			_data: &InherentData,
-		) -> result::Result<(), Self::Error> {
-			Ok(())
-		}
+	/// Authorizations used by each account in the last `MaxUsageHistory` authorization periods
+	/// it used any in, oldest first.
+	#[pallet::storage]
+	pub(super) type UsageHistory<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<PeriodUsage<BlockNumberFor<T>>, T::MaxUsageHistory>,
+		ValueQuery,
+	>;
 
-		fn is_inherent(call: &Self::Call) -> bool {
-			matches!(call, Call::check_proof { .. })
-		}
-	}
+	/// The last `MaxRecentAuthorizations` authorization grants, with the extent credited and the
+	/// block they were granted in, mirroring the `Authorized` events for verifiers that can only
+	/// read state proofs.
+	///
+	/// Grants are appended until the buffer is full, then overwrite it circularly, starting from
+	/// the oldest at `NextRecentAuthorization`. The buffer is a single value under the key
+	/// `twox128("TransactionStorage") ++ twox128("RecentAuthorizations")`, so a proof of that key
+	/// reveals all of the recent grants; they can be ordered by their blocks.
+	#[pallet::storage]
+	pub(super) type RecentAuthorizations<T: Config> = StorageValue<
+		_,
+		BoundedVec<AuthorizationGrant<T::AccountId, BlockNumberFor<T>>, T::MaxRecentAuthorizations>,
+		ValueQuery,
+	>;
 
-	impl<T: Config> Pallet<T> {
-		fn authorize(scope: AuthorizationScope<T::AccountId>, transactions: u32, bytes: u64) {
-			let period = T::AuthorizationPeriod::get();
-			if period.is_zero() {
-				return // Authorizations expire immediately
-			}
+	/// Index in `RecentAuthorizations` the next grant is written at.
+	#[pallet::storage]
+	pub(super) type NextRecentAuthorization<T: Config> = StorageValue<_, u32, ValueQuery>;
 
-			// Credit scope. Note that it is possible for authorizations to get lost due to the
-			// saturating arithmetic.
-			AuthorizationUsageByScope::<T>::mutate(scope.clone(), |usage| {
-				usage.unused.transactions = usage.unused.transactions.saturating_add(transactions);
-				usage.unused.bytes = usage.unused.bytes.saturating_add(bytes);
-			});
+	/// Content hashes that restricted account authorizations can be used for. Removed when the
+	/// account's restricted authorizations have all expired.
+	#[pallet::storage]
+	pub(super) type AllowedHashes<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<Preimage, T::MaxAllowedHashes>,
+		ValueQuery,
+	>;
 
-			// Determine expiry block
-			let Some(expiry) = frame_system::Pallet::<T>::block_number().checked_add(&period)
-			else {
-				return // Authorization never expires
+	/// Deposits held for preimage authorizations, with their depositor. Only the deposit of the
+	/// most recent signed authorization of a preimage is held.
+	#[pallet::storage]
+	pub(super) type PreimageDeposits<T: Config> =
+		StorageMap<_, Identity, Preimage, (T::AccountId, T::PreimageDeposit), OptionQuery>;
+
+	/// Reservations of preimage authorizations: the account that alone can store the preimage,
+	/// and the last block of the reservation. Kept until the authorizations of the preimage
+	/// expire.
+	#[pallet::storage]
+	pub(super) type PreimageReservations<T: Config> =
+		StorageMap<_, Identity, Preimage, (T::AccountId, BlockNumberFor<T>), OptionQuery>;
+
+	/// Number of failed uploads of each account since it was last barred or cleared.
+	#[pallet::storage]
+	pub(super) type Strikes<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// Accounts barred from uploading, with the block their bar ends at. Expired bars are left in
+	/// place until the account is struck again or cleared.
+	#[pallet::storage]
+	pub(super) type Barred<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BlockNumberFor<T>, OptionQuery>;
+
+	/// Availability challenges by the block they must be proved in.
+	#[pallet::storage]
+	pub(super) type Challenges<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<Challenge<T::AccountId, BlockNumberFor<T>>, T::MaxBlockChallenges>,
+		ValueQuery,
+	>;
+
+	/// Uncompressed form of stored compressed content, by hash of the compressed data. Removed
+	/// when the storage period of the last store or renewal of the content is over.
+	#[pallet::storage]
+	pub(super) type CompressedContent<T: Config> =
+		StorageMap<_, Identity, Preimage, CompressionInfo<BlockNumberFor<T>>, OptionQuery>;
+
+	/// Hash of the compressed data of stored content, by hash of the uncompressed data. The most
+	/// recently stored compressed form wins.
+	#[pallet::storage]
+	pub(super) type UncompressedContent<T: Config> =
+		StorageMap<_, Identity, Preimage, Preimage, OptionQuery>;
+
+	/// Hashes of the compressed content stored or renewed in each block, for pruning
+	/// `CompressedContent`.
+	#[pallet::storage]
+	pub(super) type CompressedByBlock<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<Preimage, T::MaxBlockTransactions>,
+		ValueQuery,
+	>;
+
+	/// Number of times the storage proof of each stored content was checked, with the last block
+	/// the content was stored or renewed in. Removed when the storage period of the last store or
+	/// renewal of the content is over.
+	#[pallet::storage]
+	pub(super) type ChallengeCounts<T: Config> =
+		StorageMap<_, Identity, Preimage, (u32, BlockNumberFor<T>), OptionQuery>;
+
+	/// Hashes of the content stored or renewed in each block, for pruning `ChallengeCounts`.
+	#[pallet::storage]
+	pub(super) type ChallengeCountsByBlock<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<Preimage, T::MaxBlockTransactions>,
+		ValueQuery,
+	>;
+
+	/// Stored content by namespace. Removed when the storage period of the last store or renewal
+	/// of the content is over.
+	#[pallet::storage]
+	pub(super) type ContentByNamespace<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, Namespace, Identity, Preimage, (), OptionQuery>;
+
+	/// Namespace of stored content, with the last block it was stored or renewed in.
+	#[pallet::storage]
+	pub(super) type ContentNamespaces<T: Config> =
+		StorageMap<_, Identity, Preimage, (Namespace, BlockNumberFor<T>), OptionQuery>;
+
+	/// Number of live entries of each namespace in `ContentByNamespace`.
+	#[pallet::storage]
+	pub(super) type NamespaceEntries<T: Config> =
+		StorageMap<_, Blake2_128Concat, Namespace, u32, ValueQuery>;
+
+	/// Content stored or renewed by each account, with the block and index of its last store or
+	/// renewal by the account. Removed when the storage period of that store or renewal is over.
+	#[pallet::storage]
+	pub(super) type OwnedContent<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Identity,
+		Preimage,
+		(BlockNumberFor<T>, TxIndex),
+		OptionQuery,
+	>;
+
+	/// Number of live entries of each account in `OwnedContent`.
+	#[pallet::storage]
+	pub(super) type OwnedContentCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// Owners and hashes of the content stored or renewed by accounts in each block, for pruning
+	/// `OwnedContent`.
+	#[pallet::storage]
+	pub(super) type OwnedByBlock<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<(T::AccountId, Preimage), T::MaxBlockTransactions>,
+		ValueQuery,
+	>;
+
+	/// Hashes of the namespaced content stored or renewed in each block, for pruning
+	/// `ContentByNamespace`.
+	#[pallet::storage]
+	pub(super) type NamespacedByBlock<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<Preimage, T::MaxBlockTransactions>,
+		ValueQuery,
+	>;
+
+	/// The id of the next group created.
+	#[pallet::storage]
+	pub(super) type NextGroupId<T: Config> = StorageValue<_, GroupId, ValueQuery>;
+
+	/// Members of each group.
+	#[pallet::storage]
+	pub(super) type Groups<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		GroupId,
+		BoundedVec<T::AccountId, T::MaxGroupMembers>,
+		OptionQuery,
+	>;
+
+	/// The group of each group member.
+	#[pallet::storage]
+	pub(super) type GroupOf<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, GroupId, OptionQuery>;
+
+	/// Accounts that pass the `EnsureAuthorizedAccount` origin check.
+	#[pallet::storage]
+	pub(super) type Authorizers<T: Config> =
+		CountedStorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+	/// Content hashes scheduled for removal, with the block the removal will be executed in and
+	/// the reason for it.
+	#[pallet::storage]
+	pub(super) type PendingRemovals<T: Config> =
+		StorageMap<_, Identity, Preimage, (BlockNumberFor<T>, RemovalReason), OptionQuery>;
+
+	/// Scheduled content removals by execution block.
+	#[pallet::storage]
+	pub(super) type RemovalSchedule<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<Preimage, T::MaxBlockRemovals>,
+		ValueQuery,
+	>;
+
+	/// The first block whose authorization expiries and scheduled removals have not been
+	/// processed yet, if they are behind.
+	#[pallet::storage]
+	pub(super) type ExpiryCursor<T: Config> = StorageValue<_, BlockNumberFor<T>, OptionQuery>;
+
+	/// Preimages stored with preimage authorizations under the `Strict` preimage policy, with
+	/// the block they were stored in. Removed when the preimage's authorizations have all
+	/// expired.
+	#[pallet::storage]
+	pub(super) type SatisfiedPreimages<T: Config> =
+		StorageMap<_, Identity, Preimage, BlockNumberFor<T>, OptionQuery>;
+
+	/// Hashes of removed content. Such content cannot be renewed or stored again.
+	#[pallet::storage]
+	pub(super) type RemovedContent<T: Config> = StorageMap<_, Identity, Preimage, (), OptionQuery>;
+
+	/// Why removed content was removed, for up to `MaxTombstones` removals. Tombstones are kept
+	/// until pruned with `prune_tombstone`.
+	#[pallet::storage]
+	pub(super) type Tombstones<T: Config> =
+		CountedStorageMap<_, Identity, Preimage, RemovalReason, OptionQuery>;
+
+	/// Collection of transaction metadata by block number, as stored before `TransactionInfos`.
+	/// Entries are moved to `TransactionInfos` by `migrations::v1`, and read as a fallback
+	/// until then.
+	#[pallet::storage]
+	pub(super) type Transactions<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<LegacyTransactionInfo, T::MaxBlockTransactions>,
+		OptionQuery,
+	>;
+
+	/// Transaction metadata by block number and index within the block.
+	#[pallet::storage]
+	pub(super) type TransactionInfos<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		Twox64Concat,
+		u32,
+		TransactionInfo,
+		OptionQuery,
+	>;
+
+	/// The next block whose `Transactions` entry is to be moved to `TransactionInfos`, and the
+	/// last block stored in the old format. Set while `migrations::v1` is in progress.
+	#[pallet::storage]
+	pub(super) type TransactionsMigration<T: Config> =
+		StorageValue<_, (BlockNumberFor<T>, BlockNumberFor<T>), OptionQuery>;
+
+	/// The `block_chunks` of the transactions of each block, so that the transaction holding a
+	/// chunk can be found without decoding all of `Transactions`.
+	#[pallet::storage]
+	pub(super) type ChunkEnds<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<u32, T::MaxBlockTransactions>,
+		OptionQuery,
+	>;
+
+	/// Count indexed chunks for each block.
+	#[pallet::storage]
+	pub(super) type ChunkCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, BlockNumberFor<T>, u32, ValueQuery>;
+
+	/// Storage period for data in blocks. Should match `sp_storage_proof::DEFAULT_STORAGE_PERIOD`
+	/// for block authoring.
+	#[pallet::storage]
+	pub(super) type StoragePeriod<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+	// Intermediates
+	/// Transactions indexed in this block so far, by index. Only appended to during the block
+	/// and moved to `Transactions` on finalization, so adding one doesn't re-encode the others.
+	#[pallet::storage]
+	pub(super) type BlockTransactions<T: Config> =
+		CountedStorageMap<_, Twox64Concat, u32, TransactionInfo, OptionQuery>;
+
+	/// Total number of chunks indexed in this block so far.
+	#[pallet::storage]
+	pub(super) type BlockChunks<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Index of the last extrinsic in this block that indexed data.
+	#[pallet::storage]
+	pub(super) type IndexedExtrinsic<T: Config> = StorageValue<_, u32, OptionQuery>;
+
+	/// Statuses of the storage proofs due in the last `StoragePeriod` blocks, by the block
+	/// storing the proved content. Only `Proven`, `Missed` and `Unprovable` are recorded.
+	#[pallet::storage]
+	pub(super) type ProofStatuses<T: Config> =
+		StorageMap<_, Blake2_128Concat, BlockNumberFor<T>, ProofStatus, OptionQuery>;
+
+	/// Preimages noted through `StorePreimage`, by content hash.
+	#[pallet::storage]
+	pub(super) type InlineData<T: Config> = StorageMap<
+		_,
+		Identity,
+		Preimage,
+		BoundedVec<u8, ConstU32<MAX_INLINE_DATA_SIZE>>,
+		OptionQuery,
+	>;
+
+	/// Number of outstanding requests for each preimage in `InlineData`.
+	#[pallet::storage]
+	pub(super) type InlineDataRequests<T: Config> =
+		StorageMap<_, Identity, Preimage, u32, ValueQuery>;
+
+	/// Hashes of the preimages in `InlineData` noted in each block, or carried over to it as they
+	/// were still requested when their storage period ended. Each preimage is listed in exactly
+	/// one block, for pruning `InlineData` without iterating it.
+	#[pallet::storage]
+	pub(super) type InlineByBlock<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<Preimage, T::MaxBlockTransactions>,
+		ValueQuery,
+	>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub storage_period: BlockNumberFor<T>,
+	}
+
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { storage_period: sp_transaction_storage_proof::DEFAULT_STORAGE_PERIOD.into() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			<StoragePeriod<T>>::put(self.storage_period);
+		}
+	}
+
+	#[pallet::inherent]
+	impl<T: Config> ProvideInherent for Pallet<T> {
+		type Call = Call<T>;
+		type Error = InherentError;
+		const INHERENT_IDENTIFIER: InherentIdentifier = INHERENT_IDENTIFIER;
+
+		fn create_inherent(data: &InherentData) -> Option<Self::Call> {
+			let proof = data
+				.get_data::<TransactionStorageProof>(&Self::INHERENT_IDENTIFIER)
+				.unwrap_or(None);
+			// Honest proofs always fit, see `integrity_test`
+			let proof =
+				proof.filter(|proof| proof.encoded_size() <= T::MaxProofSize::get() as usize);
+			let challenge_proofs = data
+				.get_data::<Vec<Option<TransactionStorageProof>>>(&CHALLENGE_INHERENT_IDENTIFIER)
+				.unwrap_or(None)
+				.unwrap_or_default();
+			if challenge_proofs.is_empty() {
+				return proof.map(|proof| Call::check_proof { proof })
+			}
+			let challenge_proofs = BoundedVec::truncate_from(challenge_proofs);
+			Some(Call::check_proofs { proof, challenge_proofs })
+		}
+
+		fn check_inherent(
+			call: &Self::Call,
+			_data: &InherentData,
+		) -> result::Result<(), Self::Error> {
+			// The challenged chunks are not known before the block is executed, so the length of
+			// the chunks is only checked against `CHUNK_SIZE`
+			let malformed = |proof: &TransactionStorageProof| {
+				proof.encoded_size() > T::MaxProofSize::get() as usize ||
+					check_proof_structure(proof, None).is_err()
 			};
-			let Some(min_expiry) =
-				MinAuthorizationExpiryMinus1::<T>::get().checked_add(&1u32.into())
-			else {
-				return // Authorization never expires
+			let invalid = match call {
+				Call::check_proof { proof } => malformed(proof),
+				Call::check_proofs { proof, challenge_proofs } =>
+					proof.iter().chain(challenge_proofs.iter().flatten()).any(malformed),
+				_ => false,
+			};
+			if invalid {
+				return Err(InherentError::InvalidProof)
+			}
+			Ok(())
+		}
+
+		fn is_inherent(call: &Self::Call) -> bool {
+			matches!(call, Call::check_proof { .. } | Call::check_proofs { .. })
+		}
+	}
+
+	// Unsigned calls by transaction source:
+	//
+	// - `check_proof` and `check_proofs` are inherents. They are rejected from the pool, whether
+	//   gossiped (`External`) or submitted by the node itself (`Local`), and enter blocks through
+	//   `create_inherent` only, checked by `check_inherent`.
+	// - Unsigned `store`, `store_compressed`, `renew` and `verify_content`, which use preimage
+	//   authorizations, are rejected from the pool too. Signed uploads use preimage authorizations
+	//   first, so no unsigned transactions are needed, and unsigned ones would be free to flood the
+	//   pool.
+	// - All other calls fail with `BadOrigin` when unsigned.
+	//
+	// Calls included in a block are not checked again here: inherents are checked by
+	// `check_inherent`, and other calls check their origin when dispatched.
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(
+			_source: TransactionSource,
+			_call: &Self::Call,
+		) -> TransactionValidity {
+			Err(InvalidTransaction::Call.into())
+		}
+
+		fn pre_dispatch(_call: &Self::Call) -> Result<(), TransactionValidityError> {
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn authorize(
+			scope: AuthorizationScope<T::AccountId>,
+			transactions: u32,
+			bytes: u64,
+			exact_size: Option<u64>,
+		) -> DispatchResult {
+			let period = T::AuthorizationPeriod::get();
+			if period.is_zero() {
+				return Ok(()) // Authorizations expire immediately
+			}
+			let outstanding = OutstandingAuthorizations::<T>::get();
+			ensure!(
+				outstanding < T::MaxOutstandingAuthorizations::get(),
+				Error::<T>::TooManyAuthorizations
+			);
+			let issued = BlockAuthorizations::<T>::get();
+			ensure!(
+				issued < T::MaxAuthorizationsPerBlock::get(),
+				Error::<T>::TooManyBlockAuthorizations
+			);
+
+			// Determine expiry block, if any
+			let now = frame_system::Pallet::<T>::block_number();
+			let expiry = now.checked_add(&period).and_then(|expiry| {
+				let min_expiry =
+					MinAuthorizationExpiryMinus1::<T>::get().checked_add(&1u32.into())?;
+				// Expiries never go back, so that the blocks they are in can be linked in order.
+				// They only would once `AuthorizationPeriod` is shortened.
+				let last_expiry = LastExpiryBlock::<T>::get();
+				Some(expiry.max(min_expiry).max(last_expiry.unwrap_or_default()))
+			});
+
+			// Credit scope. Extent beyond the maximum is not credited, and the authorization only
+			// records the extent credited.
+			let extent = AuthorizationExtent { transactions, bytes };
+			let credited = AuthorizationUsageByScope::<T>::mutate(scope.clone(), |usage| {
+				let before = usage.unused;
+				usage.unused = usage.unused.saturating_add(extent);
+				usage.exact_size = exact_size;
+				usage.unused.saturating_sub(before)
+			});
+			let id = NextAuthorizationId::<T>::mutate(|next| {
+				let id = *next;
+				*next = next.wrapping_add(1);
+				id
+			});
+			if credited != Default::default() {
+				let pending = PendingAuthorization { id, expiry, unused: credited };
+				PendingAuthorizations::<T>::try_mutate(&scope, |authorizations| {
+					// Authorizations that never expire are used last
+					let index = authorizations.partition_point(|authorization| {
+						match (authorization.expiry, expiry) {
+							(Some(before), Some(expiry)) => before <= expiry,
+							(before, expiry) => before.is_some() || expiry.is_none(),
+						}
+					});
+					authorizations.try_insert(index, pending)
+				})
+				.map_err(|_| Error::<T>::TooManyScopeAuthorizations)?;
+			}
+			Self::note_grant(scope.clone(), credited);
+			Self::deposit_event(Event::Authorized { scope: scope.clone(), extent: credited });
+			let Some(expiry) = expiry else {
+				return Ok(()) // Authorization never expires
 			};
-			let expiry = expiry.max(min_expiry);
 
-			// Record authorization for expire_authorizations
-			let authorization =
-				Authorization { scope, extent: AuthorizationExtent { transactions, bytes } };
+			// Record authorization for expire_authorizations, and the expiry of account
+			// authorizations for revoke_account_authorization
+			if let AuthorizationScope::Account(who) = &scope {
+				AccountAuthorizationExpiries::<T>::try_mutate(who, |expiries| {
+					match expiries.binary_search(&expiry) {
+						Ok(_) => Ok(()),
+						Err(index) => expiries.try_insert(index, expiry),
+					}
+				})
+				.map_err(|_| Error::<T>::TooManyAccountAuthorizations)?;
+			}
+			let authorization = Authorization { id, scope, extent: credited };
 			AuthorizationsByExpiry::<T>::mutate(expiry, |authorizations| {
 				authorizations.try_push(authorization).expect(
 					"Whenever a BoundedVec becomes full, MinAuthorizationExpiryMinus1 is bumped. \
@@ -549,11 +2587,41 @@ pub mod pallet {
 					MinAuthorizationExpiryMinus1::<T>::put(expiry);
 				}
 			});
+			match LastExpiryBlock::<T>::get() {
+				Some(last) if last == expiry => (),
+				Some(last) => {
+					ExpiryBlockAfter::<T>::insert(last, expiry);
+					LastExpiryBlock::<T>::put(expiry);
+				},
+				None => {
+					NextExpiryBlock::<T>::put(expiry);
+					LastExpiryBlock::<T>::put(expiry);
+				},
+			}
+			OutstandingAuthorizations::<T>::put(outstanding + 1);
+			BlockAuthorizations::<T>::put(issued + 1);
+			Ok(())
 		}
 
-		/// Returns the unused extent of (unexpired) authorizations for the given account.
+		/// Returns the unused extent of (unexpired) authorizations for the given account, including
+		/// what remains of its standing quota in the current authorization period. The extent held
+		/// from its authorizations is not included, as it cannot be used to store data.
 		pub fn unused_account_authorization_extent(who: T::AccountId) -> AuthorizationExtent {
-			AuthorizationUsageByScope::<T>::get(AuthorizationScope::Account(who)).unused
+			let standing = Self::standing_quota_remaining(who.clone());
+			let unused = Self::spendable_usage(&AuthorizationScope::Account(who)).unused;
+			unused.saturating_add(standing)
+		}
+
+		/// Returns what remains of the given account's standing quota in the current
+		/// authorization period.
+		pub fn standing_quota_remaining(who: T::AccountId) -> AuthorizationExtent {
+			let (Some(period), Some(mut quota)) =
+				(Self::current_authorization_period(), StandingQuotas::<T>::get(who))
+			else {
+				return Default::default()
+			};
+			quota.refill(period);
+			quota.remaining
 		}
 
 		/// Returns the unused extent of (unexpired) authorizations for the given preimage.
@@ -561,59 +2629,1808 @@ pub mod pallet {
 			AuthorizationUsageByScope::<T>::get(AuthorizationScope::Preimage(preimage)).unused
 		}
 
-		fn expire_authorizations(block: BlockNumberFor<T>) -> Weight {
-			let mut weight = Weight::zero();
-			let db_weight = T::DbWeight::get();
+		/// Returns the unused extent of (unexpired) authorizations for the given group.
+		pub fn unused_group_authorization_extent(group: GroupId) -> AuthorizationExtent {
+			AuthorizationUsageByScope::<T>::get(AuthorizationScope::Group(group)).unused
+		}
+
+		/// Returns the authorization usage for the given scope, or `None` if the scope has no
+		/// (unexpired) authorizations.
+		pub fn authorization_usage(
+			scope: AuthorizationScope<T::AccountId>,
+		) -> Option<AuthorizationUsage> {
+			AuthorizationUsageByScope::<T>::try_get(scope).ok()
+		}
+
+		/// Returns `true` if the given account has enough unused authorization to store a single
+		/// transaction of `size` bytes.
+		pub fn has_account_authorization(who: T::AccountId, size: u32) -> bool {
+			if Self::remaining_lifetime_allowance(who.clone()).map_or(false, |a| a < size.into()) {
+				return false
+			}
+			let scope = AuthorizationScope::Account(who.clone());
+			let charged = u64::from(Self::charged_size(size));
+			Self::spendable_usage(&scope).unused.covers(charged) ||
+				Self::standing_quota_remaining(who).covers(charged)
+		}
+
+		/// Returns the total number of bytes ever stored or renewed by the given account.
+		pub fn lifetime_used(who: T::AccountId) -> u64 {
+			LifetimeUsed::<T>::get(who)
+		}
+
+		/// Returns the number of bytes the given account can still store before reaching its
+		/// lifetime cap, or `None` if the account has no lifetime cap.
+		pub fn remaining_lifetime_allowance(who: T::AccountId) -> Option<u64> {
+			LifetimeCaps::<T>::get(&who).map(|cap| cap.saturating_sub(LifetimeUsed::<T>::get(who)))
+		}
+
+		/// Returns the storage quota held by the given account, see `StorageQuota`.
+		pub fn held_quota(who: T::AccountId) -> AuthorizationExtent {
+			HeldQuota::<T>::get(who)
+		}
+
+		/// Returns the content hashes the given account's restricted authorizations can be used
+		/// for.
+		pub fn allowed_hashes(who: T::AccountId) -> Vec<Preimage> {
+			AllowedHashes::<T>::get(who).into_inner()
+		}
+
+		/// Returns the unused extent of the authorization for the given preimage, or `None` if
+		/// the preimage is not authorized or its authorization has been fully used.
+		pub fn preimage_authorization(preimage: Preimage) -> Option<AuthorizationExtent> {
+			Self::authorization_usage(AuthorizationScope::Preimage(preimage))
+				.map(|usage| usage.unused)
+				.filter(|unused| unused.transactions != 0)
+		}
 
-			weight += db_weight.reads(1);
-			for authorization in AuthorizationsByExpiry::<T>::take(block) {
-				weight += db_weight.reads_writes(1, 1);
-				AuthorizationUsageByScope::<T>::mutate_exists(authorization.scope, |usage_slot| {
+		/// Expires the authorizations recorded for the given block, depositing an
+		/// `AuthorizationExpired` event for each. Returns the weight consumed, based on the number
+		/// of expired authorizations.
+		pub(crate) fn expire_authorizations(block: BlockNumberFor<T>) -> Weight {
+			let authorizations = AuthorizationsByExpiry::<T>::take(block);
+			OutstandingAuthorizations::<T>::mutate(|outstanding| {
+				*outstanding = outstanding.saturating_sub(authorizations.len() as u32)
+			});
+			let weight = T::WeightInfo::expire_authorizations(authorizations.len() as u32)
+				.saturating_add(
+					Self::expired_authorization_weight()
+						.saturating_mul(authorizations.len() as u64),
+				);
+			for authorization in authorizations {
+				let scope = authorization.scope.clone();
+				// Only the unused extent of this very authorization expires unused
+				let unused = Self::take_pending_authorization(&scope, authorization.id);
+				let used = authorization.extent.saturating_sub(unused);
+				let mut expired = AuthorizationExtent::default();
+				let removed = AuthorizationUsageByScope::<T>::mutate_exists(scope, |usage_slot| {
 					if let Some(usage) = usage_slot {
-						let unused_transactions = authorization
-							.extent
-							.transactions
-							.saturating_sub(usage.used.transactions);
-						let unused_bytes =
-							authorization.extent.bytes.saturating_sub(usage.used.bytes);
-						usage.used.transactions = usage
-							.used
-							.transactions
-							.saturating_sub(authorization.extent.transactions);
-						usage.used.bytes =
-							usage.used.bytes.saturating_sub(authorization.extent.bytes);
-						usage.unused.transactions =
-							usage.unused.transactions.saturating_sub(unused_transactions);
-						usage.unused.bytes = usage.unused.bytes.saturating_sub(unused_bytes);
-						if *usage == Default::default() {
+						let before = usage.unused;
+						usage.used = usage.used.saturating_sub(used);
+						usage.unused = usage.unused.saturating_sub(unused);
+						expired = before.saturating_sub(usage.unused);
+						if usage.used == Default::default() && usage.unused == Default::default() {
 							*usage_slot = None;
 						}
 					}
+					usage_slot.is_none()
+				});
+				// Even if fully used, so that each grant can be reconciled
+				Self::deposit_event(Event::AuthorizationExpired {
+					scope: authorization.scope.clone(),
+					extent: expired,
+					authorized: authorization.extent,
 				});
+				match (removed, authorization.scope) {
+					(_, AuthorizationScope::Account(who)) => {
+						Self::expire_held_quota(&who);
+						AccountAuthorizationExpiries::<T>::mutate_exists(who, |expiries| {
+							if let Some(list) = expiries {
+								list.retain(|expiry| *expiry != block);
+								if list.is_empty() {
+									*expiries = None;
+								}
+							}
+						});
+					},
+					(true, AuthorizationScope::RestrictedAccount(who)) =>
+						AllowedHashes::<T>::remove(who),
+					(true, AuthorizationScope::Preimage(preimage)) => {
+						// Expired without the preimage being stored
+						if let Some((who, deposit)) = PreimageDeposits::<T>::take(preimage) {
+							deposit.burn(&who);
+						}
+						PreimageReservations::<T>::remove(preimage);
+						if SatisfiedPreimages::<T>::take(preimage).is_some() {
+							let content_hash = preimage;
+							Self::deposit_content_event(
+								content_hash,
+								Event::PreimageSatisfactionExpired { content_hash },
+							);
+						}
+					},
+					_ => {},
+				}
 			}
 
 			weight
 		}
 
-		fn use_authorization(
-			origin: OriginFor<T>,
+		/// Authorizes `preimage`, taking a deposit from `depositor` if given, and reserves it for
+		/// `reserved_for` if given. Returns the deposit of any previous authorization of the
+		/// preimage.
+		fn authorize_preimage_with_deposit(
+			depositor: Option<&T::AccountId>,
 			preimage: Preimage,
-			size: u32,
+			bytes: u64,
+			exact_size: bool,
+			reserved_for: Option<T::AccountId>,
 		) -> DispatchResult {
-			let scope = match origin.into() {
-				Ok(RawOrigin::Signed(who)) => AuthorizationScope::Account(who),
-				Ok(RawOrigin::None) => AuthorizationScope::Preimage(preimage),
-				_ => return Err(DispatchError::BadOrigin),
+			// Sizes of transactions are `u32`s, at most `MaxTransactionSize`
+			ensure!(
+				!exact_size || bytes <= u64::from(T::MaxTransactionSize::get()),
+				Error::<T>::ExactSizeTooLarge
+			);
+			// Authorizations expiring immediately would never return the deposit
+			if let (Some(who), false) = (depositor, T::AuthorizationPeriod::get().is_zero()) {
+				let deposit = T::PreimageDeposit::new(who, bytes)?;
+				if let Some((previous, previous_deposit)) = PreimageDeposits::<T>::take(preimage) {
+					previous_deposit.drop(&previous)?;
+				}
+				PreimageDeposits::<T>::insert(preimage, (who.clone(), deposit));
+			}
+			let exact_size = exact_size.then_some(bytes);
+			Self::authorize(AuthorizationScope::Preimage(preimage), 1, bytes, exact_size)?;
+			// Authorizations expiring immediately leave nothing to reserve
+			if let (Some(who), false) = (reserved_for, T::AuthorizationPeriod::get().is_zero()) {
+				let now = <frame_system::Pallet<T>>::block_number();
+				let until = now.saturating_add(T::ReservationPeriod::get());
+				PreimageReservations::<T>::insert(preimage, (who.clone(), until));
+				let content_hash = preimage;
+				Self::deposit_content_event(
+					content_hash,
+					Event::PreimageReserved { content_hash, who, until },
+				);
+			}
+			Ok(())
+		}
+
+		/// Returns the deposit held for the authorization of `preimage`, if any.
+		fn release_preimage_deposit(preimage: Preimage) {
+			if let Some((who, deposit)) = PreimageDeposits::<T>::take(preimage) {
+				if let Err(e) = deposit.drop(&who) {
+					log::warn!(
+						target: LOG_TARGET,
+						"Failed to return the deposit of preimage {preimage:?}: {e:?}",
+					);
+				}
+			}
+		}
+
+		/// Weight of taking, returning or burning a preimage deposit: the deposit, the hold and
+		/// the account.
+		fn deposit_weight() -> Weight {
+			T::DbWeight::get().reads_writes(3, 3)
+		}
+
+		/// Removes the authorization `id` of `scope` from `PendingAuthorizations`, returning its
+		/// unused extent. Authorizations no longer listed were used up.
+		fn take_pending_authorization(
+			scope: &AuthorizationScope<T::AccountId>,
+			id: AuthorizationId,
+		) -> AuthorizationExtent {
+			let mut authorizations = PendingAuthorizations::<T>::get(scope);
+			let Some(index) = authorizations.iter().position(|pending| pending.id == id) else {
+				return Default::default()
 			};
-			AuthorizationUsageByScope::<T>::try_mutate(scope, |usage| {
-				usage.unused.transactions =
-					usage.unused.transactions.checked_sub(1).ok_or(Error::<T>::NotAuthorized)?;
-				usage.unused.bytes =
-					usage.unused.bytes.checked_sub(size.into()).ok_or(Error::<T>::NotAuthorized)?;
-				usage.used.transactions = usage.used.transactions.saturating_add(1);
-				usage.used.bytes = usage.used.bytes.saturating_add(size.into());
-				Ok(())
+			let unused = authorizations.remove(index).unused;
+			Self::put_pending_authorizations(scope, authorizations);
+			unused
+		}
+
+		/// Uses `extent` of the unused extent of the authorizations of `scope`, which must cover
+		/// it, from the authorization expiring first. Transactions and bytes are each used from
+		/// the first authorization with some left.
+		pub(crate) fn use_pending_authorizations(
+			scope: &AuthorizationScope<T::AccountId>,
+			mut extent: AuthorizationExtent,
+		) {
+			let mut authorizations = PendingAuthorizations::<T>::get(scope);
+			for pending in authorizations.iter_mut() {
+				let used = pending.unused.min(extent);
+				pending.unused = pending.unused.saturating_sub(used);
+				extent = extent.saturating_sub(used);
+			}
+			authorizations.retain(|pending| pending.unused != Default::default());
+			Self::put_pending_authorizations(scope, authorizations);
+		}
+
+		/// Sets the authorizations of `scope` with unused extent left.
+		fn put_pending_authorizations(
+			scope: &AuthorizationScope<T::AccountId>,
+			authorizations: BoundedVec<
+				PendingAuthorization<BlockNumberFor<T>>,
+				T::MaxScopeAuthorizations,
+			>,
+		) {
+			if authorizations.is_empty() {
+				PendingAuthorizations::<T>::remove(scope);
+			} else {
+				PendingAuthorizations::<T>::insert(scope, authorizations);
+			}
+		}
+
+		/// Reduces the quota held by `who` to the unused extent of its account authorizations,
+		/// after some of them expired.
+		fn expire_held_quota(who: &T::AccountId) {
+			let scope = AuthorizationScope::Account(who.clone());
+			let unused = AuthorizationUsageByScope::<T>::get(scope).unused;
+			HeldQuota::<T>::mutate_exists(who, |held| {
+				*held = held
+					.map(|extent| extent.min(unused))
+					.filter(|extent| *extent != Default::default())
+			});
+		}
+
+		/// Shrinks the pending account authorizations of `who` to the extent they used, and
+		/// removes those that used none, so that they no longer expire any unused extent.
+		/// Returns the number of expiry blocks of the account.
+		fn shrink_account_authorizations(who: &T::AccountId) -> u32 {
+			let scope = AuthorizationScope::Account(who.clone());
+			let pending = PendingAuthorizations::<T>::take(&scope);
+			let mut expiries = AccountAuthorizationExpiries::<T>::get(who);
+			let blocks = expiries.len() as u32;
+			let mut removed = 0;
+			expiries.retain(|block| {
+				let mut authorizations = AuthorizationsByExpiry::<T>::get(block).into_inner();
+				let len = authorizations.len();
+				authorizations.retain_mut(|authorization| {
+					if authorization.scope != scope {
+						return true
+					}
+					if let Some(unused) = pending
+						.iter()
+						.find(|pending| pending.id == authorization.id)
+						.map(|pending| pending.unused)
+					{
+						authorization.extent = authorization.extent.saturating_sub(unused);
+					}
+					authorization.extent != Default::default()
+				});
+				let kept = authorizations.iter().any(|authorization| authorization.scope == scope);
+				removed += len - authorizations.len();
+				if authorizations.is_empty() {
+					AuthorizationsByExpiry::<T>::remove(block);
+					Self::unlink_expiry_block(*block);
+				} else {
+					AuthorizationsByExpiry::<T>::insert(
+						block,
+						BoundedVec::truncate_from(authorizations),
+					);
+				}
+				kept
+			});
+			if expiries.is_empty() {
+				AccountAuthorizationExpiries::<T>::remove(who);
+			} else {
+				AccountAuthorizationExpiries::<T>::insert(who, expiries);
+			}
+			OutstandingAuthorizations::<T>::mutate(|outstanding| {
+				*outstanding = outstanding.saturating_sub(removed as u32)
+			});
+			blocks
+		}
+
+		/// Unlinks `block`, which no authorizations expire in any longer, if it is the first of the
+		/// blocks authorizations expire in. Other blocks stay linked until they are reached.
+		fn unlink_expiry_block(block: BlockNumberFor<T>) {
+			if NextExpiryBlock::<T>::get() != Some(block) {
+				return
+			}
+			match ExpiryBlockAfter::<T>::take(block) {
+				Some(next) => NextExpiryBlock::<T>::put(next),
+				None => {
+					NextExpiryBlock::<T>::kill();
+					LastExpiryBlock::<T>::kill();
+				},
+			}
+		}
+
+		/// Weight of cleaning up after the last authorization of a preimage expired: burning its
+		/// deposit and clearing its satisfaction and reservation.
+		fn expired_preimage_weight() -> Weight {
+			Self::deposit_weight().saturating_add(T::DbWeight::get().reads_writes(1, 2))
+		}
+
+		/// Weight of cleaning up after an authorization expired, on top of the
+		/// `expire_authorizations` benchmark: either that of a preimage, or the held quota and
+		/// expiry blocks of an account, whichever is heavier.
+		fn expired_authorization_weight() -> Weight {
+			Self::expired_preimage_weight().max(T::DbWeight::get().reads_writes(2, 2))
+		}
+
+		/// Weight of `on_finalize` for a block of `count` transactions. Its benchmark finalizes a
+		/// block with no proof due, so the storage period, the proof status, chunk count and chunk
+		/// ends of the block whose proof is due, the challenges of the block and the content
+		/// digest are added on top.
+		fn on_finalize_weight(count: u32) -> Weight {
+			T::WeightInfo::on_finalize(count).saturating_add(T::DbWeight::get().reads_writes(7, 4))
+		}
+
+		/// Weight of `store` for `len` bytes of data. Its benchmark falls back from the preimage
+		/// through all the authorizations of the signer to those of its group, so only the
+		/// preimage deposit and the hooks of the runtime are added on top.
+		fn store_weight(len: u32) -> Weight {
+			T::WeightInfo::store(len)
+				.saturating_add(Self::deposit_weight())
+				.saturating_add(T::StorageEventHandler::on_stored_weight())
+				.saturating_add(T::StoreReceipt::send_receipt_weight())
+		}
+
+		fn store_via_authorizer_weight(len: u32) -> Weight {
+			T::WeightInfo::store_via_authorizer(len)
+				.saturating_add(T::StorageEventHandler::on_stored_weight())
+		}
+
+		/// Weight of `verify_content` for `len` bytes of data. Its benchmark uses the
+		/// authorizations and bookkeeping of the benchmark of `renew`.
+		fn verify_content_weight(len: u32) -> Weight {
+			T::WeightInfo::verify_content(len)
+				.saturating_add(Self::deposit_weight())
+				.saturating_add(T::StorageEventHandler::on_renewed_weight())
+		}
+
+		/// Weight of `check_proof` for a target block with `count` transactions. The `check_proof`
+		/// benchmark proves one byte transactions, so the extra cost of the deepest chunk proof,
+		/// measured by `check_proof_worst_case` on a full block, is added on top.
+		fn check_proof_weight(count: u32) -> Weight {
+			let shallow = T::WeightInfo::check_proof(T::MaxBlockTransactions::get());
+			let deepest = T::WeightInfo::check_proof_worst_case().saturating_sub(shallow);
+			T::WeightInfo::check_proof(count).saturating_add(deepest)
+		}
+
+		/// Weight of `renew` from a block with `src_len` transactions. Its benchmark uses the
+		/// authorizations of the benchmark of `store`, for compressed and namespaced content that
+		/// changes owner, so only the preimage deposit and the hooks of the runtime are added on
+		/// top.
+		fn renew_weight(src_len: u32) -> Weight {
+			T::WeightInfo::renew(src_len)
+				.saturating_add(Self::deposit_weight())
+				.saturating_add(T::StorageEventHandler::on_renewed_weight())
+		}
+
+		/// The `block_chunks` of the transactions stored in the given block. Blocks finalized
+		/// before `ChunkEnds` was introduced only have `Transactions`.
+		fn chunk_ends(
+			block: BlockNumberFor<T>,
+		) -> Option<BoundedVec<u32, T::MaxBlockTransactions>> {
+			<ChunkEnds<T>>::get(block).or_else(|| {
+				let transactions = <Transactions<T>>::get(block)?;
+				Some(BoundedVec::truncate_from(
+					transactions.iter().map(|t| t.block_chunks).collect(),
+				))
 			})
 		}
+
+		/// The transactions stored in the given block.
+		pub fn transaction_roots(
+			block: BlockNumberFor<T>,
+		) -> Option<BoundedVec<TransactionInfo, T::MaxBlockTransactions>> {
+			let count = <ChunkEnds<T>>::decode_len(block).unwrap_or(0) as u32;
+			let transactions = (0..count)
+				.map(|index| <TransactionInfos<T>>::get(block, index))
+				.collect::<Option<Vec<_>>>()
+				.filter(|transactions| !transactions.is_empty());
+			match transactions {
+				Some(transactions) => Some(BoundedVec::truncate_from(transactions)),
+				None => <Transactions<T>>::get(block).map(|transactions| {
+					BoundedVec::truncate_from(transactions.into_iter().map(Into::into).collect())
+				}),
+			}
+		}
+
+		/// Reads the transaction at `index` of the given block, along with the number of
+		/// transactions decoded to find it.
+		pub(crate) fn stored_transaction(
+			block: BlockNumberFor<T>,
+			index: TxIndex,
+		) -> Option<(TransactionInfo, u32)> {
+			if let Some(info) = <TransactionInfos<T>>::get(block, index) {
+				return Some((info, 1))
+			}
+			let transactions = <Transactions<T>>::get(block)?;
+			let info = transactions.get(index.0 as usize)?.clone();
+			Some((info.into(), transactions.len() as u32))
+		}
+
+		/// The block and index of the transaction the content with the given hash was last stored
+		/// or renewed by, along with the transaction, or `None` if its storage period is over. As
+		/// with `times_challenged`, content stored before proof checks were counted and not
+		/// stored or renewed since is not found.
+		fn last_stored(
+			content_hash: Preimage,
+		) -> Option<(BlockNumberFor<T>, TxIndex, TransactionInfo)> {
+			let (_, block) = ChallengeCounts::<T>::get(content_hash)?;
+			TransactionInfos::<T>::iter_prefix(block)
+				.find(|(_, info)| Preimage::from(info.content_hash) == content_hash)
+				.map(|(index, info)| (block, TxIndex(index), info))
+		}
+
+		/// Checks the storage proof of block `block_number() - StoragePeriod`. Returns the number
+		/// of transactions in that block.
+		fn do_check_proof(proof: TransactionStorageProof) -> Result<u32, DispatchError> {
+			let number = <frame_system::Pallet<T>>::block_number();
+			let period = <StoragePeriod<T>>::get();
+			let target_number = number.saturating_sub(period);
+			ensure!(!ProofStatuses::<T>::contains_key(target_number), Error::<T>::DoubleCheck);
+			ensure!(
+				proof.encoded_size() <= T::MaxProofSize::get() as usize,
+				Error::<T>::ProofTooLarge
+			);
+			let (index, chunk_index, transaction_count) = Self::challenged_chunk(target_number)?;
+			let info = Self::transaction_info(target_number, index, transaction_count)
+				.ok_or(Error::<T>::MissingStateData)?;
+			check_proof_structure(&proof, Some(chunk_len(info.size, chunk_index)))
+				.map_err(Error::<T>::from)?;
+			ensure!(
+				verify_chunk(info.chunk_root, chunk_index, &proof.chunk, &proof.proof),
+				Error::<T>::InvalidProof
+			);
+			ProofStatuses::<T>::insert(target_number, ProofStatus::Proven);
+			ChallengeCounts::<T>::mutate_extant(Preimage::from(info.content_hash), |(times, _)| {
+				*times = times.saturating_add(1)
+			});
+			Self::deposit_event(Event::ProofChecked);
+			Ok(transaction_count)
+		}
+
+		/// Returns the block whose storage proof is due in the current block, along with the index
+		/// of the challenged transaction in that block and the index of the challenged chunk in
+		/// that transaction. Returns `None` if no proof is due.
+		pub fn proof_target() -> Option<(BlockNumberFor<T>, TxIndex, ChunkIndex)> {
+			let number = <frame_system::Pallet<T>>::block_number();
+			let target_number = number.saturating_sub(StoragePeriod::<T>::get());
+			let (index, chunk_index, _) = Self::challenged_chunk(target_number).ok()?;
+			Some((target_number, index, chunk_index))
+		}
+
+		/// Selects the chunk of the content stored in `target_number` to prove in the current
+		/// block. Returns the index of its transaction, its index in the transaction and the
+		/// number of transactions in `target_number`.
+		fn challenged_chunk(
+			target_number: BlockNumberFor<T>,
+		) -> Result<(TxIndex, ChunkIndex, u32), Error<T>> {
+			ensure!(!target_number.is_zero(), Error::<T>::UnexpectedProof);
+			ensure!(<ChunkCount<T>>::get(target_number) != 0, Error::<T>::UnexpectedProof);
+			let parent_hash = <frame_system::Pallet<T>>::parent_hash();
+			let chunk_ends = Self::chunk_ends(target_number).ok_or(Error::<T>::MissingStateData)?;
+			let (index, chunk_index) = select_chunk(parent_hash.as_ref(), &chunk_ends)
+				.ok_or(Error::<T>::MissingStateData)?;
+			Ok((index, chunk_index, chunk_ends.len() as u32))
+		}
+
+		/// Reads the transaction at `index` of the `count` transactions stored in the given block,
+		/// without decoding the others.
+		fn transaction_info(
+			block: BlockNumberFor<T>,
+			index: TxIndex,
+			count: u32,
+		) -> Option<TransactionInfo> {
+			if let Some(info) = <TransactionInfos<T>>::get(block, index) {
+				return Some(info)
+			}
+			// All entries of the old format have the same size, so the entry can be read at its
+			// offset
+			let size = LegacyTransactionInfo::max_encoded_len();
+			let offset = Compact::<u32>::compact_len(&count) + index.0 as usize * size;
+			let mut encoded = vec![0; size];
+			let key = <Transactions<T>>::hashed_key_for(block);
+			let remaining = sp_io::storage::read(&key, &mut encoded, offset as u32)?;
+			if (remaining as usize) < size {
+				return None
+			}
+			LegacyTransactionInfo::decode(&mut &encoded[..]).ok().map(Into::into)
+		}
+
+		/// Appends a transaction of `chunk_count` chunks to the ones indexed in this block. Returns
+		/// the index of the transaction within the block.
+		pub(crate) fn push_block_transaction(
+			chunk_root: <BlakeTwo256 as Hash>::Output,
+			size: u32,
+			content_hash: <BlakeTwo256 as Hash>::Output,
+			chunk_count: u32,
+		) -> Result<TxIndex, Error<T>> {
+			let index = TxIndex(<BlockTransactions<T>>::count());
+			Self::check_block_space()?;
+			let block_chunks = <BlockChunks<T>>::get()
+				.checked_add(chunk_count)
+				.ok_or(Error::<T>::TooManyChunks)?;
+			<BlockChunks<T>>::put(block_chunks);
+			<BlockTransactions<T>>::insert(
+				index,
+				TransactionInfo { chunk_root, size, content_hash, block_chunks, chunk_count },
+			);
+			Ok(index)
+		}
+
+		/// Ensures the call is being executed as part of an extrinsic. Calls dispatched during
+		/// block initialization or finalization (e.g. by the scheduler) would otherwise index
+		/// data that is not part of the extrinsic the node associates it with.
+		///
+		/// The execution phase is not set outside of block execution, e.g. in tests, in which
+		/// case this is left to the extrinsic index check. Returns the index of the extrinsic.
+		///
+		/// The node keeps a single index operation per extrinsic, so a second `store` or `renew`
+		/// within the same extrinsic (e.g. in a batch) would silently replace the first one and
+		/// leave its data unavailable. This is rejected instead.
+		///
+		/// This is checked before anything else, so that calls outside of an extrinsic report
+		/// their context rather than the state of their authorizations.
+		fn ensure_extrinsic_context() -> Result<u32, Error<T>> {
+			let key = storage_prefix(
+				<frame_system::Pallet<T> as PalletInfoAccess>::name().as_bytes(),
+				b"ExecutionPhase",
+			);
+			match unhashed::get::<frame_system::Phase>(&key) {
+				None | Some(frame_system::Phase::ApplyExtrinsic(_)) => (),
+				Some(_) => return Err(Error::<T>::NotInExtrinsic),
+			}
+			let extrinsic_index =
+				<frame_system::Pallet<T>>::extrinsic_index().ok_or(Error::<T>::BadContext)?;
+			ensure!(
+				<IndexedExtrinsic<T>>::get() != Some(extrinsic_index),
+				Error::<T>::AlreadyIndexedInExtrinsic
+			);
+			Ok(extrinsic_index)
+		}
+
+		/// Records that the given extrinsic, checked with `ensure_extrinsic_context`, indexes
+		/// data.
+		fn note_indexed_extrinsic(extrinsic_index: u32) {
+			<IndexedExtrinsic<T>>::put(extrinsic_index);
+		}
+
+		/// Indexes and stores `data` for `store`. Returns the content hash and index of the stored
+		/// transaction, and the actual weight of the call.
+		fn do_store(
+			origin: OriginFor<T>,
+			data: &[u8],
+		) -> Result<(Preimage, TxIndex, Weight), DispatchError> {
+			let extrinsic_index = Self::ensure_extrinsic_context()?;
+			let size = u32::try_from(data.len()).map_err(|_| Error::<T>::TransactionTooLarge)?;
+			Self::check_size(size).map_err(Error::<T>::from)?;
+			let content_hash = sp_io::hashing::blake2_256(data);
+			Self::check_not_removed(content_hash).map_err(Error::<T>::from)?;
+			Self::check_not_duplicate(content_hash).map_err(Error::<T>::from)?;
+			if ensure_none(origin.clone()).is_ok() {
+				Self::check_preimage_not_stored(content_hash).map_err(Error::<T>::from)?;
+			}
+
+			let owner = Self::use_authorization(origin, content_hash, size, false)?;
+			let index =
+				Self::index_data(extrinsic_index, data, size, content_hash, owner.as_ref())?;
+			let mut weight = Self::store_weight(size);
+			if owner.is_none() {
+				let now = <frame_system::Pallet<T>>::block_number();
+				T::StoreReceipt::send_receipt(content_hash, size, now);
+			} else {
+				weight = weight.saturating_sub(T::StoreReceipt::send_receipt_weight());
+			}
+			Ok((content_hash, index, weight))
+		}
+
+		/// Indexes `data` of the given extrinsic, checked with `ensure_extrinsic_context`, owned by
+		/// `owner` if any. `size` is the length of `data`, already checked to fit in a `u32`.
+		/// Returns the index of the stored transaction.
+		fn index_data(
+			extrinsic_index: u32,
+			data: &[u8],
+			size: u32,
+			content_hash: Preimage,
+			owner: Option<&T::AccountId>,
+		) -> Result<TxIndex, DispatchError> {
+			// Chunk data and compute storage root
+			let chunk_count = num_chunks(size);
+			let root = chunk_root(data);
+
+			Self::note_indexed_extrinsic(extrinsic_index);
+			sp_io::transaction_index::index(extrinsic_index, size, content_hash);
+
+			let index = Self::push_block_transaction(root, size, content_hash.into(), chunk_count)?;
+			let block = <frame_system::Pallet<T>>::block_number();
+			Self::note_challengeable(content_hash, block)?;
+			if let Some(who) = owner {
+				Self::note_owned(who, content_hash, block, index)?;
+			}
+			Self::deposit_content_event(content_hash, Event::Stored { index, chunk_root: root });
+			T::StorageEventHandler::on_stored(content_hash, size, owner, index);
+			Ok(index)
+		}
+
+		/// Stores `data` on behalf of another pallet of the runtime, owned by `owner` if any. No
+		/// authorization is used, so the caller must charge `store_internal_weight` for the size
+		/// of `data` in the weight of its call.
+		///
+		/// Data of up to `MAX_INLINE_DATA_SIZE` bytes is kept inline in the state, like preimages
+		/// noted through `StorePreimage`, whatever the context and wherever the data comes from. It
+		/// is kept for `StoragePeriod` blocks and can be fetched through `QueryPreimage` meanwhile.
+		/// Inline data has no owner, so storing it with one fails with `InlineDataHasNoOwner`.
+		///
+		/// Larger data is indexed like data stored with `store`, which is only sound if `data` is
+		/// part of the body of the extrinsic being executed: the node indexes it by looking it up
+		/// in the extrinsic, so data built by the runtime would be lost and its storage proofs
+		/// could never be provided. It is up to the caller to guarantee this, which is only
+		/// checked in debug builds. As with `store`, this fails outside of an extrinsic and at
+		/// most once per extrinsic.
+		pub fn store_internal(owner: Option<T::AccountId>, data: Vec<u8>) -> DispatchResult {
+			let size = u32::try_from(data.len()).map_err(|_| Error::<T>::TransactionTooLarge)?;
+			Self::check_size(size).map_err(Error::<T>::from)?;
+			if size <= MAX_INLINE_DATA_SIZE {
+				ensure!(owner.is_none(), Error::<T>::InlineDataHasNoOwner);
+				let hash = <Self as frame_support::traits::StorePreimage>::note(data.into())?;
+				// Noting counts as a request, which would keep the data past its storage period
+				<Self as frame_support::traits::QueryPreimage>::unrequest(&hash);
+				return Ok(())
+			}
+
+			let extrinsic_index = Self::ensure_extrinsic_context()?;
+			let content_hash = sp_io::hashing::blake2_256(&data);
+			Self::check_not_removed(content_hash).map_err(Error::<T>::from)?;
+			Self::check_not_duplicate(content_hash).map_err(Error::<T>::from)?;
+			debug_assert!(
+				<frame_system::Pallet<T>>::extrinsic_index().map_or(true, |index| {
+					let extrinsic = <frame_system::Pallet<T>>::extrinsic_data(index);
+					// Not recorded outside of the executive, e.g. in tests
+					extrinsic.is_empty() || extrinsic.windows(data.len()).any(|w| w == &data[..])
+				}),
+				"`store_internal` called with data that is not part of the extrinsic"
+			);
+			Self::index_data(extrinsic_index, &data, size, content_hash, owner.as_ref())?;
+			Ok(())
+		}
+
+		/// Weight of `store_internal` for `len` bytes of data, which callers must charge. Inline
+		/// data is noted like a preimage, larger data is weighed like a `store`.
+		pub fn store_internal_weight(len: u32) -> Weight {
+			match len <= MAX_INLINE_DATA_SIZE {
+				true => T::WeightInfo::note_preimage(len),
+				false => T::WeightInfo::store(len)
+					.saturating_add(T::StorageEventHandler::on_stored_weight()),
+			}
+		}
+
+		/// Looks up stored compressed content by the hash of either its compressed or its
+		/// uncompressed data. Returns the hash of the compressed data and its uncompressed form.
+		pub fn compression_info(
+			hash: Preimage,
+		) -> Option<(Preimage, CompressionInfo<BlockNumberFor<T>>)> {
+			let content_hash = match CompressedContent::<T>::contains_key(hash) {
+				true => hash,
+				false => UncompressedContent::<T>::get(hash)?,
+			};
+			CompressedContent::<T>::get(content_hash).map(|info| (content_hash, info))
+		}
+
+		/// Records the uncompressed form of compressed content stored or renewed in `info.block`.
+		fn note_compressed(
+			content_hash: Preimage,
+			info: CompressionInfo<BlockNumberFor<T>>,
+		) -> DispatchResult {
+			CompressedByBlock::<T>::try_mutate(info.block, |hashes| {
+				hashes.try_push(content_hash).map_err(|_| Error::<T>::TooManyTransactions)
+			})?;
+			UncompressedContent::<T>::insert(info.uncompressed_hash, content_hash);
+			CompressedContent::<T>::insert(content_hash, info);
+			Ok(())
+		}
+
+		/// Weight of listing stored content under a namespace, if `namespaced`.
+		fn namespace_weight(namespaced: bool) -> Weight {
+			match namespaced {
+				true => T::DbWeight::get().reads_writes(3, 4),
+				false => Weight::zero(),
+			}
+		}
+
+		/// Lists content stored or renewed in `block` under `namespace`.
+		fn note_namespace(
+			content_hash: Preimage,
+			namespace: Namespace,
+			block: BlockNumberFor<T>,
+		) -> DispatchResult {
+			match ContentNamespaces::<T>::get(content_hash) {
+				Some((listed, _)) => ensure!(listed == namespace, Error::<T>::NamespaceMismatch),
+				None => {
+					let max = T::MaxNamespaceEntries::get();
+					NamespaceEntries::<T>::try_mutate(namespace, |entries| {
+						ensure!(*entries < max, Error::<T>::NamespaceFull);
+						*entries += 1;
+						Ok::<_, DispatchError>(())
+					})?;
+					ContentByNamespace::<T>::insert(namespace, content_hash, ());
+				},
+			}
+			NamespacedByBlock::<T>::try_mutate(block, |hashes| {
+				hashes.try_push(content_hash).map_err(|_| Error::<T>::TooManyTransactions)
+			})?;
+			ContentNamespaces::<T>::insert(content_hash, (namespace, block));
+			Ok(())
+		}
+
+		/// Removes the namespaced content whose storage period ended with block `obsolete` from
+		/// its namespace, unless it was renewed since. Returns the weight consumed.
+		fn prune_namespaced(obsolete: BlockNumberFor<T>) -> Weight {
+			let hashes = NamespacedByBlock::<T>::take(obsolete);
+			let mut weight = T::DbWeight::get().reads_writes(1 + hashes.len() as u64, 1);
+			for content_hash in hashes {
+				let Some((namespace, block)) = ContentNamespaces::<T>::get(content_hash) else {
+					continue
+				};
+				if block != obsolete {
+					continue // Renewed since
+				}
+				ContentNamespaces::<T>::remove(content_hash);
+				ContentByNamespace::<T>::remove(namespace, content_hash);
+				NamespaceEntries::<T>::mutate_exists(namespace, |entries| {
+					*entries = entries.map(|n| n.saturating_sub(1)).filter(|n| *n > 0);
+				});
+				weight.saturating_accrue(T::DbWeight::get().reads_writes(1, 3));
+			}
+			weight
+		}
+
+		/// Removes the inline preimages whose storage period ended with block `obsolete`, carrying
+		/// the ones still requested over to the current block `n`. Returns the weight consumed.
+		fn prune_inline(obsolete: BlockNumberFor<T>, n: BlockNumberFor<T>) -> Weight {
+			let mut hashes = InlineByBlock::<T>::take(obsolete);
+			let listed = hashes.len() as u64;
+			hashes.retain(|hash| {
+				let requested = InlineDataRequests::<T>::contains_key(hash);
+				if !requested {
+					InlineData::<T>::remove(hash);
+				}
+				requested
+			});
+			let mut weight =
+				T::DbWeight::get().reads_writes(1 + listed, 1 + listed - hashes.len() as u64);
+			if !hashes.is_empty() {
+				// Nothing was noted in the current block yet, so its list can be replaced
+				InlineByBlock::<T>::insert(n, hashes);
+				weight.saturating_accrue(T::DbWeight::get().writes(1));
+			}
+			weight
+		}
+
+		/// Records the content of `info`, indexed again by the current extrinsic, as a transaction
+		/// of the current block, for `renew` and `verify_content`. The content is owned by `owner`
+		/// if any, otherwise by `payer`. Returns the index of the new transaction.
+		fn note_renewal(
+			info: &TransactionInfo,
+			owner: Option<T::AccountId>,
+			payer: Option<T::AccountId>,
+		) -> Result<TxIndex, DispatchError> {
+			let content_hash = info.content_hash.into();
+			let index = Self::push_block_transaction(
+				info.chunk_root,
+				info.size,
+				info.content_hash,
+				info.chunk_count,
+			)?;
+			Self::deposit_content_event(
+				content_hash,
+				Event::Renewed { index, chunk_root: info.chunk_root },
+			);
+			if let Some(owner) = owner.clone().filter(|owner| payer.as_ref() != Some(owner)) {
+				Self::deposit_content_event(
+					content_hash,
+					Event::RenewedOnBehalf { index, payer: payer.clone(), owner },
+				);
+			}
+			let owner = owner.or(payer);
+			T::StorageEventHandler::on_renewed(content_hash, info.size, owner.as_ref(), index);
+			let block = frame_system::Pallet::<T>::block_number();
+			Self::note_challengeable(content_hash, block)?;
+			if let Some(who) = &owner {
+				Self::note_owned(who, content_hash, block, index)?;
+			}
+			if let Some(compression) = CompressedContent::<T>::get(content_hash) {
+				Self::note_compressed(content_hash, CompressionInfo { block, ..compression })?;
+			}
+			if let Some((namespace, _)) = ContentNamespaces::<T>::get(content_hash) {
+				Self::note_namespace(content_hash, namespace, block)?;
+			}
+			Ok(index)
+		}
+
+		/// Records that `who` stored or renewed content as transaction `index` of `block`.
+		fn note_owned(
+			who: &T::AccountId,
+			content_hash: Preimage,
+			block: BlockNumberFor<T>,
+			index: TxIndex,
+		) -> DispatchResult {
+			if !OwnedContent::<T>::contains_key(who, content_hash) {
+				let max = T::MaxOwnedContent::get();
+				OwnedContentCount::<T>::try_mutate(who, |count| {
+					ensure!(*count < max, Error::<T>::TooMuchOwnedContent);
+					*count += 1;
+					Ok::<_, DispatchError>(())
+				})?;
+			}
+			OwnedByBlock::<T>::try_mutate(block, |owned| {
+				owned
+					.try_push((who.clone(), content_hash))
+					.map_err(|_| Error::<T>::TooManyTransactions)
+			})?;
+			OwnedContent::<T>::insert(who, content_hash, (block, index));
+			Ok(())
+		}
+
+		/// The owner of content `content_hash` as stored or renewed in `block`: an account that
+		/// stored or renewed it in `block` and still owns it, if any.
+		fn content_owner(block: BlockNumberFor<T>, content_hash: Preimage) -> Option<T::AccountId> {
+			OwnedByBlock::<T>::get(block)
+				.into_iter()
+				.filter(|(_, hash)| *hash == content_hash)
+				.map(|(who, _)| who)
+				.find(|who| OwnedContent::<T>::contains_key(who, content_hash))
+		}
+
+		/// Removes the owned content whose storage period ended with block `obsolete`, unless it
+		/// was renewed since by the same account. Returns the weight consumed.
+		fn prune_owned(obsolete: BlockNumberFor<T>) -> Weight {
+			let owned = OwnedByBlock::<T>::take(obsolete);
+			let mut weight = T::DbWeight::get().reads_writes(1 + owned.len() as u64, 1);
+			for (who, content_hash) in owned {
+				match OwnedContent::<T>::get(&who, content_hash) {
+					Some((block, _)) if block == obsolete => {},
+					_ => continue, // Renewed since, or already pruned
+				}
+				OwnedContent::<T>::remove(&who, content_hash);
+				OwnedContentCount::<T>::mutate_exists(&who, |count| {
+					*count = count.map(|n| n.saturating_sub(1)).filter(|n| *n > 0);
+				});
+				weight.saturating_accrue(T::DbWeight::get().reads_writes(1, 2));
+			}
+			weight
+		}
+
+		/// Lists up to `limit` of the live content owned by `who`, ordered by hash and starting
+		/// after `start_after` if given, with the block and index of its last store or renewal by
+		/// `who`. Removed content is skipped.
+		pub fn owned_content(
+			who: T::AccountId,
+			start_after: Option<Preimage>,
+			limit: u32,
+		) -> Vec<(Preimage, BlockNumberFor<T>, TxIndex)> {
+			let entries = match start_after {
+				Some(hash) => OwnedContent::<T>::iter_prefix_from(
+					&who,
+					OwnedContent::<T>::hashed_key_for(&who, hash),
+				),
+				None => OwnedContent::<T>::iter_prefix(&who),
+			};
+			entries
+				.filter(|(hash, _)| !RemovedContent::<T>::contains_key(hash))
+				.take(limit as usize)
+				.map(|(hash, (block, index))| (hash, block, index))
+				.collect()
+		}
+
+		/// Number of live content entries owned by `who`, including removed content until its
+		/// storage period is over.
+		pub fn owned_content_count(who: T::AccountId) -> u32 {
+			OwnedContentCount::<T>::get(who)
+		}
+
+		/// Lists up to `limit` hashes of the content in `namespace`, ordered by hash, starting
+		/// after `start_after` if given.
+		pub fn namespace_content(
+			namespace: Namespace,
+			start_after: Option<Preimage>,
+			limit: u32,
+		) -> Vec<Preimage> {
+			let hashes = match start_after {
+				Some(hash) => ContentByNamespace::<T>::iter_key_prefix_from(
+					namespace,
+					ContentByNamespace::<T>::hashed_key_for(namespace, hash),
+				),
+				None => ContentByNamespace::<T>::iter_key_prefix(namespace),
+			};
+			hashes.take(limit as usize).collect()
+		}
+
+		/// Lists the content currently stored, scanning up to `limit` transactions, capped at
+		/// `MAX_STORED_CONTENT_PAGE`, after the raw storage key `start_key`, or from the start.
+		/// Returns the records of the scanned content that was not removed, and the key to
+		/// continue from if there are transactions left to scan. Content is listed in key order,
+		/// so content stored between calls is listed if its key comes after `start_key`.
+		/// Transactions not yet moved by `migrations::v1` are not listed.
+		pub fn stored_content(
+			start_key: Option<Vec<u8>>,
+			limit: u32,
+		) -> (Vec<ContentRecord<BlockNumberFor<T>>>, Option<Vec<u8>>) {
+			let mut transactions = match start_key {
+				Some(key) => TransactionInfos::<T>::iter_from(key),
+				None => TransactionInfos::<T>::iter(),
+			};
+			let period = StoragePeriod::<T>::get();
+			let mut records = Vec::new();
+			let mut last = None;
+			for (block, index, info) in
+				transactions.by_ref().take(limit.clamp(1, MAX_STORED_CONTENT_PAGE) as usize)
+			{
+				last = Some((block, index));
+				if RemovedContent::<T>::contains_key(Preimage::from(info.content_hash)) {
+					continue
+				}
+				records.push(ContentRecord {
+					content_hash: info.content_hash,
+					size: info.size,
+					block,
+					index: TxIndex(index),
+					expires_at: block.saturating_add(period),
+				});
+			}
+			let next_key = match (last, transactions.next()) {
+				(Some((block, index)), Some(_)) =>
+					Some(TransactionInfos::<T>::hashed_key_for(block, index)),
+				_ => None,
+			};
+			(records, next_key)
+		}
+
+		/// Removes the compressed content whose storage period ended with block `obsolete`,
+		/// unless it was renewed since. Returns the weight consumed.
+		fn prune_compressed(obsolete: BlockNumberFor<T>) -> Weight {
+			let hashes = CompressedByBlock::<T>::take(obsolete);
+			let mut weight = T::DbWeight::get().reads_writes(1 + hashes.len() as u64, 1);
+			for content_hash in hashes {
+				let Some(info) = CompressedContent::<T>::get(content_hash) else { continue };
+				if info.block != obsolete {
+					continue // Renewed since
+				}
+				CompressedContent::<T>::remove(content_hash);
+				UncompressedContent::<T>::mutate_exists(info.uncompressed_hash, |compressed| {
+					if *compressed == Some(content_hash) {
+						*compressed = None;
+					}
+				});
+				weight.saturating_accrue(T::DbWeight::get().reads_writes(1, 2));
+			}
+			weight
+		}
+
+		/// Records that the content with the given hash was stored or renewed in `block`, keeping
+		/// the number of times its storage proof was checked.
+		fn note_challengeable(content_hash: Preimage, block: BlockNumberFor<T>) -> DispatchResult {
+			ChallengeCountsByBlock::<T>::try_mutate(block, |hashes| {
+				hashes.try_push(content_hash).map_err(|_| Error::<T>::TooManyTransactions)
+			})?;
+			ChallengeCounts::<T>::mutate(content_hash, |entry| {
+				*entry = Some((entry.map_or(0, |(times, _)| times), block))
+			});
+			Ok(())
+		}
+
+		/// Removes the proof check counts of the content whose storage period ended with block
+		/// `obsolete`, unless it was stored or renewed since. Returns the weight consumed.
+		fn prune_challenge_counts(obsolete: BlockNumberFor<T>) -> Weight {
+			let hashes = ChallengeCountsByBlock::<T>::take(obsolete);
+			let mut weight = T::DbWeight::get().reads_writes(1 + hashes.len() as u64, 1);
+			for content_hash in hashes {
+				match ChallengeCounts::<T>::get(content_hash) {
+					Some((_, block)) if block == obsolete => (),
+					_ => continue, // Stored or renewed since, or listed twice
+				}
+				ChallengeCounts::<T>::remove(content_hash);
+				weight.saturating_accrue(T::DbWeight::get().writes(1));
+			}
+			weight
+		}
+
+		/// The topic of events about the content with the given hash: the system hash of
+		/// `b"tx-storage::content" ++ content_hash`, i.e.
+		/// `blake2_256(b"tx-storage::content" ++ content_hash)` with the usual `BlakeTwo256`.
+		pub fn content_topic(content_hash: &Preimage) -> T::Hash {
+			T::Hashing::hash_of(&(b"tx-storage::content", content_hash))
+		}
+
+		/// Deposits an event about the content with the given hash, under its `content_topic`.
+		fn deposit_content_event(content_hash: Preimage, event: Event<T>) {
+			let event = <T as Config>::RuntimeEvent::from(event).into();
+			let topics = [Self::content_topic(&content_hash)];
+			<frame_system::Pallet<T>>::deposit_event_indexed(&topics, event);
+		}
+
+		fn remove_content(content_hash: Preimage, reason: RemovalReason) {
+			RemovedContent::<T>::insert(content_hash, ());
+			if Tombstones::<T>::count() < T::MaxTombstones::get() {
+				Tombstones::<T>::insert(content_hash, reason);
+			} else {
+				log::warn!(
+					target: LOG_TARGET,
+					"Too many tombstones, not keeping the removal reason of {content_hash:?}",
+				);
+			}
+			Self::deposit_content_event(
+				content_hash,
+				Event::ContentRemoved { content_hash, reason },
+			);
+		}
+
+		/// Returns the number of blocks data is stored for: data stored in block `n` is proven, and
+		/// can be renewed until, block `n + StoragePeriod`.
+		pub fn storage_period() -> BlockNumberFor<T> {
+			<StoragePeriod<T>>::get()
+		}
+
+		/// Returns the status of the storage proof of the content stored in `block`. Statuses are
+		/// known until `StoragePeriod` blocks after the proof was due.
+		pub fn proof_status(block: BlockNumberFor<T>) -> ProofStatus {
+			if let Some(status) = ProofStatuses::<T>::get(block) {
+				return status
+			}
+			let due = block.saturating_add(StoragePeriod::<T>::get());
+			let stored = !block.is_zero() && ChunkCount::<T>::get(block) != 0;
+			if stored && frame_system::Pallet::<T>::block_number() <= due {
+				ProofStatus::Pending
+			} else {
+				ProofStatus::NotRequired
+			}
+		}
+
+		/// Returns the number of times the storage proof of a block checked a chunk of the
+		/// content with the given hash during its lifetime, as extended by stores and renewals,
+		/// or `None` if the content is not stored. Each store or renewal is proven once, at the
+		/// end of its storage period.
+		pub fn times_challenged(content_hash: Preimage) -> Option<u32> {
+			ChallengeCounts::<T>::get(content_hash).map(|(times, _)| times)
+		}
+
+		/// Returns, for each of the given content hashes, the block and index to renew the content
+		/// with and the blocks to renew it by, or `None` if it is not stored, or was removed or is
+		/// pending removal. As with `times_challenged`, content stored before proof checks were
+		/// counted and not stored or renewed since is not found.
+		pub fn renewal_deadlines(
+			hashes: Vec<Preimage>,
+		) -> Vec<Option<RenewalDeadline<BlockNumberFor<T>>>> {
+			let period = StoragePeriod::<T>::get();
+			let lead_time = T::RenewalLeadTime::get();
+			hashes
+				.into_iter()
+				.map(|content_hash| {
+					if RemovedContent::<T>::contains_key(content_hash) ||
+						PendingRemovals::<T>::contains_key(content_hash)
+					{
+						return None
+					}
+					let (block, index, _) = Self::last_stored(content_hash)?;
+					let last_block = block.saturating_add(period);
+					// Renewals can only refer to transactions of earlier blocks
+					let recommended_block =
+						last_block.saturating_sub(lead_time).max(block.saturating_add(One::one()));
+					Some(RenewalDeadline { block, index, last_block, recommended_block })
+				})
+				.collect()
+		}
+
+		/// Returns the authorizations used by the given account in each of the last
+		/// `MaxUsageHistory` authorization periods it used any in, oldest first. Uses of preimage
+		/// authorizations by the account's signed transactions are included.
+		pub fn account_usage_history(who: T::AccountId) -> Vec<PeriodUsage<BlockNumberFor<T>>> {
+			UsageHistory::<T>::get(who).into_inner()
+		}
+
+		/// Returns the last `MaxRecentAuthorizations` authorization grants, with the extent
+		/// credited and the block they were granted in, oldest first.
+		pub fn recent_authorizations() -> Vec<AuthorizationGrant<T::AccountId, BlockNumberFor<T>>> {
+			let mut grants = RecentAuthorizations::<T>::get().into_inner();
+			let next = NextRecentAuthorization::<T>::get() as usize;
+			if next < grants.len() {
+				grants.rotate_left(next);
+			}
+			grants
+		}
+
+		/// Returns why the content with the given hash was removed, if it was removed and its
+		/// tombstone is kept.
+		pub fn tombstone(content_hash: Preimage) -> Option<RemovalReason> {
+			Tombstones::<T>::get(content_hash)
+		}
+
+		/// Checks that `ChunkEnds` and `ChunkCount` match the stored transactions, that their
+		/// `chunk_count` matches their size under the current chunk size, that `OwnedContent`
+		/// matches the stored transactions and its summaries, that each inline preimage is
+		/// listed in exactly one `InlineByBlock` list, that `OutstandingAuthorizations` counts
+		/// the authorizations pending expiry, that the blocks they expire in are linked in order,
+		/// and that only authorized preimages are reserved.
+		#[cfg(any(feature = "try-runtime", feature = "mock", test))]
+		pub(crate) fn do_try_state() -> Result<(), DispatchError> {
+			for (block, chunk_ends) in <ChunkEnds<T>>::iter() {
+				let transactions =
+					Self::transaction_roots(block).ok_or("`ChunkEnds` without transactions")?;
+				ensure!(
+					transactions.iter().map(|t| t.block_chunks).eq(chunk_ends.into_iter()),
+					"`ChunkEnds` does not match `Transactions`"
+				);
+				ensure!(
+					transactions.iter().all(|t| t.chunk_count == num_chunks(t.size)),
+					"`chunk_count` does not match the size of a transaction"
+				);
+				ensure!(<ChunkCount<T>>::contains_key(block), "`ChunkEnds` without `ChunkCount`");
+			}
+			for (block, chunk_count) in <ChunkCount<T>>::iter() {
+				let transactions =
+					Self::transaction_roots(block).ok_or("`ChunkCount` without transactions")?;
+				ensure!(
+					transactions.last().map(|t| t.block_chunks) == Some(chunk_count),
+					"`ChunkCount` does not match `Transactions`"
+				);
+			}
+			let now = frame_system::Pallet::<T>::block_number();
+			let mut counts = sp_std::collections::btree_map::BTreeMap::<T::AccountId, u32>::new();
+			for (who, content_hash, (block, index)) in OwnedContent::<T>::iter() {
+				let info = Self::stored_transaction(block, index)
+					.map(|(info, _)| info)
+					.or_else(|| BlockTransactions::<T>::get(index).filter(|_| block == now))
+					.ok_or("`OwnedContent` without transaction")?;
+				ensure!(
+					info.content_hash.0 == content_hash,
+					"`OwnedContent` does not match `Transactions`"
+				);
+				ensure!(
+					OwnedByBlock::<T>::get(block).contains(&(who.clone(), content_hash)),
+					"`OwnedContent` missing from `OwnedByBlock`"
+				);
+				*counts.entry(who).or_default() += 1;
+			}
+			for (who, count) in OwnedContentCount::<T>::iter() {
+				ensure!(
+					counts.remove(&who) == Some(count),
+					"`OwnedContentCount` does not match `OwnedContent`"
+				);
+			}
+			ensure!(counts.is_empty(), "`OwnedContent` without `OwnedContentCount`");
+			if T::StrictDedup::get() {
+				Self::try_strict_dedup(now)?;
+			}
+			let mut listed = sp_std::collections::btree_map::BTreeMap::<Preimage, u32>::new();
+			for hash in InlineByBlock::<T>::iter_values().flatten() {
+				*listed.entry(hash).or_default() += 1;
+			}
+			for hash in InlineData::<T>::iter_keys() {
+				ensure!(
+					listed.remove(&hash) == Some(1),
+					"`InlineData` not listed in exactly one `InlineByBlock` list"
+				);
+			}
+			ensure!(listed.is_empty(), "`InlineByBlock` lists a preimage not in `InlineData`");
+			let outstanding: usize =
+				AuthorizationsByExpiry::<T>::iter_values().map(|a| a.len()).sum();
+			ensure!(
+				outstanding == OutstandingAuthorizations::<T>::get() as usize,
+				"`OutstandingAuthorizations` does not match `AuthorizationsByExpiry`"
+			);
+			for preimage in PreimageReservations::<T>::iter_keys() {
+				ensure!(
+					AuthorizationUsageByScope::<T>::contains_key(AuthorizationScope::Preimage(
+						preimage
+					)),
+					"`PreimageReservations` reserves a preimage without authorizations"
+				);
+			}
+			for (who, held) in HeldQuota::<T>::iter() {
+				ensure!(held != Default::default(), "`HeldQuota` holds nothing");
+				let unused =
+					AuthorizationUsageByScope::<T>::get(AuthorizationScope::Account(who)).unused;
+				ensure!(
+					unused.checked_sub(held).is_some(),
+					"`HeldQuota` exceeds the unused account authorizations"
+				);
+			}
+			for (scope, usage) in AuthorizationUsageByScope::<T>::iter() {
+				let authorizations = PendingAuthorizations::<T>::get(&scope);
+				let unused = authorizations
+					.iter()
+					.fold(AuthorizationExtent::default(), |sum, a| sum.saturating_add(a.unused));
+				ensure!(
+					unused == usage.unused,
+					"`AuthorizationUsageByScope` does not add up `PendingAuthorizations`"
+				);
+			}
+			for (scope, authorizations) in PendingAuthorizations::<T>::iter() {
+				ensure!(
+					AuthorizationUsageByScope::<T>::contains_key(&scope),
+					"`PendingAuthorizations` of a scope without usage"
+				);
+				ensure!(
+					authorizations.windows(2).all(|pair| match (pair[0].expiry, pair[1].expiry) {
+						(Some(first), Some(second)) => first <= second,
+						(first, second) => first.is_some() || second.is_none(),
+					}),
+					"`PendingAuthorizations` not in expiry order"
+				);
+				for pending in authorizations {
+					ensure!(
+						pending.unused != Default::default(),
+						"`PendingAuthorizations` lists a used up authorization"
+					);
+					let Some(expiry) = pending.expiry else { continue };
+					let recorded = AuthorizationsByExpiry::<T>::get(expiry)
+						.into_iter()
+						.find(|a| a.id == pending.id && a.scope == scope);
+					ensure!(
+						recorded.map_or(false, |a| a.extent.checked_sub(pending.unused).is_some()),
+						"`PendingAuthorizations` exceeds an authorization of `AuthorizationsByExpiry`"
+					);
+				}
+			}
+			let mut indexed = 0;
+			for (who, expiries) in AccountAuthorizationExpiries::<T>::iter() {
+				ensure!(
+					expiries.windows(2).all(|pair| pair[0] < pair[1]),
+					"`AccountAuthorizationExpiries` not in order"
+				);
+				let scope = AuthorizationScope::Account(who);
+				for block in expiries {
+					ensure!(
+						AuthorizationsByExpiry::<T>::get(block).iter().any(|a| a.scope == scope),
+						"`AccountAuthorizationExpiries` lists a block not expiring the account"
+					);
+					indexed += 1;
+				}
+			}
+			let mut linked = sp_std::collections::btree_set::BTreeSet::new();
+			let mut next = NextExpiryBlock::<T>::get();
+			while let Some(block) = next {
+				linked.insert(block);
+				next = ExpiryBlockAfter::<T>::get(block);
+				ensure!(next.map_or(true, |next| next > block), "`ExpiryBlockAfter` not in order");
+				if next.is_none() {
+					ensure!(
+						LastExpiryBlock::<T>::get() == Some(block),
+						"`LastExpiryBlock` is not the last linked block"
+					);
+				}
+			}
+			ensure!(
+				ExpiryBlockAfter::<T>::iter_keys().count() == linked.len().saturating_sub(1),
+				"`ExpiryBlockAfter` links blocks not reached from `NextExpiryBlock`"
+			);
+			ensure!(
+				!linked.is_empty() || LastExpiryBlock::<T>::get().is_none(),
+				"`LastExpiryBlock` set without `NextExpiryBlock`"
+			);
+			for block in AuthorizationsByExpiry::<T>::iter_keys() {
+				ensure!(linked.contains(&block), "`AuthorizationsByExpiry` block not linked");
+			}
+			let mut expiring = sp_std::collections::btree_set::BTreeSet::new();
+			for (block, authorizations) in AuthorizationsByExpiry::<T>::iter() {
+				for authorization in authorizations {
+					if let AuthorizationScope::Account(who) = authorization.scope {
+						expiring.insert((block, who));
+					}
+				}
+			}
+			ensure!(
+				expiring.len() == indexed,
+				"`AccountAuthorizationExpiries` misses blocks expiring account authorizations"
+			);
+			Ok(())
+		}
+
+		/// Checks that, with `StrictDedup`, `ChallengeCounts` indexes all the stored content at a
+		/// single location: the block it was last stored or renewed in.
+		#[cfg(any(feature = "try-runtime", feature = "mock", test))]
+		fn try_strict_dedup(now: BlockNumberFor<T>) -> Result<(), DispatchError> {
+			// Transactions of the current block are only moved to `TransactionInfos` once it is
+			// finalized
+			let transactions = |block| {
+				Self::transaction_roots(block).map(BoundedVec::into_inner).or_else(|| {
+					(block == now).then(|| BlockTransactions::<T>::iter_values().collect())
+				})
+			};
+			let pending = (!<ChunkEnds<T>>::contains_key(now)).then_some(now);
+			for block in <ChunkEnds<T>>::iter_keys().chain(pending) {
+				for info in transactions(block).unwrap_or_default() {
+					let (_, latest) = ChallengeCounts::<T>::get(info.content_hash.0)
+						.ok_or("Stored content missing from `ChallengeCounts`")?;
+					ensure!(latest >= block, "`ChallengeCounts` behind the content it indexes");
+				}
+			}
+			for (content_hash, (_, latest)) in ChallengeCounts::<T>::iter() {
+				ensure!(
+					transactions(latest)
+						.unwrap_or_default()
+						.iter()
+						.any(|info| info.content_hash.0 == content_hash),
+					"`ChallengeCounts` indexes content at a block not storing it"
+				);
+			}
+			Ok(())
+		}
+
+		/// Weight of expiring `authorizations` authorizations and executing `removals` scheduled
+		/// removals for a block.
+		fn expiry_weight(authorizations: u32, removals: u32) -> Weight {
+			// Reading the number of authorizations, then the next block authorizations expire in
+			T::WeightInfo::expire_authorizations(authorizations)
+				.saturating_add(
+					Self::expired_authorization_weight().saturating_mul(authorizations.into()),
+				)
+				.saturating_add(T::DbWeight::get().reads_writes(2, 1))
+				.saturating_add(Self::removals_weight(removals))
+		}
+
+		/// Weight of executing `removals` scheduled removals for a block no authorizations
+		/// expire in.
+		fn removals_weight(removals: u32) -> Weight {
+			// Reading the number of removals
+			T::WeightInfo::execute_scheduled_removals(removals)
+				.saturating_add(T::DbWeight::get().reads(1))
+		}
+
+		/// Expires authorizations and executes removals due in blocks up to `n`, in order, while
+		/// they fit in `ExpiryWeightRatio` of the block. The rest is left for the following
+		/// blocks. Returns the weight consumed.
+		pub(crate) fn process_expiries(n: BlockNumberFor<T>) -> Weight {
+			let limit = T::ExpiryWeightRatio::get() * T::BlockWeights::get().max_block;
+			// Reading and updating the cursor, and the first and last blocks expiries are due in
+			let cursor_weight = T::DbWeight::get().reads_writes(2, 3);
+			let mut meter = WeightMeter::from_limit(limit.saturating_sub(cursor_weight));
+			let mut block = <ExpiryCursor<T>>::get().unwrap_or(n);
+			let first_expiry = NextExpiryBlock::<T>::get();
+			let mut next_expiry = first_expiry;
+			while block <= n {
+				// Authorizations expiring in a block expiries were not processed in, e.g. granted
+				// with no `AuthorizationPeriod`, expire in the next block processed
+				let expiry = next_expiry.filter(|expiry| *expiry <= block);
+				let removals = RemovalSchedule::<T>::decode_len(block).unwrap_or(0) as u32;
+				let weight = match expiry {
+					Some(expiry) => Self::expiry_weight(
+						AuthorizationsByExpiry::<T>::decode_len(expiry).unwrap_or(0) as u32,
+						removals,
+					),
+					None => Self::removals_weight(removals),
+				};
+				if !meter.check_accrue(weight) {
+					break
+				}
+				if let Some(expiry) = expiry {
+					Self::expire_authorizations(expiry);
+					next_expiry = ExpiryBlockAfter::<T>::take(expiry);
+				}
+				Self::execute_scheduled_removals(block);
+				block += One::one();
+			}
+			if block > n {
+				<ExpiryCursor<T>>::kill();
+			} else {
+				<ExpiryCursor<T>>::put(block);
+			}
+			if next_expiry != first_expiry {
+				match next_expiry {
+					Some(next) => NextExpiryBlock::<T>::put(next),
+					None => {
+						NextExpiryBlock::<T>::kill();
+						LastExpiryBlock::<T>::kill();
+					},
+				}
+			}
+			meter.consumed.saturating_add(cursor_weight)
+		}
+
+		/// Executes the content removals scheduled for the given block. Returns the weight
+		/// consumed.
+		pub(crate) fn execute_scheduled_removals(block: BlockNumberFor<T>) -> Weight {
+			let content_hashes = RemovalSchedule::<T>::take(block);
+			let weight = T::WeightInfo::execute_scheduled_removals(content_hashes.len() as u32);
+			for content_hash in content_hashes {
+				let reason = PendingRemovals::<T>::take(content_hash)
+					.map_or(RemovalReason::Takedown, |(_, reason)| reason);
+				Self::remove_content(content_hash, reason);
+			}
+
+			weight
+		}
+
+		/// Returns the index of the current authorization period, or `None` if authorizations
+		/// expire immediately.
+		fn current_authorization_period() -> Option<BlockNumberFor<T>> {
+			frame_system::Pallet::<T>::block_number().checked_div(&T::AuthorizationPeriod::get())
+		}
+
+		/// Try to use the given account's standing quota, refilling it first if a new
+		/// authorization period has started. Returns `true` if the quota covered the
+		/// transaction.
+		fn use_standing_quota(who: &T::AccountId, size: u32) -> bool {
+			let Some((mut quota, covered)) = Self::refilled_standing_quota(who, size) else {
+				return false
+			};
+			if covered {
+				quota.remaining =
+					quota.remaining.saturating_sub(AuthorizationExtent::transaction(size.into()));
+			}
+			StandingQuotas::<T>::insert(who, quota);
+			covered
+		}
+
+		/// Add `size` bytes to the given account's lifetime usage, failing if this would exceed
+		/// its lifetime cap.
+		fn use_lifetime_allowance(who: &T::AccountId, size: u32) -> DispatchResult {
+			let used = Self::check_lifetime_allowance(who, size).map_err(Error::<T>::from)?;
+			LifetimeUsed::<T>::insert(who, used);
+			Ok(())
+		}
+
+		/// Uses the authorization of `origin` to store `size` bytes of content `preimage`, for a
+		/// renewal if `renewal`. Returns the signer, if any.
+		fn use_authorization(
+			origin: OriginFor<T>,
+			preimage: Preimage,
+			size: u32,
+			renewal: bool,
+		) -> Result<Option<T::AccountId>, DispatchError> {
+			match origin.into() {
+				Ok(RawOrigin::Signed(who)) => {
+					Self::check_not_barred(&who).map_err(Error::<T>::from)?;
+					Self::check_not_reserved(preimage, Some(&who)).map_err(Error::<T>::from)?;
+					Self::use_lifetime_allowance(&who, size)?;
+					Self::note_usage(&who, size, renewal);
+					let charged = Self::charged_size(size);
+					match T::AuthorizationOrder::get() {
+						ConsumptionOrder::PreimageFirst => {
+							if Self::use_preimage_scope(preimage, size).is_err() {
+								Self::use_account_scopes(&who, preimage, charged)?;
+							}
+						},
+						ConsumptionOrder::AccountFirst => {
+							let used = Self::use_account_scopes(&who, preimage, charged);
+							// The signer's error is more useful than the preimage's
+							if used.is_err() && Self::use_preimage_scope(preimage, size).is_err() {
+								used?;
+							}
+						},
+					}
+					Ok(Some(who))
+				},
+				Ok(RawOrigin::None) => {
+					Self::check_not_reserved(preimage, None).map_err(Error::<T>::from)?;
+					Self::use_preimage_scope(preimage, size)?;
+					Ok(None)
+				},
+				_ => Err(DispatchError::BadOrigin),
+			}
+		}
+
+		/// Uses the authorizations of the signer `who` to store `size` bytes of content
+		/// `preimage`: its restricted authorizations if they allow `preimage`, then its standing
+		/// quota, its account authorizations and its group's.
+		fn use_account_scopes(who: &T::AccountId, preimage: Preimage, size: u32) -> DispatchResult {
+			let restricted = AuthorizationScope::RestrictedAccount(who.clone());
+			if AllowedHashes::<T>::get(who).contains(&preimage) &&
+				Self::use_scope(restricted, size).is_ok()
+			{
+				return Ok(())
+			}
+			if Self::use_standing_quota(who, size) {
+				return Ok(())
+			}
+			let used = Self::use_scope(AuthorizationScope::Account(who.clone()), size);
+			match (used, GroupOf::<T>::get(who)) {
+				(Err(_), Some(group)) => Self::use_scope(AuthorizationScope::Group(group), size),
+				(used, _) => used,
+			}
+		}
+
+		/// Records a grant of `extent` to `scope` in `RecentAuthorizations`, overwriting the oldest
+		/// grant once `MaxRecentAuthorizations` are kept.
+		fn note_grant(scope: AuthorizationScope<T::AccountId>, extent: AuthorizationExtent) {
+			let max = T::MaxRecentAuthorizations::get();
+			if max == 0 {
+				return
+			}
+			let grant = (scope, extent, frame_system::Pallet::<T>::block_number());
+			let next = NextRecentAuthorization::<T>::get() % max;
+			RecentAuthorizations::<T>::mutate(|grants| match grants.get_mut(next as usize) {
+				Some(oldest) => *oldest = grant,
+				// Not full yet, so `next` is the length
+				None => {
+					let _ = grants.try_push(grant);
+				},
+			});
+			NextRecentAuthorization::<T>::put((next + 1) % max);
+		}
+
+		/// Adds a transaction of `size` bytes, a renewal if `renewal`, to the usage of the given
+		/// account in the current authorization period. The usage of the oldest period is dropped
+		/// once `MaxUsageHistory` periods are kept.
+		fn note_usage(who: &T::AccountId, size: u32, renewal: bool) {
+			let Some(period) = Self::current_authorization_period() else { return };
+			let max = T::MaxUsageHistory::get();
+			if max == 0 {
+				return
+			}
+			UsageHistory::<T>::mutate(who, |history| {
+				if history.last().map_or(true, |usage| usage.period < period) {
+					if history.len() as u32 >= max {
+						history.remove(0);
+					}
+					let usage = PeriodUsage { period, ..Default::default() };
+					// Room was made above
+					let _ = history.try_push(usage);
+				}
+				let last = history.len().saturating_sub(1);
+				if let Some(usage) = history.get_mut(last) {
+					usage.transactions.saturating_inc();
+					usage.bytes.saturating_accrue(size.into());
+					if renewal {
+						usage.renewals.saturating_inc();
+					}
+				}
+			});
+		}
+
+		/// Uses the authorization of `preimage` to store `size` bytes of it, returning the
+		/// authorization's deposit. Under the `Strict` preimage policy, the preimage is then
+		/// satisfied.
+		fn use_preimage_scope(preimage: Preimage, size: u32) -> DispatchResult {
+			Self::check_preimage_not_satisfied(preimage).map_err(Error::<T>::from)?;
+			Self::use_scope(AuthorizationScope::Preimage(preimage), size)?;
+			Self::release_preimage_deposit(preimage);
+			if T::PreimagePolicy::get() == PreimageStorePolicy::Strict {
+				let block = <frame_system::Pallet<T>>::block_number();
+				SatisfiedPreimages::<T>::insert(preimage, block);
+				let content_hash = preimage;
+				Self::deposit_content_event(
+					content_hash,
+					Event::PreimageSatisfied { content_hash },
+				);
+			}
+			Ok(())
+		}
+
+		/// Checks whether storing `size` bytes as `origin` would succeed in the current state,
+		/// using the checks of `store`. Accounts are checked for arbitrary data, so the
+		/// authorizations restricted to `AllowedHashes` are not considered.
+		pub fn can_store(origin: ScopeRef<T::AccountId>, size: u32) -> Result<(), StoreRejection> {
+			match origin {
+				ScopeRef::Account(who) => Self::dry_run_store(who, size),
+				ScopeRef::Preimage(content_hash) => {
+					Self::check_size(size)?;
+					Self::check_not_removed(content_hash)?;
+					Self::check_preimage_not_stored(content_hash)?;
+					Self::check_preimage_not_satisfied(content_hash)?;
+					Self::check_not_reserved(content_hash, None)?;
+					Self::check_scope(AuthorizationScope::Preimage(content_hash), size)?;
+					Self::check_block_space()
+				},
+			}
+		}
+
+		/// Checks whether the given account could store `size` bytes of arbitrary data with a
+		/// signed `store` in the current state, as `can_store` does for accounts. Nothing is
+		/// written and the transaction index is not touched, so this can be called outside of
+		/// block execution, e.g. with `state_call`.
+		pub fn dry_run_store(who: T::AccountId, size: u32) -> Result<(), StoreRejection> {
+			Self::check_size(size)?;
+			Self::check_not_barred(&who)?;
+			Self::check_lifetime_allowance(&who, size)?;
+			let charged = Self::charged_size(size);
+			if !matches!(Self::refilled_standing_quota(&who, charged), Some((_, true))) {
+				let checked = Self::check_scope(AuthorizationScope::Account(who.clone()), charged);
+				match (checked, GroupOf::<T>::get(&who)) {
+					(Err(_), Some(group)) =>
+						Self::check_scope(AuthorizationScope::Group(group), charged)?,
+					(checked, _) => checked?,
+				}
+			}
+			Self::check_block_space()
+		}
+
+		/// Checks that `size` bytes can be stored in a transaction.
+		fn check_size(size: u32) -> Result<(), StoreRejection> {
+			ensure!(size != 0, StoreRejection::Empty);
+			ensure!(size >= T::MinTransactionSize::get(), StoreRejection::TooSmall);
+			ensure!(size <= T::MaxTransactionSize::get(), StoreRejection::TooLarge);
+			Ok(())
+		}
+
+		/// Bytes of account authorizations charged for a transaction of `size` bytes: at least
+		/// `MinBytesPerStore`, unless it carries no data, as proof requests.
+		fn charged_size(size: u32) -> u32 {
+			match size {
+				0 => 0,
+				size => size.max(T::MinBytesPerStore::get()),
+			}
+		}
+
+		/// Checks that the block has room for another transaction, besides the stores by the
+		/// authorizer still allowed in the block.
+		fn check_block_space() -> Result<(), StoreRejection> {
+			let reserved =
+				T::AuthorizerStoresPerBlock::get().saturating_sub(AuthorizerStores::<T>::get());
+			ensure!(
+				<BlockTransactions<T>>::count() <
+					T::MaxBlockTransactions::get().saturating_sub(reserved),
+				StoreRejection::BlockFull
+			);
+			Ok(())
+		}
+
+		/// Checks that the content with the given hash was not removed.
+		fn check_not_removed(content_hash: Preimage) -> Result<(), StoreRejection> {
+			ensure!(!RemovedContent::<T>::contains_key(content_hash), StoreRejection::Removed);
+			Ok(())
+		}
+
+		/// Checks that the given content is not live, if `StrictDedup` is enabled. Content is
+		/// listed in `ChallengeCounts` as soon as it is stored, so duplicates within a block are
+		/// caught too.
+		fn check_not_duplicate(content_hash: Preimage) -> Result<(), StoreRejection> {
+			ensure!(
+				!T::StrictDedup::get() || !ChallengeCounts::<T>::contains_key(content_hash),
+				StoreRejection::Duplicate
+			);
+			Ok(())
+		}
+
+		/// Checks that the preimage authorization of the given content was not used up by a
+		/// store. Contested uploads of an authorized preimage are first come, first served.
+		fn check_preimage_not_stored(content_hash: Preimage) -> Result<(), StoreRejection> {
+			let scope = AuthorizationScope::Preimage(content_hash);
+			let usage = AuthorizationUsageByScope::<T>::get(scope);
+			ensure!(
+				usage.used.transactions == 0 || usage.unused.transactions != 0,
+				StoreRejection::AlreadyStored
+			);
+			Ok(())
+		}
+
+		/// Checks that the given preimage was not satisfied by a store under the `Strict` preimage
+		/// policy.
+		fn check_preimage_not_satisfied(content_hash: Preimage) -> Result<(), StoreRejection> {
+			ensure!(
+				!SatisfiedPreimages::<T>::contains_key(content_hash),
+				StoreRejection::AlreadySatisfied
+			);
+			Ok(())
+		}
+
+		/// Checks that the content with the given hash is not reserved for another account than
+		/// `who`, or for any account if `who` is `None`.
+		fn check_not_reserved(
+			content_hash: Preimage,
+			who: Option<&T::AccountId>,
+		) -> Result<(), StoreRejection> {
+			if let Some((reserved, until)) = PreimageReservations::<T>::get(content_hash) {
+				let now = <frame_system::Pallet<T>>::block_number();
+				ensure!(now > until || who == Some(&reserved), StoreRejection::Reserved);
+			}
+			Ok(())
+		}
+
+		/// Checks that the given account is not barred from uploading.
+		fn check_not_barred(who: &T::AccountId) -> Result<(), StoreRejection> {
+			ensure!(!Self::is_barred(who), StoreRejection::Banned);
+			Ok(())
+		}
+
+		/// Checks that the given account can store `size` more bytes under its lifetime cap.
+		/// Returns its lifetime usage including them.
+		fn check_lifetime_allowance(who: &T::AccountId, size: u32) -> Result<u64, StoreRejection> {
+			let used = LifetimeUsed::<T>::get(who).saturating_add(size.into());
+			if let Some(cap) = LifetimeCaps::<T>::get(who) {
+				ensure!(used <= cap, StoreRejection::LifetimeCapExceeded);
+			}
+			Ok(used)
+		}
+
+		/// The standing quota of the given account, refilled if a new authorization period has
+		/// started, and whether it covers a transaction of `size` bytes.
+		fn refilled_standing_quota(
+			who: &T::AccountId,
+			size: u32,
+		) -> Option<(StandingQuota<BlockNumberFor<T>>, bool)> {
+			let period = Self::current_authorization_period()?;
+			let mut quota = StandingQuotas::<T>::get(who)?;
+			quota.refill(period);
+			let covered = quota.remaining.covers(size.into());
+			Some((quota, covered))
+		}
+
+		/// Checks that the authorizations of the given scope cover one transaction of `size`
+		/// bytes.
+		fn check_scope(
+			scope: AuthorizationScope<T::AccountId>,
+			size: u32,
+		) -> Result<(), StoreRejection> {
+			Self::check_usage(&Self::spendable_usage(&scope), size)
+		}
+
+		/// The usage of the authorizations of the given scope, without the quota held from them.
+		fn spendable_usage(scope: &AuthorizationScope<T::AccountId>) -> AuthorizationUsage {
+			Self::without_held(scope, &AuthorizationUsageByScope::<T>::get(scope))
+		}
+
+		/// `usage` of the authorizations of the given scope, without the quota held from them.
+		fn without_held(
+			scope: &AuthorizationScope<T::AccountId>,
+			usage: &AuthorizationUsage,
+		) -> AuthorizationUsage {
+			let held = match scope {
+				AuthorizationScope::Account(who) => HeldQuota::<T>::get(who),
+				_ => Default::default(),
+			};
+			AuthorizationUsage { unused: usage.unused.saturating_sub(held), ..*usage }
+		}
+
+		/// Checks that `usage` covers one transaction of `size` bytes.
+		fn check_usage(usage: &AuthorizationUsage, size: u32) -> Result<(), StoreRejection> {
+			if let Some(exact_size) = usage.exact_size {
+				ensure!(exact_size == u64::from(size), StoreRejection::SizeMismatch);
+			}
+			if usage.unused.transactions == 0 {
+				let authorized = usage.used.transactions != 0 || usage.unused.bytes != 0;
+				return Err(if authorized {
+					StoreRejection::InsufficientTransactions
+				} else {
+					StoreRejection::NotAuthorized
+				})
+			}
+			ensure!(usage.unused.covers(size.into()), StoreRejection::InsufficientBytes);
+			Ok(())
+		}
+
+		/// Availability challenges to be proved in the next block, in the order their proofs must
+		/// be given to `check_proofs`.
+		pub fn challenges() -> Vec<Challenge<T::AccountId, BlockNumberFor<T>>> {
+			let next = frame_system::Pallet::<T>::block_number().saturating_add(One::one());
+			Challenges::<T>::get(next).into_inner()
+		}
+
+		/// Whether the given account is currently barred from uploading.
+		pub fn is_barred(who: &T::AccountId) -> bool {
+			Barred::<T>::get(who)
+				.map_or(false, |until| frame_system::Pallet::<T>::block_number() < until)
+		}
+
+		/// Records a strike against the given account for an upload that failed authorization
+		/// checks, barring it for `BarPeriod` blocks once it reaches `StrikeThreshold` strikes.
+		pub(crate) fn strike(who: &T::AccountId) {
+			let threshold = T::StrikeThreshold::get();
+			if threshold == 0 {
+				return
+			}
+			let strikes = Strikes::<T>::get(who).saturating_add(1);
+			if strikes < threshold {
+				Strikes::<T>::insert(who, strikes);
+				Self::deposit_event(Event::Struck { who: who.clone(), strikes });
+				return
+			}
+			Strikes::<T>::remove(who);
+			let until =
+				frame_system::Pallet::<T>::block_number().saturating_add(T::BarPeriod::get());
+			Barred::<T>::insert(who, until);
+			Self::deposit_event(Event::AccountBarred { who: who.clone(), until });
+		}
+
+		/// Use one transaction and `size` bytes of the authorizations of the given scope.
+		fn use_scope(scope: AuthorizationScope<T::AccountId>, size: u32) -> DispatchResult {
+			AuthorizationUsageByScope::<T>::try_mutate(scope.clone(), |usage| {
+				let spendable = Self::without_held(&scope, usage);
+				Self::check_usage(&spendable, size).map_err(Error::<T>::from)?;
+				let extent = AuthorizationExtent::transaction(size.into());
+				// Covered, so this uses up at most all of the unused extent
+				usage.unused = usage.unused.checked_sub(extent).ok_or(Error::<T>::NotAuthorized)?;
+				usage.used = usage.used.saturating_add(extent);
+				Ok::<_, DispatchError>(())
+			})?;
+			let extent = AuthorizationExtent::transaction(size.into());
+			Self::use_pending_authorizations(&scope, extent);
+			Self::deposit_event(Event::AuthorizationUsed { scope, extent });
+			Ok(())
+		}
 	}
 }
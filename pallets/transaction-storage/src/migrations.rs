@@ -0,0 +1,367 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage migrations for the transaction storage pallet.
+
+use super::*;
+use frame_support::{
+	traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+
+#[cfg(feature = "try-runtime")]
+use frame_support::ensure;
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
+
+/// Moves `Transactions` to `TransactionInfos`, one entry per transaction, adding their
+/// `chunk_count`.
+///
+/// `Transactions` holds up to `MaxBlockTransactions` entries for each block of the storage
+/// period, too many to move in a single block. The upgrade only records the range of blocks
+/// stored in the old format; the entries are then moved block by block from `on_initialize`,
+/// in the weight left by expiries in `ExpiryWeightRatio` of the block. Until a block has been
+/// moved, its transactions are read from `Transactions`.
+pub mod v1 {
+	use super::*;
+
+	pub struct MigrateToTransactionInfos<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToTransactionInfos<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				log::info!(
+					target: LOG_TARGET,
+					"Skipping migration to v1, it has already been applied",
+				);
+				return T::DbWeight::get().reads(1)
+			}
+			let now = frame_system::Pallet::<T>::block_number();
+			// Older blocks are pruned in this block, and later ones are finalized in the new
+			// format
+			let first = now.saturating_sub(StoragePeriod::<T>::get()).max(One::one());
+			let last = now.saturating_sub(One::one());
+			if first <= last {
+				TransactionsMigration::<T>::put((first, last));
+				log::info!(
+					target: LOG_TARGET,
+					"Migrating `Transactions` of blocks {first:?}..={last:?}",
+				);
+			}
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(2, 2)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			Ok((Transactions::<T>::iter_keys().count() as u32).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let count = u32::decode(&mut &state[..]).map_err(|_| "Cannot decode the state")?;
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 1,
+				"The storage version was not updated"
+			);
+			ensure!(
+				count == 0 || TransactionsMigration::<T>::exists(),
+				"`Transactions` is not being migrated"
+			);
+			Ok(())
+		}
+	}
+
+	/// Moves the `Transactions` entries of as many blocks as fit in `limit`, in order. Returns
+	/// the weight consumed.
+	pub(crate) fn step<T: Config>(limit: Weight) -> Weight {
+		let Some((mut block, last)) = TransactionsMigration::<T>::get() else {
+			return T::DbWeight::get().reads(1)
+		};
+		// Reading and updating the cursor
+		let cursor_weight = T::DbWeight::get().reads_writes(1, 1);
+		let mut meter = WeightMeter::from_limit(limit.saturating_sub(cursor_weight));
+		while block <= last {
+			let count = Transactions::<T>::decode_len(block).unwrap_or(0);
+			if !meter.check_accrue(T::WeightInfo::migrate_block_transactions(count as u32)) {
+				break
+			}
+			migrate_block::<T>(block);
+			block += One::one();
+		}
+		if block > last {
+			TransactionsMigration::<T>::kill();
+			log::info!(target: LOG_TARGET, "Migrated `Transactions` to `TransactionInfos`");
+		} else {
+			TransactionsMigration::<T>::put((block, last));
+		}
+		meter.consumed.saturating_add(cursor_weight)
+	}
+
+	/// Moves the `Transactions` entry of the given block to `TransactionInfos`.
+	pub(crate) fn migrate_block<T: Config>(block: BlockNumberFor<T>) {
+		if let Some(transactions) = Transactions::<T>::take(block) {
+			for (index, info) in transactions.into_iter().enumerate() {
+				TransactionInfos::<T>::insert(block, index as u32, TransactionInfo::from(info));
+			}
+		}
+	}
+}
+
+/// Records the reason of scheduled removals in `PendingRemovals`. Removals scheduled before the
+/// upgrade are recorded as takedowns.
+pub mod v2 {
+	use super::*;
+
+	pub struct MigrateToRemovalReasons<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToRemovalReasons<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 1 {
+				log::info!(
+					target: LOG_TARGET,
+					"Skipping migration to v2, the storage version is not 1",
+				);
+				return T::DbWeight::get().reads(1)
+			}
+			let mut count = 0u64;
+			PendingRemovals::<T>::translate::<BlockNumberFor<T>, _>(|_, at| {
+				count += 1;
+				Some((at, RemovalReason::Takedown))
+			});
+			log::info!(target: LOG_TARGET, "Migrated {count} pending removals");
+			StorageVersion::new(2).put::<Pallet<T>>();
+			// Scheduled removals are bounded by `MaxBlockRemovals` per block
+			T::DbWeight::get().reads_writes(count.saturating_add(1), count.saturating_add(1))
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			Ok((PendingRemovals::<T>::iter_keys().count() as u32).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let count = u32::decode(&mut &state[..]).map_err(|_| "Cannot decode the state")?;
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 2,
+				"The storage version was not updated"
+			);
+			ensure!(
+				PendingRemovals::<T>::iter_values().count() as u32 == count,
+				"Pending removals were lost"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Links the blocks authorizations expire in from `NextExpiryBlock`, so that `on_initialize` only
+/// reads `AuthorizationsByExpiry` in blocks authorizations expire in.
+pub mod v3 {
+	use super::*;
+
+	pub struct MigrateToExpiryBlockList<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToExpiryBlockList<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 2 {
+				log::info!(
+					target: LOG_TARGET,
+					"Skipping migration to v3, the storage version is not 2",
+				);
+				return T::DbWeight::get().reads(1)
+			}
+			let mut blocks: Vec<_> = AuthorizationsByExpiry::<T>::iter_keys().collect();
+			blocks.sort();
+			for pair in blocks.windows(2) {
+				ExpiryBlockAfter::<T>::insert(pair[0], pair[1]);
+			}
+			if let (Some(first), Some(last)) = (blocks.first(), blocks.last()) {
+				NextExpiryBlock::<T>::put(first);
+				LastExpiryBlock::<T>::put(last);
+			}
+			let count = blocks.len() as u64;
+			log::info!(target: LOG_TARGET, "Linked {count} blocks authorizations expire in");
+			StorageVersion::new(3).put::<Pallet<T>>();
+			// Blocks authorizations expire in are bounded by `MaxOutstandingAuthorizations`
+			T::DbWeight::get().reads_writes(count.saturating_add(1), count.saturating_add(2))
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			Ok((AuthorizationsByExpiry::<T>::iter_keys().count() as u32).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let count = u32::decode(&mut &state[..]).map_err(|_| "Cannot decode the state")?;
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 3,
+				"The storage version was not updated"
+			);
+			let mut linked = 0;
+			let mut next = NextExpiryBlock::<T>::get();
+			while let Some(block) = next {
+				linked += 1;
+				next = ExpiryBlockAfter::<T>::get(block);
+			}
+			ensure!(linked == count, "Blocks authorizations expire in were not linked");
+			Ok(())
+		}
+	}
+}
+
+/// Gives each authorization pending expiry an identifier, and records what is left of the
+/// authorizations of each scope in `PendingAuthorizations`. The used extent of a scope was taken
+/// from its authorizations in the order they expire in, and is attributed to them in that order.
+/// Unused extent left over, granted by authorizations that never expire, is recorded as an
+/// authorization that never expires.
+pub mod v4 {
+	use super::*;
+	use sp_std::collections::btree_map::BTreeMap;
+
+	/// An authorization before it had an identifier.
+	#[derive(Encode, Decode)]
+	struct OldAuthorization<AccountId> {
+		scope: AuthorizationScope<AccountId>,
+		extent: AuthorizationExtent,
+	}
+
+	pub struct MigrateToPendingAuthorizations<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToPendingAuthorizations<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 3 {
+				log::info!(
+					target: LOG_TARGET,
+					"Skipping migration to v4, the storage version is not 3",
+				);
+				return T::DbWeight::get().reads(1)
+			}
+			// Identifiers are given in the order authorizations expire in
+			let mut blocks: Vec<_> = AuthorizationsByExpiry::<T>::iter_keys().collect();
+			blocks.sort();
+			let mut next_id: AuthorizationId = 0;
+			// The authorizations of each scope, keyed by the encoded scope
+			let mut expiring = BTreeMap::<Vec<u8>, Vec<_>>::new();
+			for &block in &blocks {
+				let key = AuthorizationsByExpiry::<T>::hashed_key_for(block);
+				let old: Vec<OldAuthorization<T::AccountId>> =
+					frame_support::storage::unhashed::get(&key).unwrap_or_default();
+				let authorizations: Vec<_> = old
+					.into_iter()
+					.map(|OldAuthorization { scope, extent }| {
+						let id = next_id;
+						next_id += 1;
+						expiring.entry(scope.encode()).or_default().push((block, id, extent));
+						Authorization { id, scope, extent }
+					})
+					.collect();
+				AuthorizationsByExpiry::<T>::insert(
+					block,
+					BoundedVec::truncate_from(authorizations),
+				);
+			}
+			let scopes: Vec<_> = AuthorizationUsageByScope::<T>::iter().collect();
+			let count = scopes.len() as u64;
+			for (scope, mut usage) in scopes {
+				let authorizations = expiring.remove(&scope.encode()).unwrap_or_default();
+				let mut used = usage.used;
+				let mut unused = usage.unused;
+				let mut pending = Vec::new();
+				for (block, id, extent) in authorizations {
+					let used_of = extent.min(used);
+					used = used.saturating_sub(used_of);
+					let left = extent.saturating_sub(used_of).min(unused);
+					unused = unused.saturating_sub(left);
+					if left != Default::default() {
+						pending.push(PendingAuthorization {
+							id,
+							expiry: Some(block),
+							unused: left,
+						});
+					}
+				}
+				if unused != Default::default() {
+					pending.push(PendingAuthorization { id: next_id, expiry: None, unused });
+					next_id += 1;
+				}
+				let max = T::MaxScopeAuthorizations::get() as usize;
+				if pending.len() > max {
+					// The authorizations expiring last are dropped
+					let dropped = pending
+						.split_off(max)
+						.iter()
+						.fold(AuthorizationExtent::default(), |sum, a| {
+							sum.saturating_add(a.unused)
+						});
+					log::warn!(
+						target: LOG_TARGET,
+						"Too many authorizations of {scope:?}, {dropped:?} of their unused extent is \
+						dropped",
+					);
+					usage.unused = usage.unused.saturating_sub(dropped);
+					if usage == Default::default() {
+						AuthorizationUsageByScope::<T>::remove(&scope);
+					} else {
+						AuthorizationUsageByScope::<T>::insert(&scope, usage);
+					}
+				}
+				if !pending.is_empty() {
+					PendingAuthorizations::<T>::insert(&scope, BoundedVec::truncate_from(pending));
+				}
+			}
+			NextAuthorizationId::<T>::put(next_id);
+			log::info!(
+				target: LOG_TARGET,
+				"Recorded the authorizations of {count} scopes, expiring in {} blocks",
+				blocks.len(),
+			);
+			StorageVersion::new(4).put::<Pallet<T>>();
+			// Both are bounded by `MaxOutstandingAuthorizations`
+			let items = (blocks.len() as u64).saturating_add(count);
+			T::DbWeight::get().reads_writes(items.saturating_add(1), items.saturating_add(2))
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			Ok(OutstandingAuthorizations::<T>::get().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let count = u32::decode(&mut &state[..]).map_err(|_| "Cannot decode the state")?;
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 4,
+				"The storage version was not updated"
+			);
+			ensure!(
+				NextAuthorizationId::<T>::get() >= u64::from(count),
+				"Authorizations were not given identifiers"
+			);
+			for (scope, usage) in AuthorizationUsageByScope::<T>::iter() {
+				let unused = PendingAuthorizations::<T>::get(&scope)
+					.iter()
+					.fold(AuthorizationExtent::default(), |sum, a| sum.saturating_add(a.unused));
+				ensure!(unused == usage.unused, "Unused extent was not attributed");
+			}
+			Ok(())
+		}
+	}
+}
@@ -18,18 +18,29 @@
 //! Test environment for transaction-storage pallet.
 
 use crate::{
-	self as pallet_transaction_storage, TransactionStorageProof, DEFAULT_MAX_BLOCK_TRANSACTIONS,
-	DEFAULT_MAX_TRANSACTION_SIZE,
+	self as pallet_transaction_storage, ConsumptionOrder, EnsureAuthorizedAccount, HoldDeposit,
+	OnRenewed, OnStored, OwnerOnlyRenewals, Preimage, PreimageStorePolicy, RenewalPolicy,
+	SendReceipt, TransactionStorageProof, TxIndex, DEFAULT_MAX_BLOCK_TRANSACTIONS,
+	DEFAULT_MAX_PROOF_SIZE, DEFAULT_MAX_TRANSACTION_SIZE,
 };
+use codec::Encode;
 use frame_support::{
 	parameter_types,
-	traits::{ConstU16, ConstU32, ConstU64, OnFinalize, OnInitialize},
+	traits::{
+		ConstU16, ConstU32, ConstU64, ConstU8, EitherOfDiverse, EqualPrivilegeOnly, OnFinalize,
+		OnInitialize,
+	},
+	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, IdentityFee, RuntimeDbWeight, Weight},
 };
-use frame_system::{pallet_prelude::BlockNumberFor, EnsureRoot};
-use sp_core::H256;
+use frame_system::{
+	limits::{BlockLength, BlockWeights},
+	pallet_prelude::BlockNumberFor,
+	EnsureRoot,
+};
+use sp_core::{storage::well_known_keys, H256};
 use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup},
-	BuildStorage,
+	BuildStorage, Perbill,
 };
 
 pub type Block = frame_system::mocking::MockBlock<Test>;
@@ -40,15 +51,31 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
 		TransactionStorage: pallet_transaction_storage::{
-			Pallet, Call, Storage, Config<T>, Inherent, Event<T>
+			Pallet, Call, Storage, Config<T>, Inherent, ValidateUnsigned, Event<T>
 		},
+		Utility: pallet_utility::{Pallet, Call, Event},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Scheduler: pallet_scheduler::{Pallet, Call, Storage, Event<T>},
+		TransactionPayment: pallet_transaction_payment::{Pallet, Storage, Event<T>},
+		Attestation: pallet_attestation::{Pallet, Call},
 	}
 );
 
+parameter_types! {
+	// The weights of the runtime, so that a full block of stores fits in the normal extrinsics
+	pub MockBlockWeights: BlockWeights = BlockWeights::with_sensible_defaults(
+		Weight::from_parts(2 * WEIGHT_REF_TIME_PER_SECOND, u64::MAX),
+		Perbill::from_percent(75),
+	);
+	pub static MockBlockLength: BlockLength = BlockLength::max(DEFAULT_MAX_TRANSACTION_SIZE);
+	// Free storage accesses, unless a test weighs blocks under realistic database weights
+	pub static MockDbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 0, write: 0 };
+}
+
 impl frame_system::Config for Test {
 	type BaseCallFilter = frame_support::traits::Everything;
-	type BlockWeights = ();
-	type BlockLength = ();
+	type BlockWeights = MockBlockWeights;
+	type BlockLength = MockBlockLength;
 	type RuntimeOrigin = RuntimeOrigin;
 	type RuntimeCall = RuntimeCall;
 	type Nonce = u64;
@@ -59,10 +86,10 @@ impl frame_system::Config for Test {
 	type Block = Block;
 	type RuntimeEvent = RuntimeEvent;
 	type BlockHashCount = ConstU64<250>;
-	type DbWeight = ();
+	type DbWeight = MockDbWeight;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
@@ -71,24 +98,250 @@ impl frame_system::Config for Test {
 	type MaxConsumers = ConstU32<16>;
 }
 
+impl pallet_utility::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type PalletsOrigin = OriginCaller;
+	type WeightInfo = ();
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU64<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type FreezeIdentifier = ();
+	type MaxFreezes = ();
+	type RuntimeHoldReason = ();
+	type MaxHolds = ConstU32<1>;
+}
+
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Weight::from_parts(1_000_000_000_000, u64::MAX);
+}
+
+impl pallet_scheduler::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
+	type PalletsOrigin = OriginCaller;
+	type RuntimeCall = RuntimeCall;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRoot<Self::AccountId>;
+	type MaxScheduledPerBlock = ConstU32<10>;
+	type WeightInfo = ();
+	type OriginPrivilegeCmp = EqualPrivilegeOnly;
+	type Preimages = TransactionStorage;
+}
+
+impl pallet_transaction_payment::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type OnChargeTransaction = pallet_transaction_payment::CurrencyAdapter<Balances, ()>;
+	type OperationalFeeMultiplier = ConstU8<5>;
+	type WeightToFee = IdentityFee<u64>;
+	type LengthToFee = IdentityFee<u64>;
+	type FeeMultiplierUpdate = ();
+}
+
+/// A call of the storage event handler.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum StorageEvent {
+	Stored { content_hash: Preimage, size: u32, owner: Option<u64>, index: TxIndex },
+	Renewed { content_hash: Preimage, size: u32, owner: Option<u64>, index: TxIndex },
+}
+
+parameter_types! {
+	pub static StorageEvents: Vec<StorageEvent> = vec![];
+	pub static StorageEventWeight: Weight = Weight::zero();
+}
+
+/// Storage event handler that records its calls in `StorageEvents`.
+pub struct RecordStorageEvents;
+
+impl OnStored<u64> for RecordStorageEvents {
+	fn on_stored(content_hash: Preimage, size: u32, owner: Option<&u64>, index: TxIndex) {
+		let owner = owner.copied();
+		StorageEvents::mutate(|events| {
+			events.push(StorageEvent::Stored { content_hash, size, owner, index })
+		});
+	}
+
+	fn on_stored_weight() -> Weight {
+		StorageEventWeight::get()
+	}
+}
+
+impl OnRenewed<u64> for RecordStorageEvents {
+	fn on_renewed(content_hash: Preimage, size: u32, owner: Option<&u64>, index: TxIndex) {
+		let owner = owner.copied();
+		StorageEvents::mutate(|events| {
+			events.push(StorageEvent::Renewed { content_hash, size, owner, index })
+		});
+	}
+
+	fn on_renewed_weight() -> Weight {
+		StorageEventWeight::get()
+	}
+}
+
+parameter_types! {
+	pub static SentReceipts: Vec<(Preimage, u32, u64)> = vec![];
+	pub static ReceiptWeight: Weight = Weight::zero();
+}
+
+/// Receipt sender that records the receipts in `SentReceipts`, as a message exporter would queue
+/// them.
+pub struct RecordReceipts;
+
+impl SendReceipt<u64> for RecordReceipts {
+	fn send_receipt(content_hash: Preimage, size: u32, block: u64) {
+		SentReceipts::mutate(|receipts| receipts.push((content_hash, size, block)));
+	}
+
+	fn send_receipt_weight() -> Weight {
+		ReceiptWeight::get()
+	}
+}
+
+parameter_types! {
+	pub static OwnerOnlyRenewal: bool = false;
+}
+
+/// Renewal policy of `OwnerOnlyRenewals` if `OwnerOnlyRenewal` is set, else of `()`.
+pub struct MockRenewalPolicy;
+
+impl RenewalPolicy<u64> for MockRenewalPolicy {
+	fn can_renew(payer: Option<&u64>, owner: Option<&u64>) -> bool {
+		match OwnerOnlyRenewal::get() {
+			true => OwnerOnlyRenewals::can_renew(payer, owner),
+			false => <() as RenewalPolicy<u64>>::can_renew(payer, owner),
+		}
+	}
+}
+
 parameter_types! {
 	pub const TransactionStorageAuthorizationPeriod: BlockNumberFor<Test> = 10;
+	pub static MaxBlockTransactions: u32 = DEFAULT_MAX_BLOCK_TRANSACTIONS;
+	pub static MaxTransactionSize: u32 = DEFAULT_MAX_TRANSACTION_SIZE;
+	pub static ExpiryWeightRatio: Perbill = Perbill::from_percent(25);
+	pub static MaxProofSize: u32 = DEFAULT_MAX_PROOF_SIZE;
+	pub static PreimageDepositBase: u64 = 10;
+	pub static PreimageDepositPerByte: u64 = 1;
+	pub static StrikeThreshold: u32 = 3;
+	pub static MaxTombstones: u32 = 16;
+	pub static DepositContentDigest: bool = true;
+	pub static MaxOwnedContent: u32 = DEFAULT_MAX_BLOCK_TRANSACTIONS;
+	pub static PreimagePolicy: PreimageStorePolicy = PreimageStorePolicy::Accumulate;
+	pub static AuthorizationOrder: ConsumptionOrder = ConsumptionOrder::PreimageFirst;
+	pub static MaxOutstandingAuthorizations: u32 = 4 * DEFAULT_MAX_BLOCK_TRANSACTIONS;
+	pub static MaxAuthorizationsPerBlock: u32 = 2 * DEFAULT_MAX_BLOCK_TRANSACTIONS;
+	pub static ReservationPeriod: BlockNumberFor<Test> = 3;
+	pub static MaxUsageHistory: u32 = 3;
+	pub static MaxRecentAuthorizations: u32 = 3;
+	pub static StrictDedup: bool = false;
+	pub static AuthorizerStoresPerBlock: u32 = 0;
+	pub static MaxAccountAuthorizationExpiries: u32 = 16;
+	pub static MinTransactionSize: u32 = 1;
+	pub static MinBytesPerStore: u32 = 0;
+	pub static MaxScopeAuthorizations: u32 = 16;
 }
 
 impl pallet_transaction_storage::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type RuntimeCall = RuntimeCall;
 	type WeightInfo = ();
-	type MaxBlockTransactions = ConstU32<{ DEFAULT_MAX_BLOCK_TRANSACTIONS }>;
-	type MaxTransactionSize = ConstU32<{ DEFAULT_MAX_TRANSACTION_SIZE }>;
+	type MaxBlockTransactions = MaxBlockTransactions;
+	type MaxTransactionSize = MaxTransactionSize;
 	type MaxBlockAuthorizationExpiries = ConstU32<{ DEFAULT_MAX_BLOCK_TRANSACTIONS }>;
 	type AuthorizationPeriod = TransactionStorageAuthorizationPeriod;
-	type Authorizer = EnsureRoot<Self::AccountId>;
+	type MaxAllowedHashes = ConstU32<4>;
+	type Authorizer = EitherOfDiverse<EnsureRoot<Self::AccountId>, EnsureAuthorizedAccount<Self>>;
+	type MaxAuthorizers = ConstU32<2>;
+	type MaxBlockRemovals = ConstU32<2>;
+	type ExpiryWeightRatio = ExpiryWeightRatio;
+	type MaxProofSize = MaxProofSize;
+	type StorageEventHandler = RecordStorageEvents;
+	type StoreReceipt = RecordReceipts;
+	type PreimageDeposit =
+		HoldDeposit<u64, Balances, (), PreimageDepositBase, PreimageDepositPerByte>;
+	type StrikeThreshold = StrikeThreshold;
+	type BarPeriod = ConstU64<5>;
+	type MaxBlockChallenges = ConstU32<2>;
+	type MaxTombstones = MaxTombstones;
+	type MaxNamespaceEntries = ConstU32<2>;
+	type MaxGroupMembers = ConstU32<3>;
+	type DepositContentDigest = DepositContentDigest;
+	type MaxOwnedContent = MaxOwnedContent;
+	type PreimagePolicy = PreimagePolicy;
+	type AuthorizationOrder = AuthorizationOrder;
+	type MaxOutstandingAuthorizations = MaxOutstandingAuthorizations;
+	type MaxAuthorizationsPerBlock = MaxAuthorizationsPerBlock;
+	type ReservationPeriod = ReservationPeriod;
+	type MaxUsageHistory = MaxUsageHistory;
+	type MaxRecentAuthorizations = MaxRecentAuthorizations;
+	type StrictDedup = StrictDedup;
+	type AuthorizerStoresPerBlock = AuthorizerStoresPerBlock;
+	type MaxAccountAuthorizationExpiries = MaxAccountAuthorizationExpiries;
+	type RenewalPolicy = MockRenewalPolicy;
+	type MinTransactionSize = MinTransactionSize;
+	type MinBytesPerStore = MinBytesPerStore;
+	type RenewalLeadTime = ConstU64<3>;
+	type MaxScopeAuthorizations = MaxScopeAuthorizations;
+}
+
+impl pallet_attestation::Config for Test {}
+
+/// Pallet storing attestations through `store_internal`, as other pallets of a runtime would.
+#[frame_support::pallet]
+pub mod pallet_attestation {
+	use crate::Pallet as TransactionStorage;
+	use codec::Encode;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::SaturatedConversion;
+	use sp_std::prelude::*;
+
+	#[pallet::config]
+	pub trait Config: crate::Config {}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Stores `statement`, which is part of the extrinsic, on behalf of the caller.
+		#[pallet::call_index(0)]
+		#[pallet::weight(TransactionStorage::<T>::store_internal_weight(
+			statement.len().saturated_into()
+		))]
+		pub fn attest(origin: OriginFor<T>, statement: Vec<u8>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			TransactionStorage::<T>::store_internal(Some(who), statement)
+		}
+
+		/// Stores an attestation of `statement` built by the runtime, which is not part of the
+		/// extrinsic.
+		#[pallet::call_index(1)]
+		#[pallet::weight(TransactionStorage::<T>::store_internal_weight(
+			<(T::AccountId, BlockNumberFor<T>, [u8; 32])>::max_encoded_len().saturated_into()
+		))]
+		pub fn attest_hash(origin: OriginFor<T>, statement: Vec<u8>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let block = frame_system::Pallet::<T>::block_number();
+			let attestation = (who, block, sp_io::hashing::blake2_256(&statement)).encode();
+			TransactionStorage::<T>::store_internal(None, attestation)
+		}
+	}
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
 	let t = RuntimeGenesisConfig {
 		system: Default::default(),
+		balances: Default::default(),
 		transaction_storage: pallet_transaction_storage::GenesisConfig::<Test> {
 			storage_period: 10,
 		},
@@ -103,10 +356,23 @@ pub fn run_to_block(n: u64, f: impl Fn() -> Option<TransactionStorageProof>) {
 		if let Some(proof) = f() {
 			TransactionStorage::check_proof(RuntimeOrigin::none(), proof).unwrap();
 		}
-		TransactionStorage::on_finalize(System::block_number());
-		System::on_finalize(System::block_number());
-		System::set_block_number(System::block_number() + 1);
-		System::on_initialize(System::block_number());
-		TransactionStorage::on_initialize(System::block_number());
+		next_block();
 	}
 }
+
+/// Finalizes the current block and initializes the next one. Returns the weight reported by
+/// `on_initialize`, which also covers `on_finalize`.
+pub fn next_block() -> Weight {
+	TransactionStorage::on_finalize(System::block_number());
+	TransactionStorage::do_try_state().unwrap();
+	System::on_finalize(System::block_number());
+	System::set_block_number(System::block_number() + 1);
+	System::on_initialize(System::block_number());
+	TransactionStorage::on_initialize(System::block_number())
+}
+
+/// Moves on to the next extrinsic of the current block.
+pub fn next_extrinsic() {
+	let index = System::extrinsic_index().unwrap_or_default();
+	sp_io::storage::set(well_known_keys::EXTRINSIC_INDEX, &(index + 1).encode());
+}
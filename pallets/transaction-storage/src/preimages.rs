@@ -0,0 +1,88 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Preimage provider for other pallets, e.g. the scheduler.
+//!
+//! Data stored with `store` is only indexed: it is kept by the nodes, not in the state, so the
+//! runtime cannot read it back. Preimages noted through `StorePreimage` are instead kept inline
+//! in the state, up to `MAX_INLINE_DATA_SIZE` bytes, without using any authorization as they are
+//! noted by the runtime itself. Only those can be fetched. Like stored data, they are kept for
+//! `StoragePeriod` blocks after being noted, and for as long as they are requested after that.
+
+use super::*;
+use frame_support::{
+	ensure,
+	traits::{FetchResult, QueryPreimage, StorePreimage},
+	BoundedVec,
+};
+use sp_runtime::DispatchError;
+use sp_std::borrow::Cow;
+
+impl<T: Config> QueryPreimage for Pallet<T> {
+	fn len(hash: &<BlakeTwo256 as Hash>::Output) -> Option<u32> {
+		InlineData::<T>::decode_len(hash.0).map(|len| len as u32)
+	}
+
+	fn fetch(hash: &<BlakeTwo256 as Hash>::Output, len: Option<u32>) -> FetchResult {
+		let data = InlineData::<T>::get(hash.0).ok_or(DispatchError::Unavailable)?;
+		if len.map_or(false, |len| len as usize != data.len()) {
+			return Err(DispatchError::Unavailable)
+		}
+		Ok(Cow::Owned(data.into_inner()))
+	}
+
+	fn is_requested(hash: &<BlakeTwo256 as Hash>::Output) -> bool {
+		InlineDataRequests::<T>::contains_key(hash.0)
+	}
+
+	fn request(hash: &<BlakeTwo256 as Hash>::Output) {
+		InlineDataRequests::<T>::mutate(hash.0, |count| *count = count.saturating_add(1));
+	}
+
+	fn unrequest(hash: &<BlakeTwo256 as Hash>::Output) {
+		let count = InlineDataRequests::<T>::get(hash.0).saturating_sub(1);
+		if count == 0 {
+			// The data is removed once its storage period ends
+			InlineDataRequests::<T>::remove(hash.0);
+		} else {
+			InlineDataRequests::<T>::insert(hash.0, count);
+		}
+	}
+}
+
+impl<T: Config> StorePreimage for Pallet<T> {
+	const MAX_LENGTH: usize = MAX_INLINE_DATA_SIZE as usize;
+
+	/// Keeps `bytes` in the state for at least `StoragePeriod` blocks and until it is unnoted,
+	/// counting as a request for them.
+	fn note(bytes: Cow<[u8]>) -> Result<<BlakeTwo256 as Hash>::Output, DispatchError> {
+		let hash = sp_io::hashing::blake2_256(&bytes);
+		ensure!(!RemovedContent::<T>::contains_key(hash), Error::<T>::ContentRemoved);
+		let data: BoundedVec<_, _> =
+			bytes.into_owned().try_into().map_err(|_| Error::<T>::TransactionTooLarge)?;
+		if !InlineData::<T>::contains_key(hash) {
+			let block = frame_system::Pallet::<T>::block_number();
+			InlineByBlock::<T>::try_mutate(block, |hashes| {
+				hashes.try_push(hash).map_err(|_| Error::<T>::TooManyTransactions)
+			})?;
+		}
+		InlineData::<T>::insert(hash, data);
+		let hash = hash.into();
+		Self::request(&hash);
+		Ok(hash)
+	}
+}
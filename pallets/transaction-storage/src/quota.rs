@@ -0,0 +1,149 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage quota of accounts for other pallets, which can inspect, hold and spend it like a
+//! balance, e.g. to escrow quota traded on a marketplace.
+//!
+//! The quota of an account is the unused extent of its (unexpired) account authorizations, a
+//! number of transactions and a number of bytes. Its standing quota, restricted authorizations
+//! and group authorizations are not part of it. Quota can be held: held quota cannot be used by
+//! stores and renewals of the account, nor spent with `spend`, until it is released with
+//! `unreserve` or spent with `spend_held`.
+//!
+//! Held quota still belongs to the authorizations it was granted by, and expires with them. The
+//! quota that is not held expires first: held quota is only reduced once the authorizations left
+//! unused no longer cover it.
+
+use super::*;
+use sp_runtime::DispatchResult;
+
+/// Inspection of the storage quota of accounts, in the manner of `fungible::Inspect` and
+/// `fungible::InspectHold` for balances.
+pub trait InspectQuota<AccountId> {
+	/// The quota of `who`, including what is held.
+	fn total_quota(who: &AccountId) -> AuthorizationExtent;
+
+	/// The quota of `who` that is not held, which can be used, spent or held.
+	fn spendable_quota(who: &AccountId) -> AuthorizationExtent;
+
+	/// The quota of `who` that is held.
+	fn held_quota(who: &AccountId) -> AuthorizationExtent;
+}
+
+/// Holding and spending the storage quota of accounts, in the manner of `fungible::Mutate` and
+/// `fungible::MutateHold` for balances.
+pub trait MutateQuota<AccountId>: InspectQuota<AccountId> {
+	/// Spends `extent` of the quota of `who` that is not held, as stores use it, though without
+	/// counting towards its lifetime cap or usage history. Fails with `InsufficientQuota` unless
+	/// it covers both the transactions and the bytes of `extent`.
+	fn spend(who: &AccountId, extent: AuthorizationExtent) -> DispatchResult;
+
+	/// Holds `extent` of the quota of `who` that is not held. Fails with `InsufficientQuota`
+	/// unless it covers both the transactions and the bytes of `extent`.
+	fn reserve(who: &AccountId, extent: AuthorizationExtent) -> DispatchResult;
+
+	/// Releases up to `extent` of the quota held by `who`. Returns the extent released, less than
+	/// `extent` if less is held, e.g. because some of it expired.
+	fn unreserve(who: &AccountId, extent: AuthorizationExtent) -> AuthorizationExtent;
+
+	/// Spends `extent` of the quota held by `who`. Fails with `InsufficientQuota` unless it
+	/// covers both the transactions and the bytes of `extent`.
+	fn spend_held(who: &AccountId, extent: AuthorizationExtent) -> DispatchResult;
+}
+
+/// The storage quota of accounts, backed by `AuthorizationUsageByScope` with the held quota in
+/// `HeldQuota`.
+pub struct StorageQuota<T>(PhantomData<T>);
+
+impl<T: Config> StorageQuota<T> {
+	/// Moves `extent` of the unused authorizations of `who`, which must cover it, to the used
+	/// ones.
+	fn use_quota(who: &T::AccountId, extent: AuthorizationExtent) {
+		if extent == Default::default() {
+			return
+		}
+		let scope = AuthorizationScope::Account(who.clone());
+		AuthorizationUsageByScope::<T>::mutate(&scope, |usage| {
+			usage.unused = usage.unused.saturating_sub(extent);
+			usage.used = usage.used.saturating_add(extent);
+		});
+		Pallet::<T>::use_pending_authorizations(&scope, extent);
+		Pallet::<T>::deposit_event(Event::AuthorizationUsed { scope, extent });
+	}
+
+	/// Sets the quota held by `who`.
+	fn set_held(who: &T::AccountId, held: AuthorizationExtent) {
+		if held == Default::default() {
+			HeldQuota::<T>::remove(who);
+		} else {
+			HeldQuota::<T>::insert(who, held);
+		}
+	}
+}
+
+impl<T: Config> InspectQuota<T::AccountId> for StorageQuota<T> {
+	fn total_quota(who: &T::AccountId) -> AuthorizationExtent {
+		AuthorizationUsageByScope::<T>::get(AuthorizationScope::Account(who.clone())).unused
+	}
+
+	fn spendable_quota(who: &T::AccountId) -> AuthorizationExtent {
+		Self::total_quota(who).saturating_sub(Self::held_quota(who))
+	}
+
+	fn held_quota(who: &T::AccountId) -> AuthorizationExtent {
+		HeldQuota::<T>::get(who)
+	}
+}
+
+impl<T: Config> MutateQuota<T::AccountId> for StorageQuota<T> {
+	fn spend(who: &T::AccountId, extent: AuthorizationExtent) -> DispatchResult {
+		Self::spendable_quota(who)
+			.checked_sub(extent)
+			.ok_or(Error::<T>::InsufficientQuota)?;
+		Self::use_quota(who, extent);
+		Ok(())
+	}
+
+	fn reserve(who: &T::AccountId, extent: AuthorizationExtent) -> DispatchResult {
+		Self::spendable_quota(who)
+			.checked_sub(extent)
+			.ok_or(Error::<T>::InsufficientQuota)?;
+		if extent != Default::default() {
+			Self::set_held(who, Self::held_quota(who).saturating_add(extent));
+			Pallet::<T>::deposit_event(Event::QuotaHeld { who: who.clone(), extent });
+		}
+		Ok(())
+	}
+
+	fn unreserve(who: &T::AccountId, extent: AuthorizationExtent) -> AuthorizationExtent {
+		let held = Self::held_quota(who);
+		let released = held.min(extent);
+		if released != Default::default() {
+			Self::set_held(who, held.saturating_sub(released));
+			Pallet::<T>::deposit_event(Event::QuotaReleased { who: who.clone(), extent: released });
+		}
+		released
+	}
+
+	fn spend_held(who: &T::AccountId, extent: AuthorizationExtent) -> DispatchResult {
+		let held =
+			Self::held_quota(who).checked_sub(extent).ok_or(Error::<T>::InsufficientQuota)?;
+		Self::set_held(who, held);
+		Self::use_quota(who, extent);
+		Ok(())
+	}
+}
@@ -0,0 +1,91 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API of the transaction storage pallet, used by block authors to build storage proofs.
+//! Only uses the types of `transaction-storage-primitives`.
+
+use codec::Codec;
+use sp_std::vec::Vec;
+use transaction_storage_primitives::{
+	AuthorizationExtent, Challenge, ContentHash, ContentRecord, Namespace, PeriodUsage,
+	ProofStatus, RemovalReason, RenewalDeadline, ScopeRef, StoreRejection,
+};
+
+sp_api::decl_runtime_apis! {
+	/// API to query the state of the transaction storage pallet.
+	pub trait TransactionStorageApi<AccountId, BlockNumber>
+	where
+		AccountId: Codec,
+		BlockNumber: Codec,
+	{
+		/// Availability challenges to be proved in the block built on top of this one, in the
+		/// order their proofs must be given.
+		fn challenges() -> Vec<Challenge<AccountId, BlockNumber>>;
+
+		/// Why the content with the given hash was removed, if it was removed and its tombstone
+		/// is kept.
+		fn tombstone(content_hash: ContentHash) -> Option<RemovalReason>;
+
+		/// Lists up to `limit` hashes of the content stored in `namespace`, ordered by hash,
+		/// starting after `start_after` if given.
+		fn namespace_content(
+			namespace: Namespace,
+			start_after: Option<ContentHash>,
+			limit: u32,
+		) -> Vec<ContentHash>;
+
+		/// Lists the content currently stored, scanning up to `limit` transactions after the raw
+		/// storage key `start_key`, or from the start. Returns the records of the scanned content
+		/// and the key to continue from, or `None` once all transactions were scanned.
+		fn stored_content(
+			start_key: Option<Vec<u8>>,
+			limit: u32,
+		) -> (Vec<ContentRecord<BlockNumber>>, Option<Vec<u8>>);
+
+		/// Status of the storage proof of the content stored in `block`.
+		fn proof_status(block: BlockNumber) -> ProofStatus;
+
+		/// Checks whether storing `size` bytes as `origin` would succeed in the current state,
+		/// without storing anything.
+		fn can_store(origin: ScopeRef<AccountId>, size: u32) -> Result<(), StoreRejection>;
+
+		/// Unused extent of the authorizations of `who`, including what remains of its standing
+		/// quota in the current authorization period.
+		fn unused_account_authorization_extent(who: AccountId) -> AuthorizationExtent;
+
+		/// Unused extent of the authorizations of the given preimage.
+		fn unused_preimage_authorization_extent(content_hash: ContentHash) -> AuthorizationExtent;
+
+		/// Number of times the storage proof of a block checked a chunk of the content with the
+		/// given hash during its lifetime, or `None` if it is not stored.
+		fn times_challenged(content_hash: ContentHash) -> Option<u32>;
+
+		/// Authorizations used by `who` in each of the last authorization periods it used any
+		/// in, oldest first.
+		fn account_usage_history(who: AccountId) -> Vec<PeriodUsage<BlockNumber>>;
+
+		/// Number of blocks data is stored for: data stored in block `n` is proven, and can be
+		/// renewed until, block `n + storage_period`. Nodes must keep indexed transactions at
+		/// least this long.
+		fn storage_period() -> BlockNumber;
+
+		/// For each of the given content hashes, the block and index to renew the content with,
+		/// the last block in which renewing it succeeds and the block from which renewing it is
+		/// recommended, or `None` if it is not stored.
+		fn renewal_deadlines(hashes: Vec<ContentHash>) -> Vec<Option<RenewalDeadline<BlockNumber>>>;
+	}
+}
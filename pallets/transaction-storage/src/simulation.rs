@@ -0,0 +1,433 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Randomized simulation of the pallet on the mock runtime over many blocks, interleaving
+//! authorizations of accounts and preimages, signed and unsigned stores and renewals, and their
+//! expiry. Every block is proven with the test proof builder, and after every block the `try_state`
+//! invariants are checked along with a model of the pallet kept independently: the unused and used
+//! extents of each scope, with each authorization tracked separately and used oldest first, the
+//! live content and the transactions that can be renewed.
+//!
+//! The simulation is deterministic for a given seed. On failure, the seed and the last operations
+//! are printed. The seed and the number of blocks are set with `SIMULATION_SEED` and
+//! `SIMULATION_BLOCKS`, to reproduce a failure or try other seeds, e.g.:
+//!
+//! ```bash
+//! SIMULATION_SEED=42 SIMULATION_BLOCKS=10000 cargo test -p pallet-transaction-storage simulation
+//! ```
+
+use crate::{
+	mock::*, test_utils::StoredData, AuthorizationExtent, AuthorizationScope,
+	AuthorizationUsageByScope, Error, PendingAuthorizations, Preimage, TxIndex,
+};
+use frame_support::{assert_ok, dispatch::DispatchResultWithPostInfo};
+use sp_runtime::DispatchError;
+use std::collections::BTreeMap;
+
+/// Seed used unless `SIMULATION_SEED` is set.
+const DEFAULT_SEED: u64 = 0x5eed_0f_b10c5;
+/// Number of blocks simulated unless `SIMULATION_BLOCKS` is set.
+const DEFAULT_BLOCKS: u64 = 2_000;
+/// Accounts authorized and signing transactions.
+const ACCOUNTS: u64 = 4;
+/// Number of distinct contents stored.
+const CONTENTS: usize = 12;
+/// Maximum number of operations in a block.
+const MAX_BLOCK_OPERATIONS: u64 = 5;
+/// Number of operations printed on failure.
+const LOG_TAIL: usize = 200;
+/// Storage period and authorization period of the mock runtime.
+const PERIOD: u64 = 10;
+
+/// SplitMix64, so that a seed gives the same simulation on every platform.
+struct Rng(u64);
+
+impl Rng {
+	fn next_u64(&mut self) -> u64 {
+		self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+		let mut z = self.0;
+		z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+		z ^ (z >> 31)
+	}
+
+	/// Uniform in `low..=high`, up to a negligible bias.
+	fn range(&mut self, low: u64, high: u64) -> u64 {
+		low + self.next_u64() % (high - low + 1)
+	}
+
+	/// `true` with probability `1 / n`.
+	fn one_in(&mut self, n: u64) -> bool {
+		self.next_u64() % n == 0
+	}
+}
+
+fn env_or(name: &str, default: u64) -> u64 {
+	std::env::var(name).map_or(default, |value| {
+		value.parse().unwrap_or_else(|_| panic!("{name} must be a number"))
+	})
+}
+
+/// An authorization as seen by the model: what it granted, what remains of it, and the block it
+/// expires in.
+struct Grant {
+	scope: AuthorizationScope<u64>,
+	granted: AuthorizationExtent,
+	remaining: AuthorizationExtent,
+	expiry: u64,
+}
+
+/// Independent model of the pallet's state.
+#[derive(Default)]
+struct Model {
+	/// Unexpired authorizations, oldest first.
+	grants: Vec<Grant>,
+	/// Block of the last store or renewal of each content.
+	last_stored: BTreeMap<Preimage, u64>,
+	/// Content and size of the transactions of each block, kept until after they can no longer
+	/// be renewed.
+	transactions: BTreeMap<u64, Vec<(Preimage, u32)>>,
+}
+
+impl Model {
+	fn authorize(&mut self, scope: AuthorizationScope<u64>, extent: AuthorizationExtent, now: u64) {
+		let grant = Grant { scope, granted: extent, remaining: extent, expiry: now + PERIOD };
+		self.grants.push(grant);
+	}
+
+	fn grants_of<'a>(
+		&'a self,
+		scope: &'a AuthorizationScope<u64>,
+	) -> impl Iterator<Item = &'a Grant> {
+		self.grants.iter().filter(move |grant| grant.scope == *scope)
+	}
+
+	fn unused(&self, scope: &AuthorizationScope<u64>) -> AuthorizationExtent {
+		self.grants_of(scope)
+			.fold(Default::default(), |unused, grant| unused.saturating_add(grant.remaining))
+	}
+
+	fn used(&self, scope: &AuthorizationScope<u64>) -> AuthorizationExtent {
+		self.grants_of(scope).fold(Default::default(), |used, grant| {
+			used.saturating_add(grant.granted.saturating_sub(grant.remaining))
+		})
+	}
+
+	fn covers(&self, scope: &AuthorizationScope<u64>, size: u32) -> bool {
+		let unused = self.unused(scope);
+		unused.transactions >= 1 && unused.bytes >= u64::from(size)
+	}
+
+	/// Uses a transaction of `size` bytes of the authorizations of `scope`, which must cover it.
+	/// Transactions and bytes are each taken from the oldest authorizations that have some left.
+	fn consume(&mut self, scope: &AuthorizationScope<u64>, size: u32) {
+		let mut transactions = 1;
+		let mut bytes = u64::from(size);
+		for grant in self.grants.iter_mut().filter(|grant| grant.scope == *scope) {
+			let taken = transactions.min(grant.remaining.transactions);
+			grant.remaining.transactions -= taken;
+			transactions -= taken;
+			let taken = bytes.min(grant.remaining.bytes);
+			grant.remaining.bytes -= taken;
+			bytes -= taken;
+		}
+		assert_eq!((transactions, bytes), (0, 0), "model used more than it covered");
+	}
+
+	/// The scope whose authorizations a transaction signed by `signer`, if any, storing `size`
+	/// bytes of `content` uses: its preimage's first, as configured in the mock, then the
+	/// signer's.
+	fn scope(
+		&self,
+		signer: Option<u64>,
+		content: Preimage,
+		size: u32,
+	) -> Option<AuthorizationScope<u64>> {
+		let preimage = AuthorizationScope::Preimage(content);
+		let account = signer.map(AuthorizationScope::Account);
+		[Some(preimage), account]
+			.into_iter()
+			.flatten()
+			.find(|scope| self.covers(scope, size))
+	}
+
+	/// Whether an unsigned store of `content` is rejected because its preimage authorization was
+	/// used up by a store.
+	fn preimage_stored(&self, content: Preimage) -> bool {
+		let scope = AuthorizationScope::Preimage(content);
+		self.used(&scope).transactions != 0 && self.unused(&scope).transactions == 0
+	}
+
+	fn note_stored(&mut self, content: Preimage, size: u32, now: u64) {
+		self.last_stored.insert(content, now);
+		self.transactions.entry(now).or_default().push((content, size));
+	}
+
+	/// Moves on to block `now`, expiring the authorizations due in it.
+	fn start_block(&mut self, now: u64) {
+		self.grants.retain(|grant| grant.expiry > now);
+		// Transactions of the block before the oldest renewable one are kept to try renewing them
+		self.transactions.retain(|block, _| block + PERIOD + 1 >= now);
+	}
+
+	fn is_live(&self, content: &Preimage, now: u64) -> bool {
+		self.last_stored.get(content).map_or(false, |last| now <= last + PERIOD)
+	}
+}
+
+/// The simulation, printing its seed and the last operations if dropped while panicking.
+struct Simulation {
+	seed: u64,
+	rng: Rng,
+	log: Vec<String>,
+	contents: Vec<Vec<u8>>,
+	model: Model,
+}
+
+impl Drop for Simulation {
+	fn drop(&mut self) {
+		if !std::thread::panicking() {
+			return
+		}
+		eprintln!("Simulation with SIMULATION_SEED={} failed. Last operations:", self.seed);
+		for entry in &self.log[self.log.len().saturating_sub(LOG_TAIL)..] {
+			eprintln!("  {entry}");
+		}
+	}
+}
+
+fn outcome(result: DispatchResultWithPostInfo) -> Result<(), DispatchError> {
+	result.map(|_| ()).map_err(|e| e.error)
+}
+
+impl Simulation {
+	fn new(seed: u64) -> Self {
+		let mut rng = Rng(seed);
+		let contents = (0..CONTENTS)
+			.map(|_| {
+				let size = rng.range(1, 2_000);
+				(0..size).map(|_| rng.next_u64() as u8).collect()
+			})
+			.collect();
+		Self { seed, rng, log: Vec::new(), contents, model: Default::default() }
+	}
+
+	fn now() -> u64 {
+		System::block_number()
+	}
+
+	fn content(&mut self) -> (Preimage, Vec<u8>) {
+		let data = self.contents[self.rng.range(0, CONTENTS as u64 - 1) as usize].clone();
+		(sp_io::hashing::blake2_256(&data), data)
+	}
+
+	fn account(&mut self) -> u64 {
+		self.rng.range(1, ACCOUNTS)
+	}
+
+	fn note(&mut self, operation: String, result: &Result<(), DispatchError>) {
+		self.log.push(format!("#{} {operation} -> {result:?}", Self::now()));
+	}
+
+	fn authorize_account(&mut self) {
+		let who = self.account();
+		let transactions = self.rng.range(1, 3) as u32;
+		let bytes = self.rng.range(200, 3_000);
+		let result = TransactionStorage::authorize_account(
+			RuntimeOrigin::root(),
+			who,
+			transactions,
+			bytes,
+			None,
+		);
+		self.note(format!("authorize_account({who}, {transactions}, {bytes})"), &result);
+		assert_ok!(result);
+		let extent = AuthorizationExtent { transactions, bytes };
+		self.model.authorize(AuthorizationScope::Account(who), extent, Self::now());
+	}
+
+	fn authorize_preimage(&mut self) {
+		let (content, data) = self.content();
+		// Sometimes too small for the content
+		let bytes =
+			(data.len() as u64 + self.rng.range(0, 100)).saturating_sub(self.rng.range(0, 20));
+		let result =
+			TransactionStorage::authorize_preimage(RuntimeOrigin::root(), content, bytes, false);
+		self.note(format!("authorize_preimage({}, {bytes})", hex(&content)), &result);
+		assert_ok!(result);
+		let extent = AuthorizationExtent { transactions: 1, bytes };
+		self.model.authorize(AuthorizationScope::Preimage(content), extent, Self::now());
+	}
+
+	fn store(&mut self, signer: Option<u64>) {
+		let (content, data) = self.content();
+		let size = data.len() as u32;
+		let origin = signer.map_or_else(RuntimeOrigin::none, RuntimeOrigin::signed);
+		next_extrinsic();
+		let result = outcome(TransactionStorage::store(origin, data, None));
+		self.note(format!("store({signer:?}, {})", hex(&content)), &result);
+		let scope = self.model.scope(signer, content, size);
+		let expected = if signer.is_none() && self.model.preimage_stored(content) {
+			Err(Error::<Test>::PreimageAlreadyStored)
+		} else if scope.is_none() {
+			Err(Error::<Test>::NotAuthorized)
+		} else {
+			Ok(())
+		};
+		assert_eq!(result, expected.map_err(DispatchError::from));
+		if let (Ok(()), Some(scope)) = (result, scope) {
+			self.model.consume(&scope, size);
+			self.model.note_stored(content, size, Self::now());
+		}
+	}
+
+	fn renew(&mut self, signer: Option<u64>) {
+		let now = Self::now();
+		let blocks: Vec<_> = self.model.transactions.keys().copied().collect();
+		if blocks.is_empty() {
+			return
+		}
+		let block = blocks[self.rng.range(0, blocks.len() as u64 - 1) as usize];
+		let transactions = &self.model.transactions[&block];
+		let index = TxIndex(self.rng.range(0, transactions.len() as u64 - 1) as u32);
+		let (content, size) = transactions[index.0 as usize];
+		let expected_hash = if self.rng.one_in(10) { self.content().0 } else { content };
+
+		let origin = signer.map_or_else(RuntimeOrigin::none, RuntimeOrigin::signed);
+		next_extrinsic();
+		let result = outcome(TransactionStorage::renew(origin, block, index, Some(expected_hash)));
+		self.note(format!("renew({signer:?}, {block}, {index}) of {}", hex(&content)), &result);
+		let scope = self.model.scope(signer, content, size);
+		let expected = if block + PERIOD < now {
+			Err(Error::<Test>::RenewalExpired)
+		} else if block == now {
+			// Transactions of the current block are only listed once it is finalized
+			Err(Error::<Test>::RenewedNotFound)
+		} else if expected_hash != content {
+			Err(Error::<Test>::UnexpectedContent)
+		} else if scope.is_none() {
+			Err(Error::<Test>::NotAuthorized)
+		} else {
+			Ok(())
+		};
+		assert_eq!(result, expected.map_err(DispatchError::from));
+		if let (Ok(()), Some(scope)) = (result, scope) {
+			self.model.consume(&scope, size);
+			self.model.note_stored(content, size, now);
+		}
+	}
+
+	fn run_block(&mut self, stored: &StoredData) {
+		for _ in 0..self.rng.range(0, MAX_BLOCK_OPERATIONS) {
+			match self.rng.range(0, 9) {
+				0 | 1 => self.authorize_account(),
+				2 | 3 => self.authorize_preimage(),
+				4 | 5 => {
+					let who = self.account();
+					self.store(Some(who))
+				},
+				6 => self.store(None),
+				7 | 8 => {
+					let who = self.account();
+					self.renew(Some(who))
+				},
+				_ => self.renew(None),
+			}
+		}
+		// Proves the block, and checks the `try_state` invariants
+		run_to_block(Self::now() + 1, || stored.proof::<Test>());
+		System::reset_events();
+		self.model.start_block(Self::now());
+		self.check();
+	}
+
+	/// Checks the state against the model.
+	fn check(&self) {
+		let now = Self::now();
+		let scopes = (1..=ACCOUNTS).map(AuthorizationScope::Account).chain(
+			self.contents
+				.iter()
+				.map(|data| AuthorizationScope::Preimage(sp_io::hashing::blake2_256(data))),
+		);
+		for scope in scopes {
+			let usage = AuthorizationUsageByScope::<Test>::get(&scope);
+			assert_eq!(usage.unused, self.model.unused(&scope), "unused extent of {scope:?}");
+			assert_eq!(usage.used, self.model.used(&scope), "used extent of {scope:?}");
+			let remaining: Vec<_> = self
+				.model
+				.grants_of(&scope)
+				.map(|grant| grant.remaining)
+				.filter(|remaining| *remaining != Default::default())
+				.collect();
+			let pending: Vec<_> =
+				PendingAuthorizations::<Test>::get(&scope).iter().map(|a| a.unused).collect();
+			assert_eq!(pending, remaining, "authorizations of {scope:?}");
+		}
+		for data in &self.contents {
+			let content = sp_io::hashing::blake2_256(data);
+			assert_eq!(
+				TransactionStorage::times_challenged(content).is_some(),
+				self.model.is_live(&content, now),
+				"liveness of {}",
+				hex(&content),
+			);
+		}
+		for (block, transactions) in &self.model.transactions {
+			let renewable = block + PERIOD >= now;
+			for (index, (content, _)) in transactions.iter().enumerate() {
+				let stored = TransactionStorage::stored_transaction(*block, TxIndex(index as u32))
+					.map(|(info, _)| info.content_hash.0);
+				assert_eq!(
+					stored,
+					renewable.then_some(*content),
+					"transaction {index} of #{block}"
+				);
+			}
+			let count = TxIndex(transactions.len() as u32);
+			assert!(TransactionStorage::stored_transaction(*block, count).is_none());
+		}
+	}
+}
+
+fn hex(content: &Preimage) -> String {
+	content[..4].iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[test]
+fn randomized_lifecycle_matches_model() {
+	let seed = env_or("SIMULATION_SEED", DEFAULT_SEED);
+	let blocks = env_or("SIMULATION_BLOCKS", DEFAULT_BLOCKS);
+	new_test_ext().execute_with(|| {
+		let mut simulation = Simulation::new(seed);
+		let mut stored = StoredData::default();
+		for data in &simulation.contents {
+			stored.insert(data.clone());
+		}
+		run_to_block(1, || None);
+		for _ in 0..blocks {
+			simulation.run_block(&stored);
+		}
+		// Everything was exercised
+		let log = simulation.log.join("\n");
+		for operation in ["authorize_account", "authorize_preimage", "store", "renew"] {
+			assert!(log.contains(&format!("{operation}(")), "{operation} never ran");
+		}
+		for outcome in ["Ok(())", "NotAuthorized", "PreimageAlreadyStored", "RenewalExpired"] {
+			assert!(log.contains(outcome), "no operation ended with {outcome}");
+		}
+	});
+}
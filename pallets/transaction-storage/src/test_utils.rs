@@ -0,0 +1,168 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers for tests and benchmarks of runtimes using the transaction storage pallet, building
+//! the storage proofs that a block author would include in `check_proof` inherents. Built with the
+//! `sp-trie` feature, which `mock` and `runtime-benchmarks` enable.
+
+use crate::{ChunkIndex, Config, ContentHash, Pallet, TxIndex, CHUNK_SIZE};
+use frame_system::pallet_prelude::BlockNumberFor;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+use sp_transaction_storage_proof::{encode_index, TransactionStorageProof};
+
+/// Builds the chunk trie of `data` the way `store` does and returns its root along with a proof of
+/// the chunk at `index`.
+pub fn chunk_proof(
+	data: &[u8],
+	index: ChunkIndex,
+) -> (<BlakeTwo256 as Hash>::Output, TransactionStorageProof) {
+	use sp_trie::{generate_trie_proof, LayoutV1, MemoryDB, TrieDBMutBuilder, TrieMut};
+	type Layout = LayoutV1<BlakeTwo256>;
+
+	let mut db = MemoryDB::<BlakeTwo256>::default();
+	let mut root = Default::default();
+	{
+		let mut trie = TrieDBMutBuilder::<Layout>::new(&mut db, &mut root).build();
+		for (i, chunk) in data.chunks(CHUNK_SIZE).enumerate() {
+			trie.insert(&encode_index(i as u32), chunk).unwrap();
+		}
+	}
+	let proof =
+		generate_trie_proof::<Layout, _, _, _>(&db, root, &[encode_index(index.0)]).unwrap();
+	let chunk = data.chunks(CHUNK_SIZE).nth(index.0 as usize).unwrap().to_vec();
+	(root, TransactionStorageProof { chunk, proof })
+}
+
+/// Data stored during a test, by content hash, for proving its availability once its storage
+/// proof is due.
+#[derive(Default, Clone, Debug)]
+pub struct StoredData(BTreeMap<ContentHash, Vec<u8>>);
+
+impl StoredData {
+	/// Records that `data` was stored.
+	pub fn insert(&mut self, data: Vec<u8>) {
+		self.0.insert(sp_io::hashing::blake2_256(&data), data);
+	}
+
+	/// Builds the storage proof due in the current block, or returns `None` if none is due.
+	///
+	/// Panics if the content of the challenged transaction was not recorded.
+	pub fn proof<T: Config>(&self) -> Option<TransactionStorageProof> {
+		let (block, index, chunk_index) = Pallet::<T>::proof_target()?;
+		let data = self.content::<T>(block, index)?;
+		Some(chunk_proof(data, chunk_index).1)
+	}
+
+	/// Returns the content of the transaction at `index` of `block`, or `None` if there is no such
+	/// transaction.
+	///
+	/// Panics if the content of the transaction was not recorded.
+	fn content<T: Config>(&self, block: BlockNumberFor<T>, index: TxIndex) -> Option<&Vec<u8>> {
+		let (info, _) = Pallet::<T>::stored_transaction(block, index)?;
+		Some(self.0.get(&info.content_hash.0).expect("challenged content was not recorded"))
+	}
+}
+
+/// A way a block author can get the storage proof of a block wrong.
+#[cfg(any(test, feature = "fault-injection"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofFault {
+	/// The proof is of the chunk after the challenged one in its transaction, or of the first
+	/// chunk if the challenged one is the last.
+	WrongChunk,
+	/// The proof is of the block whose proof was due in the previous block, as by an author
+	/// lagging a block behind. No proof is given if that block stored nothing.
+	StaleTarget,
+	/// The last node of the proof is missing.
+	TruncatedProof,
+	/// The proof inherent is included twice.
+	DuplicatedInherent,
+	/// No proof is given.
+	Withheld,
+}
+
+/// Provides the storage proof inherents of blocks the way a block author with the given fault
+/// would, for testing how runtimes handle misbehaving authors.
+#[cfg(any(test, feature = "fault-injection"))]
+#[derive(Clone, Debug)]
+pub struct FaultyProofProvider {
+	stored: StoredData,
+	fault: Option<ProofFault>,
+}
+
+#[cfg(any(test, feature = "fault-injection"))]
+impl FaultyProofProvider {
+	/// Creates a provider proving the data in `stored`, honestly if `fault` is `None`.
+	pub fn new(stored: StoredData, fault: Option<ProofFault>) -> Self {
+		Self { stored, fault }
+	}
+
+	/// Builds the storage proof the provider gives in the current block, if any.
+	///
+	/// Panics if the content of the proven transaction was not recorded.
+	pub fn proof<T: Config>(&self) -> Option<TransactionStorageProof> {
+		let (block, index, chunk_index) = Pallet::<T>::proof_target()?;
+		let mut proof = match self.fault {
+			Some(ProofFault::WrongChunk) => {
+				let data = self.stored.content::<T>(block, index)?;
+				let chunks = data.chunks(CHUNK_SIZE).count() as u32;
+				chunk_proof(data, ChunkIndex((chunk_index.0 + 1) % chunks)).1
+			},
+			Some(ProofFault::StaleTarget) => {
+				let block = block - 1u32.into();
+				let chunk_ends = Pallet::<T>::transaction_roots(block)?
+					.iter()
+					.map(|info| info.block_chunks)
+					.collect::<Vec<_>>();
+				let parent_hash = frame_system::Pallet::<T>::parent_hash();
+				let (index, chunk_index) = crate::select_chunk(parent_hash.as_ref(), &chunk_ends)?;
+				chunk_proof(self.stored.content::<T>(block, index)?, chunk_index).1
+			},
+			Some(ProofFault::Withheld) => return None,
+			_ => chunk_proof(self.stored.content::<T>(block, index)?, chunk_index).1,
+		};
+		if self.fault == Some(ProofFault::TruncatedProof) {
+			proof.proof.pop();
+		}
+		Some(proof)
+	}
+
+	/// Builds the inherent data the provider gives in the current block.
+	pub fn inherent_data<T: Config>(&self) -> sp_inherents::InherentData {
+		let mut data = sp_inherents::InherentData::new();
+		if let Some(proof) = self.proof::<T>() {
+			data.put_data(sp_transaction_storage_proof::INHERENT_IDENTIFIER, &proof)
+				.expect("the proof was not put before");
+		}
+		data
+	}
+
+	/// Returns the storage proof inherents a block author using the provider includes in the
+	/// current block, as created by the pallet from the provider's inherent data.
+	pub fn inherents<T: Config>(&self) -> Vec<crate::Call<T>> {
+		use frame_support::inherent::ProvideInherent;
+
+		let Some(call) = Pallet::<T>::create_inherent(&self.inherent_data::<T>()) else {
+			return Vec::new()
+		};
+		match self.fault {
+			Some(ProofFault::DuplicatedInherent) => vec![call.clone(), call],
+			_ => vec![call],
+		}
+	}
+}
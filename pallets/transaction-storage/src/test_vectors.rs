@@ -0,0 +1,113 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test vectors of the chunking of stored data, its chunk roots and the encoding of storage
+//! proofs, checked against a golden file shared with the other implementations, e.g. clients
+//! computing chunk roots before uploading or nodes building storage proofs.
+//!
+//! After an intended change, or to add vectors, regenerate the golden file with
+//! `UPDATE_TEST_VECTORS=1 cargo test -p pallet-transaction-storage test_vectors`.
+
+use crate::{
+	chunk_root, num_chunks, test_utils::chunk_proof, verify_chunk, ChunkIndex, CHUNK_SIZE,
+};
+use codec::{Decode, Encode};
+use serde_json::{json, Value};
+use sp_core::H256;
+use sp_transaction_storage_proof::{encode_index, TransactionStorageProof};
+use std::path::PathBuf;
+
+/// Sizes of the payloads: around chunk boundaries, and with chunk indices encoded in more than
+/// one byte.
+const SIZES: [u32; 10] = [1, 32, 33, 255, 256, 257, 512, 1000, 4097, 16641];
+
+fn golden_file() -> PathBuf {
+	PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+		.join("../../primitives/transaction-storage/test-vectors/chunking.json")
+}
+
+fn hex(bytes: &[u8]) -> String {
+	format!("0x{}", bytes.iter().map(|b| format!("{b:02x}")).collect::<String>())
+}
+
+fn unhex(value: &Value) -> Vec<u8> {
+	let hex = value.as_str().unwrap().trim_start_matches("0x");
+	(0..hex.len())
+		.step_by(2)
+		.map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+		.collect()
+}
+
+/// Payload of `size` bytes whose chunks all differ.
+fn payload(size: u32) -> Vec<u8> {
+	(0..size).map(|i| (i % 251) as u8).collect()
+}
+
+/// Computes the vectors of all the `SIZES`, each with the proof of its last chunk.
+fn vectors() -> Value {
+	let vectors: Vec<_> = SIZES
+		.into_iter()
+		.map(|size| {
+			let data = payload(size);
+			let chunk_count = num_chunks(size);
+			let chunk_index = chunk_count - 1;
+			let (root, proof) = chunk_proof(&data, ChunkIndex(chunk_index));
+			assert_eq!(root, chunk_root(&data));
+			json!({
+				"size": size,
+				"payload": hex(&data),
+				"content_hash": hex(&sp_io::hashing::blake2_256(&data)),
+				"chunk_count": chunk_count,
+				"chunk_root": hex(root.as_ref()),
+				"chunk_index": chunk_index,
+				"encoded_index": hex(&encode_index(chunk_index)),
+				"chunk": hex(&proof.chunk),
+				"proof_nodes": proof.proof.iter().map(|node| hex(node)).collect::<Vec<_>>(),
+				"encoded_proof": hex(&proof.encode()),
+			})
+		})
+		.collect();
+	json!({ "chunk_size": CHUNK_SIZE, "vectors": vectors })
+}
+
+fn read_golden_file() -> Value {
+	serde_json::from_str(&std::fs::read_to_string(golden_file()).unwrap()).unwrap()
+}
+
+#[test]
+fn test_vectors_match_golden_file() {
+	let vectors = vectors();
+	if std::env::var_os("UPDATE_TEST_VECTORS").is_some() {
+		let json = serde_json::to_string_pretty(&vectors).unwrap() + "\n";
+		std::fs::write(golden_file(), json).unwrap();
+	}
+	assert!(vectors == read_golden_file(), "chunking, chunk roots or proofs changed");
+}
+
+#[test]
+fn test_vectors_golden_proofs_verify() {
+	let golden = read_golden_file();
+	for vector in golden["vectors"].as_array().unwrap() {
+		let proof =
+			TransactionStorageProof::decode(&mut &unhex(&vector["encoded_proof"])[..]).unwrap();
+		let root = H256::from_slice(&unhex(&vector["chunk_root"]));
+		let chunk_index = ChunkIndex(vector["chunk_index"].as_u64().unwrap() as u32);
+		assert_eq!(proof.chunk, unhex(&vector["chunk"]));
+		assert!(verify_chunk(root, chunk_index, &proof.chunk, &proof.proof));
+		assert!(!verify_chunk(root, ChunkIndex(chunk_index.0 + 1), &proof.chunk, &proof.proof));
+	}
+}
@@ -18,14 +18,106 @@
 //! Tests for transction-storage pallet.
 
 use super::{Pallet as TransactionStorage, *};
-use crate::mock::*;
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+	mock::*,
+	test_utils::{chunk_proof, FaultyProofProvider, ProofFault, StoredData},
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::{DispatchClass, Pays},
+	inherent::{InherentData, ProvideInherent},
+	traits::{
+		schedule::{v3::Anon as ScheduleAnon, DispatchTime},
+		Currency, Get, GetStorageVersion, IntegrityTest, OnInitialize, OnRuntimeUpgrade,
+		PalletInfoAccess, QueryPreimage, StorageVersion, StorePreimage, UnfilteredDispatchable,
+	},
+	weights::constants::RocksDbWeight,
+	BoundedVec,
+};
 use frame_system::RawOrigin;
-use sp_core::blake2_256;
+use pallet_transaction_payment::ChargeTransactionPayment;
+use sp_core::{blake2_256, H256};
+use sp_runtime::{
+	traits::{Header, SignedExtension, ValidateUnsigned},
+	transaction_validity::{InvalidTransaction, TransactionSource, TransactionValidityError},
+	DispatchError, DispatchResult,
+};
 use sp_transaction_storage_proof::registration::build_proof;
 
 const MAX_DATA_SIZE: u32 = DEFAULT_MAX_TRANSACTION_SIZE;
 
+/// `info` in the format of `Transactions`.
+fn legacy(info: TransactionInfo) -> LegacyTransactionInfo {
+	LegacyTransactionInfo {
+		chunk_root: info.chunk_root,
+		content_hash: info.content_hash,
+		size: info.size,
+		block_chunks: info.block_chunks,
+	}
+}
+
+/// Dispatches `call` signed by `who` through `TrackStoreFailures`, as the executive would.
+fn dispatch_tracked(
+	who: u64,
+	call: Call<Test>,
+) -> Result<DispatchResult, TransactionValidityError> {
+	let call = RuntimeCall::TransactionStorage(call);
+	let info = call.get_dispatch_info();
+	let pre = TrackStoreFailures::<Test>::new().pre_dispatch(&who, &call, &info, 0)?;
+	let (post_info, result) = match call.dispatch(RuntimeOrigin::signed(who)) {
+		Ok(post_info) => (post_info, Ok(())),
+		Err(e) => (e.post_info, Err(e.error)),
+	};
+	TrackStoreFailures::<Test>::post_dispatch(Some(pre), &info, &post_info, 0, &result)?;
+	Ok(result)
+}
+
+/// Proof of the chunk of challenged `data` that must be proved in the current block.
+fn challenge_proof(data: &[u8]) -> TransactionStorageProof {
+	let seed = blake2_256(&(System::parent_hash().as_ref(), blake2_256(data)).encode());
+	build_proof(&seed, vec![data.to_vec()]).unwrap().unwrap()
+}
+
+/// Checks that the unused authorization extent of every scope, as derived from the events so far,
+/// matches `AuthorizationUsageByScope`.
+fn assert_authorization_events_match_storage() {
+	let mut balances: Vec<(AuthorizationScope<u64>, AuthorizationExtent)> = Vec::new();
+	for record in System::events() {
+		let (scope, extent, credit) = match record.event {
+			RuntimeEvent::TransactionStorage(Event::Authorized { scope, extent }) =>
+				(scope, extent, true),
+			RuntimeEvent::TransactionStorage(Event::AuthorizationUsed { scope, extent }) |
+			RuntimeEvent::TransactionStorage(Event::AuthorizationExpired {
+				scope, extent, ..
+			}) => (scope, extent, false),
+			_ => continue,
+		};
+		let index = match balances.iter().position(|(s, _)| *s == scope) {
+			Some(index) => index,
+			None => {
+				balances.push((scope, AuthorizationExtent::default()));
+				balances.len() - 1
+			},
+		};
+		let balance = &mut balances[index].1;
+		if credit {
+			balance.transactions += extent.transactions;
+			balance.bytes += extent.bytes;
+		} else {
+			balance.transactions -= extent.transactions;
+			balance.bytes -= extent.bytes;
+		}
+	}
+	for (scope, balance) in &balances {
+		let usage = AuthorizationUsageByScope::<Test>::get(scope);
+		assert_eq!(usage.unused, *balance, "{:?}", scope);
+	}
+	for (scope, usage) in AuthorizationUsageByScope::<Test>::iter() {
+		let balance = balances.iter().find(|(s, _)| *s == scope).map(|(_, b)| b.clone());
+		assert_eq!(usage.unused, balance.unwrap_or_default(), "{:?}", scope);
+	}
+}
+
 #[test]
 fn discards_data() {
 	new_test_ext().execute_with(|| {
@@ -35,15 +127,19 @@ fn discards_data() {
 			RawOrigin::Root.into(),
 			caller,
 			2,
-			4000
+			4000,
+			None
 		));
 		assert_ok!(TransactionStorage::<Test>::store(
 			RawOrigin::Signed(caller).into(),
-			vec![0u8; 2000]
+			vec![0u8; 2000],
+			None
 		));
+		next_extrinsic();
 		assert_ok!(TransactionStorage::<Test>::store(
 			RawOrigin::Signed(caller).into(),
-			vec![0u8; 2000]
+			vec![0u8; 2000],
+			None
 		));
 		let proof_provider = || {
 			let block_num = <frame_system::Pallet<Test>>::block_number();
@@ -58,12 +154,12 @@ fn discards_data() {
 			}
 		};
 		run_to_block(11, proof_provider);
-		assert!(Transactions::<Test>::get(1).is_some());
-		let transctions = Transactions::<Test>::get(1).unwrap();
+		assert!(TransactionStorage::<Test>::transaction_roots(1).is_some());
+		let transctions = TransactionStorage::<Test>::transaction_roots(1).unwrap();
 		assert_eq!(transctions.len(), 2);
 		assert_eq!(ChunkCount::<Test>::get(1), 16);
 		run_to_block(12, proof_provider);
-		assert!(Transactions::<Test>::get(1).is_none());
+		assert!(TransactionStorage::<Test>::transaction_roots(1).is_none());
 		assert_eq!(ChunkCount::<Test>::get(1), 0);
 	});
 }
@@ -77,26 +173,28 @@ fn uses_account_authorization() {
 			RawOrigin::Root.into(),
 			caller,
 			2,
-			2000
+			2000,
+			None
 		));
 		assert_eq!(
 			TransactionStorage::<Test>::unused_account_authorization_extent(caller),
 			AuthorizationExtent { transactions: 2, bytes: 2000 }
 		);
 		assert_noop!(
-			TransactionStorage::<Test>::store(RawOrigin::Signed(5).into(), vec![0u8; 2000]),
+			TransactionStorage::<Test>::store(RawOrigin::Signed(5).into(), vec![0u8; 2000], None),
 			Error::<Test>::NotAuthorized,
 		);
 		assert_ok!(TransactionStorage::<Test>::store(
 			RawOrigin::Signed(caller).into(),
-			vec![0u8; 2000]
+			vec![0u8; 2000],
+			None
 		));
 		assert_eq!(
 			TransactionStorage::<Test>::unused_account_authorization_extent(caller),
 			AuthorizationExtent { transactions: 1, bytes: 0 }
 		);
 		assert_noop!(
-			TransactionStorage::<Test>::store(RawOrigin::Signed(caller).into(), vec![0u8; 1]),
+			TransactionStorage::<Test>::store(RawOrigin::Signed(caller).into(), vec![0u8; 1], None),
 			Error::<Test>::NotAuthorized,
 		);
 	});
@@ -111,17 +209,18 @@ fn uses_preimage_authorization() {
 		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
 			RawOrigin::Root.into(),
 			preimage,
-			2002
+			2002,
+			false
 		));
 		assert_eq!(
 			TransactionStorage::<Test>::unused_preimage_authorization_extent(preimage),
 			AuthorizationExtent { transactions: 1, bytes: 2002 }
 		);
 		assert_noop!(
-			TransactionStorage::<Test>::store(RawOrigin::None.into(), vec![1; 2000]),
+			TransactionStorage::<Test>::store(RawOrigin::None.into(), vec![1; 2000], None),
 			Error::<Test>::NotAuthorized,
 		);
-		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::None.into(), data.clone()));
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::None.into(), data.clone(), None));
 		assert_eq!(
 			TransactionStorage::<Test>::unused_preimage_authorization_extent(preimage),
 			AuthorizationExtent { transactions: 0, bytes: 2 }
@@ -130,20 +229,23 @@ fn uses_preimage_authorization() {
 		assert_noop!(
 			TransactionStorage::<Test>::renew(
 				RawOrigin::None.into(),
-				1, // block
-				0, // transaction
+				1,          // block
+				TxIndex(0), // transaction
+				None,
 			),
 			Error::<Test>::NotAuthorized,
 		);
 		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
 			RawOrigin::Root.into(),
 			preimage,
-			2000
+			2000,
+			false
 		));
 		assert_ok!(TransactionStorage::<Test>::renew(
 			RawOrigin::None.into(),
-			1, // block
-			0, // transaction
+			1,          // block
+			TxIndex(0), // transaction
+			None,
 		));
 		assert_eq!(
 			TransactionStorage::<Test>::unused_preimage_authorization_extent(preimage),
@@ -152,6 +254,212 @@ fn uses_preimage_authorization() {
 	});
 }
 
+#[test]
+fn authorizes_preimages_in_batch() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let preimages: Vec<_> = (0..3u32).map(|i| (blake2_256(&i.encode()), 1000)).collect();
+		let preimages: BoundedVec<_, _> = preimages.try_into().unwrap();
+		// Weighed by the number of preimages
+		let call =
+			Call::<Test>::authorize_preimages { preimages: preimages.clone(), exact_size: true };
+		assert_eq!(call.get_dispatch_info().weight, <() as WeightInfo>::authorize_preimages(3));
+		assert_noop!(
+			TransactionStorage::<Test>::authorize_preimages(
+				RawOrigin::Signed(1).into(),
+				preimages.clone(),
+				true
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_ok!(TransactionStorage::<Test>::authorize_preimages(
+			RawOrigin::Root.into(),
+			preimages.clone(),
+			true
+		));
+		for (preimage, bytes) in preimages {
+			assert_eq!(
+				TransactionStorage::<Test>::unused_preimage_authorization_extent(preimage),
+				AuthorizationExtent { transactions: 1, bytes }
+			);
+		}
+		// Sizes must match exactly
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::None.into(), 0u32.encode(), None),
+			Error::<Test>::SizeMismatch,
+		);
+	});
+}
+
+#[test]
+fn reserved_preimages_are_only_stored_by_their_account_until_the_reservation_ends() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let (reserved, other) = (1, 2);
+		let data = vec![3; 2000];
+		let content_hash = blake2_256(&data);
+		let open_data = vec![4; 2000];
+		let open_hash = blake2_256(&open_data);
+		for preimage in [content_hash, open_hash] {
+			assert_ok!(TransactionStorage::<Test>::authorize_reserved_preimage(
+				RawOrigin::Root.into(),
+				preimage,
+				2000,
+				false,
+				reserved
+			));
+		}
+		System::assert_has_event(
+			Event::PreimageReserved { content_hash, who: reserved, until: 4 }.into(),
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::authorize_reserved_preimage(
+				RawOrigin::Signed(other).into(),
+				content_hash,
+				2000,
+				false,
+				other
+			),
+			DispatchError::BadOrigin,
+		);
+		// Nor can others use their own authorizations to store it early
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			other,
+			1,
+			2000,
+			None
+		));
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::Signed(other).into(), data.clone(), None),
+			Error::<Test>::PreimageReserved,
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::None.into(), data.clone(), None),
+			Error::<Test>::PreimageReserved,
+		);
+		assert_eq!(
+			TransactionStorage::<Test>::can_store(ScopeRef::Preimage(content_hash), 2000),
+			Err(StoreRejection::Reserved)
+		);
+		// The reserved account stores it with the preimage authorization
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(reserved).into(),
+			data,
+			None
+		));
+		assert_eq!(TransactionStorage::<Test>::preimage_authorization(content_hash), None);
+
+		// Once the reservation ends, anyone can store the preimage
+		run_to_block(4, || None);
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::None.into(), open_data.clone(), None),
+			Error::<Test>::PreimageReserved,
+		);
+		run_to_block(5, || None);
+		assert_ok!(TransactionStorage::<Test>::can_store(ScopeRef::Preimage(open_hash), 2000));
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::None.into(), open_data, None));
+
+		// Reservations are cleaned up with the authorizations
+		assert!(PreimageReservations::<Test>::contains_key(open_hash));
+		run_to_block(11, || None);
+		assert!(!PreimageReservations::<Test>::contains_key(content_hash));
+		assert!(!PreimageReservations::<Test>::contains_key(open_hash));
+	});
+}
+
+#[test]
+fn caps_outstanding_authorizations_across_spilled_expiries() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let per_block = <Test as Config>::MaxBlockAuthorizationExpiries::get();
+		let max = per_block + per_block / 2;
+		MaxOutstandingAuthorizations::set(max);
+		let authorize = |i: u32| {
+			TransactionStorage::<Test>::authorize_preimage(
+				RawOrigin::Root.into(),
+				blake2_256(&i.encode()),
+				1,
+				false,
+			)
+		};
+		for i in 0..max {
+			assert_ok!(authorize(i));
+		}
+		// The expiries of a full block spill over into the next one, and count towards the cap
+		assert_eq!(AuthorizationsByExpiry::<Test>::get(11).len() as u32, per_block);
+		assert_eq!(AuthorizationsByExpiry::<Test>::get(12).len() as u32, max - per_block);
+		assert_eq!(OutstandingAuthorizations::<Test>::get(), max);
+		assert_noop!(authorize(max), Error::<Test>::TooManyAuthorizations);
+		assert_noop!(
+			TransactionStorage::<Test>::authorize_account(RawOrigin::Root.into(), 1, 1, 1, None),
+			Error::<Test>::TooManyAuthorizations
+		);
+		// Nor is any preimage of a batch hitting the cap
+		assert_noop!(
+			TransactionStorage::<Test>::authorize_preimages(
+				RawOrigin::Root.into(),
+				vec![(blake2_256(&max.encode()), 1)].try_into().unwrap(),
+				false
+			),
+			Error::<Test>::TooManyAuthorizations
+		);
+
+		// Expiries free up room
+		run_to_block(11, || None);
+		assert_eq!(OutstandingAuthorizations::<Test>::get(), max - per_block);
+		assert_ok!(authorize(max));
+		run_to_block(12, || None);
+		assert_eq!(OutstandingAuthorizations::<Test>::get(), 1);
+		assert!(AuthorizationsByExpiry::<Test>::get(12).is_empty());
+	});
+}
+
+#[test]
+fn caps_authorizations_per_block() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		MaxAuthorizationsPerBlock::set(3);
+		MaxOutstandingAuthorizations::set(4);
+		let authorize = |i: u32| {
+			TransactionStorage::<Test>::authorize_preimage(
+				RawOrigin::Root.into(),
+				blake2_256(&i.encode()),
+				1,
+				false,
+			)
+		};
+		for i in 0..3 {
+			assert_ok!(authorize(i));
+		}
+		assert_eq!(BlockAuthorizations::<Test>::get(), 3);
+		// The block limit is hit before the outstanding one
+		assert_noop!(authorize(3), Error::<Test>::TooManyBlockAuthorizations);
+		assert_noop!(
+			TransactionStorage::<Test>::authorize_account(RawOrigin::Root.into(), 1, 1, 1, None),
+			Error::<Test>::TooManyBlockAuthorizations
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::authorize_preimages(
+				RawOrigin::Root.into(),
+				vec![(blake2_256(&3u32.encode()), 1)].try_into().unwrap(),
+				false
+			),
+			Error::<Test>::TooManyBlockAuthorizations
+		);
+		// The authorizations of the block expire together
+		assert_eq!(AuthorizationsByExpiry::<Test>::get(11).len(), 3);
+
+		// The limit is per block, unlike the outstanding one, and its count is not kept in the
+		// state past the block
+		run_to_block(2, || None);
+		assert!(!BlockAuthorizations::<Test>::exists());
+		assert_ok!(authorize(3));
+		assert_noop!(authorize(4), Error::<Test>::TooManyAuthorizations);
+		assert_eq!(BlockAuthorizations::<Test>::get(), 1);
+	});
+}
+
 #[test]
 fn checks_proof() {
 	new_test_ext().execute_with(|| {
@@ -161,11 +469,13 @@ fn checks_proof() {
 			RawOrigin::Root.into(),
 			caller,
 			1,
-			MAX_DATA_SIZE.into()
+			MAX_DATA_SIZE.into(),
+			None
 		));
 		assert_ok!(TransactionStorage::<Test>::store(
 			RawOrigin::Signed(caller).into(),
-			vec![0u8; MAX_DATA_SIZE as usize]
+			vec![0u8; MAX_DATA_SIZE as usize],
+			None
 		));
 		run_to_block(10, || None);
 		let parent_hash = <frame_system::Pallet<Test>>::parent_hash();
@@ -178,7 +488,8 @@ fn checks_proof() {
 		run_to_block(11, || None);
 		let parent_hash = <frame_system::Pallet<Test>>::parent_hash();
 
-		let invalid_proof = build_proof(parent_hash.as_ref(), vec![vec![0u8; 1000]]).unwrap();
+		let invalid_proof =
+			build_proof(parent_hash.as_ref(), vec![vec![1u8; MAX_DATA_SIZE as usize]]).unwrap();
 		assert_noop!(
 			TransactionStorage::<Test>::check_proof(RuntimeOrigin::none(), invalid_proof,),
 			Error::<Test>::InvalidProof,
@@ -191,72 +502,6382 @@ fn checks_proof() {
 }
 
 #[test]
-fn renews_data() {
+fn rejects_oversized_proof() {
 	new_test_ext().execute_with(|| {
 		run_to_block(1, || None);
 		let caller = 1;
 		assert_ok!(TransactionStorage::<Test>::authorize_account(
 			RawOrigin::Root.into(),
 			caller,
-			4,
-			4009
+			1,
+			1000,
+			None
 		));
 		assert_ok!(TransactionStorage::<Test>::store(
 			RawOrigin::Signed(caller).into(),
-			vec![0u8; 2000]
+			vec![0u8; 1000],
+			None
 		));
-		let info = BlockTransactions::<Test>::get().last().unwrap().clone();
-		run_to_block(6, || None);
-		assert_ok!(TransactionStorage::<Test>::renew(
+		run_to_block(11, || None);
+		let parent_hash = <frame_system::Pallet<Test>>::parent_hash();
+		let proof = build_proof(parent_hash.as_ref(), vec![vec![0u8; 1000]]).unwrap();
+		// Still a valid proof, padded with an unused node
+		let mut padded = proof.clone();
+		padded.proof.push(vec![0u8; DEFAULT_MAX_PROOF_SIZE as usize]);
+		assert_noop!(
+			TransactionStorage::<Test>::check_proof(RuntimeOrigin::none(), padded.clone()),
+			Error::<Test>::ProofTooLarge,
+		);
+
+		// It is neither provided nor accepted as an inherent
+		let mut data = InherentData::new();
+		data.put_data(INHERENT_IDENTIFIER, &padded).unwrap();
+		assert_eq!(TransactionStorage::<Test>::create_inherent(&data), None);
+		let call = Call::<Test>::check_proof { proof: padded };
+		assert!(TransactionStorage::<Test>::check_inherent(&call, &data).is_err());
+		let call = Call::<Test>::check_proof { proof: proof.clone() };
+		assert_ok!(TransactionStorage::<Test>::check_inherent(&call, &data));
+
+		assert_ok!(TransactionStorage::<Test>::check_proof(RuntimeOrigin::none(), proof));
+	});
+}
+
+#[test]
+fn rejects_malformed_proofs() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let data = vec![0u8; 1000];
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			1,
+			1000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
 			RawOrigin::Signed(caller).into(),
-			1, // block
-			0, // transaction
+			data.clone(),
+			None
 		));
-		assert_eq!(
-			TransactionStorage::<Test>::unused_account_authorization_extent(caller),
-			AuthorizationExtent { transactions: 2, bytes: 9 },
-		);
-		let proof_provider = || {
-			let block_num = <frame_system::Pallet<Test>>::block_number();
-			if block_num == 11 || block_num == 16 {
-				let parent_hash = <frame_system::Pallet<Test>>::parent_hash();
-				Some(build_proof(parent_hash.as_ref(), vec![vec![0u8; 2000]]).unwrap())
-			} else {
+		run_to_block(11, || None);
+		let mut stored = StoredData::default();
+		stored.insert(data);
+		let proof = stored.proof::<Test>().unwrap();
+		let check_malformed = |proof: TransactionStorageProof, error: Error<Test>| {
+			// Shapes that are malformed whatever chunk is challenged are not accepted as
+			// inherents either
+			let any_chunk = !matches!(error, Error::<Test>::MalformedProofChunk) ||
+				!(1..=CHUNK_SIZE).contains(&proof.chunk.len());
+			let call = Call::<Test>::check_proof { proof: proof.clone() };
+			let checked = TransactionStorage::<Test>::check_inherent(&call, &InherentData::new());
+			assert_eq!(checked.is_err(), any_chunk);
+			assert_noop!(
+				TransactionStorage::<Test>::check_proof(RuntimeOrigin::none(), proof),
+				error,
+			);
+		};
+
+		let mut short = proof.clone();
+		short.chunk.pop();
+		check_malformed(short, Error::<Test>::MalformedProofChunk);
+		let mut long = proof.clone();
+		long.chunk.push(0);
+		check_malformed(long, Error::<Test>::MalformedProofChunk);
+		let mut empty_chunk = proof.clone();
+		empty_chunk.chunk.clear();
+		check_malformed(empty_chunk, Error::<Test>::MalformedProofChunk);
+		let mut no_nodes = proof.clone();
+		no_nodes.proof.clear();
+		check_malformed(no_nodes, Error::<Test>::EmptyProof);
+		let mut large_node = proof.clone();
+		large_node.proof.push(vec![0u8; MAX_PROOF_NODE_SIZE as usize + 1]);
+		check_malformed(large_node, Error::<Test>::ProofNodeTooLarge);
+
+		assert_ok!(TransactionStorage::<Test>::check_proof(RuntimeOrigin::none(), proof));
+	});
+}
+
+/// What becomes of a storage proof inherent of a block.
+#[derive(Debug, PartialEq)]
+enum ProofOutcome {
+	/// The inherent passed `check_inherent` and was dispatched.
+	Accepted,
+	/// `check_inherent` rejected the inherent, so the block is invalid.
+	InherentRejected,
+	/// The inherent failed when dispatched, so the block is invalid.
+	DispatchFailed(DispatchError),
+	/// No proof was checked, so the proof is recorded as missed and the block cannot be
+	/// finalized.
+	Missed,
+}
+
+/// Runs the proof inherents of the given provider in block `n`, after storing 4 chunks of data
+/// in block 1 and a single chunk in block 2. Stops at the first failure, which invalidates the
+/// block.
+fn prove_with(fault: Option<ProofFault>, n: u64) -> Vec<ProofOutcome> {
+	new_test_ext().execute_with(|| {
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			2,
+			2000,
+			None
+		));
+		let contents = [(1, (0..4 * CHUNK_SIZE).map(|i| i as u8).collect()), (2, vec![2; 200])];
+		let mut stored = StoredData::default();
+		for (block, data) in contents {
+			run_to_block(block, || None);
+			assert_ok!(TransactionStorage::<Test>::store(
+				RawOrigin::Signed(caller).into(),
+				data.clone(),
 				None
+			));
+			stored.insert(data);
+		}
+		let honest = stored.clone();
+		run_to_block(n, || honest.proof::<Test>());
+
+		let provider = FaultyProofProvider::new(stored, fault);
+		let mut outcomes = Vec::new();
+		for call in provider.inherents::<Test>() {
+			if TransactionStorage::<Test>::check_inherent(&call, &InherentData::new()).is_err() {
+				outcomes.push(ProofOutcome::InherentRejected);
+				return outcomes
+			}
+			match call.dispatch_bypass_filter(RuntimeOrigin::none()) {
+				Ok(_) => outcomes.push(ProofOutcome::Accepted),
+				Err(e) => {
+					outcomes.push(ProofOutcome::DispatchFailed(e.error));
+					return outcomes
+				},
 			}
+		}
+		if std::panic::catch_unwind(|| TransactionStorage::<Test>::on_finalize(n)).is_err() {
+			assert_eq!(TransactionStorage::<Test>::proof_status(n - 10), ProofStatus::Missed);
+			outcomes.push(ProofOutcome::Missed);
+		}
+		outcomes
+	})
+}
+
+#[test]
+fn faulty_proof_providers_are_caught() {
+	use ProofOutcome::*;
+
+	let failed = |error: Error<Test>| DispatchFailed(error.into());
+	// The proof of block 1 is due in block 11, that of block 2 in block 12
+	let cases = [
+		(None, 11, vec![Accepted]),
+		(None, 12, vec![Accepted]),
+		(Some(ProofFault::WrongChunk), 11, vec![failed(Error::InvalidProof)]),
+		// There is no block before block 1 to prove instead
+		(Some(ProofFault::StaleTarget), 11, vec![Missed]),
+		(Some(ProofFault::StaleTarget), 12, vec![failed(Error::MalformedProofChunk)]),
+		(Some(ProofFault::TruncatedProof), 11, vec![failed(Error::InvalidProof)]),
+		// The proof of a single chunk has a single node, so none are left
+		(Some(ProofFault::TruncatedProof), 12, vec![InherentRejected]),
+		(Some(ProofFault::DuplicatedInherent), 11, vec![Accepted, failed(Error::DoubleCheck)]),
+		(Some(ProofFault::Withheld), 11, vec![Missed]),
+	];
+	for (fault, n, outcomes) in cases {
+		assert_eq!(prove_with(fault, n), outcomes, "{fault:?} in block {n}");
+	}
+}
+
+#[test]
+fn honest_proofs_fit_in_max_proof_size() {
+	let data = vec![0u8; MAX_DATA_SIZE as usize];
+	// The longest chunk index, and indexes at the boundaries of their encoding
+	for chunk in [num_chunks(MAX_DATA_SIZE) - 1, 63, 64, 16383, 16384] {
+		let mut seed = [0u8; 32];
+		seed[..8].copy_from_slice(&(chunk as u64).to_be_bytes());
+		let proof = build_proof(&seed, vec![data.clone()]).unwrap();
+		assert!(proof.encoded_size() <= max_proof_size(MAX_DATA_SIZE) as usize);
+	}
+	assert!(max_proof_size(MAX_DATA_SIZE) <= DEFAULT_MAX_PROOF_SIZE);
+}
+
+#[test]
+fn migrates_transactions_over_several_blocks() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			9,
+			9000,
+			None
+		));
+		let data = |block: u64| vec![block as u8; 1000];
+		let proof_provider = || {
+			let target = <frame_system::Pallet<Test>>::block_number().checked_sub(10)?;
+			let parent_hash = <frame_system::Pallet<Test>>::parent_hash();
+			(1..=8)
+				.contains(&target)
+				.then(|| build_proof(parent_hash.as_ref(), vec![data(target)]).unwrap())
 		};
-		run_to_block(16, proof_provider);
+		let store = |block| {
+			assert_ok!(TransactionStorage::<Test>::store(
+				RawOrigin::Signed(caller).into(),
+				data(block),
+				None
+			));
+		};
+		for block in 1..=5 {
+			run_to_block(block, || None);
+			store(block);
+		}
+		run_to_block(6, || None);
+		// Blocks 1 to 5 were stored in the old format
+		for block in 1..=5 {
+			let transactions = TransactionStorage::<Test>::transaction_roots(block).unwrap();
+			let _ = TransactionInfos::<Test>::clear_prefix(block, u32::MAX, None);
+			let transactions: Vec<_> = transactions.into_iter().map(legacy).collect();
+			Transactions::<Test>::insert(block, BoundedVec::truncate_from(transactions));
+		}
+		StorageVersion::new(0).put::<TransactionStorage<Test>>();
+		migrations::v1::MigrateToTransactionInfos::<Test>::on_runtime_upgrade();
+		assert_eq!(TransactionStorage::<Test>::on_chain_storage_version(), 1);
+		assert_eq!(TransactionsMigration::<Test>::get(), Some((1, 5)));
+		store(6);
+
+		// Leave room for migrating one block at a time
+		let block_expiry = <() as WeightInfo>::execute_scheduled_removals(0);
+		let block_migration = <() as WeightInfo>::migrate_block_transactions(1);
+		let max_block = <Test as frame_system::Config>::BlockWeights::get().max_block;
+		ExpiryWeightRatio::set(Perbill::from_rational(
+			block_expiry.ref_time() + block_migration.ref_time() * 3 / 2,
+			max_block.ref_time(),
+		));
+		run_to_block(7, || None);
+		assert_eq!(TransactionsMigration::<Test>::get(), Some((2, 5)));
 		assert!(Transactions::<Test>::get(1).is_none());
-		assert_eq!(Transactions::<Test>::get(6).unwrap().get(0), Some(info).as_ref());
-		run_to_block(17, proof_provider);
-		assert!(Transactions::<Test>::get(6).is_none());
+		assert_eq!(TransactionInfos::<Test>::get(1, 0).unwrap().chunk_count, 1);
+		store(7);
+		run_to_block(8, || None);
+		assert_eq!(TransactionsMigration::<Test>::get(), Some((3, 5)));
+		store(8);
+
+		// Stall the migration half way, both formats are still readable
+		ExpiryWeightRatio::set(Perbill::zero());
+		run_to_block(9, || None);
+		assert_eq!(TransactionsMigration::<Test>::get(), Some((3, 5)));
+		assert_ok!(TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(caller).into(),
+			4,
+			TxIndex(0),
+			None
+		));
+		// Proofs of blocks 1 and 2 are checked in the new format, of block 3 in the old one
+		run_to_block(14, proof_provider);
+		assert_eq!(TransactionsMigration::<Test>::get(), Some((3, 5)));
+		assert!(Transactions::<Test>::get(4).is_some());
+
+		ExpiryWeightRatio::set(Perbill::from_percent(25));
+		run_to_block(19, proof_provider);
+		assert_eq!(TransactionsMigration::<Test>::get(), None);
+		assert_eq!(Transactions::<Test>::iter().count(), 0);
+		for block in 8..=9 {
+			assert_eq!(TransactionStorage::<Test>::transaction_roots(block).unwrap().len(), 1);
+		}
 	});
 }
 
 #[test]
-fn authorization_expires() {
+fn checks_proof_without_chunk_ends() {
 	new_test_ext().execute_with(|| {
 		run_to_block(1, || None);
-		let who = 1;
+		let caller = 1;
 		assert_ok!(TransactionStorage::<Test>::authorize_account(
 			RawOrigin::Root.into(),
-			who,
+			caller,
+			3,
+			3000,
+			None
+		));
+		for i in 0..3 {
+			assert_ok!(TransactionStorage::<Test>::store(
+				RawOrigin::Signed(caller).into(),
+				vec![i; 1000],
+				None
+			));
+			next_extrinsic();
+		}
+		run_to_block(2, || None);
+		assert_eq!(ChunkEnds::<Test>::get(1).unwrap().into_inner(), vec![4, 8, 12]);
+		// Blocks finalized before `ChunkEnds` was introduced
+		let transactions = TransactionStorage::<Test>::transaction_roots(1).unwrap();
+		let transactions: Vec<_> = transactions.into_iter().map(legacy).collect();
+		Transactions::<Test>::insert(1, BoundedVec::truncate_from(transactions));
+		let _ = TransactionInfos::<Test>::clear_prefix(1, u32::MAX, None);
+		ChunkEnds::<Test>::remove(1);
+		run_to_block(11, || None);
+		let parent_hash = <frame_system::Pallet<Test>>::parent_hash();
+		let transactions = (0..3).map(|i| vec![i; 1000]).collect();
+		let proof = build_proof(parent_hash.as_ref(), transactions).unwrap();
+		assert_ok!(TransactionStorage::<Test>::check_proof(RuntimeOrigin::none(), proof));
+	});
+}
+
+#[test]
+fn try_state_checks_chunk_ends() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
 			1,
-			2000
+			1000,
+			None
 		));
-		assert_eq!(
-			TransactionStorage::<Test>::unused_account_authorization_extent(who),
-			AuthorizationExtent { transactions: 1, bytes: 2000 },
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			vec![0u8; 1000],
+			None
+		));
+		run_to_block(2, || None);
+		assert_ok!(TransactionStorage::<Test>::do_try_state());
+		ChunkEnds::<Test>::insert(1, BoundedVec::truncate_from(vec![5]));
+		assert!(TransactionStorage::<Test>::do_try_state().is_err());
+		ChunkEnds::<Test>::insert(2, BoundedVec::truncate_from(vec![4]));
+		ChunkEnds::<Test>::insert(1, BoundedVec::truncate_from(vec![4]));
+		assert!(TransactionStorage::<Test>::do_try_state().is_err());
+	});
+}
+
+#[test]
+fn tolerates_and_repairs_chunk_count_without_transactions() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			1,
+			1000,
+			None
+		));
+		let data = vec![0u8; 1000];
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			data.clone(),
+			None
+		));
+		run_to_block(11, || None);
+		// The transactions of block 1 are lost, but not its chunk count
+		ChunkEnds::<Test>::remove(1);
+		let _ = TransactionInfos::<Test>::clear_prefix(1, u32::MAX, None);
+		assert!(TransactionStorage::<Test>::do_try_state().is_err());
+		let proof = challenge_proof(&data);
+		assert_noop!(
+			TransactionStorage::<Test>::check_proof(RuntimeOrigin::none(), proof),
+			Error::<Test>::MissingStateData
 		);
-		run_to_block(10, || None);
-		assert_eq!(
-			TransactionStorage::<Test>::unused_account_authorization_extent(who),
-			AuthorizationExtent { transactions: 1, bytes: 2000 },
+		// The block is still finalized
+		TransactionStorage::<Test>::on_finalize(11);
+		System::assert_last_event(Event::<Test>::ProofUnprovable { block: 1 }.into());
+		assert_eq!(TransactionStorage::<Test>::proof_status(1), ProofStatus::Unprovable);
+
+		assert_noop!(
+			TransactionStorage::<Test>::repair_block_metadata(RawOrigin::Signed(caller).into(), 1),
+			DispatchError::BadOrigin
 		);
-		run_to_block(11, || None);
-		assert_eq!(
-			TransactionStorage::<Test>::unused_account_authorization_extent(who),
-			AuthorizationExtent { transactions: 0, bytes: 0 },
+		assert_ok!(TransactionStorage::<Test>::repair_block_metadata(RawOrigin::Root.into(), 1));
+		System::assert_last_event(
+			Event::<Test>::BlockMetadataRepaired { block: 1, chunk_count: 0 }.into(),
+		);
+		assert!(!ChunkCount::<Test>::contains_key(1));
+		assert_ok!(TransactionStorage::<Test>::do_try_state());
+	});
+}
+
+#[test]
+fn repairs_chunk_count_diverging_from_transactions() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			1,
+			1000,
+			None
+		));
+		let mut stored = StoredData::default();
+		let data = vec![0u8; 1000];
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			data.clone(),
+			None
+		));
+		stored.insert(data);
+		run_to_block(2, || None);
+		assert_eq!(ChunkCount::<Test>::get(1), 4);
+
+		// The chunk count of block 1 is lost, so no proof would be required for it
+		ChunkCount::<Test>::remove(1);
+		assert!(TransactionStorage::<Test>::do_try_state().is_err());
+		assert_eq!(TransactionStorage::<Test>::proof_status(1), ProofStatus::NotRequired);
+		assert_ok!(TransactionStorage::<Test>::repair_block_metadata(RawOrigin::Root.into(), 1));
+		System::assert_last_event(
+			Event::<Test>::BlockMetadataRepaired { block: 1, chunk_count: 4 }.into(),
 		);
+		assert_ok!(TransactionStorage::<Test>::do_try_state());
+
+		// Or it no longer matches the transactions
+		ChunkCount::<Test>::insert(1, 7);
+		assert!(TransactionStorage::<Test>::do_try_state().is_err());
+		assert_ok!(TransactionStorage::<Test>::repair_block_metadata(RawOrigin::Root.into(), 1));
+		assert_eq!(ChunkCount::<Test>::get(1), 4);
+
+		run_to_block(12, || stored.proof::<Test>());
+		assert_eq!(TransactionStorage::<Test>::proof_status(1), ProofStatus::Proven);
+	});
+}
+
+#[test]
+fn chunk_count_is_kept_with_transaction() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			2,
+			2000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			vec![0u8; 1000],
+			None
+		));
+		assert_eq!(BlockTransactions::<Test>::get(0).unwrap().chunk_count, 4);
+		run_to_block(2, || None);
+		assert_ok!(TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(caller).into(),
+			1,
+			TxIndex(0),
+			None
+		));
+		assert_eq!(BlockTransactions::<Test>::get(0).unwrap().chunk_count, 4);
+		run_to_block(3, || None);
+		assert_ok!(TransactionStorage::<Test>::do_try_state());
+		// A chunk count that no longer matches the chunk size
+		TransactionInfos::<Test>::mutate(2, 0, |info| info.as_mut().unwrap().chunk_count = 3);
+		assert!(TransactionStorage::<Test>::do_try_state().is_err());
 	});
 }
+
+#[test]
+fn check_proof_weight_depends_on_transaction_count() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			1,
+			1000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			vec![0u8; 1000],
+			None
+		));
+		run_to_block(11, || None);
+		let parent_hash = <frame_system::Pallet<Test>>::parent_hash();
+		let proof = build_proof(parent_hash.as_ref(), vec![vec![0u8; 1000]]).unwrap();
+		let info = Call::<Test>::check_proof { proof: proof.clone() }.get_dispatch_info();
+		// The full block weight is the worst case benchmark, and the extra cost of its deep proof
+		// is kept in the refund
+		let max = <Test as Config>::MaxBlockTransactions::get();
+		let worst_case = <() as WeightInfo>::check_proof_worst_case();
+		assert_eq!(info.weight, worst_case.max(<() as WeightInfo>::check_proof(max)));
+		let deepest = worst_case.saturating_sub(<() as WeightInfo>::check_proof(max));
+		let post_info =
+			TransactionStorage::<Test>::check_proof(RuntimeOrigin::none(), proof).unwrap();
+		assert_eq!(
+			post_info.actual_weight,
+			Some(<() as WeightInfo>::check_proof(1).saturating_add(deepest))
+		);
+	});
+}
+
+#[test]
+fn checks_proof_of_last_chunk_in_full_block() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let max = <Test as Config>::MaxBlockTransactions::get();
+		let size = <Test as Config>::MaxTransactionSize::get();
+		let mut transactions: Vec<_> = (1..max).map(|i| vec![i as u8]).collect();
+		transactions.push(vec![0u8; size as usize]);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			max,
+			transactions.iter().map(|data| data.len() as u64).sum(),
+			None
+		));
+		for data in &transactions {
+			assert_ok!(TransactionStorage::<Test>::store(
+				RawOrigin::Signed(caller).into(),
+				data.clone(),
+				None
+			));
+			next_extrinsic();
+		}
+		run_to_block(11, || None);
+		let total_chunks = ChunkCount::<Test>::get(1);
+		assert_eq!(total_chunks, max - 1 + num_chunks(size));
+		// Challenge the last chunk of the last transaction
+		let mut parent_hash = H256::zero();
+		parent_hash.as_bytes_mut()[..8].copy_from_slice(&u64::from(total_chunks - 1).to_be_bytes());
+		System::set_parent_hash(parent_hash);
+		assert_eq!(random_chunk(parent_hash.as_ref(), total_chunks), total_chunks - 1);
+		let proof = build_proof(parent_hash.as_ref(), transactions).unwrap();
+		assert_eq!(proof.chunk, vec![0u8; CHUNK_SIZE]);
+		assert_ok!(TransactionStorage::<Test>::check_proof(RuntimeOrigin::none(), proof));
+	});
+}
+
+#[test]
+fn checks_proof_at_chunk_boundaries() {
+	for size in [1, CHUNK_SIZE - 1, CHUNK_SIZE, CHUNK_SIZE + 1, MAX_DATA_SIZE as usize] {
+		new_test_ext().execute_with(|| {
+			run_to_block(1, || None);
+			let caller = 1;
+			// Each chunk has distinct content, and so do the two transactions
+			let first: Vec<u8> = (0..size).map(|i| (i / CHUNK_SIZE) as u8).collect();
+			let second: Vec<u8> = first.iter().map(|b| !b).collect();
+			assert_ok!(TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				caller,
+				2,
+				2 * size as u64,
+				None
+			));
+			assert_ok!(TransactionStorage::<Test>::store(
+				RawOrigin::Signed(caller).into(),
+				first.clone(),
+				None
+			));
+			next_extrinsic();
+			assert_ok!(TransactionStorage::<Test>::store(
+				RawOrigin::Signed(caller).into(),
+				second.clone(),
+				None
+			));
+			run_to_block(11, || None);
+			let chunks = num_chunks(size as u32);
+			assert_eq!(chunks as usize, (size + CHUNK_SIZE - 1) / CHUNK_SIZE);
+			assert_eq!(ChunkCount::<Test>::get(1), 2 * chunks);
+			// First, middle and last chunk of both transactions
+			for chunk in [0, chunks / 2, chunks - 1, chunks, chunks + chunks / 2, 2 * chunks - 1] {
+				let parent_hash = (0u32..)
+					.map(|seed| H256(blake2_256(&seed.to_le_bytes())))
+					.find(|hash| random_chunk(hash.as_ref(), 2 * chunks) == chunk)
+					.unwrap();
+				System::set_parent_hash(parent_hash);
+				let proof =
+					build_proof(parent_hash.as_ref(), vec![first.clone(), second.clone()]).unwrap();
+				assert_ok!(TransactionStorage::<Test>::check_proof(RuntimeOrigin::none(), proof));
+				ProofStatuses::<Test>::remove(1);
+			}
+		});
+	}
+}
+
+#[test]
+fn chunk_selection_is_proportional_to_chunk_count() {
+	// Transactions of very different sizes, with the smallest ones at both ends
+	let chunk_counts = [1u32, 3, 1, 200, 17, 1, 64, 2];
+	let chunk_ends: Vec<u32> = chunk_counts
+		.iter()
+		.scan(0, |end, chunks| {
+			*end += chunks;
+			Some(*end)
+		})
+		.collect();
+	let total_chunks = *chunk_ends.last().unwrap();
+	let samples = 200_000u32;
+	let mut transaction_hits = vec![0u32; chunk_counts.len()];
+	let mut chunk_hits = vec![0u32; total_chunks as usize];
+	for seed in 0..samples {
+		let random_hash = blake2_256(&seed.to_le_bytes());
+		let (TxIndex(index), ChunkIndex(chunk)) = select_chunk(&random_hash, &chunk_ends).unwrap();
+		let index = index as usize;
+		assert!(chunk < chunk_counts[index]);
+		transaction_hits[index] += 1;
+		let start = index.checked_sub(1).map_or(0, |prev| chunk_ends[prev]);
+		chunk_hits[(start + chunk) as usize] += 1;
+	}
+	// Within five standard deviations of the expected count
+	let within_tolerance = |hits: u32, chunks: u32| {
+		let p = chunks as f64 / total_chunks as f64;
+		let expected = samples as f64 * p;
+		let tolerance = 5.0 * (expected * (1.0 - p)).sqrt();
+		(hits as f64 - expected).abs() <= tolerance
+	};
+	for (index, (&hits, &chunks)) in transaction_hits.iter().zip(&chunk_counts).enumerate() {
+		assert!(within_tolerance(hits, chunks), "transaction {index}: {hits} hits");
+	}
+	// Including the first and last chunk of every transaction
+	for (chunk, &hits) in chunk_hits.iter().enumerate() {
+		assert!(within_tolerance(hits, 1), "chunk {chunk}: {hits} hits");
+	}
+	assert_eq!(select_chunk(&blake2_256(b"seed"), &[]), None);
+}
+
+#[test]
+fn renews_data() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			4,
+			4009,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			vec![0u8; 2000],
+			None
+		));
+		let info = BlockTransactions::<Test>::get(0).unwrap();
+		run_to_block(6, || None);
+		assert_ok!(TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(caller).into(),
+			1,          // block
+			TxIndex(0), // transaction
+			None,
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(caller),
+			AuthorizationExtent { transactions: 2, bytes: 9 },
+		);
+		let proof_provider = || {
+			let block_num = <frame_system::Pallet<Test>>::block_number();
+			if block_num == 11 || block_num == 16 {
+				let parent_hash = <frame_system::Pallet<Test>>::parent_hash();
+				Some(build_proof(parent_hash.as_ref(), vec![vec![0u8; 2000]]).unwrap())
+			} else {
+				None
+			}
+		};
+		run_to_block(16, proof_provider);
+		assert!(TransactionStorage::<Test>::transaction_roots(1).is_none());
+		assert_eq!(
+			TransactionStorage::<Test>::transaction_roots(6).unwrap().get(0),
+			Some(info).as_ref()
+		);
+		run_to_block(17, proof_provider);
+		assert!(TransactionStorage::<Test>::transaction_roots(6).is_none());
+	});
+}
+
+#[test]
+fn stored_and_renewed_events_include_chunk_root() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let data = vec![3u8; CHUNK_SIZE + 1];
+		let chunks = data.chunks(CHUNK_SIZE).map(|c| c.to_vec()).collect();
+		let chunk_root = sp_io::trie::blake2_256_ordered_root(chunks, sp_runtime::StateVersion::V1);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			2,
+			2 * data.len() as u64,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::Signed(caller).into(), data, None));
+		System::assert_last_event(Event::<Test>::Stored { index: TxIndex(0), chunk_root }.into());
+		run_to_block(2, || None);
+		assert_ok!(TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(caller).into(),
+			1,
+			TxIndex(0),
+			None
+		));
+		System::assert_last_event(Event::<Test>::Renewed { index: TxIndex(0), chunk_root }.into());
+	});
+}
+
+#[test]
+fn content_events_are_indexed_by_topic() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let data = vec![0u8; 1000];
+		let content_hash = blake2_256(&data);
+		let topic = TransactionStorage::<Test>::content_topic(&content_hash);
+		let preimage = [&b"tx-storage::content"[..], &content_hash].concat();
+		assert_eq!(topic, blake2_256(&preimage).into());
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			2,
+			2000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::Signed(caller).into(), data, None));
+		let stored = System::event_count() - 1;
+		assert_eq!(System::event_topics(topic), vec![(1, stored)]);
+		run_to_block(2, || None);
+		assert_ok!(TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(caller).into(),
+			1,
+			TxIndex(0),
+			None
+		));
+		let renewed = System::event_count() - 1;
+		assert_ok!(TransactionStorage::<Test>::schedule_removal(
+			RawOrigin::Root.into(),
+			content_hash,
+			1,
+			RemovalReason::Takedown
+		));
+		let scheduled = System::event_count() - 1;
+		assert_eq!(System::event_topics(topic), vec![(1, stored), (2, renewed), (2, scheduled)]);
+		// Events about other content are not listed
+		let other_topic = TransactionStorage::<Test>::content_topic(&[0u8; 32]);
+		assert!(System::event_topics(other_topic).is_empty());
+	});
+}
+
+#[test]
+fn renew_refunds_weight_by_source_block_size() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let max = <Test as Config>::MaxBlockTransactions::get();
+		// The preimage deposit, which the benchmark does not cover
+		let overhead = <Test as frame_system::Config>::DbWeight::get().reads_writes(3, 3);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			3,
+			3000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			vec![0u8; 1000],
+			None
+		));
+		// A full source block
+		let info = BlockTransactions::<Test>::get(0).unwrap();
+		let full: BoundedVec<_, _> = (1..=max)
+			.map(|i| LegacyTransactionInfo {
+				block_chunks: i * info.chunk_count,
+				..legacy(info.clone())
+			})
+			.collect::<Vec<_>>()
+			.try_into()
+			.unwrap();
+		Transactions::<Test>::insert(0, full);
+		run_to_block(2, || None);
+		// Nearly empty source block
+		let post_info = TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(caller).into(),
+			1,
+			TxIndex(0),
+			None,
+		)
+		.unwrap();
+		assert_eq!(
+			post_info.actual_weight,
+			Some(<() as WeightInfo>::renew(1).saturating_add(overhead))
+		);
+		next_extrinsic();
+		let post_info = TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(caller).into(),
+			0,
+			TxIndex(max - 1),
+			None,
+		)
+		.unwrap();
+		assert_eq!(
+			post_info.actual_weight,
+			Some(<() as WeightInfo>::renew(max).saturating_add(overhead))
+		);
+		let max_weight = Call::<Test>::renew { block: 0, index: TxIndex(0), expected_hash: None }
+			.get_dispatch_info()
+			.weight;
+		assert!(post_info.actual_weight.unwrap().all_lt(max_weight));
+	});
+}
+
+#[test]
+fn renewal_expiry_boundary() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			2,
+			2000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			vec![0u8; 1000],
+			None
+		));
+		// `StoragePeriod - 1` blocks old
+		run_to_block(10, || None);
+		assert_ok!(TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(caller).into(),
+			1,
+			TxIndex(0),
+			None
+		));
+		// `StoragePeriod` blocks old, the last block the data can be renewed in
+		run_to_block(11, || None);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			1,
+			1000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(caller).into(),
+			1,
+			TxIndex(0),
+			None
+		));
+		let proof_provider = || {
+			let block_num = <frame_system::Pallet<Test>>::block_number();
+			if block_num == 11 {
+				let parent_hash = <frame_system::Pallet<Test>>::parent_hash();
+				Some(build_proof(parent_hash.as_ref(), vec![vec![0u8; 1000]]).unwrap())
+			} else {
+				None
+			}
+		};
+		// `StoragePeriod + 1` blocks old
+		run_to_block(12, proof_provider);
+		assert_noop!(
+			TransactionStorage::<Test>::renew(
+				RawOrigin::Signed(caller).into(),
+				1,
+				TxIndex(0),
+				None
+			),
+			Error::<Test>::RenewalExpired,
+		);
+		// Blocks within the storage period without data are not found
+		assert_noop!(
+			TransactionStorage::<Test>::renew(
+				RawOrigin::Signed(caller).into(),
+				2,
+				TxIndex(0),
+				None
+			),
+			Error::<Test>::RenewedNotFound,
+		);
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(caller),
+			AuthorizationExtent { transactions: 0, bytes: 0 }
+		);
+	});
+}
+
+#[test]
+fn renew_checks_expected_content() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			3,
+			3000,
+			None
+		));
+		let (first, second) = (vec![1u8; 1000], vec![2u8; 1000]);
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			first.clone(),
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			second.clone(),
+			None
+		));
+		run_to_block(2, || None);
+		// A wrong index fails without spending the authorization
+		assert_noop!(
+			TransactionStorage::<Test>::renew(
+				RawOrigin::Signed(caller).into(),
+				1,
+				TxIndex(1),
+				Some(blake2_256(&first))
+			),
+			Error::<Test>::UnexpectedContent,
+		);
+		// The hash is checked before the authorization
+		assert_noop!(
+			TransactionStorage::<Test>::renew(
+				RawOrigin::Signed(2).into(),
+				1,
+				TxIndex(0),
+				Some(blake2_256(&second))
+			),
+			Error::<Test>::UnexpectedContent,
+		);
+		assert_ok!(TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(caller).into(),
+			1,
+			TxIndex(0),
+			Some(blake2_256(&first))
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(caller),
+			AuthorizationExtent { transactions: 0, bytes: 0 }
+		);
+	});
+}
+
+#[test]
+fn authorization_expires() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			1,
+			2000,
+			None
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(who),
+			AuthorizationExtent { transactions: 1, bytes: 2000 },
+		);
+		run_to_block(10, || None);
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(who),
+			AuthorizationExtent { transactions: 1, bytes: 2000 },
+		);
+		run_to_block(11, || None);
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(who),
+			AuthorizationExtent { transactions: 0, bytes: 0 },
+		);
+	});
+}
+
+#[test]
+fn on_initialize_weight_includes_expired_authorizations() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		for who in 1..=2 {
+			assert_ok!(TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				who,
+				1,
+				1000,
+				None
+			));
+		}
+		// Block 11 expires the authorizations, block 10 nothing and does not read them
+		let idle = TransactionStorage::<Test>::on_initialize(10);
+		let expiring = TransactionStorage::<Test>::on_initialize(11);
+		assert_eq!(expiring - idle, <() as WeightInfo>::expire_authorizations(2));
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(1),
+			AuthorizationExtent { transactions: 0, bytes: 0 }
+		);
+	});
+}
+
+#[test]
+fn only_blocks_authorizations_expire_in_read_them() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let authorize = |who| {
+			assert_ok!(TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				who,
+				1,
+				1000,
+				None
+			));
+		};
+		let revoke = |who| {
+			assert_ok!(TransactionStorage::<Test>::revoke_account_authorization(
+				RawOrigin::Root.into(),
+				who
+			));
+		};
+		let linked = || {
+			let mut blocks = Vec::new();
+			let mut next = NextExpiryBlock::<Test>::get();
+			while let Some(block) = next {
+				blocks.push(block);
+				next = ExpiryBlockAfter::<Test>::get(block);
+			}
+			blocks
+		};
+		authorize(1);
+		authorize(2);
+		run_to_block(3, || None);
+		authorize(3);
+		run_to_block(4, || None);
+		authorize(4);
+		assert_eq!(linked(), vec![11, 13, 14]);
+		assert_eq!(LastExpiryBlock::<Test>::get(), Some(14));
+
+		// Blocks no authorizations expire in only read the removals scheduled in them
+		assert_eq!(
+			TransactionStorage::<Test>::process_expiries(5),
+			<() as WeightInfo>::execute_scheduled_removals(0)
+		);
+		// Blocks whose authorizations were all revoked stay linked unless they come first
+		revoke(3);
+		assert!(!AuthorizationsByExpiry::<Test>::contains_key(13));
+		assert_eq!(linked(), vec![11, 13, 14]);
+
+		run_to_block(12, || None);
+		assert_eq!(linked(), vec![13, 14]);
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(2),
+			AuthorizationExtent { transactions: 0, bytes: 0 }
+		);
+		run_to_block(13, || None);
+		assert_eq!(linked(), vec![14]);
+		revoke(4);
+		assert_eq!(linked(), vec![]);
+		assert_eq!(LastExpiryBlock::<Test>::get(), None);
+
+		// Authorizations granted once no others are pending expiry are linked again
+		authorize(5);
+		assert_eq!(linked(), vec![23]);
+		assert_eq!(LastExpiryBlock::<Test>::get(), Some(23));
+	});
+}
+
+#[test]
+fn expiry_backlog_is_carried_over() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		// Two authorizations expiring in each of blocks 11 to 13
+		for block in 1..=3 {
+			run_to_block(block, || None);
+			for who in [block * 10, block * 10 + 1] {
+				assert_ok!(TransactionStorage::<Test>::authorize_account(
+					RawOrigin::Root.into(),
+					who,
+					1,
+					1000,
+					None
+				));
+			}
+		}
+		let unused = |who| TransactionStorage::<Test>::unused_account_authorization_extent(who);
+		// Simulate blocks too heavy to process any expiries
+		run_to_block(10, || None);
+		ExpiryWeightRatio::set(Perbill::zero());
+		run_to_block(13, || None);
+		assert_eq!(ExpiryCursor::<Test>::get(), Some(11));
+		assert_eq!(unused(10), AuthorizationExtent { transactions: 1, bytes: 1000 });
+		// Leave room for the expiries of one block only
+		let block_expiry = <() as WeightInfo>::expire_authorizations(2) +
+			<() as WeightInfo>::execute_scheduled_removals(0);
+		let max_block = <Test as frame_system::Config>::BlockWeights::get().max_block;
+		ExpiryWeightRatio::set(Perbill::from_rational(
+			block_expiry.ref_time() * 3 / 2,
+			max_block.ref_time(),
+		));
+		let limit = ExpiryWeightRatio::get() * max_block;
+		let fixed = <() as WeightInfo>::on_initialize_pruning() +
+			<() as WeightInfo>::on_finalize(<Test as Config>::MaxBlockTransactions::get());
+		System::set_block_number(14);
+		assert!((TransactionStorage::<Test>::on_initialize(14) - fixed).all_lte(limit));
+		assert_eq!(ExpiryCursor::<Test>::get(), Some(12));
+		assert_eq!(unused(10), AuthorizationExtent { transactions: 0, bytes: 0 });
+		assert_eq!(unused(20), AuthorizationExtent { transactions: 1, bytes: 1000 });
+		// The backlog is eventually cleared
+		let mut n = 14;
+		while ExpiryCursor::<Test>::get().is_some() {
+			n += 1;
+			assert!(n < 20, "Expiry backlog not cleared");
+			System::set_block_number(n);
+			assert!((TransactionStorage::<Test>::on_initialize(n) - fixed).all_lte(limit));
+		}
+		for who in [10, 11, 20, 21, 30, 31] {
+			assert_eq!(unused(who), AuthorizationExtent { transactions: 0, bytes: 0 });
+		}
+	});
+}
+
+#[test]
+fn on_initialize_weight_includes_on_finalize() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		// Nothing to prune, expire or remove, so only the fixed costs and the worst case
+		// `on_finalize` are charged
+		let max = <Test as Config>::MaxBlockTransactions::get();
+		assert_eq!(
+			TransactionStorage::<Test>::on_initialize(2),
+			<() as WeightInfo>::on_initialize_pruning() +
+				<() as WeightInfo>::execute_scheduled_removals(0) +
+				<() as WeightInfo>::on_finalize(max)
+		);
+	});
+}
+
+/// Ceilings of the weight of representative blocks under `RocksDbWeight`, from the hooks and the
+/// extrinsics of the block. A change that makes one of these blocks heavier has to raise its
+/// ceiling, so that the extra weight is deliberate.
+const FULL_BLOCK_OF_STORES_WEIGHT: Weight = Weight::from_parts(1_380_000_000_000, 19_950_000);
+const EXPIRY_HEAVY_BLOCK_WEIGHT: Weight = Weight::from_parts(336_000_000_000, 2_030_000);
+const PROOF_CHECK_BLOCK_WEIGHT: Weight = Weight::from_parts(121_500_000_000, 1_420_000);
+
+/// Dispatches `call` from `origin` and returns the weight it consumed.
+fn dispatch_weight(origin: RuntimeOrigin, call: Call<Test>) -> Weight {
+	let info = call.get_dispatch_info();
+	let post_info = call.dispatch_bypass_filter(origin).unwrap();
+	post_info.calc_actual_weight(&info)
+}
+
+/// Stores `MaxBlockTransactions` transactions of 1 KiB for `who` in the current block, recording
+/// them in `stored`. Returns the weight of the stores.
+fn store_full_block(who: u64, stored: &mut StoredData) -> Weight {
+	let mut weight = Weight::zero();
+	for i in 0..<Test as Config>::MaxBlockTransactions::get() {
+		let mut data = vec![0u8; 1024];
+		data[..4].copy_from_slice(&i.to_le_bytes());
+		stored.insert(data.clone());
+		weight +=
+			dispatch_weight(RuntimeOrigin::signed(who), Call::store { data, namespace: None });
+		next_extrinsic();
+	}
+	weight
+}
+
+#[test]
+fn full_block_of_stores_weight_is_under_ceiling() {
+	new_test_ext().execute_with(|| {
+		MockDbWeight::set(RocksDbWeight::get());
+		run_to_block(1, || None);
+		let max = <Test as Config>::MaxBlockTransactions::get();
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			1,
+			max,
+			u64::from(max) * 1024,
+			None
+		));
+		let weight = next_block() + store_full_block(1, &mut StoredData::default());
+		assert_eq!(BlockTransactions::<Test>::count(), max);
+		assert!(
+			weight.all_lte(FULL_BLOCK_OF_STORES_WEIGHT),
+			"{weight:?} exceeds {FULL_BLOCK_OF_STORES_WEIGHT:?}"
+		);
+	});
+}
+
+#[test]
+fn expiry_heavy_block_weight_is_under_ceiling() {
+	new_test_ext().execute_with(|| {
+		MockDbWeight::set(RocksDbWeight::get());
+		run_to_block(1, || None);
+		// As many authorizations as fit in `ExpiryWeightRatio` of a block under these weights
+		for who in 1..=256 {
+			assert_ok!(TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				who,
+				1,
+				1024,
+				None
+			));
+		}
+		run_to_block(10, || None);
+		let weight = next_block();
+		assert!(AuthorizationsByExpiry::<Test>::get(11).is_empty());
+		assert_eq!(ExpiryCursor::<Test>::get(), None);
+		assert!(
+			weight.all_lte(EXPIRY_HEAVY_BLOCK_WEIGHT),
+			"{weight:?} exceeds {EXPIRY_HEAVY_BLOCK_WEIGHT:?}"
+		);
+	});
+}
+
+#[test]
+fn proof_check_block_weight_is_under_ceiling() {
+	new_test_ext().execute_with(|| {
+		MockDbWeight::set(RocksDbWeight::get());
+		run_to_block(1, || None);
+		let max = <Test as Config>::MaxBlockTransactions::get();
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			1,
+			max,
+			u64::from(max) * 1024,
+			None
+		));
+		let mut stored = StoredData::default();
+		store_full_block(1, &mut stored);
+		run_to_block(10, || None);
+		// The proof of the full block 1 is due in block 11
+		let hooks = next_block();
+		let proof = stored.proof::<Test>().unwrap();
+		let weight = hooks + dispatch_weight(RuntimeOrigin::none(), Call::check_proof { proof });
+		assert!(
+			weight.all_lte(PROOF_CHECK_BLOCK_WEIGHT),
+			"{weight:?} exceeds {PROOF_CHECK_BLOCK_WEIGHT:?}"
+		);
+	});
+}
+
+#[test]
+fn hooks_fit_in_block() {
+	new_test_ext().execute_with(|| {
+		<TransactionStorage<Test> as IntegrityTest>::integrity_test();
+	});
+}
+
+#[test]
+#[should_panic(expected = "`MinTransactionSize` is larger than `MaxTransactionSize`")]
+fn integrity_test_checks_min_transaction_size() {
+	MinTransactionSize::set(MaxTransactionSize::get() + 1);
+	<TransactionStorage<Test> as IntegrityTest>::integrity_test();
+}
+
+#[test]
+#[should_panic(expected = "`MinBytesPerStore` is larger than `MaxTransactionSize`")]
+fn integrity_test_checks_min_bytes_per_store() {
+	MinBytesPerStore::set(MaxTransactionSize::get() + 1);
+	<TransactionStorage<Test> as IntegrityTest>::integrity_test();
+}
+
+#[test]
+#[should_panic(expected = "`MaxProofSize` is too small for the proofs")]
+fn integrity_test_checks_max_proof_size() {
+	MaxProofSize::set(max_proof_size(MaxTransactionSize::get()) - 1);
+	<TransactionStorage<Test> as IntegrityTest>::integrity_test();
+}
+
+#[test]
+#[should_panic(expected = "`MaxTransactionSize` does not fit in the block length")]
+fn integrity_test_checks_transaction_size_against_block_length() {
+	let normal_length = *MockBlockLength::get().max.get(DispatchClass::Normal);
+	MaxTransactionSize::set(normal_length + 1);
+	<TransactionStorage<Test> as IntegrityTest>::integrity_test();
+}
+
+#[test]
+#[should_panic(expected = "`MaxProofSize` does not fit in the block length of a mandatory")]
+fn integrity_test_checks_max_proof_size_against_block_length() {
+	MockBlockLength::set(frame_system::limits::BlockLength::max(MaxProofSize::get()));
+	MaxTransactionSize::set(MaxProofSize::get() / 2);
+	MaxProofSize::set(MaxProofSize::get() + 1);
+	<TransactionStorage<Test> as IntegrityTest>::integrity_test();
+}
+
+#[test]
+#[should_panic(expected = "Storing `MaxTransactionSize` bytes does not fit in the weight")]
+fn integrity_test_checks_transaction_size_against_block_weight() {
+	MockBlockLength::set(frame_system::limits::BlockLength::max(u32::MAX));
+	MaxTransactionSize::set(u32::MAX);
+	<TransactionStorage<Test> as IntegrityTest>::integrity_test();
+}
+
+#[test]
+#[should_panic(expected = "`MaxBlockTransactions` stores of a full block do not fit")]
+fn integrity_test_checks_block_transactions_against_block_weight() {
+	MaxBlockTransactions::set(100_000);
+	<TransactionStorage<Test> as IntegrityTest>::integrity_test();
+}
+
+#[test]
+#[should_panic(expected = "`MaxBlockTransactions` transactions do not fit in the block length")]
+fn integrity_test_checks_block_transactions_against_block_length() {
+	let normal_length = *MockBlockLength::get().max.get(DispatchClass::Normal);
+	MinTransactionSize::set(1024);
+	MaxBlockTransactions::set(normal_length / 1024 + 1);
+	<TransactionStorage<Test> as IntegrityTest>::integrity_test();
+}
+
+#[test]
+#[should_panic(expected = "`MaxBlockTransactions` stores of a full block do not fit")]
+fn integrity_test_checks_block_bytes_against_block_weight() {
+	// Each store fits, but not `MaxBlockTransactions * MaxTransactionSize` bytes of them
+	MockBlockLength::set(frame_system::limits::BlockLength::max(u32::MAX));
+	<TransactionStorage<Test> as IntegrityTest>::integrity_test();
+}
+
+#[test]
+#[should_panic(expected = "The mandatory work of a full block and expiry backlog")]
+fn integrity_test_checks_mandatory_work_against_block_weight() {
+	// Each part fits, but not together with a block of expiries
+	ExpiryWeightRatio::set(Perbill::from_percent(100));
+	<TransactionStorage<Test> as IntegrityTest>::integrity_test();
+}
+
+#[test]
+fn authorization_usage_query() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		let scope = AuthorizationScope::Account(who);
+		assert_eq!(TransactionStorage::<Test>::authorization_usage(scope.clone()), None);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			2,
+			2000,
+			None
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::authorization_usage(scope.clone()),
+			Some(AuthorizationUsage {
+				used: AuthorizationExtent { transactions: 0, bytes: 0 },
+				unused: AuthorizationExtent { transactions: 2, bytes: 2000 },
+				exact_size: None,
+			})
+		);
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			vec![0u8; 500],
+			None
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::authorization_usage(scope.clone()),
+			Some(AuthorizationUsage {
+				used: AuthorizationExtent { transactions: 1, bytes: 500 },
+				unused: AuthorizationExtent { transactions: 1, bytes: 1500 },
+				exact_size: None,
+			})
+		);
+		run_to_block(11, || None);
+		assert_eq!(TransactionStorage::<Test>::authorization_usage(scope), None);
+	});
+}
+
+#[test]
+fn has_account_authorization_query() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		assert!(!TransactionStorage::<Test>::has_account_authorization(who, 1));
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			2,
+			2000,
+			None
+		));
+		assert!(TransactionStorage::<Test>::has_account_authorization(who, 2000));
+		assert!(!TransactionStorage::<Test>::has_account_authorization(who, 2001));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			vec![0u8; 1500],
+			None
+		));
+		assert!(TransactionStorage::<Test>::has_account_authorization(who, 500));
+		assert!(!TransactionStorage::<Test>::has_account_authorization(who, 501));
+		next_extrinsic();
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			vec![0u8; 100],
+			None
+		));
+		// Bytes remain but no transactions do
+		assert!(!TransactionStorage::<Test>::has_account_authorization(who, 1));
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			1,
+			0,
+			None
+		));
+		assert!(TransactionStorage::<Test>::has_account_authorization(who, 400));
+		run_to_block(11, || None);
+		assert!(!TransactionStorage::<Test>::has_account_authorization(who, 1));
+	});
+}
+
+#[test]
+fn preimage_authorization_query() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let data = vec![2; 2000];
+		let preimage = blake2_256(&data);
+		assert_eq!(TransactionStorage::<Test>::preimage_authorization(preimage), None);
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			preimage,
+			2002,
+			false
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::preimage_authorization(preimage),
+			Some(AuthorizationExtent { transactions: 1, bytes: 2002 })
+		);
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::None.into(), data, None));
+		assert_eq!(TransactionStorage::<Test>::preimage_authorization(preimage), None);
+		let other = blake2_256(&[3; 100]);
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			other,
+			100,
+			false
+		));
+		assert!(TransactionStorage::<Test>::preimage_authorization(other).is_some());
+		run_to_block(11, || None);
+		assert_eq!(TransactionStorage::<Test>::preimage_authorization(other), None);
+	});
+}
+
+#[test]
+fn standing_quota_refills_every_period() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		assert_ok!(TransactionStorage::<Test>::set_standing_quota(
+			RawOrigin::Root.into(),
+			who,
+			2,
+			1000
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::standing_quota_remaining(who),
+			AuthorizationExtent { transactions: 2, bytes: 1000 }
+		);
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			vec![0u8; 600],
+			None
+		));
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::Signed(who).into(), vec![0u8; 600], None),
+			Error::<Test>::NotAuthorized,
+		);
+		next_extrinsic();
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			vec![0u8; 400],
+			None
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(who),
+			AuthorizationExtent { transactions: 0, bytes: 0 }
+		);
+		// Still in the first period
+		run_to_block(9, || None);
+		assert!(!TransactionStorage::<Test>::has_account_authorization(who, 1));
+		// The quota is reset, not accumulated, at the start of the next period
+		run_to_block(10, || None);
+		assert_eq!(
+			TransactionStorage::<Test>::standing_quota_remaining(who),
+			AuthorizationExtent { transactions: 2, bytes: 1000 }
+		);
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			vec![0u8; 1000],
+			None
+		));
+		assert_eq!(
+			StandingQuotas::<Test>::get(who).unwrap().remaining,
+			AuthorizationExtent { transactions: 1, bytes: 0 }
+		);
+	});
+}
+
+#[test]
+fn standing_quota_is_additive_with_authorizations() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		assert_ok!(TransactionStorage::<Test>::set_standing_quota(
+			RawOrigin::Root.into(),
+			who,
+			1,
+			100
+		));
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			1,
+			1000,
+			None
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(who),
+			AuthorizationExtent { transactions: 2, bytes: 1100 }
+		);
+		// Too large for the standing quota, so uses the authorization
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			vec![0u8; 500],
+			None
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::standing_quota_remaining(who),
+			AuthorizationExtent { transactions: 1, bytes: 100 }
+		);
+		next_extrinsic();
+		// Standing quota is used first
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			vec![0u8; 100],
+			None
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(who),
+			AuthorizationExtent { transactions: 0, bytes: 500 }
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::Signed(who).into(), vec![0u8; 1], None),
+			Error::<Test>::NotAuthorized,
+		);
+	});
+}
+
+#[test]
+fn clears_standing_quota() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		assert_noop!(
+			TransactionStorage::<Test>::set_standing_quota(
+				RawOrigin::Signed(who).into(),
+				who,
+				1,
+				1
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_ok!(TransactionStorage::<Test>::set_standing_quota(
+			RawOrigin::Root.into(),
+			who,
+			5,
+			1000
+		));
+		System::assert_last_event(
+			Event::<Test>::StandingQuotaSet { who, transactions: 5, bytes: 1000 }.into(),
+		);
+		assert_ok!(TransactionStorage::<Test>::clear_standing_quota(RawOrigin::Root.into(), who));
+		System::assert_last_event(Event::<Test>::StandingQuotaCleared { who }.into());
+		assert_eq!(
+			TransactionStorage::<Test>::standing_quota_remaining(who),
+			AuthorizationExtent { transactions: 0, bytes: 0 }
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::Signed(who).into(), vec![0u8; 1], None),
+			Error::<Test>::NotAuthorized,
+		);
+	});
+}
+
+#[test]
+fn store_and_renew_weights_include_account_overhead() {
+	new_test_ext().execute_with(|| {
+		let data = vec![0u8; 100];
+		let info = Call::<Test>::store { data: data.clone(), namespace: None }.get_dispatch_info();
+		// Execution phase, removal and indexed extrinsic checks, standing quota refill, lifetime
+		// usage, group fallback and preimage deposit
+		let db_weight = <Test as frame_system::Config>::DbWeight::get();
+		assert_eq!(
+			info.weight,
+			<() as WeightInfo>::store(data.len() as u32)
+				.saturating_add(db_weight.reads_writes(16, 11))
+		);
+		let info = Call::<Test>::renew { block: 1, index: TxIndex(0), expected_hash: None }
+			.get_dispatch_info();
+		let max = <Test as Config>::MaxBlockTransactions::get();
+		assert_eq!(
+			info.weight,
+			<() as WeightInfo>::renew(max).saturating_add(db_weight.reads_writes(21, 16))
+		);
+	});
+}
+
+#[test]
+fn only_authorized_stores_and_renewals_are_feeless() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let (caller, stranger) = (1, 2);
+		for who in [caller, stranger] {
+			Balances::make_free_balance_be(&who, u64::MAX / 4);
+		}
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			2,
+			2000,
+			None
+		));
+		// Dispatches `call` as an extrinsic signed by `who` and returns the fee it paid
+		let fee = |who: u64, call: Call<Test>| {
+			let call = RuntimeCall::from(call);
+			let info = call.get_dispatch_info();
+			let len = call.encoded_size();
+			let balance = Balances::free_balance(who);
+			let pre = ChargeTransactionPayment::<Test>::from(0)
+				.pre_dispatch(&who, &call, &info, len)
+				.unwrap();
+			let post_info =
+				call.dispatch(RuntimeOrigin::signed(who)).unwrap_or_else(|e| e.post_info);
+			assert_ok!(ChargeTransactionPayment::<Test>::post_dispatch(
+				Some(pre),
+				&info,
+				&post_info,
+				len,
+				&Ok(()),
+			));
+			next_extrinsic();
+			balance - Balances::free_balance(who)
+		};
+		assert_eq!(fee(caller, Call::store { data: vec![0u8; 1000], namespace: None }), 0);
+		assert!(fee(stranger, Call::store { data: vec![0u8; 1000], namespace: None }) > 0);
+		run_to_block(2, || None);
+		assert_eq!(
+			fee(caller, Call::renew { block: 1, index: TxIndex(0), expected_hash: None }),
+			0
+		);
+		assert!(
+			fee(stranger, Call::renew { block: 1, index: TxIndex(0), expected_hash: None }) > 0
+		);
+	});
+}
+
+#[test]
+fn storage_event_handler_is_called_after_store_and_renew() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let data = vec![0u8; 1000];
+		let content_hash = blake2_256(&data);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			2,
+			2000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			content_hash,
+			1000,
+			false
+		));
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::None.into(), data.clone(), None));
+		next_extrinsic();
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			data.clone(),
+			None
+		));
+		next_extrinsic();
+		// Failed calls are not reported
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::Signed(2).into(), data, None),
+			Error::<Test>::NotAuthorized,
+		);
+		run_to_block(2, || None);
+		assert_ok!(TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(caller).into(),
+			1,
+			TxIndex(1),
+			None
+		));
+		assert_eq!(
+			StorageEvents::get(),
+			vec![
+				StorageEvent::Stored { content_hash, size: 1000, owner: None, index: TxIndex(0) },
+				StorageEvent::Stored {
+					content_hash,
+					size: 1000,
+					owner: Some(caller),
+					index: TxIndex(1)
+				},
+				StorageEvent::Renewed {
+					content_hash,
+					size: 1000,
+					owner: Some(caller),
+					index: TxIndex(0)
+				},
+			]
+		);
+	});
+}
+
+#[test]
+fn receipts_are_sent_for_preimage_authorized_stores() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let receipt_weight = Weight::from_parts(1_000_000, 1000);
+		ReceiptWeight::set(receipt_weight);
+		let data = vec![0u8; 1000];
+		let content_hash = blake2_256(&data);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			1,
+			1000,
+			None
+		));
+		let store_weight = Call::<Test>::store { data: data.clone(), namespace: None }
+			.get_dispatch_info()
+			.weight;
+		// No receipt for an account authorization, and its weight is refunded
+		let post_info =
+			TransactionStorage::<Test>::store(RawOrigin::Signed(caller).into(), data.clone(), None)
+				.unwrap();
+		assert_eq!(post_info.actual_weight, Some(store_weight - receipt_weight));
+		assert!(SentReceipts::get().is_empty());
+		next_extrinsic();
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			content_hash,
+			1000,
+			false
+		));
+		let post_info =
+			TransactionStorage::<Test>::store(RawOrigin::None.into(), data, None).unwrap();
+		assert_eq!(post_info.actual_weight, Some(store_weight));
+		assert_eq!(SentReceipts::get(), vec![(content_hash, 1000, 1)]);
+	});
+}
+
+#[test]
+fn preimage_deposit_is_returned_once_stored() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let authorizer = 1;
+		Balances::make_free_balance_be(&authorizer, 1000);
+		assert_ok!(TransactionStorage::<Test>::add_authorizer(RawOrigin::Root.into(), authorizer));
+		let data = vec![0u8; 100];
+		let content_hash = blake2_256(&data);
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Signed(authorizer).into(),
+			content_hash,
+			100,
+			false
+		));
+		// Base plus a unit per byte
+		assert_eq!(Balances::free_balance(authorizer), 890);
+		// Authorizing again replaces the deposit
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Signed(authorizer).into(),
+			content_hash,
+			200,
+			false
+		));
+		assert_eq!(Balances::free_balance(authorizer), 790);
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::None.into(), data, None));
+		assert_eq!(Balances::free_balance(authorizer), 1000);
+		assert!(!PreimageDeposits::<Test>::contains_key(content_hash));
+	});
+}
+
+#[test]
+fn preimage_deposit_is_burned_if_authorization_expires_unused() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let authorizer = 1;
+		Balances::make_free_balance_be(&authorizer, 1000);
+		assert_ok!(TransactionStorage::<Test>::add_authorizer(RawOrigin::Root.into(), authorizer));
+		let content_hash = blake2_256(&[0u8; 100]);
+		assert_ok!(TransactionStorage::<Test>::authorize_preimages(
+			RawOrigin::Signed(authorizer).into(),
+			BoundedVec::truncate_from(vec![(content_hash, 100)]),
+			false
+		));
+		assert_eq!(Balances::free_balance(authorizer), 890);
+		let issuance = Balances::total_issuance();
+		run_to_block(11, || None);
+		assert_eq!(TransactionStorage::<Test>::preimage_authorization(content_hash), None);
+		assert!(!PreimageDeposits::<Test>::contains_key(content_hash));
+		assert_eq!(Balances::total_balance(&authorizer), 890);
+		assert_eq!(Balances::total_issuance(), issuance - 110);
+	});
+}
+
+#[test]
+fn preimage_authorizations_by_root_take_no_deposit() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let data = vec![0u8; 100];
+		let content_hash = blake2_256(&data);
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			content_hash,
+			100,
+			false
+		));
+		assert!(!PreimageDeposits::<Test>::contains_key(content_hash));
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::None.into(), data, None));
+	});
+}
+
+#[test]
+fn store_and_renew_weights_include_storage_event_handler() {
+	new_test_ext().execute_with(|| {
+		let store = Call::<Test>::store { data: vec![0u8; 100], namespace: None }
+			.get_dispatch_info()
+			.weight;
+		let renew = Call::<Test>::renew { block: 1, index: TxIndex(0), expected_hash: None }
+			.get_dispatch_info()
+			.weight;
+		let handler_weight = Weight::from_parts(1_000_000, 1000);
+		StorageEventWeight::set(handler_weight);
+		assert_eq!(
+			Call::<Test>::store { data: vec![0u8; 100], namespace: None }
+				.get_dispatch_info()
+				.weight,
+			store + handler_weight
+		);
+		assert_eq!(
+			Call::<Test>::renew { block: 1, index: TxIndex(0), expected_hash: None }
+				.get_dispatch_info()
+				.weight,
+			renew + handler_weight
+		);
+	});
+}
+
+#[test]
+fn block_transactions_are_consolidated_on_finalize() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let max = <Test as Config>::MaxBlockTransactions::get();
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			max + 1,
+			(max as u64 + 1) * 2000,
+			None
+		));
+		for i in 0..max {
+			let data = vec![i as u8; 1000 + i as usize];
+			assert_ok!(TransactionStorage::<Test>::store(
+				RawOrigin::Signed(caller).into(),
+				data,
+				None
+			));
+			next_extrinsic();
+		}
+		assert_noop!(
+			TransactionStorage::<Test>::store(
+				RawOrigin::Signed(caller).into(),
+				vec![0u8; 1000],
+				None
+			),
+			Error::<Test>::TooManyTransactions,
+		);
+		run_to_block(2, || None);
+		assert_eq!(BlockTransactions::<Test>::count(), 0);
+		assert_eq!(BlockChunks::<Test>::get(), 0);
+		let transactions = TransactionStorage::<Test>::transaction_roots(1).unwrap();
+		assert_eq!(transactions.len(), max as usize);
+		let mut block_chunks = 0;
+		for (i, info) in transactions.iter().enumerate() {
+			let data = vec![i as u8; 1000 + i];
+			assert_eq!(info.content_hash, blake2_256(&data).into());
+			block_chunks += num_chunks(data.len() as u32);
+			assert_eq!(info.block_chunks, block_chunks);
+		}
+		assert_eq!(ChunkCount::<Test>::get(1), block_chunks);
+	});
+}
+
+#[test]
+fn lifetime_cap_limits_stores() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			10,
+			10000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			vec![0u8; 1000],
+			None
+		));
+		assert_eq!(TransactionStorage::<Test>::remaining_lifetime_allowance(who), None);
+		// Bytes stored before the cap was set count towards it
+		assert_ok!(TransactionStorage::<Test>::set_lifetime_cap(
+			RawOrigin::Root.into(),
+			who,
+			Some(3000)
+		));
+		System::assert_last_event(Event::<Test>::LifetimeCapSet { who, cap: Some(3000) }.into());
+		assert_eq!(TransactionStorage::<Test>::remaining_lifetime_allowance(who), Some(2000));
+		assert!(!TransactionStorage::<Test>::has_account_authorization(who, 2001));
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::Signed(who).into(), vec![0u8; 2001], None),
+			Error::<Test>::LifetimeCapExceeded,
+		);
+		next_extrinsic();
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			vec![0u8; 2000],
+			None
+		));
+		assert_eq!(TransactionStorage::<Test>::remaining_lifetime_allowance(who), Some(0));
+		assert_eq!(TransactionStorage::<Test>::lifetime_used(who), 3000);
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::Signed(who).into(), vec![0u8; 1], None),
+			Error::<Test>::LifetimeCapExceeded,
+		);
+		// Removing the cap lifts the restriction
+		assert_ok!(TransactionStorage::<Test>::set_lifetime_cap(RawOrigin::Root.into(), who, None));
+		next_extrinsic();
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			vec![0u8; 1],
+			None
+		));
+		assert_eq!(TransactionStorage::<Test>::lifetime_used(who), 3001);
+	});
+}
+
+#[test]
+fn lifetime_cap_survives_authorization_expiry() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		assert_ok!(TransactionStorage::<Test>::set_lifetime_cap(
+			RawOrigin::Root.into(),
+			who,
+			Some(3000)
+		));
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			1,
+			2000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			vec![0u8; 2000],
+			None
+		));
+		// The per-period authorization is exhausted before the lifetime cap
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::Signed(who).into(), vec![0u8; 500], None),
+			Error::<Test>::NotAuthorized,
+		);
+		run_to_block(11, || None);
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(who),
+			AuthorizationExtent { transactions: 0, bytes: 0 }
+		);
+		// Expiry does not reset lifetime usage
+		assert_eq!(TransactionStorage::<Test>::lifetime_used(who), 2000);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			2,
+			2000,
+			None
+		));
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::Signed(who).into(), vec![0u8; 1001], None),
+			Error::<Test>::LifetimeCapExceeded,
+		);
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			vec![0u8; 1000],
+			None
+		));
+		assert_eq!(TransactionStorage::<Test>::remaining_lifetime_allowance(who), Some(0));
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(who),
+			AuthorizationExtent { transactions: 1, bytes: 1000 }
+		);
+	});
+}
+
+#[test]
+fn exact_size_preimage_authorization() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let data = vec![2; 2000];
+		let preimage = blake2_256(&data);
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			preimage,
+			2000,
+			true
+		));
+		// Short payload
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::None.into(), data[..1999].to_vec(), None),
+			Error::<Test>::SizeMismatch,
+		);
+		// Long payload
+		let mut long = data.clone();
+		long.push(2);
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::None.into(), long, None),
+			Error::<Test>::SizeMismatch,
+		);
+		// Exact match
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::None.into(), data, None));
+		assert_eq!(
+			TransactionStorage::<Test>::unused_preimage_authorization_extent(preimage),
+			AuthorizationExtent { transactions: 0, bytes: 0 }
+		);
+	});
+}
+
+#[test]
+fn rejects_exact_sizes_above_max_transaction_size() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let max = u64::from(MaxTransactionSize::get());
+		for bytes in [max + 1, u64::from(u32::MAX) + 1, u64::MAX] {
+			assert_noop!(
+				TransactionStorage::<Test>::authorize_preimage(
+					RawOrigin::Root.into(),
+					[bytes as u8; 32],
+					bytes,
+					true
+				),
+				Error::<Test>::ExactSizeTooLarge,
+			);
+		}
+		assert_noop!(
+			TransactionStorage::<Test>::authorize_preimages(
+				RawOrigin::Root.into(),
+				vec![([1; 32], max), ([2; 32], max + 1)],
+				true
+			),
+			Error::<Test>::ExactSizeTooLarge,
+		);
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			[1; 32],
+			max,
+			true
+		));
+		// Only exact sizes are bounded
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			[2; 32],
+			u64::MAX,
+			false
+		));
+	});
+}
+
+#[test]
+fn uses_authorizations_at_numeric_extremes() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let (unbounded, exact) = (1, 2);
+		// Byte pools saturate rather than wrap
+		for _ in 0..2 {
+			assert_ok!(TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				unbounded,
+				u32::MAX,
+				u64::MAX,
+				None
+			));
+		}
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(unbounded),
+			AuthorizationExtent { transactions: u32::MAX, bytes: u64::MAX }
+		);
+		assert!(TransactionStorage::<Test>::has_account_authorization(unbounded, u32::MAX));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(unbounded).into(),
+			vec![0u8; 2000],
+			None
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(unbounded),
+			AuthorizationExtent { transactions: u32::MAX - 1, bytes: u64::MAX - 2000 }
+		);
+
+		// A store can use up the whole pool
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			exact,
+			u32::MAX,
+			2000,
+			None
+		));
+		assert!(TransactionStorage::<Test>::has_account_authorization(exact, 2000));
+		assert!(!TransactionStorage::<Test>::has_account_authorization(exact, 2001));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(exact).into(),
+			vec![1u8; 2000],
+			None
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(exact),
+			AuthorizationExtent { transactions: u32::MAX - 1, bytes: 0 }
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::Signed(exact).into(), vec![1u8; 1], None),
+			Error::<Test>::NotAuthorized,
+		);
+	});
+}
+
+#[test]
+fn exact_size_is_set_by_most_recent_preimage_authorization() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let data = vec![2; 1000];
+		let preimage = blake2_256(&data);
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			preimage,
+			2000,
+			true
+		));
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			preimage,
+			2000,
+			false
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::authorization_usage(AuthorizationScope::Preimage(preimage))
+				.unwrap()
+				.exact_size,
+			None
+		);
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::None.into(), data, None));
+	});
+}
+
+#[test]
+fn restricted_account_authorization() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		let listed = vec![1u8; 1000];
+		let unlisted = vec![2u8; 1000];
+		let allowed_hashes: BoundedVec<_, _> = vec![blake2_256(&listed)].try_into().unwrap();
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			2,
+			2000,
+			Some(allowed_hashes)
+		));
+		assert_eq!(TransactionStorage::<Test>::allowed_hashes(who), vec![blake2_256(&listed)]);
+		// Restricted authorizations don't count as authorization for arbitrary data
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(who),
+			AuthorizationExtent { transactions: 0, bytes: 0 }
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::Signed(who).into(), unlisted, None),
+			Error::<Test>::NotAuthorized,
+		);
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::Signed(who).into(), listed, None));
+		let scope = AuthorizationScope::RestrictedAccount(who);
+		assert_eq!(
+			TransactionStorage::<Test>::authorization_usage(scope).unwrap().unused,
+			AuthorizationExtent { transactions: 1, bytes: 1000 }
+		);
+		// Expiry removes the allowed hashes along with the authorization
+		run_to_block(11, || None);
+		assert!(TransactionStorage::<Test>::allowed_hashes(who).is_empty());
+	});
+}
+
+#[test]
+fn mixed_account_authorizations() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		let listed = vec![1u8; 1000];
+		let unlisted = vec![2u8; 1000];
+		let allowed_hashes: BoundedVec<_, _> = vec![blake2_256(&listed)].try_into().unwrap();
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			1,
+			1000,
+			Some(allowed_hashes)
+		));
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			2,
+			2000,
+			None
+		));
+		// Unlisted data falls back to the unrestricted authorization
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			unlisted,
+			None
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(who),
+			AuthorizationExtent { transactions: 1, bytes: 1000 }
+		);
+		next_extrinsic();
+		// Listed data uses the restricted authorization first
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			listed.clone(),
+			None
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(who),
+			AuthorizationExtent { transactions: 1, bytes: 1000 }
+		);
+		next_extrinsic();
+		// And then falls back to the unrestricted authorization once that is used up
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::Signed(who).into(), listed, None));
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(who),
+			AuthorizationExtent { transactions: 0, bytes: 0 }
+		);
+	});
+}
+
+#[test]
+fn allowed_hashes_are_bounded() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		let hashes = |range: core::ops::Range<u8>| -> BoundedVec<_, _> {
+			range.map(|i| blake2_256(&[i])).collect::<Vec<_>>().try_into().unwrap()
+		};
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			1,
+			1000,
+			Some(hashes(0..3))
+		));
+		// Hashes already in the list don't count twice
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			1,
+			1000,
+			Some(hashes(1..4))
+		));
+		assert_eq!(TransactionStorage::<Test>::allowed_hashes(who).len(), 4);
+		assert_noop!(
+			TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				who,
+				1,
+				1000,
+				Some(hashes(4..5))
+			),
+			Error::<Test>::TooManyAllowedHashes,
+		);
+	});
+}
+
+#[test]
+fn authorizers_set_backs_authorizer_origin() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let authorizer = 1;
+		let who = 2;
+		assert!(
+			EnsureAuthorizedAccount::<Test>::try_origin(RuntimeOrigin::signed(authorizer)).is_err()
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Signed(authorizer).into(),
+				who,
+				1,
+				1000,
+				None
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::add_authorizer(RawOrigin::Signed(authorizer).into(), who),
+			DispatchError::BadOrigin,
+		);
+		assert_ok!(TransactionStorage::<Test>::add_authorizer(RawOrigin::Root.into(), authorizer));
+		System::assert_last_event(Event::<Test>::AuthorizerAdded { who: authorizer }.into());
+		assert_eq!(
+			EnsureAuthorizedAccount::<Test>::try_origin(RuntimeOrigin::signed(authorizer)).ok(),
+			Some(authorizer)
+		);
+		assert!(EnsureAuthorizedAccount::<Test>::try_origin(RuntimeOrigin::signed(who)).is_err());
+		assert!(EnsureAuthorizedAccount::<Test>::try_origin(RuntimeOrigin::none()).is_err());
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Signed(authorizer).into(),
+			who,
+			1,
+			1000,
+			None
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(who),
+			AuthorizationExtent { transactions: 1, bytes: 1000 }
+		);
+		assert_ok!(TransactionStorage::<Test>::remove_authorizer(
+			RawOrigin::Root.into(),
+			authorizer
+		));
+		System::assert_last_event(Event::<Test>::AuthorizerRemoved { who: authorizer }.into());
+		assert!(
+			EnsureAuthorizedAccount::<Test>::try_origin(RuntimeOrigin::signed(authorizer)).is_err()
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::remove_authorizer(RawOrigin::Root.into(), authorizer),
+			Error::<Test>::NotAuthorizer,
+		);
+	});
+}
+
+#[test]
+fn authorizers_set_is_bounded() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		assert_ok!(TransactionStorage::<Test>::add_authorizer(RawOrigin::Root.into(), 1));
+		assert_noop!(
+			TransactionStorage::<Test>::add_authorizer(RawOrigin::Root.into(), 1),
+			Error::<Test>::AlreadyAuthorizer,
+		);
+		assert_ok!(TransactionStorage::<Test>::add_authorizer(RawOrigin::Root.into(), 2));
+		assert_noop!(
+			TransactionStorage::<Test>::add_authorizer(RawOrigin::Root.into(), 3),
+			Error::<Test>::TooManyAuthorizers,
+		);
+		assert_ok!(TransactionStorage::<Test>::remove_authorizer(RawOrigin::Root.into(), 2));
+		assert_ok!(TransactionStorage::<Test>::add_authorizer(RawOrigin::Root.into(), 3));
+	});
+}
+
+#[test]
+fn scheduled_removal() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		let data = vec![0u8; 1000];
+		let content_hash = blake2_256(&data);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			3,
+			3000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			data.clone(),
+			None
+		));
+		run_to_block(2, || None);
+		assert_noop!(
+			TransactionStorage::<Test>::cancel_removal(RawOrigin::Root.into(), content_hash),
+			Error::<Test>::RemovalNotScheduled,
+		);
+		assert_ok!(TransactionStorage::<Test>::schedule_removal(
+			RawOrigin::Root.into(),
+			content_hash,
+			3,
+			RemovalReason::Takedown
+		));
+		System::assert_last_event(Event::<Test>::RemovalScheduled { content_hash, at: 5 }.into());
+		assert_noop!(
+			TransactionStorage::<Test>::schedule_removal(
+				RawOrigin::Root.into(),
+				content_hash,
+				3,
+				RemovalReason::Takedown
+			),
+			Error::<Test>::RemovalPending,
+		);
+		// Renewals are rejected as soon as the removal is scheduled
+		assert_noop!(
+			TransactionStorage::<Test>::renew(RawOrigin::Signed(who).into(), 1, TxIndex(0), None),
+			Error::<Test>::RemovalPending,
+		);
+		assert_ok!(TransactionStorage::<Test>::cancel_removal(
+			RawOrigin::Root.into(),
+			content_hash
+		));
+		System::assert_last_event(Event::<Test>::RemovalCancelled { content_hash }.into());
+		run_to_block(5, || None);
+		assert!(!RemovedContent::<Test>::contains_key(content_hash));
+		assert_ok!(TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(who).into(),
+			1,
+			TxIndex(0),
+			None
+		));
+
+		assert_ok!(TransactionStorage::<Test>::schedule_removal(
+			RawOrigin::Root.into(),
+			content_hash,
+			0,
+			RemovalReason::Takedown
+		));
+		System::assert_last_event(Event::<Test>::RemovalScheduled { content_hash, at: 6 }.into());
+		run_to_block(6, || None);
+		let reason = RemovalReason::Takedown;
+		System::assert_last_event(Event::<Test>::ContentRemoved { content_hash, reason }.into());
+		assert!(!PendingRemovals::<Test>::contains_key(content_hash));
+		assert_noop!(
+			TransactionStorage::<Test>::renew(RawOrigin::Signed(who).into(), 1, TxIndex(0), None),
+			Error::<Test>::ContentRemoved,
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::Signed(who).into(), data, None),
+			Error::<Test>::ContentRemoved,
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::schedule_removal(
+				RawOrigin::Root.into(),
+				content_hash,
+				1,
+				RemovalReason::Takedown
+			),
+			Error::<Test>::ContentRemoved,
+		);
+	});
+}
+
+#[test]
+fn force_removal() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let content_hash = [1u8; 32];
+		assert_noop!(
+			TransactionStorage::<Test>::force_remove(
+				RawOrigin::Signed(1).into(),
+				content_hash,
+				RemovalReason::Takedown
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_ok!(TransactionStorage::<Test>::schedule_removal(
+			RawOrigin::Root.into(),
+			content_hash,
+			5,
+			RemovalReason::Takedown
+		));
+		assert_ok!(TransactionStorage::<Test>::force_remove(
+			RawOrigin::Root.into(),
+			content_hash,
+			RemovalReason::Takedown
+		));
+		let reason = RemovalReason::Takedown;
+		System::assert_last_event(Event::<Test>::ContentRemoved { content_hash, reason }.into());
+		// The pending removal is dropped from the schedule
+		assert!(!PendingRemovals::<Test>::contains_key(content_hash));
+		assert!(RemovalSchedule::<Test>::get(6).is_empty());
+		assert_noop!(
+			TransactionStorage::<Test>::force_remove(
+				RawOrigin::Root.into(),
+				content_hash,
+				RemovalReason::Takedown
+			),
+			Error::<Test>::ContentRemoved,
+		);
+	});
+}
+
+#[test]
+fn removed_content_cannot_be_resurrected() {
+	// Removal mechanisms, applied in block 2 to the content stored in blocks 1 and 2
+	let force_remove = |content_hash| {
+		assert_ok!(TransactionStorage::<Test>::force_remove(
+			RawOrigin::Root.into(),
+			content_hash,
+			RemovalReason::Takedown
+		));
+	};
+	let scheduled_removal = |content_hash| {
+		assert_ok!(TransactionStorage::<Test>::schedule_removal(
+			RawOrigin::Root.into(),
+			content_hash,
+			1,
+			RemovalReason::OwnerRequest
+		));
+		run_to_block(3, || None);
+	};
+	let pruned_tombstone = |content_hash| {
+		force_remove(content_hash);
+		assert_ok!(TransactionStorage::<Test>::prune_tombstone(
+			RawOrigin::Root.into(),
+			content_hash
+		));
+	};
+	let removals: [&dyn Fn(Preimage); 3] = [&force_remove, &scheduled_removal, &pruned_tombstone];
+
+	for remove in removals {
+		new_test_ext().execute_with(|| {
+			run_to_block(1, || None);
+			let who = 1;
+			let data = vec![0u8; 1000];
+			let content_hash = blake2_256(&data);
+			assert_ok!(TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				who,
+				10,
+				10000,
+				None
+			));
+			let store = |origin: RawOrigin<u64>| {
+				TransactionStorage::<Test>::store(origin.into(), data.clone(), None)
+			};
+			assert_ok!(store(RawOrigin::Signed(who)));
+			run_to_block(2, || None);
+			assert_ok!(store(RawOrigin::Signed(who)));
+			next_extrinsic();
+			remove(content_hash);
+			run_to_block(3, || None);
+			assert!(RemovedContent::<Test>::contains_key(content_hash));
+
+			// Every occurrence of the content is covered, not only the latest one
+			for block in [1, 2] {
+				assert_noop!(
+					TransactionStorage::<Test>::renew(
+						RawOrigin::Signed(who).into(),
+						block,
+						TxIndex(0),
+						None
+					),
+					Error::<Test>::ContentRemoved,
+				);
+				assert_noop!(
+					TransactionStorage::<Test>::verify_content(
+						RawOrigin::Signed(who).into(),
+						block,
+						TxIndex(0),
+						data.clone()
+					),
+					Error::<Test>::ContentRemoved,
+				);
+			}
+			assert_noop!(store(RawOrigin::Signed(who)), Error::<Test>::ContentRemoved);
+			assert_noop!(store(RawOrigin::None), Error::<Test>::ContentRemoved);
+			assert_noop!(
+				TransactionStorage::<Test>::store_compressed(
+					RawOrigin::Signed(who).into(),
+					data.clone(),
+					[0u8; 32],
+					2000
+				),
+				Error::<Test>::ContentRemoved,
+			);
+			assert_noop!(
+				<TransactionStorage<Test> as StorePreimage>::note(data.clone().into()),
+				Error::<Test>::ContentRemoved,
+			);
+		});
+	}
+}
+
+#[test]
+fn removal_schedule_is_bounded() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		for i in 0..2 {
+			assert_ok!(TransactionStorage::<Test>::schedule_removal(
+				RawOrigin::Root.into(),
+				[i; 32],
+				1,
+				RemovalReason::Takedown
+			));
+		}
+		assert_noop!(
+			TransactionStorage::<Test>::schedule_removal(
+				RawOrigin::Root.into(),
+				[2; 32],
+				1,
+				RemovalReason::Takedown
+			),
+			Error::<Test>::TooManyRemovals,
+		);
+		assert_ok!(TransactionStorage::<Test>::schedule_removal(
+			RawOrigin::Root.into(),
+			[2; 32],
+			2,
+			RemovalReason::Takedown
+		));
+	});
+}
+
+#[test]
+fn removals_leave_tombstones() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let taken_down = [1u8; 32];
+		let requested = [2u8; 32];
+		assert_ok!(TransactionStorage::<Test>::force_remove(
+			RawOrigin::Root.into(),
+			taken_down,
+			RemovalReason::Takedown
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::tombstone(taken_down),
+			Some(RemovalReason::Takedown)
+		);
+
+		assert_ok!(TransactionStorage::<Test>::schedule_removal(
+			RawOrigin::Root.into(),
+			requested,
+			1,
+			RemovalReason::OwnerRequest
+		));
+		assert_eq!(TransactionStorage::<Test>::tombstone(requested), None);
+		run_to_block(2, || None);
+		let reason = RemovalReason::OwnerRequest;
+		System::assert_last_event(
+			Event::<Test>::ContentRemoved { content_hash: requested, reason }.into(),
+		);
+		assert_eq!(TransactionStorage::<Test>::tombstone(requested), Some(reason));
+
+		assert_noop!(
+			TransactionStorage::<Test>::prune_tombstone(RawOrigin::Signed(1).into(), requested),
+			DispatchError::BadOrigin,
+		);
+		assert_ok!(TransactionStorage::<Test>::prune_tombstone(RawOrigin::Root.into(), requested));
+		System::assert_last_event(
+			Event::<Test>::TombstonePruned { content_hash: requested }.into(),
+		);
+		assert_eq!(TransactionStorage::<Test>::tombstone(requested), None);
+		assert_noop!(
+			TransactionStorage::<Test>::prune_tombstone(RawOrigin::Root.into(), requested),
+			Error::<Test>::NoTombstone,
+		);
+		// The content stays removed
+		assert!(RemovedContent::<Test>::contains_key(requested));
+	});
+}
+
+#[test]
+fn tombstones_are_bounded() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		MaxTombstones::set(1);
+		let remove = |content_hash| {
+			assert_ok!(TransactionStorage::<Test>::force_remove(
+				RawOrigin::Root.into(),
+				content_hash,
+				RemovalReason::Takedown
+			));
+		};
+		remove([1; 32]);
+		remove([2; 32]);
+		// The content is removed without a tombstone
+		assert!(RemovedContent::<Test>::contains_key([2; 32]));
+		assert_eq!(TransactionStorage::<Test>::tombstone([2; 32]), None);
+		assert_eq!(Tombstones::<Test>::count(), 1);
+		// Pruning makes room again
+		assert_ok!(TransactionStorage::<Test>::prune_tombstone(RawOrigin::Root.into(), [1; 32]));
+		remove([3; 32]);
+		assert_eq!(TransactionStorage::<Test>::tombstone([3; 32]), Some(RemovalReason::Takedown));
+	});
+}
+
+#[test]
+fn migrates_pending_removals_to_removal_reasons() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let content_hash = [1u8; 32];
+		let key = PendingRemovals::<Test>::hashed_key_for(content_hash);
+		frame_support::storage::unhashed::put(&key, &5u64);
+		StorageVersion::new(1).put::<TransactionStorage<Test>>();
+		migrations::v2::MigrateToRemovalReasons::<Test>::on_runtime_upgrade();
+		assert_eq!(TransactionStorage::<Test>::on_chain_storage_version(), 2);
+		assert_eq!(PendingRemovals::<Test>::get(content_hash), Some((5, RemovalReason::Takedown)));
+	});
+}
+
+#[test]
+fn migrates_to_linked_expiry_blocks() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let authorize = |who| {
+			assert_ok!(TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				who,
+				1,
+				1000,
+				None
+			));
+		};
+		authorize(1);
+		run_to_block(3, || None);
+		authorize(2);
+		// The blocks authorizations expire in were not linked before the upgrade
+		NextExpiryBlock::<Test>::kill();
+		LastExpiryBlock::<Test>::kill();
+		ExpiryBlockAfter::<Test>::remove(11);
+		StorageVersion::new(2).put::<TransactionStorage<Test>>();
+		migrations::v3::MigrateToExpiryBlockList::<Test>::on_runtime_upgrade();
+		assert_eq!(TransactionStorage::<Test>::on_chain_storage_version(), 3);
+		assert_eq!(NextExpiryBlock::<Test>::get(), Some(11));
+		assert_eq!(ExpiryBlockAfter::<Test>::get(11), Some(13));
+		assert_eq!(LastExpiryBlock::<Test>::get(), Some(13));
+
+		run_to_block(12, || None);
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(1),
+			AuthorizationExtent { transactions: 0, bytes: 0 }
+		);
+		assert_eq!(NextExpiryBlock::<Test>::get(), Some(13));
+	});
+}
+
+#[test]
+fn migrates_to_pending_authorizations() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		let scope = AuthorizationScope::Account(who);
+		let extent = |transactions, bytes| AuthorizationExtent { transactions, bytes };
+		let authorize = |transactions, bytes| {
+			TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				who,
+				transactions,
+				bytes,
+				None,
+			)
+		};
+		assert_ok!(authorize(2, 1000));
+		run_to_block(3, || None);
+		assert_ok!(authorize(2, 2000));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			vec![0; 1500],
+			None
+		));
+		// Authorizations had no identifiers before the upgrade, and only their usage was pooled
+		for block in [11, 13] {
+			let old: Vec<_> = AuthorizationsByExpiry::<Test>::get(block)
+				.into_iter()
+				.map(|authorization| (authorization.scope, authorization.extent))
+				.collect();
+			let key = AuthorizationsByExpiry::<Test>::hashed_key_for(block);
+			frame_support::storage::unhashed::put(&key, &old);
+		}
+		PendingAuthorizations::<Test>::remove(&scope);
+		NextAuthorizationId::<Test>::kill();
+		StorageVersion::new(3).put::<TransactionStorage<Test>>();
+		migrations::v4::MigrateToPendingAuthorizations::<Test>::on_runtime_upgrade();
+		assert_eq!(TransactionStorage::<Test>::on_chain_storage_version(), 4);
+		assert_eq!(
+			AuthorizationsByExpiry::<Test>::get(13).into_inner(),
+			vec![Authorization { id: 1, scope: scope.clone(), extent: extent(2, 2000) }]
+		);
+		// The used extent is attributed to the authorization expiring first
+		assert_eq!(
+			PendingAuthorizations::<Test>::get(&scope).into_inner(),
+			vec![
+				PendingAuthorization { id: 0, expiry: Some(11), unused: extent(1, 0) },
+				PendingAuthorization { id: 1, expiry: Some(13), unused: extent(2, 1500) },
+			]
+		);
+		assert_eq!(NextAuthorizationId::<Test>::get(), 2);
+
+		run_to_block(11, || None);
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(who),
+			extent(2, 1500)
+		);
+	});
+}
+
+#[test]
+fn only_one_store_per_extrinsic() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			4,
+			4000,
+			None
+		));
+		let store = |data| RuntimeCall::TransactionStorage(Call::store { data, namespace: None });
+		// A single store in a batch is fine
+		assert_ok!(RuntimeCall::Utility(pallet_utility::Call::batch {
+			calls: vec![store(vec![0u8; 1000])]
+		})
+		.dispatch(RuntimeOrigin::signed(caller)));
+		assert_eq!(BlockTransactions::<Test>::count(), 1);
+		// The batch is interrupted at the second store
+		next_extrinsic();
+		assert_ok!(RuntimeCall::Utility(pallet_utility::Call::batch {
+			calls: vec![store(vec![1u8; 1000]), store(vec![2u8; 1000])]
+		})
+		.dispatch(RuntimeOrigin::signed(caller)));
+		System::assert_last_event(
+			pallet_utility::Event::BatchInterrupted {
+				index: 1,
+				error: Error::<Test>::AlreadyIndexedInExtrinsic.into(),
+			}
+			.into(),
+		);
+		assert_eq!(BlockTransactions::<Test>::count(), 2);
+		// And `batch_all` fails as a whole
+		next_extrinsic();
+		assert_noop!(
+			RuntimeCall::Utility(pallet_utility::Call::batch_all {
+				calls: vec![store(vec![3u8; 1000]), store(vec![4u8; 1000])]
+			})
+			.dispatch(RuntimeOrigin::signed(caller))
+			.map_err(|e| e.error),
+			Error::<Test>::AlreadyIndexedInExtrinsic,
+		);
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(caller),
+			AuthorizationExtent { transactions: 2, bytes: 2000 }
+		);
+		// Renewals are subject to the same restriction
+		run_to_block(2, || None);
+		let renew = |index| {
+			RuntimeCall::TransactionStorage(Call::renew {
+				block: 1,
+				index: TxIndex(index),
+				expected_hash: None,
+			})
+		};
+		assert_noop!(
+			RuntimeCall::Utility(pallet_utility::Call::batch_all {
+				calls: vec![renew(0), renew(1)]
+			})
+			.dispatch(RuntimeOrigin::signed(caller))
+			.map_err(|e| e.error),
+			Error::<Test>::AlreadyIndexedInExtrinsic,
+		);
+		assert_ok!(TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(caller).into(),
+			1,
+			TxIndex(0),
+			None
+		));
+	});
+}
+
+#[test]
+fn scheduled_store_is_rejected() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			1,
+			1000,
+			None
+		));
+		let call =
+			RuntimeCall::TransactionStorage(Call::store { data: vec![0u8; 1000], namespace: None });
+		assert_ok!(<Scheduler as ScheduleAnon<_, _, OriginCaller>>::schedule(
+			DispatchTime::At(2),
+			None,
+			0,
+			RawOrigin::Signed(caller).into(),
+			<TransactionStorage<Test> as StorePreimage>::bound(call.clone()).unwrap(),
+		));
+		// Scheduled calls are dispatched during block initialization
+		System::initialize(&2, &System::parent_hash(), &Default::default());
+		Scheduler::on_initialize(2);
+		System::assert_last_event(
+			pallet_scheduler::Event::Dispatched {
+				task: (2, 0),
+				id: None,
+				result: Err(Error::<Test>::NotInExtrinsic.into()),
+			}
+			.into(),
+		);
+		assert_eq!(BlockTransactions::<Test>::count(), 0);
+		// The authorization is still available to an extrinsic
+		System::note_finished_initialize();
+		assert_ok!(call.dispatch(RuntimeOrigin::signed(caller)));
+		assert_eq!(BlockTransactions::<Test>::count(), 1);
+	});
+}
+
+#[test]
+fn scheduled_call_preimage_is_kept_inline() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let allowed_hashes: BoundedVec<_, _> =
+			vec![[1; 32], [2; 32], [3; 32], [4; 32]].try_into().unwrap();
+		let call = RuntimeCall::TransactionStorage(Call::authorize_account {
+			who: caller,
+			transactions: 1,
+			bytes: 1000,
+			allowed_hashes: Some(allowed_hashes),
+		});
+		// Too large to be inlined in the scheduler's agenda
+		let bounded = <TransactionStorage<Test> as StorePreimage>::bound(call.clone()).unwrap();
+		let hash = bounded.lookup_hash().unwrap();
+		assert_eq!(TransactionStorage::<Test>::len(&hash), Some(call.encoded_size() as u32));
+		assert_eq!(TransactionStorage::<Test>::fetch(&hash, None).unwrap(), call.encode());
+		assert_ok!(<Scheduler as ScheduleAnon<_, _, OriginCaller>>::schedule(
+			DispatchTime::At(2),
+			None,
+			0,
+			RawOrigin::Root.into(),
+			bounded,
+		));
+		assert!(TransactionStorage::<Test>::is_requested(&hash));
+
+		System::initialize(&2, &System::parent_hash(), &Default::default());
+		Scheduler::on_initialize(2);
+		System::assert_last_event(
+			pallet_scheduler::Event::Dispatched { task: (2, 0), id: None, result: Ok(()) }.into(),
+		);
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(caller),
+			AuthorizationExtent { transactions: 1, bytes: 1000 }
+		);
+		// The preimage is dropped once its storage period ends, after its last request
+		TransactionStorage::<Test>::unnote(&hash);
+		assert!(!TransactionStorage::<Test>::is_requested(&hash));
+		run_to_block(11, || None);
+		assert_eq!(TransactionStorage::<Test>::fetch(&hash, None).unwrap(), call.encode());
+		run_to_block(12, || None);
+		assert!(InlineData::<Test>::get(hash.0).is_none());
+		assert_eq!(TransactionStorage::<Test>::fetch(&hash, None), Err(DispatchError::Unavailable));
+	});
+}
+
+#[test]
+fn inline_and_chunked_data_expire_in_the_same_block() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			1,
+			1000,
+			None
+		));
+		let mut stored = StoredData::default();
+		let data = vec![0u8; 1000];
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			data.clone(),
+			None
+		));
+		stored.insert(data);
+		let dropped = TransactionStorage::<Test>::note(vec![1u8; 10].into()).unwrap();
+		let kept = TransactionStorage::<Test>::note(vec![2u8; 10].into()).unwrap();
+		// Noting again does not list the preimage twice
+		assert_ok!(TransactionStorage::<Test>::note(vec![2u8; 10].into()));
+		TransactionStorage::<Test>::unnote(&dropped);
+		assert_eq!(InlineByBlock::<Test>::get(1).into_inner(), vec![dropped.0, kept.0]);
+
+		run_to_block(11, || stored.proof::<Test>());
+		assert!(TransactionStorage::<Test>::stored_transaction(1, TxIndex(0)).is_some());
+		assert!(InlineData::<Test>::contains_key(dropped.0));
+		run_to_block(12, || stored.proof::<Test>());
+		assert!(TransactionStorage::<Test>::stored_transaction(1, TxIndex(0)).is_none());
+		assert!(!InlineData::<Test>::contains_key(dropped.0));
+		// Preimages still requested are carried over to the current block
+		assert_eq!(TransactionStorage::<Test>::fetch(&kept, None).unwrap(), vec![2u8; 10]);
+		assert!(InlineByBlock::<Test>::get(1).is_empty());
+		assert_eq!(InlineByBlock::<Test>::get(12).into_inner(), vec![kept.0]);
+
+		TransactionStorage::<Test>::unnote(&kept);
+		TransactionStorage::<Test>::unnote(&kept);
+		run_to_block(23, || None);
+		assert!(!InlineData::<Test>::contains_key(kept.0));
+		assert!(InlineByBlock::<Test>::get(12).is_empty());
+	});
+}
+
+#[test]
+fn data_stored_off_chain_cannot_be_fetched() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			1,
+			1000,
+			None
+		));
+		let data = vec![0u8; 1000];
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			data.clone(),
+			None
+		));
+		let hash = blake2_256(&data).into();
+		assert_eq!(TransactionStorage::<Test>::len(&hash), None);
+		assert_eq!(TransactionStorage::<Test>::fetch(&hash, None), Err(DispatchError::Unavailable));
+		// Nor can more than `MAX_INLINE_DATA_SIZE` bytes be noted
+		let data = vec![0u8; MAX_INLINE_DATA_SIZE as usize + 1];
+		assert_noop!(
+			TransactionStorage::<Test>::note(data.into()),
+			Error::<Test>::TransactionTooLarge
+		);
+	});
+}
+
+#[test]
+fn pallets_store_small_data_inline() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let statement = vec![1u8; 100];
+		assert_ok!(Attestation::attest_hash(RuntimeOrigin::signed(caller), statement.clone()));
+		let attestation = (caller, 1u64, blake2_256(&statement)).encode();
+		let hash = blake2_256(&attestation).into();
+		// No authorization is needed and nothing is indexed
+		assert_eq!(TransactionStorage::<Test>::fetch(&hash, None).unwrap(), attestation);
+		assert!(!TransactionStorage::<Test>::is_requested(&hash));
+		assert_eq!(BlockTransactions::<Test>::count(), 0);
+
+		// Small data is kept inline whatever the context
+		System::note_finished_extrinsics();
+		assert_ok!(TransactionStorage::<Test>::store_internal(None, vec![2u8; 10]));
+		assert!(InlineData::<Test>::contains_key(blake2_256(&[2u8; 10])));
+
+		// Like stored data, it is kept for the storage period
+		run_to_block(11, || None);
+		assert!(InlineData::<Test>::contains_key(hash.0));
+		run_to_block(12, || None);
+		assert!(!InlineData::<Test>::contains_key(hash.0));
+	});
+}
+
+#[test]
+fn pallets_cannot_own_inline_data() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let statement = vec![1u8; 100];
+		assert_noop!(
+			Attestation::attest(RuntimeOrigin::signed(caller), statement.clone()),
+			Error::<Test>::InlineDataHasNoOwner
+		);
+		assert!(!InlineData::<Test>::contains_key(blake2_256(&statement)));
+		assert_eq!(TransactionStorage::<Test>::owned_content_count(caller), 0);
+		assert_eq!(StorageEvents::get(), vec![]);
+	});
+}
+
+#[test]
+fn pallets_charge_the_weight_of_store_internal() {
+	let attest = |statement: Vec<u8>| {
+		let len = statement.len() as u32;
+		let call = pallet_attestation::Call::<Test>::attest { statement };
+		(call.get_dispatch_info().weight, TransactionStorage::<Test>::store_internal_weight(len))
+	};
+	// Inline data is weighed like a noted preimage, larger data like a store
+	let (weight, inline) = attest(vec![1u8; 100]);
+	assert_eq!(weight, inline);
+	assert_eq!(inline, <() as WeightInfo>::note_preimage(100));
+	let len = MAX_INLINE_DATA_SIZE + 1;
+	let (weight, indexed) = attest(vec![1u8; len as usize]);
+	assert_eq!(weight, indexed);
+	assert_eq!(indexed, <() as WeightInfo>::store(len).saturating_add(StorageEventWeight::get()));
+}
+
+#[test]
+fn pallets_index_large_data_of_extrinsic() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let mut stored = StoredData::default();
+		let statement = vec![1u8; MAX_INLINE_DATA_SIZE as usize + 1];
+		assert_ok!(Attestation::attest(RuntimeOrigin::signed(caller), statement.clone()));
+		stored.insert(statement.clone());
+		let content_hash = blake2_256(&statement);
+		assert!(!InlineData::<Test>::contains_key(content_hash));
+		assert_eq!(
+			StorageEvents::get(),
+			vec![StorageEvent::Stored {
+				content_hash,
+				size: statement.len() as u32,
+				owner: Some(caller),
+				index: TxIndex(0),
+			}]
+		);
+		assert_eq!(TransactionStorage::<Test>::owned_content_count(caller), 1);
+		// No authorization was used
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(caller),
+			AuthorizationExtent { transactions: 0, bytes: 0 }
+		);
+		// Only once per extrinsic
+		assert_noop!(
+			Attestation::attest(RuntimeOrigin::signed(caller), vec![2u8; statement.len()]),
+			Error::<Test>::AlreadyIndexedInExtrinsic
+		);
+
+		// The data can be proven like stored data
+		run_to_block(12, || stored.proof::<Test>());
+		assert!(TransactionStorage::<Test>::stored_transaction(1, TxIndex(0)).is_none());
+	});
+}
+
+#[test]
+fn checks_context_before_authorizations() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let (caller, unauthorized) = (1, 2);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			1,
+			1000,
+			None
+		));
+		// Outside of an extrinsic, the context is reported whatever the authorizations
+		sp_io::storage::clear(sp_core::storage::well_known_keys::EXTRINSIC_INDEX);
+		for who in [caller, unauthorized] {
+			assert_noop!(
+				TransactionStorage::<Test>::store(
+					RawOrigin::Signed(who).into(),
+					vec![0u8; 1000],
+					None
+				),
+				Error::<Test>::BadContext,
+			);
+		}
+		assert_noop!(
+			TransactionStorage::<Test>::renew(
+				RawOrigin::Signed(unauthorized).into(),
+				1,
+				TxIndex(0),
+				None
+			),
+			Error::<Test>::BadContext,
+		);
+		// And so is an extrinsic that already indexed data
+		next_extrinsic();
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			vec![0u8; 1000],
+			None
+		));
+		assert_noop!(
+			TransactionStorage::<Test>::store(
+				RawOrigin::Signed(unauthorized).into(),
+				vec![1u8; 1000],
+				None
+			),
+			Error::<Test>::AlreadyIndexedInExtrinsic,
+		);
+	});
+}
+
+#[test]
+fn dry_run_store_does_not_mutate_state() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let (caller, unauthorized) = (1, 2);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			1,
+			1000,
+			None
+		));
+		// Dry runs need no extrinsic
+		sp_io::storage::clear(sp_core::storage::well_known_keys::EXTRINSIC_INDEX);
+		let root = sp_io::storage::root(sp_runtime::StateVersion::V1);
+		assert_eq!(TransactionStorage::<Test>::dry_run_store(caller, 1000), Ok(()));
+		assert_eq!(
+			TransactionStorage::<Test>::dry_run_store(caller, 1001),
+			Err(StoreRejection::InsufficientBytes)
+		);
+		assert_eq!(
+			TransactionStorage::<Test>::dry_run_store(unauthorized, 1),
+			Err(StoreRejection::NotAuthorized)
+		);
+		assert_eq!(
+			TransactionStorage::<Test>::dry_run_store(caller, 0),
+			Err(StoreRejection::Empty)
+		);
+		assert_eq!(sp_io::storage::root(sp_runtime::StateVersion::V1), root);
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(caller),
+			AuthorizationExtent { transactions: 1, bytes: 1000 }
+		);
+		assert_eq!(IndexedExtrinsic::<Test>::get(), None);
+	});
+}
+
+#[test]
+fn pallets_cannot_index_data_outside_of_extrinsics() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		System::note_finished_extrinsics();
+		let data = vec![1u8; MAX_INLINE_DATA_SIZE as usize + 1];
+		assert_noop!(
+			TransactionStorage::<Test>::store_internal(None, data),
+			Error::<Test>::NotInExtrinsic
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::store_internal(None, vec![]),
+			Error::<Test>::EmptyTransaction
+		);
+	});
+}
+
+#[test]
+fn failed_uploads_bar_account() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		let store = || Call::<Test>::store { data: vec![0u8; 100], namespace: None };
+		let not_authorized = DispatchError::from(Error::<Test>::NotAuthorized);
+		for strikes in 1..3 {
+			assert_eq!(dispatch_tracked(who, store()), Ok(Err(not_authorized)));
+			System::assert_last_event(Event::<Test>::Struck { who, strikes }.into());
+		}
+		assert_eq!(dispatch_tracked(who, store()), Ok(Err(not_authorized)));
+		System::assert_last_event(Event::<Test>::AccountBarred { who, until: 6 }.into());
+		assert_eq!(Strikes::<Test>::get(who), 0);
+		// Barred accounts cannot upload, even when authorized
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			1,
+			100,
+			None
+		));
+		assert_eq!(
+			dispatch_tracked(who, store()),
+			Err(InvalidTransaction::Custom(BARRED_ACCOUNT).into())
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::Signed(who).into(), vec![0u8; 100], None),
+			Error::<Test>::AccountBarred
+		);
+		// Other accounts are not affected
+		assert!(!TransactionStorage::<Test>::is_barred(&2));
+	});
+}
+
+#[test]
+fn account_bar_expires() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		for _ in 0..3 {
+			assert_ok!(dispatch_tracked(
+				who,
+				Call::<Test>::store { data: vec![0u8; 100], namespace: None }
+			));
+		}
+		assert!(TransactionStorage::<Test>::is_barred(&who));
+		run_to_block(6, || None);
+		assert!(!TransactionStorage::<Test>::is_barred(&who));
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			1,
+			100,
+			None
+		));
+		assert_eq!(
+			dispatch_tracked(who, Call::<Test>::store { data: vec![0u8; 100], namespace: None }),
+			Ok(Ok(()))
+		);
+	});
+}
+
+#[test]
+fn authorizer_can_clear_bar() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		let store = || Call::<Test>::store { data: vec![0u8; 100], namespace: None };
+		for _ in 0..3 {
+			assert_ok!(dispatch_tracked(who, store()));
+		}
+		assert!(TransactionStorage::<Test>::is_barred(&who));
+		assert_noop!(
+			TransactionStorage::<Test>::clear_bar(RawOrigin::Signed(2).into(), who),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(TransactionStorage::<Test>::clear_bar(RawOrigin::Root.into(), who));
+		System::assert_last_event(Event::<Test>::BarCleared { who }.into());
+		assert!(!TransactionStorage::<Test>::is_barred(&who));
+		// Strikes are cleared too
+		assert_ok!(dispatch_tracked(who, store()));
+		assert_eq!(Strikes::<Test>::get(who), 1);
+		assert_ok!(TransactionStorage::<Test>::clear_bar(RawOrigin::Root.into(), who));
+		assert_eq!(Strikes::<Test>::get(who), 0);
+		// No strikes are recorded with a zero threshold
+		StrikeThreshold::set(0);
+		assert_ok!(dispatch_tracked(who, store()));
+		assert_eq!(Strikes::<Test>::get(who), 0);
+	});
+}
+
+#[test]
+fn challenged_content_is_proved_in_next_block() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let data = vec![7u8; 2000];
+		let content_hash = blake2_256(&data);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			2,
+			2000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			data.clone(),
+			None
+		));
+		run_to_block(2, || None);
+		assert_ok!(TransactionStorage::<Test>::request_proof(
+			RawOrigin::Signed(caller).into(),
+			content_hash
+		));
+		System::assert_last_event(
+			Event::<Test>::ProofRequested { who: caller, content_hash, at: 3 }.into(),
+		);
+		// A transaction of the authorization is used, with no bytes
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(caller),
+			AuthorizationExtent { transactions: 0, bytes: 0 }
+		);
+		let challenges = TransactionStorage::<Test>::challenges();
+		assert_eq!(challenges.len(), 1);
+		assert_eq!(challenges[0].chunk_count, 8);
+
+		run_to_block(3, || None);
+		let proof = challenge_proof(&data);
+		let mut inherent_data = InherentData::new();
+		inherent_data
+			.put_data(CHALLENGE_INHERENT_IDENTIFIER, &vec![Some(proof.clone())])
+			.unwrap();
+		let call = TransactionStorage::<Test>::create_inherent(&inherent_data).unwrap();
+		let challenge_proofs = BoundedVec::truncate_from(vec![Some(proof)]);
+		assert_eq!(
+			call,
+			Call::check_proofs { proof: None, challenge_proofs: challenge_proofs.clone() }
+		);
+		assert!(TransactionStorage::<Test>::is_inherent(&call));
+		assert_ok!(TransactionStorage::<Test>::check_proofs(
+			RawOrigin::None.into(),
+			None,
+			challenge_proofs
+		));
+		System::assert_last_event(Event::<Test>::ChallengeProved { content_hash }.into());
+		run_to_block(4, || None);
+	});
+}
+
+#[test]
+fn storage_proofs_are_not_pool_transactions() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let data = vec![1u8; 1000];
+		let proof = chunk_proof(&data, ChunkIndex(0)).1;
+		let calls = [
+			RuntimeCall::TransactionStorage(Call::check_proof { proof: proof.clone() }),
+			RuntimeCall::TransactionStorage(Call::check_proofs {
+				proof: Some(proof),
+				challenge_proofs: BoundedVec::new(),
+			}),
+			RuntimeCall::TransactionStorage(Call::store { data, namespace: None }),
+		];
+		for call in calls {
+			// As validated by the pool
+			for source in [TransactionSource::External, TransactionSource::Local] {
+				assert_eq!(
+					<Test as ValidateUnsigned>::validate_unsigned(source, &call),
+					InvalidTransaction::Call.into()
+				);
+			}
+			// Included in a block
+			assert_ok!(<Test as ValidateUnsigned>::pre_dispatch(&call));
+		}
+	});
+}
+
+#[test]
+#[should_panic(expected = "Challenged content must be proved in the block")]
+fn missing_challenge_proof_is_like_missing_storage_proof() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			2,
+			2000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			vec![0u8; 2000],
+			None
+		));
+		run_to_block(2, || None);
+		assert_ok!(TransactionStorage::<Test>::request_proof(
+			RawOrigin::Signed(caller).into(),
+			blake2_256(&[0u8; 2000])
+		));
+		run_to_block(4, || None);
+	});
+}
+
+#[test]
+fn rejects_invalid_challenge_proofs() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let data = vec![7u8; 2000];
+		let content_hash = blake2_256(&data);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			2,
+			2000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			data.clone(),
+			None
+		));
+		run_to_block(2, || None);
+		assert_noop!(
+			TransactionStorage::<Test>::check_proofs(
+				RawOrigin::None.into(),
+				None,
+				BoundedVec::truncate_from(vec![Some(challenge_proof(&data))])
+			),
+			Error::<Test>::UnexpectedProof
+		);
+		assert_ok!(TransactionStorage::<Test>::request_proof(
+			RawOrigin::Signed(caller).into(),
+			content_hash
+		));
+		run_to_block(3, || None);
+		assert_noop!(
+			TransactionStorage::<Test>::check_proofs(
+				RawOrigin::None.into(),
+				None,
+				BoundedVec::truncate_from(vec![Some(challenge_proof(&[8u8; 2000]))])
+			),
+			Error::<Test>::InvalidProof
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::check_proofs(
+				RawOrigin::None.into(),
+				None,
+				BoundedVec::truncate_from(vec![])
+			),
+			Error::<Test>::InvalidProof
+		);
+		// Proving a challenge does not replace the storage proof
+		assert_noop!(
+			TransactionStorage::<Test>::check_proof(RawOrigin::None.into(), challenge_proof(&data)),
+			Error::<Test>::UnexpectedProof
+		);
+	});
+}
+
+#[test]
+fn proof_requests_are_bounded() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		for caller in 1..=3 {
+			assert_ok!(TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				caller,
+				3,
+				2000,
+				None
+			));
+		}
+		let data = vec![0u8; 2000];
+		let content_hash = blake2_256(&data);
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::Signed(1).into(), data, None));
+		run_to_block(2, || None);
+		let request_proof = |caller| {
+			TransactionStorage::<Test>::request_proof(
+				RawOrigin::Signed(caller).into(),
+				content_hash,
+			)
+		};
+		assert_noop!(
+			TransactionStorage::<Test>::request_proof(
+				RawOrigin::Signed(1).into(),
+				blake2_256(&[1u8; 2000])
+			),
+			Error::<Test>::ChallengedNotFound
+		);
+		assert_noop!(request_proof(4), Error::<Test>::NotAuthorized);
+		assert_ok!(request_proof(1));
+		assert_noop!(request_proof(1), Error::<Test>::ChallengeAlreadyRequested);
+		assert_ok!(request_proof(2));
+		assert_noop!(request_proof(3), Error::<Test>::TooManyChallenges);
+	});
+}
+
+#[test]
+fn proof_requests_do_not_use_preimage_authorizations() {
+	new_test_ext().execute_with(|| {
+		// As configured in the runtime
+		PreimagePolicy::set(PreimageStorePolicy::Strict);
+		AuthorizationOrder::set(ConsumptionOrder::PreimageFirst);
+		run_to_block(1, || None);
+		let (authorizer, owner, challenger) = (1, 2, 3);
+		let data = vec![0u8; 100];
+		let content_hash = blake2_256(&data);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			owner,
+			1,
+			100,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::Signed(owner).into(), data, None));
+		run_to_block(2, || None);
+		// Another account's grant for the content, with a deposit
+		Balances::make_free_balance_be(&authorizer, 1000);
+		assert_ok!(TransactionStorage::<Test>::add_authorizer(RawOrigin::Root.into(), authorizer));
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Signed(authorizer).into(),
+			content_hash,
+			100,
+			false
+		));
+		let preimage_extent = AuthorizationExtent { transactions: 1, bytes: 100 };
+		assert_noop!(
+			TransactionStorage::<Test>::request_proof(
+				RawOrigin::Signed(challenger).into(),
+				content_hash
+			),
+			Error::<Test>::NotAuthorized
+		);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			challenger,
+			1,
+			0,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::request_proof(
+			RawOrigin::Signed(challenger).into(),
+			content_hash
+		));
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(challenger),
+			AuthorizationExtent { transactions: 0, bytes: 0 }
+		);
+		assert_eq!(
+			TransactionStorage::<Test>::unused_preimage_authorization_extent(content_hash),
+			preimage_extent
+		);
+		assert!(PreimageDeposits::<Test>::contains_key(content_hash));
+		assert_eq!(Balances::free_balance(authorizer), 890);
+		assert!(!SatisfiedPreimages::<Test>::contains_key(content_hash));
+	});
+}
+
+#[test]
+fn only_live_content_can_be_challenged() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			4,
+			4000,
+			None
+		));
+		let data = vec![0u8; 2000];
+		let content_hash = blake2_256(&data);
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			data.clone(),
+			None
+		));
+		let request_proof = || {
+			TransactionStorage::<Test>::request_proof(
+				RawOrigin::Signed(caller).into(),
+				content_hash,
+			)
+		};
+		// The content must be kept until the storage proof of block 1, in block 11
+		run_to_block(10, || None);
+		assert_ok!(request_proof());
+		assert_eq!(TransactionStorage::<Test>::challenges()[0].block, 1);
+		run_to_block(11, || None);
+		let proof = chunk_proof(&data, TransactionStorage::<Test>::proof_target().unwrap().2).1;
+		assert_ok!(TransactionStorage::<Test>::check_proofs(
+			RawOrigin::None.into(),
+			Some(proof),
+			BoundedVec::truncate_from(vec![Some(challenge_proof(&data))])
+		));
+		assert_noop!(request_proof(), Error::<Test>::ChallengedNotFound);
+		run_to_block(12, || None);
+		assert_noop!(request_proof(), Error::<Test>::ChallengedNotFound);
+
+		// Removed content is not challenged
+		let data = vec![1u8; 1000];
+		let content_hash = blake2_256(&data);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			2,
+			1000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::Signed(caller).into(), data, None));
+		assert_ok!(TransactionStorage::<Test>::force_remove(
+			RawOrigin::Root.into(),
+			content_hash,
+			RemovalReason::Takedown
+		));
+		assert_noop!(
+			TransactionStorage::<Test>::request_proof(
+				RawOrigin::Signed(caller).into(),
+				content_hash
+			),
+			Error::<Test>::ContentRemoved
+		);
+	});
+}
+
+#[test]
+fn challenges_of_missing_content_are_unprovable() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let data = vec![7u8; 2000];
+		let content_hash = blake2_256(&data);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			2,
+			2000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::Signed(caller).into(), data, None));
+		run_to_block(2, || None);
+		assert_ok!(TransactionStorage::<Test>::request_proof(
+			RawOrigin::Signed(caller).into(),
+			content_hash
+		));
+		run_to_block(3, || None);
+		let mut inherent_data = InherentData::new();
+		inherent_data
+			.put_data(CHALLENGE_INHERENT_IDENTIFIER, &vec![None::<TransactionStorageProof>])
+			.unwrap();
+		let call = TransactionStorage::<Test>::create_inherent(&inherent_data).unwrap();
+		let challenge_proofs = BoundedVec::truncate_from(vec![None]);
+		assert_eq!(
+			call,
+			Call::check_proofs { proof: None, challenge_proofs: challenge_proofs.clone() }
+		);
+		assert_ok!(TransactionStorage::<Test>::check_inherent(&call, &inherent_data));
+		assert_ok!(TransactionStorage::<Test>::check_proofs(
+			RawOrigin::None.into(),
+			None,
+			challenge_proofs
+		));
+		System::assert_last_event(Event::<Test>::ChallengeUnprovable { content_hash }.into());
+		run_to_block(4, || None);
+	});
+}
+
+#[test]
+fn verify_content_reindexes_matching_data() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let data = vec![7u8; 2000];
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			2,
+			4000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			data.clone(),
+			None
+		));
+		run_to_block(2, || None);
+		assert_ok!(TransactionStorage::<Test>::verify_content(
+			RawOrigin::Signed(caller).into(),
+			1,
+			TxIndex(0),
+			data.clone()
+		));
+		System::assert_has_event(
+			Event::<Test>::Renewed { index: TxIndex(0), chunk_root: chunk_root(&data) }.into(),
+		);
+		System::assert_last_event(
+			Event::<Test>::ContentVerified { block: 1, index: TxIndex(0) }.into(),
+		);
+		// Verification uses the authorization like a store
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(caller),
+			AuthorizationExtent { transactions: 0, bytes: 0 }
+		);
+		// The data is indexed by this extrinsic and recorded as a transaction of the block
+		assert_eq!(IndexedExtrinsic::<Test>::get(), System::extrinsic_index());
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::Signed(caller).into(), data.clone(), None),
+			Error::<Test>::AlreadyIndexedInExtrinsic
+		);
+		assert_eq!(BlockTransactions::<Test>::count(), 1);
+		assert_eq!(ChunkCount::<Test>::get(2), 0);
+		run_to_block(3, || None);
+		assert_eq!(ChunkCount::<Test>::get(2), num_chunks(2000));
+		assert_eq!(ChallengeCounts::<Test>::get(blake2_256(&data)), Some((0, 2)));
+	});
+}
+
+#[test]
+fn verify_content_is_challenged_like_the_node_indexes_it() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let verified = vec![7u8; 5 * CHUNK_SIZE];
+		let stored = vec![8u8; 3 * CHUNK_SIZE - 1];
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			3,
+			16 * CHUNK_SIZE as u64,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			verified.clone(),
+			None
+		));
+		run_to_block(2, || None);
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			stored.clone(),
+			None
+		));
+		next_extrinsic();
+		assert_ok!(TransactionStorage::<Test>::verify_content(
+			RawOrigin::Signed(caller).into(),
+			1,
+			TxIndex(0),
+			verified.clone()
+		));
+		run_to_block(3, || None);
+
+		// The node selects the challenged chunk among all the data indexed by the extrinsics of
+		// the block, in extrinsic order
+		let body = vec![stored, verified.clone()];
+		let node_select_chunk = |random_hash: &[u8]| {
+			let total_chunks: u32 = body.iter().map(|data| num_chunks(data.len() as u32)).sum();
+			let mut selected = random_chunk(random_hash, total_chunks);
+			for (index, data) in body.iter().enumerate() {
+				let chunks = num_chunks(data.len() as u32);
+				if selected < chunks {
+					return (TxIndex(index as u32), ChunkIndex(selected))
+				}
+				selected -= chunks;
+			}
+			unreachable!("the selected chunk is within the block")
+		};
+		let chunk_ends = ChunkEnds::<Test>::get(2).unwrap();
+		assert_eq!(chunk_ends.into_inner(), vec![3, 8]);
+		for seed in 0u8..64 {
+			let random_hash = blake2_256(&[seed]);
+			assert_eq!(
+				select_chunk(&random_hash, &chunk_ends),
+				Some(node_select_chunk(&random_hash))
+			);
+		}
+
+		// A proof built from the indexed body the way the node does is accepted
+		let mut stored_data = StoredData::default();
+		stored_data.insert(verified);
+		run_to_block(12, || stored_data.proof::<Test>());
+		let parent_hash = System::parent_hash();
+		let (index, chunk_index) = node_select_chunk(parent_hash.as_ref());
+		let proof = chunk_proof(&body[index.0 as usize], chunk_index).1;
+		assert_ok!(TransactionStorage::<Test>::check_proof(RuntimeOrigin::none(), proof));
+	});
+}
+
+#[test]
+fn verify_content_rejects_mismatching_data() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let data = vec![7u8; 2000];
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			2,
+			4000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			data.clone(),
+			None
+		));
+		run_to_block(2, || None);
+		let verify = |block, index, data| {
+			TransactionStorage::<Test>::verify_content(
+				RawOrigin::Signed(caller).into(),
+				block,
+				TxIndex(index),
+				data,
+			)
+		};
+		let mut corrupted = data.clone();
+		corrupted[1000] = 0;
+		assert_noop!(verify(1, 0, corrupted), Error::<Test>::VerificationFailed);
+		assert_noop!(verify(1, 0, data[..1999].to_vec()), Error::<Test>::VerificationFailed);
+		assert_noop!(verify(1, 1, data.clone()), Error::<Test>::VerificationFailed);
+		assert_noop!(verify(2, 0, data), Error::<Test>::VerificationFailed);
+		assert_eq!(IndexedExtrinsic::<Test>::get(), None);
+	});
+}
+
+#[test]
+fn authorization_mutations_emit_events() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let data = vec![1u8; 1000];
+		let content_hash = blake2_256(&data);
+		let restricted_data = vec![2u8; 500];
+		let allowed = BoundedVec::truncate_from(vec![blake2_256(&restricted_data)]);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			1,
+			2,
+			2000,
+			None
+		));
+		System::assert_last_event(
+			Event::<Test>::Authorized {
+				scope: AuthorizationScope::Account(1),
+				extent: AuthorizationExtent { transactions: 2, bytes: 2000 },
+			}
+			.into(),
+		);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			2,
+			1,
+			1000,
+			Some(allowed)
+		));
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			content_hash,
+			1000,
+			false
+		));
+		assert_authorization_events_match_storage();
+
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(1).into(),
+			vec![0u8; 300],
+			None
+		));
+		System::assert_has_event(
+			Event::<Test>::AuthorizationUsed {
+				scope: AuthorizationScope::Account(1),
+				extent: AuthorizationExtent { transactions: 1, bytes: 300 },
+			}
+			.into(),
+		);
+		next_extrinsic();
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::None.into(), data, None));
+		next_extrinsic();
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(2).into(),
+			restricted_data,
+			None
+		));
+		assert_authorization_events_match_storage();
+
+		// Authorizations expire after `AuthorizationPeriod`
+		run_to_block(11, || None);
+		System::assert_has_event(
+			Event::<Test>::AuthorizationExpired {
+				scope: AuthorizationScope::Account(1),
+				extent: AuthorizationExtent { transactions: 1, bytes: 1700 },
+				authorized: AuthorizationExtent { transactions: 2, bytes: 2000 },
+			}
+			.into(),
+		);
+		assert_authorization_events_match_storage();
+		assert_eq!(AuthorizationUsageByScope::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn each_expired_authorization_emits_an_event() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		let scope = AuthorizationScope::Account(who);
+		let extent = |transactions, bytes| AuthorizationExtent { transactions, bytes };
+		for bytes in [100, 200] {
+			assert_ok!(TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				who,
+				1,
+				bytes,
+				None
+			));
+		}
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			vec![0u8; 150],
+			None
+		));
+		run_to_block(11, || None);
+		// The used extent is attributed to the first authorization, and the 50 bytes exceeding it
+		// to the second
+		let expired: Vec<_> = System::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				RuntimeEvent::TransactionStorage(event @ Event::AuthorizationExpired { .. }) =>
+					Some(event),
+				_ => None,
+			})
+			.collect();
+		assert_eq!(
+			expired,
+			vec![
+				Event::AuthorizationExpired {
+					scope: scope.clone(),
+					extent: extent(0, 0),
+					authorized: extent(1, 100),
+				},
+				Event::AuthorizationExpired {
+					scope,
+					extent: extent(1, 150),
+					authorized: extent(1, 200)
+				},
+			]
+		);
+		assert_authorization_events_match_storage();
+	});
+}
+
+#[test]
+fn compressed_content_is_found_by_either_hash() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let compressed = vec![1u8; 700];
+		let content_hash = blake2_256(&compressed);
+		let uncompressed_hash = blake2_256(&[1u8; 5000]);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			3,
+			2000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store_compressed(
+			RawOrigin::Signed(caller).into(),
+			compressed,
+			uncompressed_hash,
+			5000
+		));
+		System::assert_last_event(
+			Event::<Test>::StoredCompressed {
+				index: TxIndex(0),
+				uncompressed_hash,
+				uncompressed_len: 5000,
+			}
+			.into(),
+		);
+		// Authorization is used by the compressed size
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(caller),
+			AuthorizationExtent { transactions: 2, bytes: 1300 }
+		);
+		let info = CompressionInfo { uncompressed_hash, uncompressed_len: 5000, block: 1 };
+		let expected = Some((content_hash, info));
+		assert_eq!(TransactionStorage::<Test>::compression_info(content_hash), expected);
+		assert_eq!(TransactionStorage::<Test>::compression_info(uncompressed_hash), expected);
+		assert_eq!(TransactionStorage::<Test>::compression_info([0u8; 32]), None);
+	});
+}
+
+#[test]
+fn compressed_content_is_pruned_with_its_last_renewal() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let (a, b) = (vec![1u8; 700], vec![2u8; 600]);
+		let (a_hash, b_hash) = (blake2_256(&a), blake2_256(&b));
+		let uncompressed_hash = blake2_256(&[1u8; 5000]);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			3,
+			2000,
+			None
+		));
+		let compression_info = TransactionStorage::<Test>::compression_info;
+		let store = |data: &Vec<u8>| {
+			assert_ok!(TransactionStorage::<Test>::store_compressed(
+				RawOrigin::Signed(caller).into(),
+				data.clone(),
+				uncompressed_hash,
+				5000
+			));
+		};
+		store(&a);
+		run_to_block(2, || None);
+		// The most recently stored compressed form is found by the uncompressed hash
+		store(&b);
+		assert_eq!(compression_info(uncompressed_hash).unwrap().0, b_hash);
+		assert_eq!(compression_info(a_hash).unwrap().0, a_hash);
+		run_to_block(3, || None);
+		assert_ok!(TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(caller).into(),
+			1,
+			TxIndex(0),
+			None
+		));
+		assert_eq!(compression_info(a_hash).unwrap().1.block, 3);
+
+		let proof_provider = || {
+			let target = <frame_system::Pallet<Test>>::block_number().checked_sub(10)?;
+			let parent_hash = <frame_system::Pallet<Test>>::parent_hash();
+			let data = match target {
+				2 => b.clone(),
+				_ => a.clone(),
+			};
+			(1..=3)
+				.contains(&target)
+				.then(|| build_proof(parent_hash.as_ref(), vec![data]).unwrap())
+		};
+		// Renewed content outlives its original storage period
+		run_to_block(12, proof_provider);
+		assert!(compression_info(a_hash).is_some());
+		assert_eq!(compression_info(uncompressed_hash).unwrap().0, b_hash);
+		run_to_block(13, proof_provider);
+		assert_eq!(compression_info(b_hash), None);
+		assert_eq!(compression_info(uncompressed_hash), None);
+		assert!(compression_info(a_hash).is_some());
+		run_to_block(14, proof_provider);
+		assert_eq!(compression_info(a_hash), None);
+		assert_eq!(CompressedByBlock::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn namespaced_content_is_listed() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let (app, other_app) = (*b"app\0\0\0\0\0", *b"other\0\0\0");
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			5,
+			5000,
+			None
+		));
+		let store = |data: Vec<u8>, namespace| {
+			TransactionStorage::<Test>::store(RawOrigin::Signed(caller).into(), data, namespace)
+		};
+		assert_ok!(store(vec![1u8; 100], Some(app)));
+		System::assert_last_event(
+			Event::<Test>::StoredInNamespace { index: TxIndex(0), namespace: app }.into(),
+		);
+		next_extrinsic();
+		assert_ok!(store(vec![2u8; 100], Some(app)));
+		next_extrinsic();
+		assert_ok!(store(vec![3u8; 100], Some(other_app)));
+		next_extrinsic();
+		assert_ok!(store(vec![4u8; 100], None));
+		next_extrinsic();
+
+		let mut listed = vec![blake2_256(&[1u8; 100]), blake2_256(&[2u8; 100])];
+		listed.sort();
+		let namespace_content = TransactionStorage::<Test>::namespace_content;
+		assert_eq!(namespace_content(app, None, 10), listed);
+		assert_eq!(namespace_content(other_app, None, 10), vec![blake2_256(&[3u8; 100])]);
+		// Pagination
+		assert_eq!(namespace_content(app, None, 1), vec![listed[0]]);
+		assert_eq!(namespace_content(app, Some(listed[0]), 10), vec![listed[1]]);
+		assert_eq!(namespace_content(app, Some(listed[1]), 10), vec![]);
+
+		// Live entries of a namespace are bounded
+		assert_noop!(store(vec![5u8; 100], Some(app)), Error::<Test>::NamespaceFull);
+		// Content is listed under a single namespace
+		assert_noop!(store(vec![1u8; 100], Some(other_app)), Error::<Test>::NamespaceMismatch);
+		assert_ok!(store(vec![1u8; 100], Some(app)));
+		assert_eq!(NamespaceEntries::<Test>::get(app), 2);
+	});
+}
+
+#[test]
+fn namespaced_content_is_pruned_with_its_last_renewal() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let namespace = *b"app\0\0\0\0\0";
+		let (a, b) = (vec![1u8; 700], vec![2u8; 600]);
+		let (a_hash, b_hash) = (blake2_256(&a), blake2_256(&b));
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			3,
+			2000,
+			None
+		));
+		let store = |data: &Vec<u8>| {
+			assert_ok!(TransactionStorage::<Test>::store(
+				RawOrigin::Signed(caller).into(),
+				data.clone(),
+				Some(namespace)
+			));
+		};
+		let listed = || {
+			let mut hashes = vec![];
+			for hash in [a_hash, b_hash] {
+				if ContentByNamespace::<Test>::contains_key(namespace, hash) {
+					hashes.push(hash);
+				}
+			}
+			hashes
+		};
+		store(&a);
+		run_to_block(2, || None);
+		store(&b);
+		run_to_block(3, || None);
+		assert_ok!(TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(caller).into(),
+			1,
+			TxIndex(0),
+			None
+		));
+		assert_eq!(ContentNamespaces::<Test>::get(a_hash), Some((namespace, 3)));
+
+		let mut stored = StoredData::default();
+		stored.insert(a.clone());
+		stored.insert(b.clone());
+		let proof_provider = || stored.proof::<Test>();
+		// Renewed content outlives its original storage period
+		run_to_block(12, proof_provider);
+		assert_eq!(listed(), vec![a_hash, b_hash]);
+		run_to_block(13, proof_provider);
+		assert_eq!(listed(), vec![a_hash]);
+		assert_eq!(NamespaceEntries::<Test>::get(namespace), 1);
+		run_to_block(14, proof_provider);
+		assert_eq!(listed(), vec![]);
+		assert!(!NamespaceEntries::<Test>::contains_key(namespace));
+		assert_eq!(ContentNamespaces::<Test>::iter().count(), 0);
+		assert_eq!(NamespacedByBlock::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn counts_proof_checks_over_the_lifetime_of_content() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let data = vec![1u8; 2000];
+		let content_hash = blake2_256(&data);
+		let authorize = || {
+			assert_ok!(TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				caller,
+				2,
+				4000,
+				None
+			));
+		};
+		let renew = |block| {
+			assert_ok!(TransactionStorage::<Test>::renew(
+				RawOrigin::Signed(caller).into(),
+				block,
+				TxIndex(0),
+				None
+			));
+		};
+		authorize();
+		assert_eq!(TransactionStorage::<Test>::times_challenged(content_hash), None);
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			data.clone(),
+			None
+		));
+		assert_eq!(TransactionStorage::<Test>::times_challenged(content_hash), Some(0));
+
+		let mut stored = StoredData::default();
+		stored.insert(data);
+		let proof_provider = || stored.proof::<Test>();
+		// Each store or renewal is proven at the end of its storage period
+		run_to_block(5, proof_provider);
+		renew(1);
+		run_to_block(12, proof_provider);
+		assert_eq!(TransactionStorage::<Test>::times_challenged(content_hash), Some(1));
+		run_to_block(14, proof_provider);
+		authorize();
+		renew(5);
+		run_to_block(16, proof_provider);
+		assert_eq!(TransactionStorage::<Test>::times_challenged(content_hash), Some(2));
+		run_to_block(24, proof_provider);
+		assert_ok!(TransactionStorage::<Test>::check_proof(
+			RuntimeOrigin::none(),
+			proof_provider().unwrap()
+		));
+		assert_eq!(ChallengeCounts::<Test>::get(content_hash), Some((3, 14)));
+		// The count is pruned with the last renewal
+		run_to_block(25, || None);
+		assert_eq!(TransactionStorage::<Test>::times_challenged(content_hash), None);
+		assert_eq!(ChallengeCountsByBlock::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn lists_renewal_deadlines_of_stored_content() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let (data, other) = (vec![1u8; 1000], vec![2u8; 1000]);
+		let (content_hash, other_hash) = (blake2_256(&data), blake2_256(&other));
+		let deadlines = || {
+			TransactionStorage::<Test>::renewal_deadlines(vec![
+				content_hash,
+				other_hash,
+				blake2_256(b"unknown"),
+			])
+		};
+		let deadline = |block, index, last_block, recommended_block| {
+			Some(RenewalDeadline { block, index: TxIndex(index), last_block, recommended_block })
+		};
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			3,
+			3000,
+			None
+		));
+		for data in [&data, &other] {
+			assert_ok!(TransactionStorage::<Test>::store(
+				RawOrigin::Signed(caller).into(),
+				data.clone(),
+				None
+			));
+		}
+		let mut stored = StoredData::default();
+		stored.insert(data);
+		stored.insert(other);
+		let proof_provider = || stored.proof::<Test>();
+		run_to_block(2, proof_provider);
+		// Renewing is recommended `RenewalLeadTime` blocks before the end of the storage period
+		assert_eq!(deadlines(), vec![deadline(1, 0, 11, 8), deadline(1, 1, 11, 8), None]);
+
+		run_to_block(5, proof_provider);
+		assert_ok!(TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(caller).into(),
+			1,
+			TxIndex(0),
+			None
+		));
+		run_to_block(6, proof_provider);
+		assert_eq!(deadlines(), vec![deadline(5, 0, 15, 12), deadline(1, 1, 11, 8), None]);
+
+		// Content not renewed can still be renewed in the last block of its storage period
+		run_to_block(11, proof_provider);
+		assert_eq!(deadlines()[1], deadline(1, 1, 11, 8));
+		run_to_block(12, proof_provider);
+		assert_eq!(deadlines(), vec![deadline(5, 0, 15, 12), None, None]);
+
+		RemovedContent::<Test>::insert(content_hash, ());
+		assert_eq!(deadlines(), vec![None, None, None]);
+	});
+}
+
+#[test]
+fn keeps_the_usage_of_the_last_authorization_periods() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let data = vec![1u8; 2000];
+		let authorize = || {
+			assert_ok!(TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				caller,
+				2,
+				4000,
+				None
+			));
+		};
+		let renew = |block| {
+			assert_ok!(TransactionStorage::<Test>::renew(
+				RawOrigin::Signed(caller).into(),
+				block,
+				TxIndex(0),
+				None
+			));
+		};
+		let usage = |period, transactions, bytes, renewals| PeriodUsage {
+			period,
+			transactions,
+			bytes,
+			renewals,
+		};
+		authorize();
+		assert_eq!(TransactionStorage::<Test>::account_usage_history(caller), vec![]);
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(caller).into(),
+			data.clone(),
+			None
+		));
+
+		let mut stored = StoredData::default();
+		stored.insert(data);
+		let proof_provider = || stored.proof::<Test>();
+		run_to_block(5, proof_provider);
+		renew(1);
+		assert_eq!(
+			TransactionStorage::<Test>::account_usage_history(caller),
+			vec![usage(0, 2, 4000, 1)]
+		);
+		// Failed uses are not recorded
+		next_extrinsic();
+		assert_noop!(
+			TransactionStorage::<Test>::renew(
+				RawOrigin::Signed(caller).into(),
+				1,
+				TxIndex(0),
+				None
+			),
+			Error::<Test>::NotAuthorized,
+		);
+
+		run_to_block(12, proof_provider);
+		authorize();
+		renew(5);
+		run_to_block(21, proof_provider);
+		authorize();
+		renew(12);
+		assert_eq!(
+			TransactionStorage::<Test>::account_usage_history(caller),
+			vec![usage(0, 2, 4000, 1), usage(1, 1, 2000, 1), usage(2, 1, 2000, 1)]
+		);
+
+		// The oldest period is dropped once `MaxUsageHistory` periods are kept
+		run_to_block(30, proof_provider);
+		authorize();
+		renew(21);
+		assert_eq!(
+			TransactionStorage::<Test>::account_usage_history(caller),
+			vec![usage(1, 1, 2000, 1), usage(2, 1, 2000, 1), usage(3, 1, 2000, 1)]
+		);
+		assert_eq!(TransactionStorage::<Test>::account_usage_history(2), vec![]);
+	});
+}
+
+#[test]
+fn rejects_duplicates_of_live_content_in_strict_mode() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let data = vec![1u8; 2000];
+		let store = || {
+			TransactionStorage::<Test>::store(RawOrigin::Signed(caller).into(), data.clone(), None)
+		};
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			4,
+			8000,
+			None
+		));
+		// Duplicates are stored again unless strict
+		assert_ok!(store());
+		next_extrinsic();
+		assert_ok!(store());
+		StrictDedup::set(true);
+		next_extrinsic();
+		assert_noop!(store(), Error::<Test>::DuplicateContent);
+
+		let mut stored = StoredData::default();
+		stored.insert(data.clone());
+		let proof_provider = || stored.proof::<Test>();
+		run_to_block(2, proof_provider);
+		assert_ok!(TransactionStorage::<Test>::do_try_state());
+		assert_noop!(store(), Error::<Test>::DuplicateContent);
+		// Renewals are the only way to extend live content
+		assert_ok!(TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(caller).into(),
+			1,
+			TxIndex(0),
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::do_try_state());
+		run_to_block(12, proof_provider);
+		next_extrinsic();
+		assert_noop!(store(), Error::<Test>::DuplicateContent);
+
+		// Content can be stored again once expired
+		run_to_block(13, proof_provider);
+		assert_eq!(TransactionStorage::<Test>::times_challenged(blake2_256(&data)), None);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			1,
+			2000,
+			None
+		));
+		assert_ok!(store());
+		assert_ok!(TransactionStorage::<Test>::do_try_state());
+
+		// Stored content must be indexed at the block it was last stored in
+		ChallengeCounts::<Test>::insert(blake2_256(&data), (0, 14));
+		assert!(TransactionStorage::<Test>::do_try_state().is_err());
+		ChallengeCounts::<Test>::remove(blake2_256(&data));
+		assert!(TransactionStorage::<Test>::do_try_state().is_err());
+	});
+}
+
+#[test]
+fn authorizer_stores_get_in_when_other_stores_fill_the_block() {
+	new_test_ext().execute_with(|| {
+		MaxBlockTransactions::set(4);
+		AuthorizerStoresPerBlock::set(2);
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			6,
+			6000,
+			None
+		));
+		let store = |i: u8| {
+			next_extrinsic();
+			TransactionStorage::<Test>::store(RawOrigin::Signed(caller).into(), vec![i; 100], None)
+		};
+		let store_via_authorizer = |origin: RawOrigin<u64>, i: u8| {
+			next_extrinsic();
+			TransactionStorage::<Test>::store_via_authorizer(origin.into(), vec![i; 100])
+		};
+		let info = Call::<Test>::store_via_authorizer { data: vec![0; 100] }.get_dispatch_info();
+		assert_eq!((info.class, info.pays_fee), (DispatchClass::Operational, Pays::No));
+
+		// Other stores leave room for the allowed stores by the authorizer
+		assert_ok!(store(0));
+		assert_ok!(store(1));
+		assert_noop!(store(2), Error::<Test>::TooManyTransactions);
+		assert_noop!(store_via_authorizer(RawOrigin::Signed(caller), 2), DispatchError::BadOrigin);
+		assert_ok!(store_via_authorizer(RawOrigin::Root, 2));
+		System::assert_last_event(Event::<Test>::StoredByAuthorizer { index: TxIndex(2) }.into());
+		assert_noop!(store(3), Error::<Test>::TooManyTransactions);
+		assert_ok!(store_via_authorizer(RawOrigin::Root, 3));
+		assert_noop!(
+			store_via_authorizer(RawOrigin::Root, 4),
+			Error::<Test>::TooManyAuthorizerStores
+		);
+		assert_eq!(BlockTransactions::<Test>::count(), 4);
+		// The authorizer's stores don't use the caller's authorizations
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(caller).transactions,
+			4
+		);
+
+		// The allowance is reset in the next block
+		run_to_block(2, || None);
+		assert!(!AuthorizerStores::<Test>::exists());
+		assert_ok!(store_via_authorizer(RawOrigin::Root, 4));
+		assert_ok!(store(5));
+		assert_ok!(store(6));
+		assert_noop!(store(7), Error::<Test>::TooManyTransactions);
+	});
+}
+
+#[test]
+fn recent_authorizations_overwrite_the_oldest_grant() {
+	new_test_ext().execute_with(|| {
+		let grant = |who: u64, block: u64| {
+			run_to_block(block, || None);
+			assert_ok!(TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				who,
+				1,
+				who * 1000,
+				None
+			));
+		};
+		let recent = |who: u64, block: u64| {
+			let extent = AuthorizationExtent { transactions: 1, bytes: who * 1000 };
+			(AuthorizationScope::Account(who), extent, block)
+		};
+		grant(1, 1);
+		grant(2, 2);
+		let preimage = blake2_256(&[1]);
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			preimage,
+			1,
+			false
+		));
+		let preimage_grant = (
+			AuthorizationScope::Preimage(preimage),
+			AuthorizationExtent { transactions: 1, bytes: 1 },
+			2,
+		);
+		assert_eq!(
+			TransactionStorage::<Test>::recent_authorizations(),
+			vec![recent(1, 1), recent(2, 2), preimage_grant.clone()]
+		);
+
+		// Once full, the oldest grant is overwritten
+		grant(3, 3);
+		assert_eq!(
+			RecentAuthorizations::<Test>::get().into_inner(),
+			vec![recent(3, 3), recent(2, 2), preimage_grant.clone()]
+		);
+		assert_eq!(
+			TransactionStorage::<Test>::recent_authorizations(),
+			vec![recent(2, 2), preimage_grant.clone(), recent(3, 3)]
+		);
+		grant(4, 4);
+		grant(5, 5);
+		assert_eq!(
+			TransactionStorage::<Test>::recent_authorizations(),
+			vec![recent(3, 3), recent(4, 4), recent(5, 5)]
+		);
+		grant(6, 6);
+		assert_eq!(
+			TransactionStorage::<Test>::recent_authorizations(),
+			vec![recent(4, 4), recent(5, 5), recent(6, 6)]
+		);
+
+		// None are recorded when none are kept
+		MaxRecentAuthorizations::set(0);
+		grant(7, 7);
+		MaxRecentAuthorizations::set(3);
+		assert_eq!(
+			TransactionStorage::<Test>::recent_authorizations(),
+			vec![recent(4, 4), recent(5, 5), recent(6, 6)]
+		);
+	});
+}
+
+#[test]
+fn recent_authorizations_can_be_proven() {
+	use sp_trie::{read_trie_value, LayoutV1};
+
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		run_to_block(1, || None);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			1,
+			2,
+			2000,
+			None
+		));
+	});
+	ext.commit_all().unwrap();
+	let root = *ext.backend.root();
+	let (key, proof) = ext.execute_and_prove(|| {
+		RecentAuthorizations::<Test>::get();
+		RecentAuthorizations::<Test>::hashed_key()
+	});
+	assert_eq!(
+		key.to_vec(),
+		[sp_core::twox_128(b"TransactionStorage"), sp_core::twox_128(b"RecentAuthorizations")]
+			.concat()
+	);
+
+	// The proof of the documented key alone reveals the recent grants
+	let db = proof.into_memory_db::<BlakeTwo256>();
+	let value = read_trie_value::<LayoutV1<BlakeTwo256>, _>(&db, &root, &key, None, None)
+		.unwrap()
+		.unwrap();
+	let grants = Vec::<AuthorizationGrant<u64, u64>>::decode(&mut &value[..]).unwrap();
+	let extent = AuthorizationExtent { transactions: 2, bytes: 2000 };
+	assert_eq!(grants, vec![(AuthorizationScope::Account(1), extent, 1)]);
+}
+
+#[test]
+fn storage_keys_match_the_keys_written() {
+	use frame_support::storage::unhashed;
+	use primitives::storage_keys;
+
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let data = vec![1u8; 2000];
+		let content_hash = blake2_256(&data);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			1,
+			2000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::Signed(caller).into(), data, None));
+		run_to_block(2, || None);
+		let info = TransactionInfos::<Test>::get(1, 0).unwrap();
+		Transactions::<Test>::insert(1, BoundedVec::truncate_from(vec![legacy(info.clone())]));
+
+		let key = storage_keys::content_index_key(&content_hash);
+		assert_eq!(key, ChallengeCounts::<Test>::hashed_key_for(content_hash));
+		assert_eq!(unhashed::get::<(u32, u64)>(&key), Some((0, 1)));
+		let key = storage_keys::chunk_count_key(1u64);
+		assert_eq!(key, ChunkCount::<Test>::hashed_key_for(1));
+		assert_eq!(unhashed::get::<u32>(&key), Some(num_chunks(2000)));
+		let key = storage_keys::chunk_ends_key(1u64);
+		assert_eq!(key, ChunkEnds::<Test>::hashed_key_for(1));
+		assert_eq!(unhashed::get::<Vec<u32>>(&key), Some(vec![num_chunks(2000)]));
+		let key = storage_keys::transaction_info_key(1u64, TxIndex(0));
+		assert_eq!(key, TransactionInfos::<Test>::hashed_key_for(1, 0));
+		assert_eq!(unhashed::get::<TransactionInfo>(&key), Some(info.clone()));
+		let key = storage_keys::transactions_key(1u64);
+		assert_eq!(key, Transactions::<Test>::hashed_key_for(1));
+		assert_eq!(unhashed::get::<Vec<LegacyTransactionInfo>>(&key), Some(vec![legacy(info)]));
+		let key = storage_keys::storage_period_key();
+		assert_eq!(key, StoragePeriod::<Test>::hashed_key());
+		assert_eq!(unhashed::get::<u64>(&key), Some(10));
+		let key = storage_keys::recent_authorizations_key();
+		assert_eq!(key, RecentAuthorizations::<Test>::hashed_key());
+		let grants = unhashed::get::<Vec<AuthorizationGrant<u64, u64>>>(&key).unwrap();
+		assert_eq!(grants.len(), 1);
+	});
+}
+#[test]
+fn group_members_draw_from_a_shared_pot() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let (alice, bob, stranger) = (1, 2, 3);
+		let members = BoundedVec::truncate_from(vec![alice, bob]);
+		assert_ok!(TransactionStorage::<Test>::create_group(RawOrigin::Root.into(), members));
+		System::assert_last_event(
+			Event::<Test>::GroupCreated { group: 0, members: vec![alice, bob] }.into(),
+		);
+		assert_ok!(TransactionStorage::<Test>::authorize_group(RawOrigin::Root.into(), 0, 3, 3000));
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			alice,
+			1,
+			1000,
+			None
+		));
+		let store = |who: u64, data: Vec<u8>| {
+			TransactionStorage::<Test>::store(RawOrigin::Signed(who).into(), data, None)
+		};
+
+		// Personal authorizations are used first
+		assert_ok!(store(alice, vec![1u8; 1000]));
+		next_extrinsic();
+		assert_eq!(
+			TransactionStorage::<Test>::unused_group_authorization_extent(0),
+			AuthorizationExtent { transactions: 3, bytes: 3000 }
+		);
+		assert_ok!(store(alice, vec![2u8; 1000]));
+		next_extrinsic();
+		System::assert_has_event(
+			Event::<Test>::AuthorizationUsed {
+				scope: AuthorizationScope::Group(0),
+				extent: AuthorizationExtent { transactions: 1, bytes: 1000 },
+			}
+			.into(),
+		);
+		// Members contend for what remains of the pot
+		assert_ok!(store(bob, vec![3u8; 1500]));
+		next_extrinsic();
+		assert_noop!(store(alice, vec![4u8; 600]), Error::<Test>::NotAuthorized);
+		assert_ok!(store(alice, vec![4u8; 500]));
+		next_extrinsic();
+		assert_eq!(
+			TransactionStorage::<Test>::unused_group_authorization_extent(0),
+			AuthorizationExtent { transactions: 0, bytes: 0 }
+		);
+		assert_noop!(store(bob, vec![5u8; 1]), Error::<Test>::NotAuthorized);
+		// Non-members cannot draw from the pot
+		assert_ok!(TransactionStorage::<Test>::authorize_group(RawOrigin::Root.into(), 0, 1, 100));
+		assert_noop!(store(stranger, vec![5u8; 1]), Error::<Test>::NotAuthorized);
+		assert_authorization_events_match_storage();
+	});
+}
+
+#[test]
+fn group_membership_is_managed_by_authorizers() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let members = |members: Vec<u64>| BoundedVec::truncate_from(members);
+		assert_noop!(
+			TransactionStorage::<Test>::create_group(RawOrigin::Signed(1).into(), members(vec![1])),
+			DispatchError::BadOrigin,
+		);
+		assert_ok!(TransactionStorage::<Test>::create_group(
+			RawOrigin::Root.into(),
+			members(vec![1, 2])
+		));
+		assert_ok!(TransactionStorage::<Test>::create_group(
+			RawOrigin::Root.into(),
+			members(vec![])
+		));
+		assert_noop!(
+			TransactionStorage::<Test>::create_group(RawOrigin::Root.into(), members(vec![3, 2])),
+			Error::<Test>::AlreadyInGroup,
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::authorize_group(RawOrigin::Root.into(), 2, 1, 100),
+			Error::<Test>::UnknownGroup,
+		);
+
+		assert_ok!(TransactionStorage::<Test>::add_group_member(RawOrigin::Root.into(), 0, 3));
+		System::assert_last_event(Event::<Test>::GroupMemberAdded { group: 0, who: 3 }.into());
+		assert_noop!(
+			TransactionStorage::<Test>::add_group_member(RawOrigin::Root.into(), 1, 3),
+			Error::<Test>::AlreadyInGroup,
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::add_group_member(RawOrigin::Root.into(), 0, 4),
+			Error::<Test>::TooManyGroupMembers,
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::add_group_member(RawOrigin::Root.into(), 2, 4),
+			Error::<Test>::UnknownGroup,
+		);
+
+		assert_noop!(
+			TransactionStorage::<Test>::remove_group_member(RawOrigin::Root.into(), 1, 3),
+			Error::<Test>::NotGroupMember,
+		);
+		assert_ok!(TransactionStorage::<Test>::remove_group_member(RawOrigin::Root.into(), 0, 3));
+		System::assert_last_event(Event::<Test>::GroupMemberRemoved { group: 0, who: 3 }.into());
+		assert_eq!(Groups::<Test>::get(0).unwrap().into_inner(), vec![1, 2]);
+
+		// Removed members no longer draw from the pot
+		assert_ok!(TransactionStorage::<Test>::authorize_group(RawOrigin::Root.into(), 0, 1, 100));
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::Signed(3).into(), vec![0u8; 1], None),
+			Error::<Test>::NotAuthorized,
+		);
+		assert_ok!(TransactionStorage::<Test>::add_group_member(RawOrigin::Root.into(), 1, 3));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(2).into(),
+			vec![0u8; 1],
+			None
+		));
+	});
+}
+
+#[test]
+fn group_authorizations_expire() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let members = BoundedVec::truncate_from(vec![1]);
+		assert_ok!(TransactionStorage::<Test>::create_group(RawOrigin::Root.into(), members));
+		assert_ok!(TransactionStorage::<Test>::authorize_group(RawOrigin::Root.into(), 0, 2, 2000));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(1).into(),
+			vec![0u8; 500],
+			None
+		));
+		run_to_block(11, || None);
+		System::assert_has_event(
+			Event::<Test>::AuthorizationExpired {
+				scope: AuthorizationScope::Group(0),
+				extent: AuthorizationExtent { transactions: 1, bytes: 1500 },
+				authorized: AuthorizationExtent { transactions: 2, bytes: 2000 },
+			}
+			.into(),
+		);
+		assert_eq!(
+			TransactionStorage::<Test>::authorization_usage(AuthorizationScope::Group(0)),
+			None
+		);
+		assert_noop!(
+			TransactionStorage::<Test>::store(RawOrigin::Signed(1).into(), vec![0u8; 1], None),
+			Error::<Test>::NotAuthorized,
+		);
+	});
+}
+
+#[test]
+fn primitives_decode_content_events() {
+	use primitives::events;
+	let chunk_root = H256::repeat_byte(1);
+	assert_eq!(
+		Event::<Test>::Stored { index: TxIndex(2), chunk_root }.encode(),
+		(events::Stored::INDEX, events::Stored { index: TxIndex(2), chunk_root }).encode()
+	);
+	assert_eq!(
+		Event::<Test>::Renewed { index: TxIndex(3), chunk_root }.encode(),
+		(events::Renewed::INDEX, events::Renewed { index: TxIndex(3), chunk_root }).encode()
+	);
+	let reason = RemovalReason::OwnerRequest;
+	assert_eq!(
+		Event::<Test>::ContentRemoved { content_hash: [4; 32], reason }.encode(),
+		(events::ContentRemoved::INDEX, events::ContentRemoved { content_hash: [4; 32], reason })
+			.encode()
+	);
+}
+
+/// Calls as encoded by clients. These must keep decoding to the same calls: fix the change that
+/// breaks one rather than the fixture.
+fn call_fixtures() -> Vec<(&'static str, Call<Test>)> {
+	vec![
+		("000c01020300", Call::store { data: vec![1, 2, 3], namespace: None }),
+		(
+			"000c010203016578616d706c6573",
+			Call::store { data: vec![1, 2, 3], namespace: Some(*b"examples") },
+		),
+		("0101000000000000000200000000", Call::renew { block: 1, index: TxIndex(2), expected_hash: None }),
+		(
+			"01010000000000000002000000014444444444444444444444444444444444444444444444444444444444444444",
+			Call::renew { block: 1, index: TxIndex(2), expected_hash: Some([0x44; 32]) },
+		),
+		(
+			"03010000000000000002000000b80b00000000000000",
+			Call::authorize_account { who: 1, transactions: 2, bytes: 3000, allowed_hashes: None },
+		),
+		(
+			"03010000000000000002000000b80b00000000000001045555555555555555555555555555555555555555555555555555555555555555",
+			Call::authorize_account {
+				who: 1,
+				transactions: 2,
+				bytes: 3000,
+				allowed_hashes: Some(vec![[0x55; 32]].try_into().unwrap()),
+			},
+		),
+		(
+			"046666666666666666666666666666666666666666666666666666666666666666a00f00000000000001",
+			Call::authorize_preimage { preimage: [0x66; 32], bytes: 4000, exact_size: true },
+		),
+	]
+}
+
+#[test]
+fn calls_decode_from_fixtures() {
+	for (hex, call) in call_fixtures() {
+		let encoded: Vec<u8> = (0..hex.len())
+			.step_by(2)
+			.map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+			.collect();
+		assert_eq!(Call::<Test>::decode(&mut &encoded[..]).unwrap(), call, "{hex}");
+		assert_eq!(call.encode(), encoded, "{hex}");
+	}
+}
+
+#[test]
+fn primitives_encode_client_calls() {
+	use primitives::calls;
+	let (data, namespace) = (vec![1u8, 2, 3], Some(*b"examples"));
+	assert_eq!(
+		Call::<Test>::store { data: data.clone(), namespace }.encode(),
+		(calls::Store::INDEX, calls::Store { data, namespace }).encode()
+	);
+	let expected_hash = Some([4; 32]);
+	assert_eq!(
+		Call::<Test>::renew { block: 5, index: TxIndex(6), expected_hash }.encode(),
+		(
+			calls::Renew::<u64>::INDEX,
+			calls::Renew { block: 5u64, index: TxIndex(6), expected_hash }
+		)
+			.encode()
+	);
+}
+
+#[test]
+fn transaction_indices_are_encoded_as_u32() {
+	// Calls, events and storage held plain `u32` indices before `TxIndex`
+	let chunk_root = H256::repeat_byte(1);
+	assert_eq!(
+		Event::<Test>::Stored { index: TxIndex(2), chunk_root }.encode(),
+		(0u8, 2u32, chunk_root).encode()
+	);
+	assert_eq!(
+		Event::<Test>::ContentVerified { block: 1, index: TxIndex(2) }.encode()[1..],
+		(1u64, 2u32).encode()
+	);
+	assert_eq!(
+		Call::<Test>::verify_content { block: 1, index: TxIndex(2), data: vec![3] }.encode()[1..],
+		(1u64, 2u32, vec![3u8]).encode()
+	);
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			TransactionInfos::<Test>::hashed_key_for(1, TxIndex(2)),
+			TransactionInfos::<Test>::hashed_key_for(1, 2u32)
+		);
+		OwnedContent::<Test>::insert(1, [1; 32], (3, TxIndex(2)));
+		let key = OwnedContent::<Test>::hashed_key_for(1, [1; 32]);
+		assert_eq!(frame_support::storage::unhashed::get::<(u64, u32)>(&key), Some((3, 2)));
+	});
+}
+
+#[test]
+fn proves_stored_content_against_extrinsics_root() {
+	use crate::client::{
+		extrinsic_inclusion_proof, verify_extrinsic_inclusion, verify_stored_content,
+		verify_stored_content_inclusion, InclusionError,
+	};
+	type Extrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	let store = |data: Vec<u8>, namespace| {
+		let call = RuntimeCall::TransactionStorage(Call::store { data, namespace });
+		Extrinsic::new_unsigned(call).encode()
+	};
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let data = vec![7u8; 1000];
+		let content_hash = blake2_256(&data);
+		let nested = vec![8u8; 100];
+		let extrinsics = vec![
+			Extrinsic::new_unsigned(RuntimeCall::System(frame_system::Call::remark {
+				remark: vec![1],
+			}))
+			.encode(),
+			store(data.clone(), Some(*b"examples")),
+			// Stores an extrinsic storing other data
+			store(store(nested.clone(), None), None),
+		];
+		for extrinsic in &extrinsics {
+			System::note_extrinsic(extrinsic.clone());
+			next_extrinsic();
+		}
+		System::note_finished_extrinsics();
+		let header = System::finalize();
+		let pallet_index = <TransactionStorage<Test> as PalletInfoAccess>::index() as u8;
+
+		assert_eq!(
+			verify_stored_content(&extrinsics, 1, pallet_index, content_hash),
+			Ok(chunk_root(&data))
+		);
+		assert_eq!(
+			verify_stored_content(&extrinsics, 0, pallet_index, content_hash),
+			Err(InclusionError::NotAStore)
+		);
+		assert_eq!(
+			verify_stored_content(&extrinsics, 1, pallet_index + 1, content_hash),
+			Err(InclusionError::NotAStore)
+		);
+		assert_eq!(
+			verify_stored_content(&extrinsics, 3, pallet_index, content_hash),
+			Err(InclusionError::NoSuchExtrinsic)
+		);
+		// Only the data of the outer call is stored
+		assert_eq!(
+			verify_stored_content(&extrinsics, 2, pallet_index, blake2_256(&nested)),
+			Err(InclusionError::ContentMismatch)
+		);
+
+		for index in 0..extrinsics.len() as u32 {
+			let proof = extrinsic_inclusion_proof(&extrinsics, index).unwrap();
+			assert!(verify_extrinsic_inclusion(&header, &proof));
+		}
+		let proof = extrinsic_inclusion_proof(&extrinsics, 1).unwrap();
+		assert_eq!(
+			verify_stored_content_inclusion(&header, &proof, pallet_index, content_hash),
+			Ok(chunk_root(&data))
+		);
+		let mut moved = proof.clone();
+		moved.index = 2;
+		assert!(!verify_extrinsic_inclusion(&header, &moved));
+		let mut tampered = proof.clone();
+		*tampered.extrinsic.last_mut().unwrap() ^= 1;
+		assert_eq!(
+			verify_stored_content_inclusion(&header, &tampered, pallet_index, content_hash),
+			Err(InclusionError::InvalidProof)
+		);
+		assert_eq!(extrinsic_inclusion_proof(&extrinsics, 3), Err(InclusionError::NoSuchExtrinsic));
+	});
+}
+
+#[test]
+fn headers_commit_to_stored_content() {
+	use crate::client::verify_block_content_commitment;
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			1,
+			2,
+			5000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(1).into(),
+			vec![1u8; 1000],
+			None
+		));
+		next_extrinsic();
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(1).into(),
+			vec![2u8; 100],
+			None
+		));
+		TransactionStorage::<Test>::on_finalize(1);
+		let header = System::finalize();
+		let contents: Vec<_> = (0..2)
+			.map(|index| {
+				let info = TransactionInfos::<Test>::get(1, index).unwrap();
+				(info.content_hash, info.size, info.chunk_root)
+			})
+			.collect();
+		assert_eq!(contents[0].0, blake2_256(&[1u8; 1000]).into());
+		assert_eq!(contents[1].1, 100);
+		assert_eq!(
+			header.digest.logs,
+			vec![DigestItem::Consensus(
+				CONTENT_COMMITMENT_ENGINE_ID,
+				content_commitment(&contents).encode()
+			)]
+		);
+		assert!(verify_block_content_commitment(&header, &contents));
+		// Content must be claimed in full, in index order
+		assert!(!verify_block_content_commitment(&header, &contents[..1]));
+		let swapped = vec![contents[1], contents[0]];
+		assert!(!verify_block_content_commitment(&header, &swapped));
+		let mut resized = contents.clone();
+		resized[1].1 = 99;
+		assert!(!verify_block_content_commitment(&header, &resized));
+		assert!(!verify_block_content_commitment(&header, &[]));
+
+		// Blocks storing nothing have no digest item
+		System::initialize(&2, &header.hash(), &Default::default());
+		TransactionStorage::<Test>::on_finalize(2);
+		let header = System::finalize();
+		assert!(header.digest.logs.is_empty());
+		assert!(verify_block_content_commitment(&header, &[]));
+		assert!(!verify_block_content_commitment(&header, &contents));
+	});
+}
+
+#[test]
+fn content_digest_can_be_disabled() {
+	new_test_ext().execute_with(|| {
+		DepositContentDigest::set(false);
+		run_to_block(1, || None);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			1,
+			1,
+			5000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(1).into(),
+			vec![1u8; 1000],
+			None
+		));
+		TransactionStorage::<Test>::on_finalize(1);
+		assert!(System::finalize().digest.logs.is_empty());
+		assert!(TransactionInfos::<Test>::get(1, 0).is_some());
+	});
+}
+
+#[test]
+fn stored_content_is_listed_in_pages() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			1,
+			10,
+			5000,
+			None
+		));
+		let store = |byte: u8| {
+			assert_ok!(TransactionStorage::<Test>::store(
+				RawOrigin::Signed(1).into(),
+				vec![byte; 100 + byte as usize],
+				None
+			));
+			next_extrinsic();
+		};
+		(1..=4).for_each(&store);
+		run_to_block(2, || None);
+		store(5);
+		run_to_block(3, || None);
+
+		let stored_content = TransactionStorage::<Test>::stored_content;
+		let (all, next_key) = stored_content(None, 10);
+		assert_eq!(next_key, None);
+		assert_eq!(all.len(), 5);
+		let record = all.iter().find(|r| r.content_hash == blake2_256(&[5u8; 105]).into());
+		assert_eq!(
+			record,
+			Some(&ContentRecord {
+				content_hash: blake2_256(&[5u8; 105]).into(),
+				size: 105,
+				block: 2,
+				index: TxIndex(0),
+				expires_at: 12,
+			})
+		);
+
+		// Paging through yields every entry once
+		let mut listed = Vec::new();
+		let mut start_key = None;
+		loop {
+			let (records, next_key) = stored_content(start_key, 2);
+			assert!(records.len() <= 2);
+			listed.extend(records);
+			match next_key {
+				Some(key) => start_key = Some(key),
+				None => break,
+			}
+		}
+		assert_eq!(listed, all);
+
+		// Content stored or removed between pages is listed if its key comes after the
+		// continuation key, and content listed before is not listed again
+		let (first, next_key) = stored_content(None, 2);
+		assert_ok!(TransactionStorage::<Test>::force_remove(
+			RawOrigin::Root.into(),
+			all[4].content_hash.into(),
+			RemovalReason::Takedown
+		));
+		store(6);
+		run_to_block(4, || None);
+		let (rest, next_key) = stored_content(next_key, MAX_STORED_CONTENT_PAGE);
+		assert_eq!(next_key, None);
+		assert!(first.iter().all(|r| !rest.contains(r)));
+		assert!(!rest.contains(&all[4]));
+		assert!(rest.contains(&all[2]) && rest.contains(&all[3]));
+		let new = stored_content(None, 10).0.into_iter().find(|r| r.block == 3).unwrap();
+		let new_key = TransactionInfos::<Test>::hashed_key_for(3, 0);
+		let first_key = TransactionInfos::<Test>::hashed_key_for(first[1].block, first[1].index);
+		assert_eq!(rest.contains(&new), new_key > first_key);
+
+		// Pages scan at least one transaction
+		assert_eq!(stored_content(None, 0).0.len(), 1);
+	});
+}
+
+#[test]
+fn proof_statuses_are_tracked() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			1,
+			1,
+			1000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(1).into(),
+			vec![0u8; 1000],
+			None
+		));
+		let proof_status = TransactionStorage::<Test>::proof_status;
+		run_to_block(2, || None);
+		assert_eq!(proof_status(1), ProofStatus::Pending);
+		assert_eq!(proof_status(2), ProofStatus::NotRequired);
+		run_to_block(11, || None);
+		assert_eq!(proof_status(1), ProofStatus::Pending);
+
+		let parent_hash = <frame_system::Pallet<Test>>::parent_hash();
+		let proof = || build_proof(parent_hash.as_ref(), vec![vec![0u8; 1000]]).unwrap();
+		assert_ok!(TransactionStorage::<Test>::check_proof(RuntimeOrigin::none(), proof()));
+		assert_eq!(proof_status(1), ProofStatus::Proven);
+		assert_noop!(
+			TransactionStorage::<Test>::check_proof(RuntimeOrigin::none(), proof()),
+			Error::<Test>::DoubleCheck,
+		);
+
+		// Kept for a storage period after the proof was due
+		run_to_block(21, || None);
+		assert_eq!(proof_status(1), ProofStatus::Proven);
+		run_to_block(22, || None);
+		assert_eq!(proof_status(1), ProofStatus::NotRequired);
+	});
+}
+
+#[test]
+fn contested_preimage_uploads_are_first_come_first_served() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let data = vec![3u8; 1000];
+		let preimage = blake2_256(&data);
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			preimage,
+			1000,
+			true
+		));
+		let store =
+			|data: Vec<u8>| TransactionStorage::<Test>::store(RawOrigin::None.into(), data, None);
+		// Within one block
+		assert_ok!(store(data.clone()));
+		next_extrinsic();
+		assert_noop!(store(data.clone()), Error::<Test>::PreimageAlreadyStored);
+		// Across blocks
+		run_to_block(2, || None);
+		assert_noop!(store(data.clone()), Error::<Test>::PreimageAlreadyStored);
+		// Other content is still not authorized
+		assert_noop!(store(vec![4u8; 1000]), Error::<Test>::NotAuthorized);
+		// Signed uploads are not affected
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			1,
+			1,
+			1000,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(1).into(),
+			data.clone(),
+			None
+		));
+		// Authorizing the preimage again allows storing it again
+		next_extrinsic();
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			preimage,
+			1000,
+			true
+		));
+		assert_ok!(store(data));
+	});
+}
+
+#[test]
+fn can_store_matches_store() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		// Checks that `can_store` rejects storing `data`, as `store` does
+		let check_rejected = |signer: Option<u64>, data: Vec<u8>, rejection| {
+			let scope = match signer {
+				Some(who) => ScopeRef::Account(who),
+				None => ScopeRef::Preimage(blake2_256(&data)),
+			};
+			let size = data.len() as u32;
+			assert_eq!(TransactionStorage::<Test>::can_store(scope, size), Err(rejection));
+			let origin = signer.map_or(RawOrigin::None, RawOrigin::Signed);
+			assert_noop!(
+				TransactionStorage::<Test>::store(origin.into(), data, None),
+				Error::<Test>::from(rejection),
+			);
+		};
+		let authorize = |who, transactions, bytes| {
+			assert_ok!(TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				who,
+				transactions,
+				bytes,
+				None
+			));
+		};
+
+		check_rejected(Some(1), vec![1; 100], StoreRejection::NotAuthorized);
+		check_rejected(Some(1), vec![], StoreRejection::Empty);
+		MaxTransactionSize::set(1000);
+		check_rejected(Some(1), vec![1; 1001], StoreRejection::TooLarge);
+		MinTransactionSize::set(10);
+		check_rejected(Some(1), vec![1; 9], StoreRejection::TooSmall);
+		MinTransactionSize::set(1);
+
+		authorize(2, 1, 100);
+		check_rejected(Some(2), vec![1; 101], StoreRejection::InsufficientBytes);
+		assert_eq!(TransactionStorage::<Test>::can_store(ScopeRef::Account(2), 100), Ok(()));
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(2).into(),
+			vec![1; 100],
+			None
+		));
+		next_extrinsic();
+		check_rejected(Some(2), vec![2; 50], StoreRejection::InsufficientTransactions);
+
+		authorize(3, 1, 100);
+		Barred::<Test>::insert(3, 10);
+		check_rejected(Some(3), vec![1; 100], StoreRejection::Banned);
+
+		authorize(4, 1, 100);
+		assert_ok!(TransactionStorage::<Test>::set_lifetime_cap(
+			RawOrigin::Root.into(),
+			4,
+			Some(50)
+		));
+		check_rejected(Some(4), vec![1; 100], StoreRejection::LifetimeCapExceeded);
+
+		let data = vec![3; 100];
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			blake2_256(&data),
+			100,
+			true
+		));
+		let preimage = ScopeRef::Preimage(blake2_256(&data));
+		assert_eq!(
+			TransactionStorage::<Test>::can_store(preimage.clone(), 99),
+			Err(StoreRejection::SizeMismatch)
+		);
+		assert_eq!(TransactionStorage::<Test>::can_store(preimage, 100), Ok(()));
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::None.into(), data.clone(), None));
+		next_extrinsic();
+		check_rejected(None, data, StoreRejection::AlreadyStored);
+
+		let removed = vec![4; 100];
+		assert_ok!(TransactionStorage::<Test>::force_remove(
+			RawOrigin::Root.into(),
+			blake2_256(&removed),
+			RemovalReason::Takedown
+		));
+		check_rejected(None, removed, StoreRejection::Removed);
+
+		authorize(5, 1, 100);
+		MaxBlockTransactions::set(BlockTransactions::<Test>::count());
+		check_rejected(Some(5), vec![1; 100], StoreRejection::BlockFull);
+	});
+}
+
+#[test]
+fn stores_smaller_than_min_transaction_size_are_rejected() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			2,
+			2000,
+			None
+		));
+		let store = |size: usize| {
+			TransactionStorage::<Test>::store(RawOrigin::Signed(caller).into(), vec![0; size], None)
+		};
+		MinTransactionSize::set(100);
+		assert_noop!(store(0), Error::<Test>::EmptyTransaction);
+		assert_noop!(store(99), Error::<Test>::TransactionTooSmall);
+		assert_ok!(store(100));
+		next_extrinsic();
+		// The default of one byte lets any data be stored
+		MinTransactionSize::set(1);
+		assert_ok!(store(1));
+		<TransactionStorage<Test> as IntegrityTest>::integrity_test();
+	});
+}
+
+#[test]
+fn dust_stores_are_charged_min_bytes_per_store() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let extent = |transactions, bytes| AuthorizationExtent { transactions, bytes };
+		let unused = || TransactionStorage::<Test>::unused_account_authorization_extent(caller);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			4,
+			250,
+			None
+		));
+		let store = |data: Vec<u8>| {
+			TransactionStorage::<Test>::store(RawOrigin::Signed(caller).into(), data, None)
+		};
+		MinBytesPerStore::set(100);
+		assert_ok!(store(vec![1]));
+		next_extrinsic();
+		assert_eq!(unused(), extent(3, 150));
+		// Stores of at least `MinBytesPerStore` bytes are charged their size
+		assert_ok!(store(vec![2; 100]));
+		next_extrinsic();
+		assert_eq!(unused(), extent(2, 50));
+		assert_eq!(
+			TransactionStorage::<Test>::can_store(ScopeRef::Account(caller), 40),
+			Err(StoreRejection::InsufficientBytes)
+		);
+		assert!(!TransactionStorage::<Test>::has_account_authorization(caller, 40));
+		assert_noop!(store(vec![3; 40]), Error::<Test>::NotAuthorized);
+		// Only the stored bytes count towards the lifetime usage
+		assert_eq!(TransactionStorage::<Test>::lifetime_used(caller), 101);
+
+		// Preimage authorizations are charged the size of their preimage
+		let data = vec![4];
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			blake2_256(&data),
+			1,
+			true
+		));
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::None.into(), data, None));
+		assert_eq!(unused(), extent(2, 50));
+	});
+}
+
+#[test]
+fn owned_content_is_indexed() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			5,
+			5000,
+			None
+		));
+		let store = |data: Vec<u8>| {
+			TransactionStorage::<Test>::store(RawOrigin::Signed(caller).into(), data, None)
+		};
+		MaxOwnedContent::set(2);
+		assert_ok!(store(vec![1u8; 100]));
+		next_extrinsic();
+		assert_ok!(store(vec![2u8; 100]));
+		next_extrinsic();
+		assert_eq!(
+			OwnedContent::<Test>::get(caller, blake2_256(&[2u8; 100])),
+			Some((1, TxIndex(1)))
+		);
+		assert_ok!(TransactionStorage::<Test>::do_try_state());
+
+		let mut listed = vec![
+			(blake2_256(&[1u8; 100]), 1, TxIndex(0)),
+			(blake2_256(&[2u8; 100]), 1, TxIndex(1)),
+		];
+		listed.sort();
+		let owned_content = TransactionStorage::<Test>::owned_content;
+		assert_eq!(owned_content(caller, None, 10), listed);
+		assert_eq!(owned_content(2, None, 10), vec![]);
+		// Pagination
+		assert_eq!(owned_content(caller, None, 1), vec![listed[0]]);
+		assert_eq!(owned_content(caller, Some(listed[0].0), 10), vec![listed[1]]);
+
+		// Live entries of an account are bounded, but content it already owns can be stored again
+		assert_noop!(store(vec![3u8; 100]), Error::<Test>::TooMuchOwnedContent);
+		assert_ok!(store(vec![1u8; 100]));
+		assert_eq!(TransactionStorage::<Test>::owned_content_count(caller), 2);
+		assert_eq!(
+			OwnedContent::<Test>::get(caller, blake2_256(&[1u8; 100])),
+			Some((1, TxIndex(2)))
+		);
+
+		// Unsigned stores have no owner
+		let data = vec![4u8; 100];
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			blake2_256(&data),
+			100,
+			true
+		));
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::None.into(), data, None));
+		assert_eq!(OwnedContent::<Test>::iter().count(), 2);
+		assert_ok!(TransactionStorage::<Test>::do_try_state());
+	});
+}
+
+#[test]
+fn owned_content_is_pruned_with_its_last_renewal() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let (a, b) = (vec![1u8; 700], vec![2u8; 600]);
+		let (a_hash, b_hash) = (blake2_256(&a), blake2_256(&b));
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			3,
+			2000,
+			None
+		));
+		let store = |data: &Vec<u8>| {
+			assert_ok!(TransactionStorage::<Test>::store(
+				RawOrigin::Signed(caller).into(),
+				data.clone(),
+				None
+			));
+		};
+		let owned = || {
+			TransactionStorage::<Test>::owned_content(caller, None, 10)
+				.into_iter()
+				.map(|(hash, _, _)| hash)
+				.collect::<Vec<_>>()
+		};
+		store(&a);
+		run_to_block(2, || None);
+		store(&b);
+		run_to_block(3, || None);
+		assert_ok!(TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(caller).into(),
+			1,
+			TxIndex(0),
+			None
+		));
+		assert_eq!(OwnedContent::<Test>::get(caller, a_hash), Some((3, TxIndex(0))));
+
+		let mut stored = StoredData::default();
+		stored.insert(a.clone());
+		stored.insert(b.clone());
+		let proof_provider = || stored.proof::<Test>();
+		// Renewed content outlives its original storage period
+		run_to_block(12, proof_provider);
+		let mut both = vec![a_hash, b_hash];
+		both.sort();
+		assert_eq!(owned(), both);
+		assert_ok!(TransactionStorage::<Test>::do_try_state());
+		run_to_block(13, proof_provider);
+		assert_eq!(owned(), vec![a_hash]);
+		assert_eq!(TransactionStorage::<Test>::owned_content_count(caller), 1);
+		assert_ok!(TransactionStorage::<Test>::do_try_state());
+		run_to_block(14, proof_provider);
+		assert_eq!(owned(), vec![]);
+		assert!(!OwnedContentCount::<Test>::contains_key(caller));
+		assert_eq!(OwnedByBlock::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn third_parties_renew_content_for_its_owner() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let (owner, archive) = (1, 2);
+		let data = vec![1u8; 700];
+		let content_hash = blake2_256(&data);
+		for who in [owner, archive] {
+			assert_ok!(TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				who,
+				1,
+				700,
+				None
+			));
+		}
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::Signed(owner).into(), data, None));
+		run_to_block(3, || None);
+		assert_ok!(TransactionStorage::<Test>::renew(
+			RawOrigin::Signed(archive).into(),
+			1,
+			TxIndex(0),
+			None
+		));
+		System::assert_has_event(
+			Event::<Test>::RenewedOnBehalf { index: TxIndex(0), payer: Some(archive), owner }
+				.into(),
+		);
+
+		// The archive paid for the renewal, which the owner keeps owning
+		let unused = TransactionStorage::<Test>::unused_account_authorization_extent;
+		assert_eq!(unused(archive), AuthorizationExtent { transactions: 0, bytes: 0 });
+		assert_eq!(TransactionStorage::<Test>::account_usage_history(owner)[0].renewals, 0);
+		assert_eq!(TransactionStorage::<Test>::account_usage_history(archive)[0].renewals, 1);
+		assert_eq!(OwnedContent::<Test>::get(owner, content_hash), Some((3, TxIndex(0))));
+		assert_eq!(OwnedContent::<Test>::get(archive, content_hash), None);
+		assert_eq!(
+			StorageEvents::get().last(),
+			Some(&StorageEvent::Renewed {
+				content_hash,
+				size: 700,
+				owner: Some(owner),
+				index: TxIndex(0)
+			})
+		);
+		assert_ok!(TransactionStorage::<Test>::do_try_state());
+	});
+}
+
+#[test]
+fn owner_only_renewal_policy_rejects_third_parties() {
+	new_test_ext().execute_with(|| {
+		OwnerOnlyRenewal::set(true);
+		run_to_block(1, || None);
+		let (owner, archive) = (1, 2);
+		for who in [owner, archive] {
+			assert_ok!(TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				who,
+				2,
+				2000,
+				None
+			));
+		}
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(owner).into(),
+			vec![1u8; 700],
+			None
+		));
+		next_extrinsic();
+		// Content stored with a preimage authorization has no owner
+		let unowned = vec![2u8; 300];
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			blake2_256(&unowned),
+			300,
+			true
+		));
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::None.into(), unowned, None));
+		run_to_block(3, || None);
+
+		let renew = |who: u64, index: u32| {
+			TransactionStorage::<Test>::renew(
+				RawOrigin::Signed(who).into(),
+				1,
+				TxIndex(index),
+				None,
+			)
+		};
+		assert_noop!(renew(archive, 0), Error::<Test>::RenewalNotAllowed);
+		assert_ok!(renew(owner, 0));
+		next_extrinsic();
+		assert_ok!(renew(archive, 1));
+		// The archive renewed unowned content, so it now owns it
+		assert_eq!(
+			OwnedContent::<Test>::get(archive, blake2_256(&[2u8; 300])),
+			Some((3, TxIndex(1)))
+		);
+		assert!(!System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::TransactionStorage(Event::RenewedOnBehalf { .. })
+		)));
+	});
+}
+
+#[test]
+fn preimage_policy_controls_repeated_authorizations() {
+	for policy in [PreimageStorePolicy::Accumulate, PreimageStorePolicy::Strict] {
+		new_test_ext().execute_with(|| {
+			PreimagePolicy::set(policy);
+			let strict = policy == PreimageStorePolicy::Strict;
+			run_to_block(1, || None);
+			let data = vec![3u8; 1000];
+			let preimage = blake2_256(&data);
+			let authorize = || {
+				assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+					RawOrigin::Root.into(),
+					preimage,
+					1000,
+					true
+				));
+			};
+			let store = |origin: RawOrigin<u64>| {
+				TransactionStorage::<Test>::store(origin.into(), data.clone(), None)
+			};
+			authorize();
+			run_to_block(2, || None);
+			authorize();
+			assert_ok!(store(RawOrigin::None));
+			next_extrinsic();
+			if strict {
+				let content_hash = preimage;
+				System::assert_has_event(Event::<Test>::PreimageSatisfied { content_hash }.into());
+				assert_eq!(
+					TransactionStorage::<Test>::can_store(ScopeRef::Preimage(preimage), 1000),
+					Err(StoreRejection::AlreadySatisfied)
+				);
+				assert_noop!(store(RawOrigin::None), Error::<Test>::PreimageAlreadySatisfied);
+			} else {
+				// Each authorization allows a store
+				assert_ok!(store(RawOrigin::None));
+				next_extrinsic();
+				assert_noop!(store(RawOrigin::None), Error::<Test>::PreimageAlreadyStored);
+			}
+			assert_eq!(SatisfiedPreimages::<Test>::get(preimage), strict.then_some(2));
+
+			// Stores authorized by account are not affected
+			assert_ok!(TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				1,
+				1,
+				1000,
+				None
+			));
+			assert_ok!(store(RawOrigin::Signed(1)));
+
+			// Satisfied until the last authorization of the preimage expires
+			run_to_block(11, || None);
+			assert_eq!(SatisfiedPreimages::<Test>::contains_key(preimage), strict);
+			run_to_block(12, || None);
+			assert!(!SatisfiedPreimages::<Test>::contains_key(preimage));
+			if strict {
+				let content_hash = preimage;
+				System::assert_last_event(
+					Event::<Test>::PreimageSatisfactionExpired { content_hash }.into(),
+				);
+			}
+		});
+	}
+}
+
+#[test]
+fn signed_stores_use_preimage_authorizations_first() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let (data, other) = (vec![1u8; 1000], vec![2u8; 1000]);
+		let (preimage, other_preimage) = (blake2_256(&data), blake2_256(&other));
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			2,
+			2000,
+			None
+		));
+		for (preimage, bytes) in [(preimage, 1000), (other_preimage, 500)] {
+			assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+				RawOrigin::Root.into(),
+				preimage,
+				bytes,
+				false
+			));
+		}
+		let store = |data: &Vec<u8>| {
+			assert_ok!(TransactionStorage::<Test>::store(
+				RawOrigin::Signed(caller).into(),
+				data.clone(),
+				None
+			));
+			next_extrinsic();
+		};
+		let unused_account = || TransactionStorage::<Test>::unused_account_authorization_extent(1);
+		let unused_preimage = TransactionStorage::<Test>::unused_preimage_authorization_extent;
+
+		// The preimage authorization covers the store, and the account's is left untouched
+		store(&data);
+		System::assert_has_event(
+			Event::<Test>::AuthorizationUsed {
+				scope: AuthorizationScope::Preimage(preimage),
+				extent: AuthorizationExtent { transactions: 1, bytes: 1000 },
+			}
+			.into(),
+		);
+		assert_eq!(unused_preimage(preimage), AuthorizationExtent::default());
+		assert_eq!(unused_account(), AuthorizationExtent { transactions: 2, bytes: 2000 });
+		let account_usage =
+			AuthorizationUsageByScope::<Test>::get(AuthorizationScope::Account(caller));
+		assert_eq!(account_usage.used, AuthorizationExtent::default());
+
+		// Once used up, the account's authorization is used
+		store(&data);
+		assert_eq!(unused_account(), AuthorizationExtent { transactions: 1, bytes: 1000 });
+
+		// As it is when the preimage authorization is too small
+		store(&other);
+		System::assert_has_event(
+			Event::<Test>::AuthorizationUsed {
+				scope: AuthorizationScope::Account(caller),
+				extent: AuthorizationExtent { transactions: 1, bytes: 1000 },
+			}
+			.into(),
+		);
+		assert_eq!(unused_preimage(other_preimage), AuthorizationExtent::transaction(500));
+		assert_eq!(unused_account(), AuthorizationExtent::default());
+	});
+}
+
+#[test]
+fn signed_stores_use_account_authorizations_first_if_configured() {
+	new_test_ext().execute_with(|| {
+		AuthorizationOrder::set(ConsumptionOrder::AccountFirst);
+		run_to_block(1, || None);
+		let caller = 1;
+		let (data, other) = (vec![1u8; 1000], vec![2u8; 1000]);
+		let preimage = blake2_256(&data);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			caller,
+			2,
+			1500,
+			None
+		));
+		assert_ok!(TransactionStorage::<Test>::authorize_preimage(
+			RawOrigin::Root.into(),
+			preimage,
+			1000,
+			false
+		));
+		let store = |data: &Vec<u8>| {
+			TransactionStorage::<Test>::store(RawOrigin::Signed(caller).into(), data.clone(), None)
+		};
+		let unused_account = || TransactionStorage::<Test>::unused_account_authorization_extent(1);
+		let unused_preimage =
+			|| TransactionStorage::<Test>::unused_preimage_authorization_extent(preimage);
+
+		// The account's authorization covers the store, and the preimage's is left for others
+		assert_ok!(store(&data));
+		System::assert_has_event(
+			Event::<Test>::AuthorizationUsed {
+				scope: AuthorizationScope::Account(caller),
+				extent: AuthorizationExtent { transactions: 1, bytes: 1000 },
+			}
+			.into(),
+		);
+		assert_eq!(unused_account(), AuthorizationExtent { transactions: 1, bytes: 500 });
+		assert_eq!(unused_preimage(), AuthorizationExtent::transaction(1000));
+
+		// The account's authorization is too small, so the preimage's is used
+		next_extrinsic();
+		assert_ok!(store(&data));
+		System::assert_has_event(
+			Event::<Test>::AuthorizationUsed {
+				scope: AuthorizationScope::Preimage(preimage),
+				extent: AuthorizationExtent { transactions: 1, bytes: 1000 },
+			}
+			.into(),
+		);
+		assert_eq!(unused_account(), AuthorizationExtent { transactions: 1, bytes: 500 });
+		assert_eq!(unused_preimage(), AuthorizationExtent::default());
+
+		// Neither covers other content
+		next_extrinsic();
+		assert_noop!(store(&other), Error::<Test>::NotAuthorized);
+	});
+}
+
+#[test]
+fn held_storage_quota_cannot_be_used_by_stores() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		let extent = |transactions, bytes| AuthorizationExtent { transactions, bytes };
+		let store = |size: usize| {
+			TransactionStorage::<Test>::store(RawOrigin::Signed(who).into(), vec![0; size], None)
+		};
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			2,
+			2000,
+			None
+		));
+		assert_ok!(StorageQuota::<Test>::reserve(&who, extent(1, 1500)));
+		System::assert_last_event(Event::<Test>::QuotaHeld { who, extent: extent(1, 1500) }.into());
+		assert_eq!(StorageQuota::<Test>::total_quota(&who), extent(2, 2000));
+		assert_eq!(StorageQuota::<Test>::held_quota(&who), extent(1, 1500));
+		assert_eq!(StorageQuota::<Test>::spendable_quota(&who), extent(1, 500));
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(who),
+			extent(1, 500)
+		);
+
+		// Only the quota that is not held can be held again, spent or used
+		assert_noop!(
+			StorageQuota::<Test>::reserve(&who, extent(1, 501)),
+			Error::<Test>::InsufficientQuota
+		);
+		assert_noop!(
+			StorageQuota::<Test>::spend(&who, extent(2, 0)),
+			Error::<Test>::InsufficientQuota
+		);
+		assert_eq!(
+			TransactionStorage::<Test>::dry_run_store(who, 501),
+			Err(StoreRejection::InsufficientBytes)
+		);
+		assert!(!TransactionStorage::<Test>::has_account_authorization(who, 501));
+		assert_noop!(store(501), Error::<Test>::NotAuthorized);
+		assert_ok!(store(500));
+		next_extrinsic();
+		assert_noop!(store(1), Error::<Test>::NotAuthorized);
+		assert_eq!(StorageQuota::<Test>::spendable_quota(&who), extent(0, 0));
+		assert_eq!(StorageQuota::<Test>::held_quota(&who), extent(1, 1500));
+
+		// Released quota can be used again, and no more than is held is released
+		assert_eq!(StorageQuota::<Test>::unreserve(&who, extent(2, 500)), extent(1, 500));
+		System::assert_last_event(
+			Event::<Test>::QuotaReleased { who, extent: extent(1, 500) }.into(),
+		);
+		assert_eq!(StorageQuota::<Test>::held_quota(&who), extent(0, 1000));
+		assert_ok!(store(500));
+		assert_eq!(StorageQuota::<Test>::total_quota(&who), extent(0, 1000));
+		assert_eq!(StorageQuota::<Test>::unreserve(&who, extent(0, 2000)), extent(0, 1000));
+		assert!(!HeldQuota::<Test>::contains_key(who));
+		assert_eq!(StorageQuota::<Test>::unreserve(&who, extent(1, 1)), extent(0, 0));
+	});
+}
+
+#[test]
+fn storage_quota_can_be_spent() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		let extent = |transactions, bytes| AuthorizationExtent { transactions, bytes };
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			3,
+			3000,
+			None
+		));
+		assert_ok!(StorageQuota::<Test>::reserve(&who, extent(2, 2000)));
+
+		assert_ok!(StorageQuota::<Test>::spend(&who, extent(1, 1000)));
+		System::assert_last_event(
+			Event::<Test>::AuthorizationUsed {
+				scope: AuthorizationScope::Account(who),
+				extent: extent(1, 1000),
+			}
+			.into(),
+		);
+		assert_noop!(
+			StorageQuota::<Test>::spend(&who, extent(0, 1)),
+			Error::<Test>::InsufficientQuota
+		);
+
+		// Spending held quota, e.g. once an escrow is settled
+		assert_noop!(
+			StorageQuota::<Test>::spend_held(&who, extent(3, 0)),
+			Error::<Test>::InsufficientQuota
+		);
+		assert_ok!(StorageQuota::<Test>::spend_held(&who, extent(1, 1500)));
+		assert_eq!(StorageQuota::<Test>::held_quota(&who), extent(1, 500));
+		assert_eq!(StorageQuota::<Test>::total_quota(&who), extent(1, 500));
+		let usage =
+			TransactionStorage::<Test>::authorization_usage(AuthorizationScope::Account(who));
+		assert_eq!(usage.map(|usage| usage.used), Some(extent(2, 2500)));
+		assert_ok!(StorageQuota::<Test>::spend_held(&who, extent(1, 500)));
+		assert!(!HeldQuota::<Test>::contains_key(who));
+		assert_eq!(StorageQuota::<Test>::total_quota(&who), extent(0, 0));
+	});
+}
+
+#[test]
+fn held_storage_quota_expires_with_its_authorizations() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		let extent = |transactions, bytes| AuthorizationExtent { transactions, bytes };
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			2,
+			2000,
+			None
+		));
+		assert_ok!(StorageQuota::<Test>::reserve(&who, extent(1, 1500)));
+		run_to_block(5, || None);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			1,
+			500,
+			None
+		));
+
+		// The quota that is not held expires first
+		run_to_block(11, || None);
+		assert_eq!(StorageQuota::<Test>::total_quota(&who), extent(1, 500));
+		assert_eq!(StorageQuota::<Test>::held_quota(&who), extent(1, 500));
+		assert_eq!(StorageQuota::<Test>::spendable_quota(&who), extent(0, 0));
+
+		run_to_block(15, || None);
+		assert_eq!(StorageQuota::<Test>::total_quota(&who), extent(0, 0));
+		assert!(!HeldQuota::<Test>::contains_key(who));
+		assert_eq!(StorageQuota::<Test>::unreserve(&who, extent(1, 500)), extent(0, 0));
+	});
+}
+
+#[test]
+fn revoking_account_authorizations_keeps_the_used_extent() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		let scope = AuthorizationScope::Account(who);
+		let extent = |transactions, bytes| AuthorizationExtent { transactions, bytes };
+		let revoke = |origin: RuntimeOrigin| {
+			TransactionStorage::<Test>::revoke_account_authorization(origin, who)
+		};
+		let store = |size: usize| {
+			TransactionStorage::<Test>::store(RawOrigin::Signed(who).into(), vec![0; size], None)
+		};
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			2,
+			2000,
+			None
+		));
+		assert_ok!(store(500));
+		next_extrinsic();
+		assert_ok!(StorageQuota::<Test>::reserve(&who, extent(0, 500)));
+
+		assert_noop!(revoke(RawOrigin::Signed(who).into()), DispatchError::BadOrigin);
+		assert_ok!(revoke(RawOrigin::Root.into()));
+		System::assert_last_event(
+			Event::<Test>::AuthorizationRevoked { who, extent: extent(1, 1500) }.into(),
+		);
+		let usage = TransactionStorage::<Test>::authorization_usage(scope.clone()).unwrap();
+		assert_eq!(usage.used, extent(1, 500));
+		assert_eq!(usage.unused, extent(0, 0));
+		assert!(!HeldQuota::<Test>::contains_key(who));
+		assert_noop!(store(1), Error::<Test>::NotAuthorized);
+		// The authorization only covers the used extent until it expires
+		assert_eq!(
+			AuthorizationsByExpiry::<Test>::get(11).into_inner(),
+			vec![Authorization { id: 0, scope: scope.clone(), extent: extent(1, 500) }]
+		);
+
+		// Nothing is left to revoke
+		assert_ok!(revoke(RawOrigin::Root.into()));
+		System::assert_last_event(
+			Event::<Test>::AuthorizationRevoked { who, extent: extent(0, 0) }.into(),
+		);
+
+		// Later authorizations are not reduced by the expiry of the revoked one
+		run_to_block(5, || None);
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			who,
+			1,
+			100,
+			None
+		));
+		run_to_block(11, || None);
+		assert_eq!(
+			TransactionStorage::<Test>::authorization_usage(scope.clone()),
+			Some(AuthorizationUsage {
+				used: extent(0, 0),
+				unused: extent(1, 100),
+				exact_size: None
+			})
+		);
+		System::assert_has_event(
+			Event::<Test>::AuthorizationExpired {
+				scope: scope.clone(),
+				extent: extent(0, 0),
+				authorized: extent(1, 500),
+			}
+			.into(),
+		);
+		run_to_block(15, || None);
+		assert_eq!(TransactionStorage::<Test>::authorization_usage(scope), None);
+	});
+}
+
+#[test]
+fn revoking_account_authorizations_spanning_expiry_blocks() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		let scope = AuthorizationScope::Account(who);
+		let extent = |transactions, bytes| AuthorizationExtent { transactions, bytes };
+		let authorize = |who, transactions, bytes| {
+			TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				who,
+				transactions,
+				bytes,
+				None,
+			)
+		};
+		assert_ok!(authorize(who, 1, 1000));
+		run_to_block(3, || None);
+		assert_ok!(authorize(who, 2, 2000));
+		assert_ok!(authorize(2, 1, 1000));
+		run_to_block(5, || None);
+		assert_ok!(authorize(who, 1, 1000));
+		assert_eq!(AccountAuthorizationExpiries::<Test>::get(who).into_inner(), vec![11, 13, 15]);
+		for size in [1000, 500] {
+			assert_ok!(TransactionStorage::<Test>::store(
+				RawOrigin::Signed(who).into(),
+				vec![0; size],
+				None
+			));
+			next_extrinsic();
+		}
+
+		// The used extent is covered by the authorizations expiring first
+		run_to_block(6, || None);
+		assert_ok!(TransactionStorage::<Test>::revoke_account_authorization(
+			RawOrigin::Root.into(),
+			who
+		));
+		System::assert_last_event(
+			Event::<Test>::AuthorizationRevoked { who, extent: extent(2, 2500) }.into(),
+		);
+		assert_eq!(AccountAuthorizationExpiries::<Test>::get(who).into_inner(), vec![11, 13]);
+		assert_eq!(
+			AuthorizationsByExpiry::<Test>::get(11).into_inner(),
+			vec![Authorization { id: 0, scope: scope.clone(), extent: extent(1, 1000) }]
+		);
+		assert_eq!(
+			AuthorizationsByExpiry::<Test>::get(13).into_inner(),
+			vec![
+				Authorization { id: 1, scope: scope.clone(), extent: extent(1, 500) },
+				Authorization {
+					id: 2,
+					scope: AuthorizationScope::Account(2),
+					extent: extent(1, 1000)
+				},
+			]
+		);
+		assert!(!AuthorizationsByExpiry::<Test>::contains_key(15));
+		assert_eq!(OutstandingAuthorizations::<Test>::get(), 3);
+
+		run_to_block(7, || None);
+		assert_ok!(authorize(who, 1, 100));
+		run_to_block(11, || None);
+		let usage = TransactionStorage::<Test>::authorization_usage(scope.clone()).unwrap();
+		assert_eq!((usage.used, usage.unused), (extent(1, 500), extent(1, 100)));
+		run_to_block(13, || None);
+		let usage = TransactionStorage::<Test>::authorization_usage(scope.clone()).unwrap();
+		assert_eq!((usage.used, usage.unused), (extent(0, 0), extent(1, 100)));
+		assert_eq!(AccountAuthorizationExpiries::<Test>::get(who).into_inner(), vec![17]);
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(2),
+			extent(0, 0)
+		);
+		run_to_block(17, || None);
+		assert_eq!(TransactionStorage::<Test>::authorization_usage(scope), None);
+		assert!(!AccountAuthorizationExpiries::<Test>::contains_key(who));
+	});
+}
+
+#[test]
+fn expiring_authorizations_only_take_their_own_unused_extent() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		let scope = AuthorizationScope::Account(who);
+		let extent = |transactions, bytes| AuthorizationExtent { transactions, bytes };
+		let authorize = |transactions, bytes| {
+			TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				who,
+				transactions,
+				bytes,
+				None,
+			)
+		};
+		assert_ok!(authorize(2, 1000));
+		run_to_block(3, || None);
+		assert_ok!(authorize(2, 2000));
+		// The store uses the authorization expiring first, then the bytes of the next one
+		assert_ok!(TransactionStorage::<Test>::store(
+			RawOrigin::Signed(who).into(),
+			vec![0; 1500],
+			None
+		));
+		assert_eq!(
+			PendingAuthorizations::<Test>::get(&scope).into_inner(),
+			vec![
+				PendingAuthorization { id: 0, expiry: Some(11), unused: extent(1, 0) },
+				PendingAuthorization { id: 1, expiry: Some(13), unused: extent(2, 1500) },
+			]
+		);
+
+		// Only the transaction left of the first authorization expires with it
+		run_to_block(11, || None);
+		System::assert_has_event(
+			Event::<Test>::AuthorizationExpired {
+				scope: scope.clone(),
+				extent: extent(1, 0),
+				authorized: extent(2, 1000),
+			}
+			.into(),
+		);
+		assert_eq!(
+			TransactionStorage::<Test>::authorization_usage(scope.clone()),
+			Some(AuthorizationUsage {
+				used: extent(0, 500),
+				unused: extent(2, 1500),
+				exact_size: None
+			})
+		);
+		assert_eq!(
+			PendingAuthorizations::<Test>::get(&scope).into_inner(),
+			vec![PendingAuthorization { id: 1, expiry: Some(13), unused: extent(2, 1500) }]
+		);
+		assert_authorization_events_match_storage();
+
+		run_to_block(13, || None);
+		assert_eq!(TransactionStorage::<Test>::authorization_usage(scope.clone()), None);
+		assert!(!PendingAuthorizations::<Test>::contains_key(&scope));
+	});
+}
+
+#[test]
+fn authorizations_only_expire_the_extent_they_credited() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let who = 1;
+		let scope = AuthorizationScope::Account(who);
+		let extent = |transactions, bytes| AuthorizationExtent { transactions, bytes };
+		let authorize = |bytes| {
+			TransactionStorage::<Test>::authorize_account(
+				RawOrigin::Root.into(),
+				who,
+				1,
+				bytes,
+				None,
+			)
+		};
+		assert_ok!(authorize(u64::MAX - 100));
+		run_to_block(2, || None);
+		// Only 100 bytes fit in the byte pool
+		assert_ok!(authorize(1000));
+		System::assert_has_event(
+			Event::<Test>::Authorized { scope: scope.clone(), extent: extent(1, 100) }.into(),
+		);
+		assert_eq!(
+			AuthorizationsByExpiry::<Test>::get(12).into_inner(),
+			vec![Authorization { id: 1, scope: scope.clone(), extent: extent(1, 100) }]
+		);
+		run_to_block(11, || None);
+		assert_ok!(authorize(1000));
+
+		// The expiry of the second authorization leaves the third one intact
+		run_to_block(12, || None);
+		assert_eq!(
+			TransactionStorage::<Test>::unused_account_authorization_extent(who),
+			extent(1, 1000)
+		);
+		assert_authorization_events_match_storage();
+	});
+}
+
+#[test]
+fn authorizations_with_unused_extent_are_bounded_per_scope() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		MaxScopeAuthorizations::set(2);
+		let data = vec![0u8; 1000];
+		let authorize = || {
+			TransactionStorage::<Test>::authorize_preimage(
+				RawOrigin::Root.into(),
+				blake2_256(&data),
+				1000,
+				false,
+			)
+		};
+		assert_ok!(authorize());
+		assert_ok!(authorize());
+		assert_noop!(authorize(), Error::<Test>::TooManyScopeAuthorizations);
+		// Used up authorizations no longer count
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::None.into(), data.clone(), None));
+		assert_ok!(authorize());
+	});
+}
+
+#[test]
+fn account_authorizations_are_bounded_by_their_expiry_blocks() {
+	new_test_ext().execute_with(|| {
+		MaxAccountAuthorizationExpiries::set(2);
+		let authorize =
+			|| TransactionStorage::<Test>::authorize_account(RawOrigin::Root.into(), 1, 1, 1, None);
+		for block in 1..=2 {
+			run_to_block(block, || None);
+			assert_ok!(authorize());
+		}
+		// Authorizations of the same block share its expiry block
+		assert_ok!(authorize());
+		run_to_block(3, || None);
+		assert_noop!(authorize(), Error::<Test>::TooManyAccountAuthorizations);
+		// Restricted authorizations are not revoked, so they are not limited
+		assert_ok!(TransactionStorage::<Test>::authorize_account(
+			RawOrigin::Root.into(),
+			1,
+			1,
+			1,
+			Some(Default::default())
+		));
+		run_to_block(11, || None);
+		assert_ok!(authorize());
+	});
+}
+
+#[cfg(feature = "proptests")]
+mod properties {
+	use super::*;
+	use proptest::prelude::*;
+
+	const MAX_TRANSACTIONS: usize = 16;
+
+	fn transaction_size() -> impl Strategy<Value = u32> {
+		// Mostly transactions of a few chunks, so that boundaries are hit often
+		prop_oneof![3 => 1..=4 * CHUNK_SIZE as u32, 1 => 1..=DEFAULT_MAX_TRANSACTION_SIZE]
+	}
+
+	/// Indexes transactions of the given sizes in a block and returns their `block_chunks` as
+	/// stored by the pallet.
+	fn index_transactions(sizes: &[u32]) -> Vec<u32> {
+		new_test_ext().execute_with(|| {
+			run_to_block(1, || None);
+			for (i, size) in sizes.iter().enumerate() {
+				let hash = blake2_256(&i.encode()).into();
+				assert_ok!(TransactionStorage::<Test>::push_block_transaction(
+					hash,
+					*size,
+					hash,
+					num_chunks(*size)
+				));
+			}
+			run_to_block(2, || None);
+			ChunkEnds::<Test>::get(1).unwrap().into_inner()
+		})
+	}
+
+	proptest! {
+		#![proptest_config(ProptestConfig::with_cases(64))]
+
+		#[test]
+		fn num_chunks_covers_data(bytes in any::<u32>()) {
+			let chunks = num_chunks(bytes) as u64;
+			let chunk_size = CHUNK_SIZE as u64;
+			prop_assert!(chunks * chunk_size >= bytes as u64);
+			prop_assert!(chunks * chunk_size < bytes as u64 + chunk_size);
+		}
+
+		#[test]
+		fn num_chunks_matches_chunking(bytes in 0..=4 * CHUNK_SIZE) {
+			let chunks = vec![0u8; bytes].chunks(CHUNK_SIZE).count();
+			prop_assert_eq!(num_chunks(bytes as u32) as usize, chunks);
+		}
+
+		#[test]
+		fn finds_every_chunk(
+			sizes in prop::collection::vec(transaction_size(), 1..=MAX_TRANSACTIONS),
+		) {
+			let chunk_ends = index_transactions(&sizes);
+			prop_assert_eq!(chunk_ends.len(), sizes.len());
+			// Walk the chunks of all transactions in order
+			let mut block_chunk = 0;
+			for (index, size) in sizes.iter().enumerate() {
+				for chunk in 0..num_chunks(*size) {
+					prop_assert_eq!(
+						find_chunk(&chunk_ends, block_chunk),
+						Some((TxIndex(index as u32), ChunkIndex(chunk)))
+					);
+					block_chunk += 1;
+				}
+			}
+			prop_assert_eq!(*chunk_ends.last().unwrap(), block_chunk);
+			prop_assert_eq!(find_chunk(&chunk_ends, block_chunk), None);
+		}
+
+		#[test]
+		fn random_chunk_is_in_range(seed in any::<[u8; 32]>(), total in 1..=u32::MAX) {
+			prop_assert!(random_chunk(&seed, total) < total);
+		}
+	}
+}
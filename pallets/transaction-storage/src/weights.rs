@@ -53,48 +53,535 @@ use core::marker::PhantomData;
 /// Weight functions needed for pallet_transaction_storage.
 pub trait WeightInfo {
 	fn store(l: u32, ) -> Weight;
-	fn renew() -> Weight;
-	fn check_proof_max() -> Weight;
+	fn store_via_authorizer(l: u32, ) -> Weight;
+	fn renew(s: u32, ) -> Weight;
+	fn check_proof(n: u32, ) -> Weight;
+	fn expire_authorizations(n: u32, ) -> Weight;
+	fn authorize_account() -> Weight;
+	fn authorize_preimage() -> Weight;
+	fn set_standing_quota() -> Weight;
+	fn clear_standing_quota() -> Weight;
+	fn set_lifetime_cap() -> Weight;
+	fn add_authorizer() -> Weight;
+	fn remove_authorizer() -> Weight;
+	fn force_remove() -> Weight;
+	fn schedule_removal() -> Weight;
+	fn cancel_removal() -> Weight;
+	fn on_initialize_pruning() -> Weight;
+	fn execute_scheduled_removals(n: u32, ) -> Weight;
+	fn on_finalize(n: u32, ) -> Weight;
+	fn store_block(n: u32, ) -> Weight;
+	fn authorize_preimages(n: u32, ) -> Weight;
+	fn check_proof_worst_case() -> Weight;
+	fn migrate_block_transactions(n: u32, ) -> Weight;
+	fn clear_bar() -> Weight;
+	fn request_proof() -> Weight;
+	fn check_challenge_proofs(n: u32, ) -> Weight;
+	fn verify_content(l: u32, ) -> Weight;
+	fn prune_tombstone() -> Weight;
+	fn create_group(m: u32, ) -> Weight;
+	fn authorize_group() -> Weight;
+	fn add_group_member() -> Weight;
+	fn remove_group_member() -> Weight;
+	fn revoke_account_authorization(n: u32, ) -> Weight;
+	fn note_preimage(l: u32, ) -> Weight;
 }
 
 /// Weights for pallet_transaction_storage using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-	/// Storage: TransactionStorage ByteFee (r:1 w:0)
-	/// Proof: TransactionStorage ByteFee (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
-	/// Storage: TransactionStorage EntryFee (r:1 w:0)
-	/// Proof: TransactionStorage EntryFee (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
-	/// Storage: TransactionStorage BlockTransactions (r:1 w:1)
-	/// Proof: TransactionStorage BlockTransactions (max_values: Some(1), max_size: Some(36866), added: 37361, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage IndexedExtrinsic (r:1 w:1)
+	/// Proof: TransactionStorage IndexedExtrinsic (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RemovedContent (r:1 w:0)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCounts (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCounts (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Barred (r:1 w:0)
+	/// Proof: TransactionStorage Barred (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PreimageReservations (r:1 w:0)
+	/// Proof: TransactionStorage PreimageReservations (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage LifetimeUsed (r:1 w:1)
+	/// Proof: TransactionStorage LifetimeUsed (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage LifetimeCaps (r:1 w:0)
+	/// Proof: TransactionStorage LifetimeCaps (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage UsageHistory (r:1 w:1)
+	/// Proof: TransactionStorage UsageHistory (max_values: None, max_size: Some(289), added: 2764, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage SatisfiedPreimages (r:1 w:0)
+	/// Proof: TransactionStorage SatisfiedPreimages (max_values: None, max_size: Some(36), added: 2511, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:4 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AllowedHashes (r:1 w:0)
+	/// Proof: TransactionStorage AllowedHashes (max_values: None, max_size: Some(4145), added: 6620, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage StandingQuotas (r:1 w:1)
+	/// Proof: TransactionStorage StandingQuotas (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage HeldQuota (r:1 w:0)
+	/// Proof: TransactionStorage HeldQuota (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage GroupOf (r:1 w:0)
+	/// Proof: TransactionStorage GroupOf (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage PendingAuthorizations (max_values: None, max_size: Some(1651), added: 4126, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForBlockTransactions (r:1 w:1)
+	/// Proof: TransactionStorage CounterForBlockTransactions (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockChunks (r:1 w:1)
+	/// Proof: TransactionStorage BlockChunks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockTransactions (r:0 w:1)
+	/// Proof: TransactionStorage BlockTransactions (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCountsByBlock (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCountsByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedContent (r:1 w:1)
+	/// Proof: TransactionStorage OwnedContent (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedContentCount (r:1 w:1)
+	/// Proof: TransactionStorage OwnedContentCount (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedByBlock (r:1 w:1)
+	/// Proof: TransactionStorage OwnedByBlock (max_values: None, max_size: Some(32791), added: 35266, mode: MaxEncodedLen)
 	/// The range of component `l` is `[1, 8388608]`.
 	fn store(l: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `176`
-		//  Estimated: `38351`
-		// Minimum execution time: 34_844_000 picoseconds.
-		Weight::from_parts(35_489_000, 38351)
+		//  Measured:  `143`
+		//  Estimated: `36256`
+		// Minimum execution time: 33_412_000 picoseconds.
+		Weight::from_parts(33_796_000, 36256)
 			// Standard Error: 11
-			.saturating_add(Weight::from_parts(6_912, 0).saturating_mul(l.into()))
-			.saturating_add(T::DbWeight::get().reads(3_u64))
-			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_parts(6_909, 0).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().reads(24_u64))
+			.saturating_add(T::DbWeight::get().writes(14_u64))
 	}
+	/// Storage: TransactionStorage Authorizers (r:1 w:0)
+	/// Proof: TransactionStorage Authorizers (max_values: None, max_size: Some(48), added: 2523, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage IndexedExtrinsic (r:1 w:1)
+	/// Proof: TransactionStorage IndexedExtrinsic (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RemovedContent (r:1 w:0)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCounts (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCounts (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizerStores (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizerStores (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForBlockTransactions (r:1 w:1)
+	/// Proof: TransactionStorage CounterForBlockTransactions (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockChunks (r:1 w:1)
+	/// Proof: TransactionStorage BlockChunks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockTransactions (r:0 w:1)
+	/// Proof: TransactionStorage BlockTransactions (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCountsByBlock (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCountsByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// The range of component `l` is `[1, 8388608]`.
+	fn store_via_authorizer(l: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `19872`
+		// Minimum execution time: 27_508_000 picoseconds.
+		Weight::from_parts(27_914_000, 19872)
+			// Standard Error: 11
+			.saturating_add(Weight::from_parts(6_907, 0).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+	}
+	/// Storage: TransactionStorage IndexedExtrinsic (r:1 w:1)
+	/// Proof: TransactionStorage IndexedExtrinsic (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage TransactionInfos (r:1 w:0)
+	/// Proof: TransactionStorage TransactionInfos (max_values: None, max_size: Some(108), added: 2583, mode: MaxEncodedLen)
 	/// Storage: TransactionStorage Transactions (r:1 w:0)
 	/// Proof: TransactionStorage Transactions (max_values: None, max_size: Some(36886), added: 39361, mode: MaxEncodedLen)
-	/// Storage: TransactionStorage ByteFee (r:1 w:0)
-	/// Proof: TransactionStorage ByteFee (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
-	/// Storage: TransactionStorage EntryFee (r:1 w:0)
-	/// Proof: TransactionStorage EntryFee (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
-	/// Storage: TransactionStorage BlockTransactions (r:1 w:1)
-	/// Proof: TransactionStorage BlockTransactions (max_values: Some(1), max_size: Some(36866), added: 37361, mode: MaxEncodedLen)
-	fn renew() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `326`
+	/// Storage: TransactionStorage RemovedContent (r:1 w:0)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingRemovals (r:1 w:0)
+	/// Proof: TransactionStorage PendingRemovals (max_values: None, max_size: Some(37), added: 2512, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Barred (r:1 w:0)
+	/// Proof: TransactionStorage Barred (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PreimageReservations (r:1 w:0)
+	/// Proof: TransactionStorage PreimageReservations (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage LifetimeUsed (r:1 w:1)
+	/// Proof: TransactionStorage LifetimeUsed (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage LifetimeCaps (r:1 w:0)
+	/// Proof: TransactionStorage LifetimeCaps (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage UsageHistory (r:1 w:1)
+	/// Proof: TransactionStorage UsageHistory (max_values: None, max_size: Some(289), added: 2764, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage SatisfiedPreimages (r:1 w:0)
+	/// Proof: TransactionStorage SatisfiedPreimages (max_values: None, max_size: Some(36), added: 2511, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:4 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AllowedHashes (r:1 w:0)
+	/// Proof: TransactionStorage AllowedHashes (max_values: None, max_size: Some(4145), added: 6620, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage StandingQuotas (r:1 w:1)
+	/// Proof: TransactionStorage StandingQuotas (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage HeldQuota (r:1 w:0)
+	/// Proof: TransactionStorage HeldQuota (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage GroupOf (r:1 w:0)
+	/// Proof: TransactionStorage GroupOf (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage PendingAuthorizations (max_values: None, max_size: Some(1651), added: 4126, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedByBlock (r:2 w:1)
+	/// Proof: TransactionStorage OwnedByBlock (max_values: None, max_size: Some(32791), added: 35266, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedContent (r:2 w:1)
+	/// Proof: TransactionStorage OwnedContent (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForBlockTransactions (r:1 w:1)
+	/// Proof: TransactionStorage CounterForBlockTransactions (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockChunks (r:1 w:1)
+	/// Proof: TransactionStorage BlockChunks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockTransactions (r:0 w:1)
+	/// Proof: TransactionStorage BlockTransactions (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCountsByBlock (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCountsByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCounts (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCounts (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedContentCount (r:1 w:1)
+	/// Proof: TransactionStorage OwnedContentCount (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CompressedContent (r:1 w:1)
+	/// Proof: TransactionStorage CompressedContent (max_values: None, max_size: Some(72), added: 2547, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CompressedByBlock (r:1 w:1)
+	/// Proof: TransactionStorage CompressedByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage UncompressedContent (r:0 w:1)
+	/// Proof: TransactionStorage UncompressedContent (max_values: None, max_size: Some(64), added: 2539, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ContentNamespaces (r:1 w:1)
+	/// Proof: TransactionStorage ContentNamespaces (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage NamespacedByBlock (r:1 w:1)
+	/// Proof: TransactionStorage NamespacedByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// The range of component `s` is `[1, 512]`.
+	fn renew(s: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0 + s * (72 ±0)`
 		//  Estimated: `40351`
-		// Minimum execution time: 48_244_000 picoseconds.
-		Weight::from_parts(50_939_000, 40351)
-			.saturating_add(T::DbWeight::get().reads(4_u64))
+		// Minimum execution time: 21_120_000 picoseconds.
+		Weight::from_parts(22_305_000, 40351)
+			// Standard Error: 141
+			.saturating_add(Weight::from_parts(58_932, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(33_u64))
+			.saturating_add(T::DbWeight::get().writes(19_u64))
+	}
+	/// Storage: TransactionStorage ProofChecked (r:1 w:1)
+	/// Proof: TransactionStorage ProofChecked (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage StoragePeriod (r:1 w:0)
+	/// Proof: TransactionStorage StoragePeriod (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChunkCount (r:1 w:0)
+	/// Proof: TransactionStorage ChunkCount (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: System ParentHash (r:1 w:0)
+	/// Proof: System ParentHash (max_values: Some(1), max_size: Some(32), added: 527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChunkEnds (r:1 w:0)
+	/// Proof: TransactionStorage ChunkEnds (max_values: None, max_size: Some(2070), added: 4545, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Transactions (r:1 w:0)
+	/// Proof: TransactionStorage Transactions (max_values: None, max_size: Some(36886), added: 39361, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCounts (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCounts (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// The range of component `n` is `[1, 512]`.
+	fn check_proof(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `227 + n * (76 ±0)`
+		//  Estimated: `42866`
+		// Minimum execution time: 40_862_000 picoseconds.
+		Weight::from_parts(41_790_000, 42866)
+			// Standard Error: 21
+			.saturating_add(Weight::from_parts(7_208, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage AuthorizationsByExpiry (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationsByExpiry (max_values: None, max_size: Some(36882), added: 39357, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:512 w:512)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AllowedHashes (r:0 w:512)
+	/// Proof: TransactionStorage AllowedHashes (max_values: None, max_size: Some(4145), added: 6620, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OutstandingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage OutstandingAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 512]`.
+	fn expire_authorizations(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `139 + n * (119 ±0)`
+		//  Estimated: `40347 + n * (2557 ±0)`
+		// Minimum execution time: 5_312_000 picoseconds.
+		Weight::from_parts(7_084_000, 40347)
+			// Standard Error: 3_197
+			.saturating_add(Weight::from_parts(9_734_529, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2557).saturating_mul(n.into()))
+	}
+	/// Storage: TransactionStorage AllowedHashes (r:1 w:1)
+	/// Proof: TransactionStorage AllowedHashes (max_values: None, max_size: Some(4145), added: 6620, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage MinAuthorizationExpiryMinus1 (r:1 w:1)
+	/// Proof: TransactionStorage MinAuthorizationExpiryMinus1 (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationsByExpiry (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationsByExpiry (max_values: None, max_size: Some(36882), added: 39357, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OutstandingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage OutstandingAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage BlockAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage NextRecentAuthorization (r:1 w:1)
+	/// Proof: TransactionStorage NextRecentAuthorization (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RecentAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage RecentAuthorizations (max_values: Some(1), max_size: Some(1569), added: 2064, mode: MaxEncodedLen)
+	fn authorize_account() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4233`
+		//  Estimated: `40347`
+		// Minimum execution time: 26_102_000 picoseconds.
+		Weight::from_parts(27_418_000, 40347)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage MinAuthorizationExpiryMinus1 (r:1 w:1)
+	/// Proof: TransactionStorage MinAuthorizationExpiryMinus1 (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationsByExpiry (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationsByExpiry (max_values: None, max_size: Some(36882), added: 39357, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OutstandingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage OutstandingAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage BlockAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage NextRecentAuthorization (r:1 w:1)
+	/// Proof: TransactionStorage NextRecentAuthorization (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RecentAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage RecentAuthorizations (max_values: Some(1), max_size: Some(1569), added: 2064, mode: MaxEncodedLen)
+	fn authorize_preimage() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `88`
+		//  Estimated: `40347`
+		// Minimum execution time: 17_021_000 picoseconds.
+		Weight::from_parts(17_863_000, 40347)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+	}
+	/// Storage: TransactionStorage StandingQuotas (r:0 w:1)
+	/// Proof: TransactionStorage StandingQuotas (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
+	fn set_standing_quota() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 9_318_000 picoseconds.
+		Weight::from_parts(9_712_000, 0)
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: TransactionStorage StandingQuotas (r:1 w:1)
+	/// Proof: TransactionStorage StandingQuotas (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
+	fn clear_standing_quota() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `111`
+		//  Estimated: `3541`
+		// Minimum execution time: 12_761_000 picoseconds.
+		Weight::from_parts(13_204_000, 3541)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: TransactionStorage LifetimeCaps (r:0 w:1)
+	/// Proof: TransactionStorage LifetimeCaps (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	fn set_lifetime_cap() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_602_000 picoseconds.
+		Weight::from_parts(8_937_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: TransactionStorage Authorizers (r:1 w:1)
+	/// Proof: TransactionStorage Authorizers (max_values: None, max_size: Some(48), added: 2523, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForAuthorizers (r:1 w:1)
+	/// Proof: TransactionStorage CounterForAuthorizers (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	fn add_authorizer() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `76`
+		//  Estimated: `3513`
+		// Minimum execution time: 12_677_000 picoseconds.
+		Weight::from_parts(13_116_000, 3513)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage Authorizers (r:1 w:1)
+	/// Proof: TransactionStorage Authorizers (max_values: None, max_size: Some(48), added: 2523, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForAuthorizers (r:1 w:1)
+	/// Proof: TransactionStorage CounterForAuthorizers (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	fn remove_authorizer() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `142`
+		//  Estimated: `3513`
+		// Minimum execution time: 14_833_000 picoseconds.
+		Weight::from_parts(15_340_000, 3513)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage RemovedContent (r:1 w:1)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingRemovals (r:1 w:1)
+	/// Proof: TransactionStorage PendingRemovals (max_values: None, max_size: Some(37), added: 2512, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RemovalSchedule (r:1 w:1)
+	/// Proof: TransactionStorage RemovalSchedule (max_values: None, max_size: Some(534), added: 3009, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForTombstones (r:1 w:1)
+	/// Proof: TransactionStorage CounterForTombstones (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Tombstones (r:0 w:1)
+	/// Proof: TransactionStorage Tombstones (max_values: None, max_size: Some(33), added: 2508, mode: MaxEncodedLen)
+	fn force_remove() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `659`
+		//  Estimated: `3999`
+		// Minimum execution time: 25_472_000 picoseconds.
+		Weight::from_parts(26_318_000, 3999)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: TransactionStorage RemovedContent (r:1 w:0)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingRemovals (r:1 w:1)
+	/// Proof: TransactionStorage PendingRemovals (max_values: None, max_size: Some(37), added: 2512, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RemovalSchedule (r:1 w:1)
+	/// Proof: TransactionStorage RemovalSchedule (max_values: None, max_size: Some(534), added: 3009, mode: MaxEncodedLen)
+	fn schedule_removal() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `627`
+		//  Estimated: `3999`
+		// Minimum execution time: 19_284_000 picoseconds.
+		Weight::from_parts(19_957_000, 3999)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage PendingRemovals (r:1 w:1)
+	/// Proof: TransactionStorage PendingRemovals (max_values: None, max_size: Some(37), added: 2512, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RemovalSchedule (r:1 w:1)
+	/// Proof: TransactionStorage RemovalSchedule (max_values: None, max_size: Some(534), added: 3009, mode: MaxEncodedLen)
+	fn cancel_removal() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `659`
+		//  Estimated: `3999`
+		// Minimum execution time: 17_493_000 picoseconds.
+		Weight::from_parts(18_066_000, 3999)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage StoragePeriod (r:1 w:0)
+	/// Proof: TransactionStorage StoragePeriod (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Transactions (r:0 w:1)
+	/// Proof: TransactionStorage Transactions (max_values: None, max_size: Some(36886), added: 39361, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChunkEnds (r:0 w:1)
+	/// Proof: TransactionStorage ChunkEnds (max_values: None, max_size: Some(2070), added: 4545, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChunkCount (r:0 w:1)
+	/// Proof: TransactionStorage ChunkCount (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	fn on_initialize_pruning() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `32`
+		//  Estimated: `1489`
+		// Minimum execution time: 5_901_000 picoseconds.
+		Weight::from_parts(6_174_000, 1489)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: TransactionStorage RemovalSchedule (r:1 w:1)
+	/// Proof: TransactionStorage RemovalSchedule (max_values: None, max_size: Some(534), added: 3009, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForTombstones (r:1 w:1)
+	/// Proof: TransactionStorage CounterForTombstones (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingRemovals (r:16 w:16)
+	/// Proof: TransactionStorage PendingRemovals (max_values: None, max_size: Some(37), added: 2512, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Tombstones (r:0 w:16)
+	/// Proof: TransactionStorage Tombstones (max_values: None, max_size: Some(33), added: 2508, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RemovedContent (r:0 w:16)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 16]`.
+	fn execute_scheduled_removals(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `115 + n * (69 ±0)`
+		//  Estimated: `3999 + n * (2512 ±0)`
+		// Minimum execution time: 6_388_000 picoseconds.
+		Weight::from_parts(6_743_000, 3999)
+			// Standard Error: 2_318
+			.saturating_add(Weight::from_parts(5_912_407, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(T::DbWeight::get().writes((3_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2512).saturating_mul(n.into()))
+	}
+	/// Storage: TransactionStorage ProofChecked (r:1 w:1)
+	/// Proof: TransactionStorage ProofChecked (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage IndexedExtrinsic (r:0 w:1)
+	/// Proof: TransactionStorage IndexedExtrinsic (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockChunks (r:0 w:1)
+	/// Proof: TransactionStorage BlockChunks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForBlockTransactions (r:1 w:1)
+	/// Proof: TransactionStorage CounterForBlockTransactions (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockTransactions (r:512 w:512)
+	/// Proof: TransactionStorage BlockTransactions (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChunkCount (r:0 w:1)
+	/// Proof: TransactionStorage ChunkCount (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChunkEnds (r:0 w:1)
+	/// Proof: TransactionStorage ChunkEnds (max_values: None, max_size: Some(2070), added: 4545, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage TransactionInfos (r:0 w:512)
+	/// Proof: TransactionStorage TransactionInfos (max_values: None, max_size: Some(108), added: 2583, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 512]`.
+	fn on_finalize(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `36 + n * (88 ±0)`
+		//  Estimated: `1486 + n * (2563 ±0)`
+		// Minimum execution time: 7_240_000 picoseconds.
+		Weight::from_parts(7_788_000, 1486)
+			// Standard Error: 415
+			.saturating_add(Weight::from_parts(3_419_502, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2563).saturating_mul(n.into()))
+	}
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForBlockTransactions (r:1 w:1)
+	/// Proof: TransactionStorage CounterForBlockTransactions (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockChunks (r:1 w:1)
+	/// Proof: TransactionStorage BlockChunks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage IndexedExtrinsic (r:0 w:1)
+	/// Proof: TransactionStorage IndexedExtrinsic (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockTransactions (r:512 w:512)
+	/// Proof: TransactionStorage BlockTransactions (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ProofChecked (r:1 w:1)
+	/// Proof: TransactionStorage ProofChecked (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChunkCount (r:0 w:1)
+	/// Proof: TransactionStorage ChunkCount (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Transactions (r:0 w:1)
+	/// Proof: TransactionStorage Transactions (max_values: None, max_size: Some(36886), added: 39361, mode: MaxEncodedLen)
+	/// The range of component `n` is `[1, 512]`.
+	fn store_block(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `143 + n * (88 ±0)`
+		//  Estimated: `3547 + n * (2563 ±0)`
+		// Minimum execution time: 49_871_000 picoseconds.
+		Weight::from_parts(12_480_000, 3547)
+			// Standard Error: 2_946
+			.saturating_add(Weight::from_parts(38_214_507, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2563).saturating_mul(n.into()))
+	}
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:512 w:512)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage MinAuthorizationExpiryMinus1 (r:1 w:1)
+	/// Proof: TransactionStorage MinAuthorizationExpiryMinus1 (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationsByExpiry (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationsByExpiry (max_values: None, max_size: Some(36882), added: 39357, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OutstandingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage OutstandingAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage BlockAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage NextRecentAuthorization (r:1 w:1)
+	/// Proof: TransactionStorage NextRecentAuthorization (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RecentAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage RecentAuthorizations (max_values: Some(1), max_size: Some(1569), added: 2064, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 512]`.
+	fn authorize_preimages(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `88`
+		//  Estimated: `40347 + n * (2557 ±0)`
+		// Minimum execution time: 8_733_000 picoseconds.
+		Weight::from_parts(9_104_000, 40347)
+			// Standard Error: 1_183
+			.saturating_add(Weight::from_parts(9_586_231, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2557).saturating_mul(n.into()))
+	}
 	/// Storage: TransactionStorage ProofChecked (r:1 w:1)
 	/// Proof: TransactionStorage ProofChecked (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
 	/// Storage: TransactionStorage StoragePeriod (r:1 w:0)
@@ -103,56 +590,797 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	/// Proof: TransactionStorage ChunkCount (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
 	/// Storage: System ParentHash (r:1 w:0)
 	/// Proof: System ParentHash (max_values: Some(1), max_size: Some(32), added: 527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChunkEnds (r:1 w:0)
+	/// Proof: TransactionStorage ChunkEnds (max_values: None, max_size: Some(2070), added: 4545, mode: MaxEncodedLen)
 	/// Storage: TransactionStorage Transactions (r:1 w:0)
 	/// Proof: TransactionStorage Transactions (max_values: None, max_size: Some(36886), added: 39361, mode: MaxEncodedLen)
-	fn check_proof_max() -> Weight {
+	/// Storage: TransactionStorage ChallengeCounts (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCounts (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	fn check_proof_worst_case() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `37145`
-		//  Estimated: `40351`
-		// Minimum execution time: 80_913_000 picoseconds.
-		Weight::from_parts(84_812_000, 40351)
-			.saturating_add(T::DbWeight::get().reads(5_u64))
+		//  Measured:  `39185`
+		//  Estimated: `42866`
+		// Minimum execution time: 62_051_000 picoseconds.
+		Weight::from_parts(63_417_000, 42866)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage Transactions (r:1 w:1)
+	/// Proof: TransactionStorage Transactions (max_values: None, max_size: Some(36886), added: 39361, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage TransactionInfos (r:0 w:512)
+	/// Proof: TransactionStorage TransactionInfos (max_values: None, max_size: Some(108), added: 2583, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 512]`.
+	fn migrate_block_transactions(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `38 + n * (72 ±0)`
+		//  Estimated: `39361`
+		// Minimum execution time: 5_118_000 picoseconds.
+		Weight::from_parts(5_342_000, 39361)
+			// Standard Error: 312
+			.saturating_add(Weight::from_parts(3_047_122, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
+	/// Storage: TransactionStorage Barred (r:0 w:1)
+	/// Proof: TransactionStorage Barred (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Strikes (r:0 w:1)
+	/// Proof: TransactionStorage Strikes (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn clear_bar() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 10_038_000 picoseconds.
+		Weight::from_parts(10_412_000, 0)
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage RemovedContent (r:1 w:0)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingRemovals (r:1 w:0)
+	/// Proof: TransactionStorage PendingRemovals (max_values: None, max_size: Some(37), added: 2512, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCounts (r:1 w:0)
+	/// Proof: TransactionStorage ChallengeCounts (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage TransactionInfos (r:512 w:0)
+	/// Proof: TransactionStorage TransactionInfos (max_values: None, max_size: Some(108), added: 2583, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Challenges (r:1 w:1)
+	/// Proof: TransactionStorage Challenges (max_values: None, max_size: Some(1749), added: 4224, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Barred (r:1 w:0)
+	/// Proof: TransactionStorage Barred (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage UsageHistory (r:1 w:1)
+	/// Proof: TransactionStorage UsageHistory (max_values: None, max_size: Some(289), added: 2764, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AllowedHashes (r:1 w:0)
+	/// Proof: TransactionStorage AllowedHashes (max_values: None, max_size: Some(4145), added: 6620, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:3 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage StandingQuotas (r:1 w:1)
+	/// Proof: TransactionStorage StandingQuotas (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage HeldQuota (r:1 w:0)
+	/// Proof: TransactionStorage HeldQuota (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage GroupOf (r:1 w:0)
+	/// Proof: TransactionStorage GroupOf (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage PendingAuthorizations (max_values: None, max_size: Some(1651), added: 4126, mode: MaxEncodedLen)
+	fn request_proof() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `287`
+		//  Estimated: `1327710`
+		// Minimum execution time: 26_508_000 picoseconds.
+		Weight::from_parts(27_262_000, 1327710)
+			.saturating_add(T::DbWeight::get().reads(526_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: TransactionStorage Challenges (r:1 w:1)
+	/// Proof: TransactionStorage Challenges (max_values: None, max_size: Some(1749), added: 4224, mode: MaxEncodedLen)
+	/// Storage: System ParentHash (r:1 w:0)
+	/// Proof: System ParentHash (max_values: Some(1), max_size: Some(32), added: 527, mode: MaxEncodedLen)
+	/// The range of component `n` is `[1, 16]`.
+	fn check_challenge_proofs(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `36 + n * (108 ±0)`
+		//  Estimated: `5214`
+		// Minimum execution time: 5_894_000 picoseconds.
+		Weight::from_parts(6_126_000, 5214)
+			// Standard Error: 9_412
+			.saturating_add(Weight::from_parts(58_311_204, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: TransactionStorage IndexedExtrinsic (r:1 w:1)
+	/// Proof: TransactionStorage IndexedExtrinsic (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage TransactionInfos (r:1 w:0)
+	/// Proof: TransactionStorage TransactionInfos (max_values: None, max_size: Some(108), added: 2583, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RemovedContent (r:1 w:0)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingRemovals (r:1 w:0)
+	/// Proof: TransactionStorage PendingRemovals (max_values: None, max_size: Some(37), added: 2512, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Barred (r:1 w:0)
+	/// Proof: TransactionStorage Barred (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PreimageReservations (r:1 w:0)
+	/// Proof: TransactionStorage PreimageReservations (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage LifetimeUsed (r:1 w:1)
+	/// Proof: TransactionStorage LifetimeUsed (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage LifetimeCaps (r:1 w:0)
+	/// Proof: TransactionStorage LifetimeCaps (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage UsageHistory (r:1 w:1)
+	/// Proof: TransactionStorage UsageHistory (max_values: None, max_size: Some(289), added: 2764, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage SatisfiedPreimages (r:1 w:0)
+	/// Proof: TransactionStorage SatisfiedPreimages (max_values: None, max_size: Some(36), added: 2511, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:4 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AllowedHashes (r:1 w:0)
+	/// Proof: TransactionStorage AllowedHashes (max_values: None, max_size: Some(4145), added: 6620, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage StandingQuotas (r:1 w:1)
+	/// Proof: TransactionStorage StandingQuotas (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage HeldQuota (r:1 w:0)
+	/// Proof: TransactionStorage HeldQuota (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage GroupOf (r:1 w:0)
+	/// Proof: TransactionStorage GroupOf (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage PendingAuthorizations (max_values: None, max_size: Some(1651), added: 4126, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedByBlock (r:2 w:1)
+	/// Proof: TransactionStorage OwnedByBlock (max_values: None, max_size: Some(32791), added: 35266, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedContent (r:2 w:1)
+	/// Proof: TransactionStorage OwnedContent (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForBlockTransactions (r:1 w:1)
+	/// Proof: TransactionStorage CounterForBlockTransactions (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockChunks (r:1 w:1)
+	/// Proof: TransactionStorage BlockChunks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockTransactions (r:0 w:1)
+	/// Proof: TransactionStorage BlockTransactions (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCountsByBlock (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCountsByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCounts (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCounts (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedContentCount (r:1 w:1)
+	/// Proof: TransactionStorage OwnedContentCount (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CompressedContent (r:1 w:1)
+	/// Proof: TransactionStorage CompressedContent (max_values: None, max_size: Some(72), added: 2547, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CompressedByBlock (r:1 w:1)
+	/// Proof: TransactionStorage CompressedByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage UncompressedContent (r:0 w:1)
+	/// Proof: TransactionStorage UncompressedContent (max_values: None, max_size: Some(64), added: 2539, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ContentNamespaces (r:1 w:1)
+	/// Proof: TransactionStorage ContentNamespaces (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage NamespacedByBlock (r:1 w:1)
+	/// Proof: TransactionStorage NamespacedByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// The range of component `l` is `[1, 8388608]`.
+	fn verify_content(l: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `182`
+		//  Estimated: `36256`
+		// Minimum execution time: 49_991_000 picoseconds.
+		Weight::from_parts(51_809_000, 36256)
+			// Standard Error: 12
+			.saturating_add(Weight::from_parts(6_921, 0).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().reads(32_u64))
+			.saturating_add(T::DbWeight::get().writes(19_u64))
+	}
+	/// Storage: TransactionStorage Tombstones (r:1 w:1)
+	/// Proof: TransactionStorage Tombstones (max_values: None, max_size: Some(33), added: 2508, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForTombstones (r:1 w:1)
+	/// Proof: TransactionStorage CounterForTombstones (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	fn prune_tombstone() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `109`
+		//  Estimated: `3498`
+		// Minimum execution time: 13_815_000 picoseconds.
+		Weight::from_parts(14_207_000, 3498)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage NextGroupId (r:1 w:1)
+	/// Proof: TransactionStorage NextGroupId (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage GroupOf (r:64 w:64)
+	/// Proof: TransactionStorage GroupOf (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Groups (r:0 w:1)
+	/// Proof: TransactionStorage Groups (max_values: None, max_size: Some(2062), added: 4537, mode: MaxEncodedLen)
+	/// The range of component `m` is `[0, 64]`.
+	fn create_group(m: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `42`
+		//  Estimated: `1489 + m * (2527 ±0)`
+		// Minimum execution time: 11_260_000 picoseconds.
+		Weight::from_parts(11_842_000, 1489)
+			// Standard Error: 1_906
+			.saturating_add(Weight::from_parts(4_317_209, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(m.into())))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(m.into())))
+			.saturating_add(Weight::from_parts(0, 2527).saturating_mul(m.into()))
+	}
+	/// Storage: TransactionStorage Groups (r:1 w:0)
+	/// Proof: TransactionStorage Groups (max_values: None, max_size: Some(2062), added: 4537, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage MinAuthorizationExpiryMinus1 (r:1 w:1)
+	/// Proof: TransactionStorage MinAuthorizationExpiryMinus1 (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationsByExpiry (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationsByExpiry (max_values: None, max_size: Some(36882), added: 39357, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OutstandingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage OutstandingAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage BlockAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage NextRecentAuthorization (r:1 w:1)
+	/// Proof: TransactionStorage NextRecentAuthorization (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RecentAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage RecentAuthorizations (max_values: Some(1), max_size: Some(1569), added: 2064, mode: MaxEncodedLen)
+	fn authorize_group() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2170`
+		//  Estimated: `40347`
+		// Minimum execution time: 22_734_000 picoseconds.
+		Weight::from_parts(23_516_000, 40347)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+	}
+	/// Storage: TransactionStorage GroupOf (r:1 w:1)
+	/// Proof: TransactionStorage GroupOf (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Groups (r:1 w:1)
+	/// Proof: TransactionStorage Groups (max_values: None, max_size: Some(2062), added: 4537, mode: MaxEncodedLen)
+	fn add_group_member() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2133`
+		//  Estimated: `5527`
+		// Minimum execution time: 18_551_000 picoseconds.
+		Weight::from_parts(19_208_000, 5527)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage GroupOf (r:1 w:1)
+	/// Proof: TransactionStorage GroupOf (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Groups (r:1 w:1)
+	/// Proof: TransactionStorage Groups (max_values: None, max_size: Some(2062), added: 4537, mode: MaxEncodedLen)
+	fn remove_group_member() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2271`
+		//  Estimated: `5527`
+		// Minimum execution time: 20_940_000 picoseconds.
+		Weight::from_parts(21_674_000, 5527)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AccountAuthorizationExpiries (r:1 w:1)
+	/// Proof: TransactionStorage AccountAuthorizationExpiries (max_values: None, max_size: Some(305), added: 2780, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationsByExpiry (r:64 w:64)
+	/// Proof: TransactionStorage AuthorizationsByExpiry (max_values: None, max_size: Some(36882), added: 39357, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OutstandingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage OutstandingAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage HeldQuota (r:0 w:1)
+	/// Proof: TransactionStorage HeldQuota (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 64]`.
+	fn revoke_account_authorization(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `298 + n * (36867 ±0)`
+		//  Estimated: `3770 + n * (39357 ±0)`
+		// Minimum execution time: 17_962_000 picoseconds.
+		Weight::from_parts(19_140_512, 3770)
+			// Standard Error: 21_908
+			.saturating_add(Weight::from_parts(36_457_301, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 39357).saturating_mul(n.into()))
+	}
+	/// Storage: TransactionStorage RemovedContent (r:1 w:0)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage InlineData (r:1 w:1)
+	/// Proof: TransactionStorage InlineData (max_values: None, max_size: Some(65572), added: 68047, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage InlineByBlock (r:1 w:1)
+	/// Proof: TransactionStorage InlineByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage InlineDataRequests (r:1 w:1)
+	/// Proof: TransactionStorage InlineDataRequests (max_values: None, max_size: Some(36), added: 2511, mode: MaxEncodedLen)
+	/// The range of component `l` is `[1, 65536]`.
+	fn note_preimage(l: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `16452`
+		//  Estimated: `69037`
+		// Minimum execution time: 31_507_000 picoseconds.
+		Weight::from_parts(32_260_117, 69037)
+			// Standard Error: 4
+			.saturating_add(Weight::from_parts(1_391, 0).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-	/// Storage: TransactionStorage ByteFee (r:1 w:0)
-	/// Proof: TransactionStorage ByteFee (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
-	/// Storage: TransactionStorage EntryFee (r:1 w:0)
-	/// Proof: TransactionStorage EntryFee (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
-	/// Storage: TransactionStorage BlockTransactions (r:1 w:1)
-	/// Proof: TransactionStorage BlockTransactions (max_values: Some(1), max_size: Some(36866), added: 37361, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage IndexedExtrinsic (r:1 w:1)
+	/// Proof: TransactionStorage IndexedExtrinsic (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RemovedContent (r:1 w:0)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCounts (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCounts (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Barred (r:1 w:0)
+	/// Proof: TransactionStorage Barred (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PreimageReservations (r:1 w:0)
+	/// Proof: TransactionStorage PreimageReservations (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage LifetimeUsed (r:1 w:1)
+	/// Proof: TransactionStorage LifetimeUsed (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage LifetimeCaps (r:1 w:0)
+	/// Proof: TransactionStorage LifetimeCaps (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage UsageHistory (r:1 w:1)
+	/// Proof: TransactionStorage UsageHistory (max_values: None, max_size: Some(289), added: 2764, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage SatisfiedPreimages (r:1 w:0)
+	/// Proof: TransactionStorage SatisfiedPreimages (max_values: None, max_size: Some(36), added: 2511, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:4 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AllowedHashes (r:1 w:0)
+	/// Proof: TransactionStorage AllowedHashes (max_values: None, max_size: Some(4145), added: 6620, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage StandingQuotas (r:1 w:1)
+	/// Proof: TransactionStorage StandingQuotas (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage HeldQuota (r:1 w:0)
+	/// Proof: TransactionStorage HeldQuota (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage GroupOf (r:1 w:0)
+	/// Proof: TransactionStorage GroupOf (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage PendingAuthorizations (max_values: None, max_size: Some(1651), added: 4126, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForBlockTransactions (r:1 w:1)
+	/// Proof: TransactionStorage CounterForBlockTransactions (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockChunks (r:1 w:1)
+	/// Proof: TransactionStorage BlockChunks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockTransactions (r:0 w:1)
+	/// Proof: TransactionStorage BlockTransactions (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCountsByBlock (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCountsByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedContent (r:1 w:1)
+	/// Proof: TransactionStorage OwnedContent (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedContentCount (r:1 w:1)
+	/// Proof: TransactionStorage OwnedContentCount (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedByBlock (r:1 w:1)
+	/// Proof: TransactionStorage OwnedByBlock (max_values: None, max_size: Some(32791), added: 35266, mode: MaxEncodedLen)
 	/// The range of component `l` is `[1, 8388608]`.
 	fn store(l: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `176`
-		//  Estimated: `38351`
-		// Minimum execution time: 34_844_000 picoseconds.
-		Weight::from_parts(35_489_000, 38351)
+		//  Measured:  `143`
+		//  Estimated: `36256`
+		// Minimum execution time: 33_412_000 picoseconds.
+		Weight::from_parts(33_796_000, 36256)
 			// Standard Error: 11
-			.saturating_add(Weight::from_parts(6_912, 0).saturating_mul(l.into()))
-			.saturating_add(RocksDbWeight::get().reads(3_u64))
-			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_parts(6_909, 0).saturating_mul(l.into()))
+			.saturating_add(RocksDbWeight::get().reads(24_u64))
+			.saturating_add(RocksDbWeight::get().writes(14_u64))
 	}
+	/// Storage: TransactionStorage Authorizers (r:1 w:0)
+	/// Proof: TransactionStorage Authorizers (max_values: None, max_size: Some(48), added: 2523, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage IndexedExtrinsic (r:1 w:1)
+	/// Proof: TransactionStorage IndexedExtrinsic (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RemovedContent (r:1 w:0)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCounts (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCounts (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizerStores (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizerStores (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForBlockTransactions (r:1 w:1)
+	/// Proof: TransactionStorage CounterForBlockTransactions (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockChunks (r:1 w:1)
+	/// Proof: TransactionStorage BlockChunks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockTransactions (r:0 w:1)
+	/// Proof: TransactionStorage BlockTransactions (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCountsByBlock (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCountsByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// The range of component `l` is `[1, 8388608]`.
+	fn store_via_authorizer(l: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `19872`
+		// Minimum execution time: 27_508_000 picoseconds.
+		Weight::from_parts(27_914_000, 19872)
+			// Standard Error: 11
+			.saturating_add(Weight::from_parts(6_907, 0).saturating_mul(l.into()))
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+	}
+	/// Storage: TransactionStorage IndexedExtrinsic (r:1 w:1)
+	/// Proof: TransactionStorage IndexedExtrinsic (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage TransactionInfos (r:1 w:0)
+	/// Proof: TransactionStorage TransactionInfos (max_values: None, max_size: Some(108), added: 2583, mode: MaxEncodedLen)
 	/// Storage: TransactionStorage Transactions (r:1 w:0)
 	/// Proof: TransactionStorage Transactions (max_values: None, max_size: Some(36886), added: 39361, mode: MaxEncodedLen)
-	/// Storage: TransactionStorage ByteFee (r:1 w:0)
-	/// Proof: TransactionStorage ByteFee (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
-	/// Storage: TransactionStorage EntryFee (r:1 w:0)
-	/// Proof: TransactionStorage EntryFee (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
-	/// Storage: TransactionStorage BlockTransactions (r:1 w:1)
-	/// Proof: TransactionStorage BlockTransactions (max_values: Some(1), max_size: Some(36866), added: 37361, mode: MaxEncodedLen)
-	fn renew() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `326`
+	/// Storage: TransactionStorage RemovedContent (r:1 w:0)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingRemovals (r:1 w:0)
+	/// Proof: TransactionStorage PendingRemovals (max_values: None, max_size: Some(37), added: 2512, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Barred (r:1 w:0)
+	/// Proof: TransactionStorage Barred (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PreimageReservations (r:1 w:0)
+	/// Proof: TransactionStorage PreimageReservations (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage LifetimeUsed (r:1 w:1)
+	/// Proof: TransactionStorage LifetimeUsed (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage LifetimeCaps (r:1 w:0)
+	/// Proof: TransactionStorage LifetimeCaps (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage UsageHistory (r:1 w:1)
+	/// Proof: TransactionStorage UsageHistory (max_values: None, max_size: Some(289), added: 2764, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage SatisfiedPreimages (r:1 w:0)
+	/// Proof: TransactionStorage SatisfiedPreimages (max_values: None, max_size: Some(36), added: 2511, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:4 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AllowedHashes (r:1 w:0)
+	/// Proof: TransactionStorage AllowedHashes (max_values: None, max_size: Some(4145), added: 6620, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage StandingQuotas (r:1 w:1)
+	/// Proof: TransactionStorage StandingQuotas (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage HeldQuota (r:1 w:0)
+	/// Proof: TransactionStorage HeldQuota (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage GroupOf (r:1 w:0)
+	/// Proof: TransactionStorage GroupOf (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage PendingAuthorizations (max_values: None, max_size: Some(1651), added: 4126, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedByBlock (r:2 w:1)
+	/// Proof: TransactionStorage OwnedByBlock (max_values: None, max_size: Some(32791), added: 35266, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedContent (r:2 w:1)
+	/// Proof: TransactionStorage OwnedContent (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForBlockTransactions (r:1 w:1)
+	/// Proof: TransactionStorage CounterForBlockTransactions (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockChunks (r:1 w:1)
+	/// Proof: TransactionStorage BlockChunks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockTransactions (r:0 w:1)
+	/// Proof: TransactionStorage BlockTransactions (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCountsByBlock (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCountsByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCounts (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCounts (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedContentCount (r:1 w:1)
+	/// Proof: TransactionStorage OwnedContentCount (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CompressedContent (r:1 w:1)
+	/// Proof: TransactionStorage CompressedContent (max_values: None, max_size: Some(72), added: 2547, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CompressedByBlock (r:1 w:1)
+	/// Proof: TransactionStorage CompressedByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage UncompressedContent (r:0 w:1)
+	/// Proof: TransactionStorage UncompressedContent (max_values: None, max_size: Some(64), added: 2539, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ContentNamespaces (r:1 w:1)
+	/// Proof: TransactionStorage ContentNamespaces (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage NamespacedByBlock (r:1 w:1)
+	/// Proof: TransactionStorage NamespacedByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// The range of component `s` is `[1, 512]`.
+	fn renew(s: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0 + s * (72 ±0)`
 		//  Estimated: `40351`
-		// Minimum execution time: 48_244_000 picoseconds.
-		Weight::from_parts(50_939_000, 40351)
-			.saturating_add(RocksDbWeight::get().reads(4_u64))
+		// Minimum execution time: 21_120_000 picoseconds.
+		Weight::from_parts(22_305_000, 40351)
+			// Standard Error: 141
+			.saturating_add(Weight::from_parts(58_932, 0).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(33_u64))
+			.saturating_add(RocksDbWeight::get().writes(19_u64))
+	}
+	/// Storage: TransactionStorage ProofChecked (r:1 w:1)
+	/// Proof: TransactionStorage ProofChecked (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage StoragePeriod (r:1 w:0)
+	/// Proof: TransactionStorage StoragePeriod (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChunkCount (r:1 w:0)
+	/// Proof: TransactionStorage ChunkCount (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: System ParentHash (r:1 w:0)
+	/// Proof: System ParentHash (max_values: Some(1), max_size: Some(32), added: 527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChunkEnds (r:1 w:0)
+	/// Proof: TransactionStorage ChunkEnds (max_values: None, max_size: Some(2070), added: 4545, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Transactions (r:1 w:0)
+	/// Proof: TransactionStorage Transactions (max_values: None, max_size: Some(36886), added: 39361, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCounts (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCounts (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// The range of component `n` is `[1, 512]`.
+	fn check_proof(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `227 + n * (76 ±0)`
+		//  Estimated: `42866`
+		// Minimum execution time: 40_862_000 picoseconds.
+		Weight::from_parts(41_790_000, 42866)
+			// Standard Error: 21
+			.saturating_add(Weight::from_parts(7_208, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage AuthorizationsByExpiry (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationsByExpiry (max_values: None, max_size: Some(36882), added: 39357, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:512 w:512)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AllowedHashes (r:0 w:512)
+	/// Proof: TransactionStorage AllowedHashes (max_values: None, max_size: Some(4145), added: 6620, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OutstandingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage OutstandingAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 512]`.
+	fn expire_authorizations(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `139 + n * (119 ±0)`
+		//  Estimated: `40347 + n * (2557 ±0)`
+		// Minimum execution time: 5_312_000 picoseconds.
+		Weight::from_parts(7_084_000, 40347)
+			// Standard Error: 3_197
+			.saturating_add(Weight::from_parts(9_734_529, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2557).saturating_mul(n.into()))
+	}
+	/// Storage: TransactionStorage AllowedHashes (r:1 w:1)
+	/// Proof: TransactionStorage AllowedHashes (max_values: None, max_size: Some(4145), added: 6620, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage MinAuthorizationExpiryMinus1 (r:1 w:1)
+	/// Proof: TransactionStorage MinAuthorizationExpiryMinus1 (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationsByExpiry (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationsByExpiry (max_values: None, max_size: Some(36882), added: 39357, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OutstandingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage OutstandingAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage BlockAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage NextRecentAuthorization (r:1 w:1)
+	/// Proof: TransactionStorage NextRecentAuthorization (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RecentAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage RecentAuthorizations (max_values: Some(1), max_size: Some(1569), added: 2064, mode: MaxEncodedLen)
+	fn authorize_account() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4233`
+		//  Estimated: `40347`
+		// Minimum execution time: 26_102_000 picoseconds.
+		Weight::from_parts(27_418_000, 40347)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage MinAuthorizationExpiryMinus1 (r:1 w:1)
+	/// Proof: TransactionStorage MinAuthorizationExpiryMinus1 (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationsByExpiry (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationsByExpiry (max_values: None, max_size: Some(36882), added: 39357, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OutstandingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage OutstandingAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage BlockAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage NextRecentAuthorization (r:1 w:1)
+	/// Proof: TransactionStorage NextRecentAuthorization (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RecentAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage RecentAuthorizations (max_values: Some(1), max_size: Some(1569), added: 2064, mode: MaxEncodedLen)
+	fn authorize_preimage() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `88`
+		//  Estimated: `40347`
+		// Minimum execution time: 17_021_000 picoseconds.
+		Weight::from_parts(17_863_000, 40347)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+	}
+	/// Storage: TransactionStorage StandingQuotas (r:0 w:1)
+	/// Proof: TransactionStorage StandingQuotas (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
+	fn set_standing_quota() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 9_318_000 picoseconds.
+		Weight::from_parts(9_712_000, 0)
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	/// Storage: TransactionStorage StandingQuotas (r:1 w:1)
+	/// Proof: TransactionStorage StandingQuotas (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
+	fn clear_standing_quota() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `111`
+		//  Estimated: `3541`
+		// Minimum execution time: 12_761_000 picoseconds.
+		Weight::from_parts(13_204_000, 3541)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: TransactionStorage LifetimeCaps (r:0 w:1)
+	/// Proof: TransactionStorage LifetimeCaps (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	fn set_lifetime_cap() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_602_000 picoseconds.
+		Weight::from_parts(8_937_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: TransactionStorage Authorizers (r:1 w:1)
+	/// Proof: TransactionStorage Authorizers (max_values: None, max_size: Some(48), added: 2523, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForAuthorizers (r:1 w:1)
+	/// Proof: TransactionStorage CounterForAuthorizers (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	fn add_authorizer() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `76`
+		//  Estimated: `3513`
+		// Minimum execution time: 12_677_000 picoseconds.
+		Weight::from_parts(13_116_000, 3513)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage Authorizers (r:1 w:1)
+	/// Proof: TransactionStorage Authorizers (max_values: None, max_size: Some(48), added: 2523, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForAuthorizers (r:1 w:1)
+	/// Proof: TransactionStorage CounterForAuthorizers (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	fn remove_authorizer() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `142`
+		//  Estimated: `3513`
+		// Minimum execution time: 14_833_000 picoseconds.
+		Weight::from_parts(15_340_000, 3513)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage RemovedContent (r:1 w:1)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingRemovals (r:1 w:1)
+	/// Proof: TransactionStorage PendingRemovals (max_values: None, max_size: Some(37), added: 2512, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RemovalSchedule (r:1 w:1)
+	/// Proof: TransactionStorage RemovalSchedule (max_values: None, max_size: Some(534), added: 3009, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForTombstones (r:1 w:1)
+	/// Proof: TransactionStorage CounterForTombstones (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Tombstones (r:0 w:1)
+	/// Proof: TransactionStorage Tombstones (max_values: None, max_size: Some(33), added: 2508, mode: MaxEncodedLen)
+	fn force_remove() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `659`
+		//  Estimated: `3999`
+		// Minimum execution time: 25_472_000 picoseconds.
+		Weight::from_parts(26_318_000, 3999)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: TransactionStorage RemovedContent (r:1 w:0)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingRemovals (r:1 w:1)
+	/// Proof: TransactionStorage PendingRemovals (max_values: None, max_size: Some(37), added: 2512, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RemovalSchedule (r:1 w:1)
+	/// Proof: TransactionStorage RemovalSchedule (max_values: None, max_size: Some(534), added: 3009, mode: MaxEncodedLen)
+	fn schedule_removal() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `627`
+		//  Estimated: `3999`
+		// Minimum execution time: 19_284_000 picoseconds.
+		Weight::from_parts(19_957_000, 3999)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage PendingRemovals (r:1 w:1)
+	/// Proof: TransactionStorage PendingRemovals (max_values: None, max_size: Some(37), added: 2512, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RemovalSchedule (r:1 w:1)
+	/// Proof: TransactionStorage RemovalSchedule (max_values: None, max_size: Some(534), added: 3009, mode: MaxEncodedLen)
+	fn cancel_removal() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `659`
+		//  Estimated: `3999`
+		// Minimum execution time: 17_493_000 picoseconds.
+		Weight::from_parts(18_066_000, 3999)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage StoragePeriod (r:1 w:0)
+	/// Proof: TransactionStorage StoragePeriod (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Transactions (r:0 w:1)
+	/// Proof: TransactionStorage Transactions (max_values: None, max_size: Some(36886), added: 39361, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChunkEnds (r:0 w:1)
+	/// Proof: TransactionStorage ChunkEnds (max_values: None, max_size: Some(2070), added: 4545, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChunkCount (r:0 w:1)
+	/// Proof: TransactionStorage ChunkCount (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	fn on_initialize_pruning() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `32`
+		//  Estimated: `1489`
+		// Minimum execution time: 5_901_000 picoseconds.
+		Weight::from_parts(6_174_000, 1489)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: TransactionStorage RemovalSchedule (r:1 w:1)
+	/// Proof: TransactionStorage RemovalSchedule (max_values: None, max_size: Some(534), added: 3009, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForTombstones (r:1 w:1)
+	/// Proof: TransactionStorage CounterForTombstones (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingRemovals (r:16 w:16)
+	/// Proof: TransactionStorage PendingRemovals (max_values: None, max_size: Some(37), added: 2512, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Tombstones (r:0 w:16)
+	/// Proof: TransactionStorage Tombstones (max_values: None, max_size: Some(33), added: 2508, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RemovedContent (r:0 w:16)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 16]`.
+	fn execute_scheduled_removals(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `115 + n * (69 ±0)`
+		//  Estimated: `3999 + n * (2512 ±0)`
+		// Minimum execution time: 6_388_000 picoseconds.
+		Weight::from_parts(6_743_000, 3999)
+			// Standard Error: 2_318
+			.saturating_add(Weight::from_parts(5_912_407, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(RocksDbWeight::get().writes((3_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2512).saturating_mul(n.into()))
+	}
+	/// Storage: TransactionStorage ProofChecked (r:1 w:1)
+	/// Proof: TransactionStorage ProofChecked (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage IndexedExtrinsic (r:0 w:1)
+	/// Proof: TransactionStorage IndexedExtrinsic (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockChunks (r:0 w:1)
+	/// Proof: TransactionStorage BlockChunks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForBlockTransactions (r:1 w:1)
+	/// Proof: TransactionStorage CounterForBlockTransactions (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockTransactions (r:512 w:512)
+	/// Proof: TransactionStorage BlockTransactions (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChunkCount (r:0 w:1)
+	/// Proof: TransactionStorage ChunkCount (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChunkEnds (r:0 w:1)
+	/// Proof: TransactionStorage ChunkEnds (max_values: None, max_size: Some(2070), added: 4545, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage TransactionInfos (r:0 w:512)
+	/// Proof: TransactionStorage TransactionInfos (max_values: None, max_size: Some(108), added: 2583, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 512]`.
+	fn on_finalize(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `36 + n * (88 ±0)`
+		//  Estimated: `1486 + n * (2563 ±0)`
+		// Minimum execution time: 7_240_000 picoseconds.
+		Weight::from_parts(7_788_000, 1486)
+			// Standard Error: 415
+			.saturating_add(Weight::from_parts(3_419_502, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2563).saturating_mul(n.into()))
+	}
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForBlockTransactions (r:1 w:1)
+	/// Proof: TransactionStorage CounterForBlockTransactions (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockChunks (r:1 w:1)
+	/// Proof: TransactionStorage BlockChunks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage IndexedExtrinsic (r:0 w:1)
+	/// Proof: TransactionStorage IndexedExtrinsic (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockTransactions (r:512 w:512)
+	/// Proof: TransactionStorage BlockTransactions (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ProofChecked (r:1 w:1)
+	/// Proof: TransactionStorage ProofChecked (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChunkCount (r:0 w:1)
+	/// Proof: TransactionStorage ChunkCount (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Transactions (r:0 w:1)
+	/// Proof: TransactionStorage Transactions (max_values: None, max_size: Some(36886), added: 39361, mode: MaxEncodedLen)
+	/// The range of component `n` is `[1, 512]`.
+	fn store_block(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `143 + n * (88 ±0)`
+		//  Estimated: `3547 + n * (2563 ±0)`
+		// Minimum execution time: 49_871_000 picoseconds.
+		Weight::from_parts(12_480_000, 3547)
+			// Standard Error: 2_946
+			.saturating_add(Weight::from_parts(38_214_507, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2563).saturating_mul(n.into()))
+	}
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:512 w:512)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage MinAuthorizationExpiryMinus1 (r:1 w:1)
+	/// Proof: TransactionStorage MinAuthorizationExpiryMinus1 (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationsByExpiry (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationsByExpiry (max_values: None, max_size: Some(36882), added: 39357, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OutstandingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage OutstandingAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage BlockAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage NextRecentAuthorization (r:1 w:1)
+	/// Proof: TransactionStorage NextRecentAuthorization (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RecentAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage RecentAuthorizations (max_values: Some(1), max_size: Some(1569), added: 2064, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 512]`.
+	fn authorize_preimages(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `88`
+		//  Estimated: `40347 + n * (2557 ±0)`
+		// Minimum execution time: 8_733_000 picoseconds.
+		Weight::from_parts(9_104_000, 40347)
+			// Standard Error: 1_183
+			.saturating_add(Weight::from_parts(9_586_231, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2557).saturating_mul(n.into()))
+	}
 	/// Storage: TransactionStorage ProofChecked (r:1 w:1)
 	/// Proof: TransactionStorage ProofChecked (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
 	/// Storage: TransactionStorage StoragePeriod (r:1 w:0)
@@ -161,15 +1389,299 @@ impl WeightInfo for () {
 	/// Proof: TransactionStorage ChunkCount (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
 	/// Storage: System ParentHash (r:1 w:0)
 	/// Proof: System ParentHash (max_values: Some(1), max_size: Some(32), added: 527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChunkEnds (r:1 w:0)
+	/// Proof: TransactionStorage ChunkEnds (max_values: None, max_size: Some(2070), added: 4545, mode: MaxEncodedLen)
 	/// Storage: TransactionStorage Transactions (r:1 w:0)
 	/// Proof: TransactionStorage Transactions (max_values: None, max_size: Some(36886), added: 39361, mode: MaxEncodedLen)
-	fn check_proof_max() -> Weight {
+	/// Storage: TransactionStorage ChallengeCounts (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCounts (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	fn check_proof_worst_case() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `37145`
-		//  Estimated: `40351`
-		// Minimum execution time: 80_913_000 picoseconds.
-		Weight::from_parts(84_812_000, 40351)
-			.saturating_add(RocksDbWeight::get().reads(5_u64))
+		//  Measured:  `39185`
+		//  Estimated: `42866`
+		// Minimum execution time: 62_051_000 picoseconds.
+		Weight::from_parts(63_417_000, 42866)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage Transactions (r:1 w:1)
+	/// Proof: TransactionStorage Transactions (max_values: None, max_size: Some(36886), added: 39361, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage TransactionInfos (r:0 w:512)
+	/// Proof: TransactionStorage TransactionInfos (max_values: None, max_size: Some(108), added: 2583, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 512]`.
+	fn migrate_block_transactions(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `38 + n * (72 ±0)`
+		//  Estimated: `39361`
+		// Minimum execution time: 5_118_000 picoseconds.
+		Weight::from_parts(5_342_000, 39361)
+			// Standard Error: 312
+			.saturating_add(Weight::from_parts(3_047_122, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
+	/// Storage: TransactionStorage Barred (r:0 w:1)
+	/// Proof: TransactionStorage Barred (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Strikes (r:0 w:1)
+	/// Proof: TransactionStorage Strikes (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn clear_bar() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 10_038_000 picoseconds.
+		Weight::from_parts(10_412_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage RemovedContent (r:1 w:0)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingRemovals (r:1 w:0)
+	/// Proof: TransactionStorage PendingRemovals (max_values: None, max_size: Some(37), added: 2512, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCounts (r:1 w:0)
+	/// Proof: TransactionStorage ChallengeCounts (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage TransactionInfos (r:512 w:0)
+	/// Proof: TransactionStorage TransactionInfos (max_values: None, max_size: Some(108), added: 2583, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Challenges (r:1 w:1)
+	/// Proof: TransactionStorage Challenges (max_values: None, max_size: Some(1749), added: 4224, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Barred (r:1 w:0)
+	/// Proof: TransactionStorage Barred (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage UsageHistory (r:1 w:1)
+	/// Proof: TransactionStorage UsageHistory (max_values: None, max_size: Some(289), added: 2764, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AllowedHashes (r:1 w:0)
+	/// Proof: TransactionStorage AllowedHashes (max_values: None, max_size: Some(4145), added: 6620, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:3 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage StandingQuotas (r:1 w:1)
+	/// Proof: TransactionStorage StandingQuotas (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage HeldQuota (r:1 w:0)
+	/// Proof: TransactionStorage HeldQuota (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage GroupOf (r:1 w:0)
+	/// Proof: TransactionStorage GroupOf (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage PendingAuthorizations (max_values: None, max_size: Some(1651), added: 4126, mode: MaxEncodedLen)
+	fn request_proof() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `287`
+		//  Estimated: `1327710`
+		// Minimum execution time: 26_508_000 picoseconds.
+		Weight::from_parts(27_262_000, 1327710)
+			.saturating_add(RocksDbWeight::get().reads(526_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: TransactionStorage Challenges (r:1 w:1)
+	/// Proof: TransactionStorage Challenges (max_values: None, max_size: Some(1749), added: 4224, mode: MaxEncodedLen)
+	/// Storage: System ParentHash (r:1 w:0)
+	/// Proof: System ParentHash (max_values: Some(1), max_size: Some(32), added: 527, mode: MaxEncodedLen)
+	/// The range of component `n` is `[1, 16]`.
+	fn check_challenge_proofs(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `36 + n * (108 ±0)`
+		//  Estimated: `5214`
+		// Minimum execution time: 5_894_000 picoseconds.
+		Weight::from_parts(6_126_000, 5214)
+			// Standard Error: 9_412
+			.saturating_add(Weight::from_parts(58_311_204, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	/// Storage: TransactionStorage IndexedExtrinsic (r:1 w:1)
+	/// Proof: TransactionStorage IndexedExtrinsic (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage TransactionInfos (r:1 w:0)
+	/// Proof: TransactionStorage TransactionInfos (max_values: None, max_size: Some(108), added: 2583, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RemovedContent (r:1 w:0)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingRemovals (r:1 w:0)
+	/// Proof: TransactionStorage PendingRemovals (max_values: None, max_size: Some(37), added: 2512, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Barred (r:1 w:0)
+	/// Proof: TransactionStorage Barred (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PreimageReservations (r:1 w:0)
+	/// Proof: TransactionStorage PreimageReservations (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage LifetimeUsed (r:1 w:1)
+	/// Proof: TransactionStorage LifetimeUsed (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage LifetimeCaps (r:1 w:0)
+	/// Proof: TransactionStorage LifetimeCaps (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage UsageHistory (r:1 w:1)
+	/// Proof: TransactionStorage UsageHistory (max_values: None, max_size: Some(289), added: 2764, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage SatisfiedPreimages (r:1 w:0)
+	/// Proof: TransactionStorage SatisfiedPreimages (max_values: None, max_size: Some(36), added: 2511, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:4 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AllowedHashes (r:1 w:0)
+	/// Proof: TransactionStorage AllowedHashes (max_values: None, max_size: Some(4145), added: 6620, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage StandingQuotas (r:1 w:1)
+	/// Proof: TransactionStorage StandingQuotas (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage HeldQuota (r:1 w:0)
+	/// Proof: TransactionStorage HeldQuota (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage GroupOf (r:1 w:0)
+	/// Proof: TransactionStorage GroupOf (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage PendingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage PendingAuthorizations (max_values: None, max_size: Some(1651), added: 4126, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedByBlock (r:2 w:1)
+	/// Proof: TransactionStorage OwnedByBlock (max_values: None, max_size: Some(32791), added: 35266, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedContent (r:2 w:1)
+	/// Proof: TransactionStorage OwnedContent (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForBlockTransactions (r:1 w:1)
+	/// Proof: TransactionStorage CounterForBlockTransactions (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockChunks (r:1 w:1)
+	/// Proof: TransactionStorage BlockChunks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockTransactions (r:0 w:1)
+	/// Proof: TransactionStorage BlockTransactions (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCountsByBlock (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCountsByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ChallengeCounts (r:1 w:1)
+	/// Proof: TransactionStorage ChallengeCounts (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OwnedContentCount (r:1 w:1)
+	/// Proof: TransactionStorage OwnedContentCount (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CompressedContent (r:1 w:1)
+	/// Proof: TransactionStorage CompressedContent (max_values: None, max_size: Some(72), added: 2547, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CompressedByBlock (r:1 w:1)
+	/// Proof: TransactionStorage CompressedByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage UncompressedContent (r:0 w:1)
+	/// Proof: TransactionStorage UncompressedContent (max_values: None, max_size: Some(64), added: 2539, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage ContentNamespaces (r:1 w:1)
+	/// Proof: TransactionStorage ContentNamespaces (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage NamespacedByBlock (r:1 w:1)
+	/// Proof: TransactionStorage NamespacedByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// The range of component `l` is `[1, 8388608]`.
+	fn verify_content(l: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `182`
+		//  Estimated: `36256`
+		// Minimum execution time: 49_991_000 picoseconds.
+		Weight::from_parts(51_809_000, 36256)
+			// Standard Error: 12
+			.saturating_add(Weight::from_parts(6_921, 0).saturating_mul(l.into()))
+			.saturating_add(RocksDbWeight::get().reads(32_u64))
+			.saturating_add(RocksDbWeight::get().writes(19_u64))
+	}
+	/// Storage: TransactionStorage Tombstones (r:1 w:1)
+	/// Proof: TransactionStorage Tombstones (max_values: None, max_size: Some(33), added: 2508, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage CounterForTombstones (r:1 w:1)
+	/// Proof: TransactionStorage CounterForTombstones (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	fn prune_tombstone() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `109`
+		//  Estimated: `3498`
+		// Minimum execution time: 13_815_000 picoseconds.
+		Weight::from_parts(14_207_000, 3498)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage NextGroupId (r:1 w:1)
+	/// Proof: TransactionStorage NextGroupId (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage GroupOf (r:64 w:64)
+	/// Proof: TransactionStorage GroupOf (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Groups (r:0 w:1)
+	/// Proof: TransactionStorage Groups (max_values: None, max_size: Some(2062), added: 4537, mode: MaxEncodedLen)
+	/// The range of component `m` is `[0, 64]`.
+	fn create_group(m: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `42`
+		//  Estimated: `1489 + m * (2527 ±0)`
+		// Minimum execution time: 11_260_000 picoseconds.
+		Weight::from_parts(11_842_000, 1489)
+			// Standard Error: 1_906
+			.saturating_add(Weight::from_parts(4_317_209, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(m.into())))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(m.into())))
+			.saturating_add(Weight::from_parts(0, 2527).saturating_mul(m.into()))
+	}
+	/// Storage: TransactionStorage Groups (r:1 w:0)
+	/// Proof: TransactionStorage Groups (max_values: None, max_size: Some(2062), added: 4537, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage MinAuthorizationExpiryMinus1 (r:1 w:1)
+	/// Proof: TransactionStorage MinAuthorizationExpiryMinus1 (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationsByExpiry (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationsByExpiry (max_values: None, max_size: Some(36882), added: 39357, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OutstandingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage OutstandingAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage BlockAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage BlockAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage NextRecentAuthorization (r:1 w:1)
+	/// Proof: TransactionStorage NextRecentAuthorization (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage RecentAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage RecentAuthorizations (max_values: Some(1), max_size: Some(1569), added: 2064, mode: MaxEncodedLen)
+	fn authorize_group() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2170`
+		//  Estimated: `40347`
+		// Minimum execution time: 22_734_000 picoseconds.
+		Weight::from_parts(23_516_000, 40347)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+	}
+	/// Storage: TransactionStorage GroupOf (r:1 w:1)
+	/// Proof: TransactionStorage GroupOf (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Groups (r:1 w:1)
+	/// Proof: TransactionStorage Groups (max_values: None, max_size: Some(2062), added: 4537, mode: MaxEncodedLen)
+	fn add_group_member() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2133`
+		//  Estimated: `5527`
+		// Minimum execution time: 18_551_000 picoseconds.
+		Weight::from_parts(19_208_000, 5527)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage GroupOf (r:1 w:1)
+	/// Proof: TransactionStorage GroupOf (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage Groups (r:1 w:1)
+	/// Proof: TransactionStorage Groups (max_values: None, max_size: Some(2062), added: 4537, mode: MaxEncodedLen)
+	fn remove_group_member() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2271`
+		//  Estimated: `5527`
+		// Minimum execution time: 20_940_000 picoseconds.
+		Weight::from_parts(21_674_000, 5527)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: TransactionStorage AuthorizationUsageByScope (r:1 w:1)
+	/// Proof: TransactionStorage AuthorizationUsageByScope (max_values: None, max_size: Some(82), added: 2557, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AccountAuthorizationExpiries (r:1 w:1)
+	/// Proof: TransactionStorage AccountAuthorizationExpiries (max_values: None, max_size: Some(305), added: 2780, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage AuthorizationsByExpiry (r:64 w:64)
+	/// Proof: TransactionStorage AuthorizationsByExpiry (max_values: None, max_size: Some(36882), added: 39357, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage OutstandingAuthorizations (r:1 w:1)
+	/// Proof: TransactionStorage OutstandingAuthorizations (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage HeldQuota (r:0 w:1)
+	/// Proof: TransactionStorage HeldQuota (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 64]`.
+	fn revoke_account_authorization(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `298 + n * (36867 ±0)`
+		//  Estimated: `3770 + n * (39357 ±0)`
+		// Minimum execution time: 17_962_000 picoseconds.
+		Weight::from_parts(19_140_512, 3770)
+			// Standard Error: 21_908
+			.saturating_add(Weight::from_parts(36_457_301, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 39357).saturating_mul(n.into()))
+	}
+	/// Storage: TransactionStorage RemovedContent (r:1 w:0)
+	/// Proof: TransactionStorage RemovedContent (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage InlineData (r:1 w:1)
+	/// Proof: TransactionStorage InlineData (max_values: None, max_size: Some(65572), added: 68047, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage InlineByBlock (r:1 w:1)
+	/// Proof: TransactionStorage InlineByBlock (max_values: None, max_size: Some(16407), added: 18882, mode: MaxEncodedLen)
+	/// Storage: TransactionStorage InlineDataRequests (r:1 w:1)
+	/// Proof: TransactionStorage InlineDataRequests (max_values: None, max_size: Some(36), added: 2511, mode: MaxEncodedLen)
+	/// The range of component `l` is `[1, 65536]`.
+	fn note_preimage(l: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `16452`
+		//  Estimated: `69037`
+		// Minimum execution time: 31_507_000 picoseconds.
+		Weight::from_parts(32_260_117, 69037)
+			// Standard Error: 4
+			.saturating_add(Weight::from_parts(1_391, 0).saturating_mul(l.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
 }
@@ -0,0 +1,56 @@
+//! Prepares the upload of a file: computes what the pallet and IPFS will identify it by and
+//! encodes the `store` call, and a `renew` call of it once stored, to submit as extrinsics.
+//!
+//! Usage: `prepare_upload <data file> <pallet index> [<block> <index>]`, where `<pallet index>`
+//! is the index of the transaction storage pallet in the runtime's metadata, and `<block>` and
+//! `<index>` are where the data was stored, as reported by the `Stored` event.
+
+use sp_core::hexdisplay::HexDisplay;
+use std::process::ExitCode;
+use transaction_storage_primitives::{calls, codec::Encode, TxIndex, Upload};
+
+fn main() -> ExitCode {
+	let args: Vec<_> = std::env::args().skip(1).collect();
+	let (path, pallet_index, stored) = match &args[..] {
+		[path, pallet_index] => (path, pallet_index, None),
+		[path, pallet_index, block, index] => (path, pallet_index, Some((block, index))),
+		_ => {
+			eprintln!("Usage: prepare_upload <data file> <pallet index> [<block> <index>]");
+			return ExitCode::FAILURE
+		},
+	};
+	let Ok(pallet_index) = pallet_index.parse::<u8>() else {
+		eprintln!("Invalid pallet index: {pallet_index}");
+		return ExitCode::FAILURE
+	};
+	let data = match std::fs::read(path) {
+		Ok(data) => data,
+		Err(e) => {
+			eprintln!("Cannot read {path}: {e}");
+			return ExitCode::FAILURE
+		},
+	};
+
+	let upload = Upload::new(&data);
+	println!("Content hash: 0x{}", HexDisplay::from(&upload.content_hash));
+	println!("Size: {}", upload.size);
+	println!("Chunks: {}", upload.chunk_count);
+	println!("Chunk root: {:?}", upload.chunk_root);
+
+	let store = calls::Store { data, namespace: None };
+	let call = (pallet_index, calls::Store::INDEX, store).encode();
+	println!("Store call: 0x{}", HexDisplay::from(&call));
+
+	if let Some((block, index)) = stored {
+		let (Ok(block), Ok(index)) = (block.parse::<u32>(), index.parse::<u32>()) else {
+			eprintln!("Invalid block or index: {block} {index}");
+			return ExitCode::FAILURE
+		};
+		// Fails unless the data at `block` and `index` is this data
+		let renew =
+			calls::Renew { block, index: TxIndex(index), expected_hash: Some(upload.content_hash) };
+		let call = (pallet_index, calls::Renew::<u32>::INDEX, renew).encode();
+		println!("Renew call: 0x{}", HexDisplay::from(&call));
+	}
+	ExitCode::SUCCESS
+}
@@ -0,0 +1,627 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types shared by the transaction storage pallet, its runtime API and its clients. Only depends
+//! on `codec`, `scale-info` and the hash functions of `sp-core-hashing`, so that clients can
+//! decode the pallet's events and runtime API results, and find its storage, without pulling in
+//! FRAME.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+
+pub use codec;
+pub use primitive_types::H256;
+
+/// Size of the chunks that stored data is split into to build its chunk trie.
+pub const CHUNK_SIZE: usize = 256;
+/// Maximum bytes that can be stored in one transaction.
+// Setting higher limit also requires raising the allocator limit.
+pub const DEFAULT_MAX_TRANSACTION_SIZE: u32 = 8 * 1024 * 1024;
+pub const DEFAULT_MAX_BLOCK_TRANSACTIONS: u32 = 512;
+
+/// Plain (Blake2-256) hash of stored content.
+pub type ContentHash = [u8; 32];
+
+/// Tag of the application that stored some content, for listing the content of an application.
+pub type Namespace = [u8; 8];
+
+/// Index of a transaction among the transactions stored or renewed in a block, which `renew`
+/// takes along with the block. Encoded as the `u32` it wraps, so calls, events and storage keys
+/// holding one are encoded as they were with a plain `u32`.
+#[derive(
+	Clone,
+	Copy,
+	Debug,
+	Default,
+	PartialEq,
+	Eq,
+	PartialOrd,
+	Ord,
+	Hash,
+	Encode,
+	Decode,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct TxIndex(pub u32);
+
+impl From<u32> for TxIndex {
+	fn from(index: u32) -> Self {
+		Self(index)
+	}
+}
+
+impl From<TxIndex> for u32 {
+	fn from(index: TxIndex) -> Self {
+		index.0
+	}
+}
+
+impl core::fmt::Display for TxIndex {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+// Storage items keyed by plain `u32` transaction indices can be read with a `TxIndex`
+impl codec::EncodeLike<u32> for TxIndex {}
+
+/// Index of a chunk of stored data, as selected for storage proofs. Encoded as the `u32` it
+/// wraps. Distinct from `TxIndex`, so that the two cannot be mixed up.
+#[derive(
+	Clone,
+	Copy,
+	Debug,
+	Default,
+	PartialEq,
+	Eq,
+	PartialOrd,
+	Ord,
+	Hash,
+	Encode,
+	Decode,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct ChunkIndex(pub u32);
+
+impl From<u32> for ChunkIndex {
+	fn from(index: u32) -> Self {
+		Self(index)
+	}
+}
+
+impl From<ChunkIndex> for u32 {
+	fn from(index: ChunkIndex) -> Self {
+		index.0
+	}
+}
+
+impl core::fmt::Display for ChunkIndex {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+/// Number of chunks of `bytes` bytes of data.
+pub fn num_chunks(bytes: u32) -> u32 {
+	((bytes as u64 + CHUNK_SIZE as u64 - 1) / CHUNK_SIZE as u64) as u32
+}
+
+/// Root of the chunk trie of `data`, as emitted in the `Stored` event when it is stored.
+#[cfg(feature = "chunk-root")]
+pub fn chunk_root(data: &[u8]) -> H256 {
+	use sp_trie::TrieConfiguration;
+	sp_trie::LayoutV1::<sp_core::Blake2Hasher>::ordered_trie_root(data.chunks(CHUNK_SIZE))
+}
+
+/// What a client knows of data before storing it: how the pallet identifies it and what the
+/// `Stored` event will report.
+#[cfg(feature = "chunk-root")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Upload {
+	/// Plain hash of the data, which preimage authorizations and IPFS CIDs are derived from.
+	pub content_hash: ContentHash,
+	/// Size of the data in bytes.
+	pub size: u32,
+	/// Number of chunks of the data, which storage proofs select from.
+	pub chunk_count: u32,
+	/// Root of the chunk trie of the data.
+	pub chunk_root: H256,
+}
+
+#[cfg(feature = "chunk-root")]
+impl Upload {
+	/// Computes the hashes of `data`. Data larger than `u32::MAX` bytes cannot be stored.
+	pub fn new(data: &[u8]) -> Self {
+		let size = data.len() as u32;
+		Self {
+			content_hash: sp_core::hashing::blake2_256(data),
+			size,
+			chunk_count: num_chunks(size),
+			chunk_root: chunk_root(data),
+		}
+	}
+
+	/// Whether `stored` reports the storage of this data.
+	pub fn matches(&self, stored: &events::Stored) -> bool {
+		stored.chunk_root == self.chunk_root
+	}
+}
+
+/// Number of transactions and bytes covered by an authorization or authorizations.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct AuthorizationExtent {
+	/// Number of transactions.
+	pub transactions: u32,
+	/// Number of bytes.
+	pub bytes: u64,
+}
+
+impl AuthorizationExtent {
+	/// Extent of a single transaction of `size` bytes.
+	pub fn transaction(size: u64) -> Self {
+		Self { transactions: 1, bytes: size }
+	}
+
+	/// Adds the transactions and bytes of `other`, saturating each at its maximum.
+	pub fn saturating_add(self, other: Self) -> Self {
+		Self {
+			transactions: self.transactions.saturating_add(other.transactions),
+			bytes: self.bytes.saturating_add(other.bytes),
+		}
+	}
+
+	/// Subtracts the transactions and bytes of `other`, saturating each at zero.
+	pub fn saturating_sub(self, other: Self) -> Self {
+		Self {
+			transactions: self.transactions.saturating_sub(other.transactions),
+			bytes: self.bytes.saturating_sub(other.bytes),
+		}
+	}
+
+	/// Subtracts the transactions and bytes of `other`, or returns `None` if either would
+	/// underflow.
+	pub fn checked_sub(self, other: Self) -> Option<Self> {
+		Some(Self {
+			transactions: self.transactions.checked_sub(other.transactions)?,
+			bytes: self.bytes.checked_sub(other.bytes)?,
+		})
+	}
+
+	/// The lesser of the transactions and the lesser of the bytes of `self` and `other`.
+	pub fn min(self, other: Self) -> Self {
+		Self {
+			transactions: self.transactions.min(other.transactions),
+			bytes: self.bytes.min(other.bytes),
+		}
+	}
+
+	/// Whether the extent covers one more transaction of `size` bytes.
+	pub fn covers(&self, size: u64) -> bool {
+		self.transactions >= 1 && self.bytes >= size
+	}
+}
+
+impl core::ops::Add for AuthorizationExtent {
+	type Output = Self;
+
+	/// Same as `saturating_add`.
+	fn add(self, other: Self) -> Self {
+		self.saturating_add(other)
+	}
+}
+
+/// Why content was removed.
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub enum RemovalReason {
+	/// The content was taken down, e.g. following a legal request.
+	Takedown,
+	/// The owner of the content asked for its removal.
+	OwnerRequest,
+}
+
+/// Availability challenge of stored content, requested with `request_proof`.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct Challenge<AccountId, BlockNumber> {
+	/// Account that requested the challenge.
+	pub who: AccountId,
+	/// Block the content was stored or renewed in.
+	pub block: BlockNumber,
+	/// Index of the content's transaction in `block`.
+	pub index: TxIndex,
+	/// Plain hash of the content.
+	pub content_hash: H256,
+	/// Chunk trie root of the content.
+	pub chunk_root: H256,
+	/// Number of chunks of the content.
+	pub chunk_count: u32,
+}
+
+/// Status of the storage proof of the content stored in a block, which is due `StoragePeriod`
+/// blocks later.
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub enum ProofStatus {
+	/// The proof was checked.
+	Proven,
+	/// The proof is not due yet.
+	Pending,
+	/// The proof was not given when due.
+	Missed,
+	/// The block stored nothing, or its status is no longer known.
+	NotRequired,
+	/// The proof was due, but the block's transactions are missing, so it could not be checked.
+	Unprovable,
+}
+
+/// Who would store data, for checking whether a store would succeed with `can_store`.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub enum ScopeRef<AccountId> {
+	/// A signed store of arbitrary data by the given account.
+	Account(AccountId),
+	/// An unsigned store of the content with the given hash.
+	Preimage(ContentHash),
+}
+
+/// Why a store would be rejected, as reported by `can_store`.
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub enum StoreRejection {
+	/// There is no authorization for the store.
+	NotAuthorized,
+	/// The authorizations do not cover the bytes of the store.
+	InsufficientBytes,
+	/// The authorizations do not cover another transaction.
+	InsufficientTransactions,
+	/// The data is larger than the maximum transaction size.
+	TooLarge,
+	/// The block holds the maximum number of transactions.
+	BlockFull,
+	/// The account is barred from uploading.
+	Banned,
+	/// The data is empty.
+	Empty,
+	/// The size of the data differs from the exact size of the preimage authorization.
+	SizeMismatch,
+	/// The store would exceed the lifetime cap of the account.
+	LifetimeCapExceeded,
+	/// The preimage authorization was already used to store the content.
+	AlreadyStored,
+	/// The content was removed and cannot be stored again.
+	Removed,
+	/// The preimage was already stored with preimage authorizations, which are honoured once.
+	AlreadySatisfied,
+	/// The preimage is reserved for an account during its reservation period.
+	Reserved,
+	/// The content is already stored and not expired, and duplicates are rejected.
+	Duplicate,
+	/// The data is smaller than the minimum transaction size.
+	TooSmall,
+}
+
+/// Content currently stored, as listed by the `stored_content` runtime API.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct ContentRecord<BlockNumber> {
+	/// Plain hash of the content.
+	pub content_hash: H256,
+	/// Size of the content in bytes.
+	pub size: u32,
+	/// Block the content was last stored or renewed in.
+	pub block: BlockNumber,
+	/// Index of the content's transaction in `block`.
+	pub index: TxIndex,
+	/// Last block of the storage period of the content, after which it is pruned unless renewed.
+	pub expires_at: BlockNumber,
+}
+
+/// When stored content must be renewed by, as listed by the `renewal_deadlines` runtime API.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct RenewalDeadline<BlockNumber> {
+	/// Block the content was last stored or renewed in, to be given to `renew`.
+	pub block: BlockNumber,
+	/// Index of the content's transaction in `block`, to be given to `renew`.
+	pub index: TxIndex,
+	/// Last block in which renewing the content succeeds.
+	pub last_block: BlockNumber,
+	/// Block from which renewing the content is recommended, `RenewalLeadTime` blocks before
+	/// `last_block`, so that the renewal can be included and finalized in time.
+	pub recommended_block: BlockNumber,
+}
+
+/// Authorizations used by an account in an authorization period, as listed by the
+/// `account_usage_history` runtime API.
+#[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct PeriodUsage<BlockNumber> {
+	/// Index of the authorization period: the block number divided by the authorization period.
+	pub period: BlockNumber,
+	/// Transactions used by stores, renewals and proof requests.
+	pub transactions: u32,
+	/// Bytes stored or renewed.
+	pub bytes: u64,
+	/// Transactions used by renewals.
+	pub renewals: u32,
+}
+
+/// Fields of the pallet's events about stored content, encoded as the events are. An event is
+/// encoded as the index of the pallet in the runtime, the `INDEX` of the event and its fields.
+pub mod events {
+	use super::*;
+
+	/// Data was stored under the given index.
+	#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo)]
+	pub struct Stored {
+		/// Index of the transaction in its block.
+		pub index: TxIndex,
+		/// Root of the chunk trie of the data.
+		pub chunk_root: H256,
+	}
+
+	impl Stored {
+		/// Index of the event in the pallet's `Event` enum.
+		pub const INDEX: u8 = 0;
+	}
+
+	/// Data was renewed under the given index.
+	#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo)]
+	pub struct Renewed {
+		/// Index of the transaction in its block.
+		pub index: TxIndex,
+		/// Root of the chunk trie of the data.
+		pub chunk_root: H256,
+	}
+
+	impl Renewed {
+		/// Index of the event in the pallet's `Event` enum.
+		pub const INDEX: u8 = 1;
+	}
+
+	/// Content was removed.
+	#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo)]
+	pub struct ContentRemoved {
+		/// Plain hash of the content.
+		pub content_hash: ContentHash,
+		/// Why the content was removed.
+		pub reason: RemovalReason,
+	}
+
+	impl ContentRemoved {
+		/// Index of the event in the pallet's `Event` enum.
+		pub const INDEX: u8 = 10;
+	}
+}
+
+/// Fields of the pallet's calls that clients submit, encoded as the calls are. A call is encoded
+/// as the index of the pallet in the runtime, the `INDEX` of the call and its fields.
+pub mod calls {
+	use super::*;
+	use alloc::vec::Vec;
+
+	/// Stores `data`, optionally listed under `namespace`.
+	#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo)]
+	pub struct Store {
+		/// The data to store.
+		pub data: Vec<u8>,
+		/// Namespace to list the data under.
+		pub namespace: Option<Namespace>,
+	}
+
+	impl Store {
+		/// Index of the call in the pallet's `Call` enum.
+		pub const INDEX: u8 = 0;
+	}
+
+	/// Renews the data stored or renewed as transaction `index` of `block`.
+	#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo)]
+	pub struct Renew<BlockNumber> {
+		/// Block the data was last stored or renewed in.
+		pub block: BlockNumber,
+		/// Index of the data's transaction in `block`.
+		pub index: TxIndex,
+		/// Plain hash the data must have, if any.
+		pub expected_hash: Option<ContentHash>,
+	}
+
+	impl<BlockNumber> Renew<BlockNumber> {
+		/// Index of the call in the pallet's `Call` enum.
+		pub const INDEX: u8 = 1;
+	}
+}
+
+/// Keys of the pallet's storage items, for verifiers of state proofs that cannot derive them from
+/// the runtime metadata. The keys assume the pallet is named `TransactionStorage` in the runtime,
+/// as in the bulletin chain runtime. Block numbers must be of the runtime's type, `u32` in the
+/// bulletin chain runtime.
+pub mod storage_keys {
+	use alloc::vec::Vec;
+	use codec::Encode;
+	use sp_core_hashing::{blake2_128, twox_128, twox_64};
+
+	use super::{ContentHash, TxIndex};
+
+	/// Name of the pallet in the runtime, hashed into the keys of all its storage items.
+	pub const PALLET_PREFIX: &[u8] = b"TransactionStorage";
+
+	/// Prefix of the keys of the pallet's storage item `item`, which is the key of a storage
+	/// value: `twox128(PALLET_PREFIX) ++ twox128(item)`.
+	pub fn storage_prefix(item: &[u8]) -> [u8; 32] {
+		let mut prefix = [0; 32];
+		prefix[..16].copy_from_slice(&twox_128(PALLET_PREFIX));
+		prefix[16..].copy_from_slice(&twox_128(item));
+		prefix
+	}
+
+	fn blake2_128_concat(key: &impl Encode) -> Vec<u8> {
+		let encoded = key.encode();
+		[&blake2_128(&encoded)[..], &encoded[..]].concat()
+	}
+
+	fn twox_64_concat(key: &impl Encode) -> Vec<u8> {
+		let encoded = key.encode();
+		[&twox_64(&encoded)[..], &encoded[..]].concat()
+	}
+
+	/// Key of the entry of the content with the given hash in the index of live content,
+	/// `ChallengeCounts`. The entry exists while the content is stored and holds the number of
+	/// times its storage proof was checked and the last block it was stored or renewed in, as
+	/// `(u32, BlockNumber)`.
+	pub fn content_index_key(content_hash: &ContentHash) -> Vec<u8> {
+		[&storage_prefix(b"ChallengeCounts")[..], &content_hash[..]].concat()
+	}
+
+	/// Key of the number of chunks stored in `block`, a `u32` in `ChunkCount`. There is no entry
+	/// if the block stored nothing.
+	pub fn chunk_count_key<BlockNumber: Encode>(block: BlockNumber) -> Vec<u8> {
+		[&storage_prefix(b"ChunkCount")[..], &blake2_128_concat(&block)[..]].concat()
+	}
+
+	/// Key of the end of the chunk range of each transaction stored in `block`, a `Vec<u32>` in
+	/// `ChunkEnds`.
+	pub fn chunk_ends_key<BlockNumber: Encode>(block: BlockNumber) -> Vec<u8> {
+		[&storage_prefix(b"ChunkEnds")[..], &blake2_128_concat(&block)[..]].concat()
+	}
+
+	/// Key of the metadata of the transaction at `index` of `block`, in `TransactionInfos`.
+	pub fn transaction_info_key<BlockNumber: Encode>(
+		block: BlockNumber,
+		index: TxIndex,
+	) -> Vec<u8> {
+		[
+			&storage_prefix(b"TransactionInfos")[..],
+			&blake2_128_concat(&block)[..],
+			&twox_64_concat(&index)[..],
+		]
+		.concat()
+	}
+
+	/// Key of the metadata of all transactions of `block` in `Transactions`, the format of blocks
+	/// stored before `TransactionInfos`. Read it if `transaction_info_key` has no entry.
+	pub fn transactions_key<BlockNumber: Encode>(block: BlockNumber) -> Vec<u8> {
+		[&storage_prefix(b"Transactions")[..], &blake2_128_concat(&block)[..]].concat()
+	}
+
+	/// Key of the storage period, a `BlockNumber` in `StoragePeriod`.
+	pub fn storage_period_key() -> Vec<u8> {
+		storage_prefix(b"StoragePeriod").to_vec()
+	}
+
+	/// Key of the most recent authorization grants, in `RecentAuthorizations`.
+	pub fn recent_authorizations_key() -> Vec<u8> {
+		storage_prefix(b"RecentAuthorizations").to_vec()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn extent(transactions: u32, bytes: u64) -> AuthorizationExtent {
+		AuthorizationExtent { transactions, bytes }
+	}
+
+	#[test]
+	fn extent_arithmetic_saturates() {
+		assert_eq!(extent(1, 10).saturating_add(extent(2, 20)), extent(3, 30));
+		assert_eq!(extent(1, 10) + extent(2, 20), extent(3, 30));
+		assert_eq!(
+			extent(u32::MAX, 10).saturating_add(extent(1, u64::MAX)),
+			extent(u32::MAX, u64::MAX)
+		);
+		assert_eq!(extent(3, 30).saturating_sub(extent(1, 10)), extent(2, 20));
+		assert_eq!(extent(1, 30).saturating_sub(extent(2, 10)), extent(0, 20));
+	}
+
+	#[test]
+	fn extent_checked_sub_fails_on_any_underflow() {
+		assert_eq!(extent(3, 30).checked_sub(extent(3, 30)), Some(extent(0, 0)));
+		assert_eq!(extent(1, 30).checked_sub(extent(2, 10)), None);
+		assert_eq!(extent(2, 10).checked_sub(extent(1, 30)), None);
+	}
+
+	#[test]
+	fn extent_min_is_componentwise() {
+		assert_eq!(extent(1, 30).min(extent(2, 10)), extent(1, 10));
+		assert_eq!(extent(3, 30).min(extent(3, 30)), extent(3, 30));
+	}
+
+	#[test]
+	fn extent_covers_one_transaction() {
+		assert!(extent(1, 10).covers(10));
+		assert!(extent(1, 10).covers(0));
+		assert!(!extent(1, 10).covers(11));
+		assert!(!extent(0, 10).covers(1));
+		assert_eq!(AuthorizationExtent::transaction(10), extent(1, 10));
+	}
+
+	#[test]
+	fn indices_are_encoded_as_u32() {
+		assert_eq!(TxIndex(5).encode(), 5u32.encode());
+		assert_eq!(ChunkIndex(u32::MAX).encode(), u32::MAX.encode());
+		assert_eq!(TxIndex::decode(&mut &7u32.encode()[..]).ok(), Some(TxIndex(7)));
+		assert_eq!(ChunkIndex::decode(&mut &7u32.encode()[..]).ok(), Some(ChunkIndex(7)));
+		assert_eq!(TxIndex::max_encoded_len(), u32::max_encoded_len());
+		assert_eq!(u32::from(TxIndex::from(3)), 3);
+		assert_eq!(u32::from(ChunkIndex::from(3)), 3);
+	}
+
+	#[test]
+	fn calls_and_events_keep_their_encoding() {
+		let hash = [1; 32];
+		let renew = calls::Renew { block: 10u32, index: TxIndex(2), expected_hash: Some(hash) };
+		assert_eq!(renew.encode(), (10u32, 2u32, Some(hash)).encode());
+		let chunk_root = H256::repeat_byte(2);
+		assert_eq!(
+			events::Stored { index: TxIndex(3), chunk_root }.encode(),
+			(3u32, chunk_root).encode()
+		);
+		assert_eq!(
+			storage_keys::transaction_info_key(10u32, TxIndex(2)),
+			[
+				&storage_keys::storage_prefix(b"TransactionInfos")[..],
+				&sp_core_hashing::blake2_128(&10u32.encode())[..],
+				&10u32.encode()[..],
+				&sp_core_hashing::twox_64(&2u32.encode())[..],
+				&2u32.encode()[..],
+			]
+			.concat()
+		);
+	}
+
+	/// Checks the chunking and chunk roots against the golden file of the pallet's test vectors.
+	#[cfg(feature = "chunk-root")]
+	#[test]
+	fn chunk_roots_match_test_vectors() {
+		let golden: serde_json::Value =
+			serde_json::from_str(include_str!("../test-vectors/chunking.json")).unwrap();
+		let bytes = |value: &serde_json::Value| {
+			let hex = value.as_str().unwrap().trim_start_matches("0x");
+			(0..hex.len())
+				.step_by(2)
+				.map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+				.collect::<Vec<u8>>()
+		};
+		assert_eq!(golden["chunk_size"], CHUNK_SIZE);
+		for vector in golden["vectors"].as_array().unwrap() {
+			let payload = bytes(&vector["payload"]);
+			assert_eq!(num_chunks(payload.len() as u32), vector["chunk_count"]);
+			assert_eq!(sp_core::hashing::blake2_256(&payload)[..], bytes(&vector["content_hash"]));
+			assert_eq!(chunk_root(&payload).as_bytes(), bytes(&vector["chunk_root"]));
+		}
+	}
+}
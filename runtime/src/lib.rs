@@ -8,6 +8,7 @@ include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
 use frame_system::EnsureRoot;
 use pallet_grandpa::AuthorityId as GrandpaId;
+use pallet_transaction_storage::EnsureAuthorizedAccount;
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
@@ -26,8 +27,8 @@ use sp_version::RuntimeVersion;
 pub use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{
-		ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness,
-		StorageInfo,
+		ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, EitherOfDiverse, KeyOwnerProofSystem,
+		Randomness, StorageInfo,
 	},
 	weights::{
 		constants::{
@@ -95,10 +96,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	state_version: 1,
 };
 
@@ -227,17 +228,62 @@ impl pallet_sudo::Config for Runtime {
 
 parameter_types! {
 	pub const TransactionStorageAuthorizationPeriod: BlockNumber = 100800;
+	pub const TransactionStorageExpiryWeightRatio: Perbill = Perbill::from_percent(10);
+	// Identical preimages authorized twice are indexed once
+	pub const TransactionStoragePreimagePolicy: pallet_transaction_storage::PreimageStorePolicy =
+		pallet_transaction_storage::PreimageStorePolicy::Strict;
+	// Signed stores use preimage authorizations before the signer's own
+	pub const TransactionStorageAuthorizationOrder: pallet_transaction_storage::ConsumptionOrder =
+		pallet_transaction_storage::ConsumptionOrder::PreimageFirst;
 }
 
+// Store throughput is bound by block length, not weight: the normal dispatch class gets 75% of
+// the 5 MiB block (~3.75 MiB, ~54 GiB/day at 6s blocks), while the per-byte store weight would
+// admit far more. `MaxTransactionSize` must stay under that limit, which `integrity_test` checks.
 impl pallet_transaction_storage::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type RuntimeCall = RuntimeCall;
 	type WeightInfo = pallet_transaction_storage::weights::SubstrateWeight<Runtime>;
 	type MaxBlockTransactions = ConstU32<512>;
-	type MaxTransactionSize = ConstU32<{ 8 * 1024 * 1024 }>;
+	type MaxTransactionSize = ConstU32<{ 2 * 1024 * 1024 }>;
 	type MaxBlockAuthorizationExpiries = ConstU32<512>;
 	type AuthorizationPeriod = TransactionStorageAuthorizationPeriod;
-	type Authorizer = EnsureRoot<Self::AccountId>;
+	type MaxAllowedHashes = ConstU32<128>;
+	type Authorizer = EitherOfDiverse<EnsureRoot<Self::AccountId>, EnsureAuthorizedAccount<Self>>;
+	type MaxAuthorizers = ConstU32<64>;
+	type MaxBlockRemovals = ConstU32<16>;
+	type ExpiryWeightRatio = TransactionStorageExpiryWeightRatio;
+	type MaxProofSize = ConstU32<{ 16 * 1024 }>;
+	type StorageEventHandler = ();
+	// There is no bridge to send receipts over yet
+	type StoreReceipt = ();
+	// There are no balances to take deposits from
+	type PreimageDeposit = ();
+	type StrikeThreshold = ConstU32<10>;
+	type BarPeriod = ConstU32<HOURS>;
+	type MaxBlockChallenges = ConstU32<16>;
+	type MaxTombstones = ConstU32<65536>;
+	type MaxNamespaceEntries = ConstU32<65536>;
+	type MaxGroupMembers = ConstU32<64>;
+	type DepositContentDigest = ConstBool<true>;
+	type MaxOwnedContent = ConstU32<65536>;
+	type PreimagePolicy = TransactionStoragePreimagePolicy;
+	type AuthorizationOrder = TransactionStorageAuthorizationOrder;
+	// At most 512 blocks of full expiries
+	type MaxOutstandingAuthorizations = ConstU32<{ 512 * 512 }>;
+	type MaxAuthorizationsPerBlock = ConstU32<512>;
+	type ReservationPeriod = ConstU32<HOURS>;
+	type MaxUsageHistory = ConstU32<12>;
+	type MaxRecentAuthorizations = ConstU32<32>;
+	type StrictDedup = ConstBool<false>;
+	type AuthorizerStoresPerBlock = ConstU32<8>;
+	type MaxAccountAuthorizationExpiries = ConstU32<64>;
+	// Archives can keep the content of others alive
+	type RenewalPolicy = ();
+	type MinTransactionSize = ConstU32<1>;
+	type MinBytesPerStore = ConstU32<0>;
+	type RenewalLeadTime = ConstU32<HOURS>;
+	type MaxScopeAuthorizations = ConstU32<64>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -267,6 +313,7 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
+	pallet_transaction_storage::TrackStoreFailures<Runtime>,
 );
 
 /// Unchecked extrinsic type as expected by this runtime.
@@ -274,6 +321,14 @@ pub type UncheckedExtrinsic =
 	generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
+/// Migrations to apply on runtime upgrade.
+pub type Migrations = (
+	pallet_transaction_storage::migrations::v1::MigrateToTransactionInfos<Runtime>,
+	pallet_transaction_storage::migrations::v2::MigrateToRemovalReasons<Runtime>,
+	pallet_transaction_storage::migrations::v3::MigrateToExpiryBlockList<Runtime>,
+	pallet_transaction_storage::migrations::v4::MigrateToPendingAuthorizations<Runtime>,
+);
+
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
 	Runtime,
@@ -281,6 +336,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -422,6 +478,81 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_transaction_storage::runtime_api::TransactionStorageApi<Block, AccountId, BlockNumber>
+		for Runtime
+	{
+		fn challenges() -> Vec<pallet_transaction_storage::Challenge<AccountId, BlockNumber>> {
+			TransactionStorage::challenges()
+		}
+
+		fn tombstone(
+			content_hash: pallet_transaction_storage::Preimage,
+		) -> Option<pallet_transaction_storage::RemovalReason> {
+			TransactionStorage::tombstone(content_hash)
+		}
+
+		fn namespace_content(
+			namespace: pallet_transaction_storage::Namespace,
+			start_after: Option<pallet_transaction_storage::Preimage>,
+			limit: u32,
+		) -> Vec<pallet_transaction_storage::Preimage> {
+			TransactionStorage::namespace_content(namespace, start_after, limit)
+		}
+
+		fn stored_content(
+			start_key: Option<Vec<u8>>,
+			limit: u32,
+		) -> (
+			Vec<pallet_transaction_storage::ContentRecord<BlockNumber>>,
+			Option<Vec<u8>>,
+		) {
+			TransactionStorage::stored_content(start_key, limit)
+		}
+
+		fn proof_status(block: BlockNumber) -> pallet_transaction_storage::ProofStatus {
+			TransactionStorage::proof_status(block)
+		}
+
+		fn can_store(
+			origin: pallet_transaction_storage::ScopeRef<AccountId>,
+			size: u32,
+		) -> Result<(), pallet_transaction_storage::StoreRejection> {
+			TransactionStorage::can_store(origin, size)
+		}
+
+		fn unused_account_authorization_extent(
+			who: AccountId,
+		) -> pallet_transaction_storage::AuthorizationExtent {
+			TransactionStorage::unused_account_authorization_extent(who)
+		}
+
+		fn unused_preimage_authorization_extent(
+			content_hash: pallet_transaction_storage::Preimage,
+		) -> pallet_transaction_storage::AuthorizationExtent {
+			TransactionStorage::unused_preimage_authorization_extent(content_hash)
+		}
+
+		fn times_challenged(content_hash: pallet_transaction_storage::Preimage) -> Option<u32> {
+			TransactionStorage::times_challenged(content_hash)
+		}
+
+		fn account_usage_history(
+			who: AccountId,
+		) -> Vec<pallet_transaction_storage::PeriodUsage<BlockNumber>> {
+			TransactionStorage::account_usage_history(who)
+		}
+
+		fn storage_period() -> BlockNumber {
+			TransactionStorage::storage_period()
+		}
+
+		fn renewal_deadlines(
+			hashes: Vec<pallet_transaction_storage::Preimage>,
+		) -> Vec<Option<pallet_transaction_storage::RenewalDeadline<BlockNumber>>> {
+			TransactionStorage::renewal_deadlines(hashes)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (